@@ -0,0 +1,67 @@
+use std::{
+    cell::Cell,
+    rc::Rc,
+    time::Instant,
+};
+
+/// A monotonic time source for an event loop.
+pub trait Clock{
+    /// Returns the ticks (nanoseconds) passed since an arbitrary moment.
+    fn now(&self)->u64;
+}
+
+/// The monotonic system clock.
+pub struct SystemClock{
+    start:Instant,
+}
+
+impl SystemClock{
+    pub fn new()->SystemClock{
+        Self{
+            start:Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock{
+    fn default()->SystemClock{
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock{
+    fn now(&self)->u64{
+        self.start.elapsed().as_nanos() as u64
+    }
+}
+
+/// A clock that only moves when it's told to.
+///
+/// The copies share the time, so a loop handler can advance the clock of the loop
+/// to simulate slow frames.
+#[derive(Clone,Default)]
+pub struct ManualClock{
+    ticks:Rc<Cell<u64>>,
+}
+
+impl ManualClock{
+    /// Creates a clock starting at `0`.
+    pub fn new()->ManualClock{
+        Self::default()
+    }
+
+    /// Sets the time, the clock doesn't go backwards.
+    pub fn set(&self,ticks:u64){
+        self.ticks.set(self.ticks.get().max(ticks))
+    }
+
+    pub fn advance(&self,ticks:u64){
+        self.ticks.set(self.ticks.get()+ticks)
+    }
+}
+
+impl Clock for ManualClock{
+    fn now(&self)->u64{
+        self.ticks.get()
+    }
+}
@@ -18,7 +18,7 @@ pub enum ProcessEvent{
     EventLoopBreak,
 }
 
-#[derive(Debug)]
+#[derive(Debug,Clone)]
 pub enum WindowEvent{
     MouseMove([u16;2]),
     MousePress{
@@ -0,0 +1,330 @@
+//! A platform-independent event loop.
+//!
+//! The loop schedules updates and redraw requests by a [Clock]
+//! and takes everything else from an [EventSource].
+//! The platform modules implement `EventLoop::run` with the [SystemClock] and their own sources,
+//! [EventLoop::run_with] accepts any,
+//! e.g. a [ManualClock] with a [SimulatedEventSource] for deterministic runs.
+//!
+//! An [EventRecorder] writes the input of a session to a file
+//! and an [EventPlayer] reads it back for a replay.
+
+mod event;
+pub use event::{
+    Event,
+    ProcessEvent,
+    WindowEvent,
+    MouseButton,
+    VirtualKeyCode,
+};
+
+mod clock;
+pub use clock::{
+    Clock,
+    SystemClock,
+    ManualClock,
+};
+
+mod recording;
+pub use recording::{
+    EventRecorder,
+    EventPlayer,
+    RecordedEvent,
+    RecordingError,
+};
+
+mod simulation;
+pub use simulation::SimulatedEventSource;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Clone,Debug)]
+pub enum LoopControl{
+    /// The loop is running with the defeault settings.
+    Run,
+
+    /// Updates are disabled.
+    Lazy,
+
+    /// The loop will be closed.
+    Break,
+}
+
+/// Represents interval between events or an event rate.
+///
+/// The event is disabled if an interval or rate is `0`.
+#[derive(Clone,Copy,Debug)]
+pub enum EventInterval{
+    EventsPerSecond(u32),
+    NanoSeconds(u32),
+    Seconds(u32),
+}
+
+impl EventInterval{
+    pub fn into_nanoseconds(self)->u64{
+        match self{
+            EventInterval::EventsPerSecond(updates)=>{
+                if updates==0{
+                    0u64
+                }
+                else{
+                    1_000_000_000u64/updates as u64
+                }
+            }
+            EventInterval::NanoSeconds(nanoseconds)=>nanoseconds as u64,
+            EventInterval::Seconds(seconds)=>seconds as u64*1_000_000_000u64,
+        }
+    }
+}
+
+/// Nanoseconds of the loop clock.
+///
+/// Before the platform-independent loop a tick was a performance counter tick on Windows,
+/// use `as_nanoseconds` and `as_seconds` instead of the raw value.
+#[derive(Debug,Clone,Copy)]
+pub struct Ticks(pub u64);
+
+impl Ticks{
+    pub fn as_seconds(self)->u64{
+        self.0/1_000_000_000u64
+    }
+
+    pub fn as_nanoseconds(self)->u64{
+        self.0
+    }
+}
+
+/// The result of `EventSource::process`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum SourceState{
+    /// The source may produce more events.
+    Running,
+
+    /// The current thread has made a request to quit,
+    /// the loop emits `ProcessEvent::Quit` and breaks.
+    Quit,
+
+    /// The source can't produce events anymore,
+    /// the loop breaks without `ProcessEvent::Quit`.
+    Closed,
+}
+
+/// Platform events for an [EventLoop].
+pub trait EventSource{
+    /// Processes pending events dispatching them to the windows,
+    /// the events for the loop handler are passed to `f`.
+    ///
+    /// If there is nothing to process, waits for events no longer than `timeout` ticks
+    /// or until one comes if `timeout` is `None`.
+    /// A source may return earlier, the loop checks the clock anyway.
+    fn process<F:FnMut(Event,&mut LoopControl)>(
+        &mut self,
+        timeout:Option<u64>,
+        f:&mut F,
+        control:&mut LoopControl
+    )->SourceState;
+
+    /// Requests a redraw of the windows.
+    fn request_redraw(&mut self);
+}
+
+/// A periodic event.
+struct Timer{
+    interval:u64,
+    last:u64,
+}
+
+impl Timer{
+    fn new(interval:u64,start:u64)->Timer{
+        Self{
+            interval,
+            last:start,
+        }
+    }
+
+    /// The moment the event is due or `None` if the event is disabled.
+    fn deadline(&self)->Option<u64>{
+        if self.interval!=0{
+            Some(self.last+self.interval)
+        }
+        else{
+            None
+        }
+    }
+
+    /// Returns the ticks passed since the last event if the event is due.
+    ///
+    /// A late event is caught up with by the next one
+    /// unless it's late for more than an interval, then the schedule is reset.
+    fn check(&mut self,current_ticks:u64)->Option<u64>{
+        // если интервал не нулевой (событие включёно)
+        if self.interval==0{
+            return None
+        }
+
+        let ticks_passed=current_ticks.saturating_sub(self.last);
+        if ticks_passed<self.interval{
+            return None
+        }
+
+        if ticks_passed<self.interval<<1{
+            self.last+=self.interval;
+        }
+        else{
+            self.last=current_ticks;
+        }
+
+        Some(ticks_passed)
+    }
+}
+
+fn nearest(a:Option<u64>,b:Option<u64>)->Option<u64>{
+    match (a,b){
+        (Some(a),Some(b))=>Some(a.min(b)),
+        (a,None)=>a,
+        (None,b)=>b,
+    }
+}
+
+// Mister Programmer, may I have some loops?
+//      /\_____/\
+//     /  o   o  \
+//    ( ==  ^  == )
+//     )         (
+//    (           )
+//   ( (  )   (  ) )
+//  (__(__)___(__)__)
+
+pub struct EventLoop{
+    // в тактах (наносекундах)
+    update_interval:u64,
+    redraw_request_interval:u64,
+}
+
+impl EventLoop{
+    pub fn new(attributes:EventLoopAttributes)->EventLoop{
+        let update_interval=attributes.update_interval.into_nanoseconds();
+
+        let redraw_request_interval=attributes.redraw_request_interval.into_nanoseconds();
+
+        Self{
+            update_interval,
+            redraw_request_interval,
+        }
+    }
+
+    /// Runs an event loop with the given clock and event source.
+    ///
+    /// Запускает цикл событий с заданными часами и источником событий.
+    pub fn run_with<C,S,F>(&self,clock:&C,source:&mut S,mut f:F)
+        where
+            C:Clock+?Sized,
+            S:EventSource+?Sized,
+            F:FnMut(Event,&mut LoopControl)
+    {
+        // Флаг управления циклом
+        let mut loop_control=LoopControl::Run;
+
+        let start=clock.now();
+        let mut update=Timer::new(self.update_interval,start);
+        let mut redraw_request=Timer::new(self.redraw_request_interval,start);
+
+        // Начальное событие
+        f(Event::Process(ProcessEvent::EventLoopStart),&mut loop_control);
+
+        loop{
+            let state=match loop_control{
+                LoopControl::Run=>{
+                    let deadline=nearest(update.deadline(),redraw_request.deadline());
+                    let timeout=deadline.map(|deadline|deadline.saturating_sub(clock.now()));
+
+                    let state=source.process(timeout,&mut f,&mut loop_control);
+
+                    if state==SourceState::Running{
+                        // проверка интервала события обновления
+                        if let Some(ticks_passed)=update.check(clock.now()){
+                            f(
+                                Event::Process(ProcessEvent::Update(Ticks(ticks_passed))),
+                                &mut loop_control
+                            );
+                        }
+                    }
+
+                    state
+                }
+
+                LoopControl::Lazy=>{
+                    let timeout=redraw_request.deadline()
+                        .map(|deadline|deadline.saturating_sub(clock.now()));
+
+                    source.process(timeout,&mut f,&mut loop_control)
+                }
+
+                LoopControl::Break=>break,
+            };
+
+            match state{
+                SourceState::Running=>{}
+
+                SourceState::Quit=>{
+                    f(Event::Process(ProcessEvent::Quit),&mut loop_control);
+                    break
+                }
+
+                SourceState::Closed=>break,
+            }
+
+            // проверка интервала события отрисовки
+            if redraw_request.check(clock.now()).is_some(){
+                source.request_redraw()
+            }
+        }
+
+        // Завершение цикла
+        f(Event::Process(ProcessEvent::EventLoopBreak),&mut loop_control);
+    }
+}
+
+impl EventLoop{
+    pub fn set_update_interval(&mut self,interval:EventInterval){
+        self.update_interval=interval.into_nanoseconds()
+    }
+
+    pub fn set_redraw_request_interval(&mut self,interval:EventInterval){
+        self.redraw_request_interval=interval.into_nanoseconds()
+    }
+}
+
+#[derive(Clone,Debug)]
+pub struct EventLoopAttributes{
+    /// Defines an interval between `Process::Update` events.
+    ///
+    /// The event is disabled if an interval or rate is `0`.
+    ///
+    /// The default is `EventInteval::UpdatesPerSecond(50u32)`.
+    pub update_interval:EventInterval,
+
+    /// Defines an interval between `Window::Redraw` requests.
+    ///
+    /// The event is disabled if an interval or rate is `0`.
+    ///
+    /// The default is `EventInteval::UpdatesPerSecond(0u32)`
+    /// (disabled).
+    pub redraw_request_interval:EventInterval,
+}
+
+impl EventLoopAttributes{
+    pub fn new()->EventLoopAttributes{
+        Self{
+            update_interval:EventInterval::EventsPerSecond(50u32),
+            redraw_request_interval:EventInterval::EventsPerSecond(0u32),
+        }
+    }
+}
+
+impl Default for EventLoopAttributes{
+    fn default()->EventLoopAttributes{
+        EventLoopAttributes::new()
+    }
+}
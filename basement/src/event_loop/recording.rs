@@ -0,0 +1,288 @@
+use super::{
+    Clock,
+    SystemClock,
+    Event,
+    ProcessEvent,
+    WindowEvent,
+    MouseButton,
+    VirtualKeyCode,
+};
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    fs::File,
+    io::{
+        self,
+        BufRead,
+        BufReader,
+        BufWriter,
+        Write,
+    },
+    path::Path,
+};
+
+/// The first line of a recording.
+const HEADER:&str="# cat_engine events 1";
+
+/// An event of a recording.
+#[derive(Debug,Clone)]
+pub enum RecordedEvent{
+    Window(WindowEvent),
+    /// The thread has made a request to quit.
+    Quit,
+}
+
+#[derive(Debug)]
+pub enum RecordingError{
+    Io(io::Error),
+    /// A line of a recording can't be parsed, lines are counted from `1`.
+    Parse{
+        line:usize,
+        message:String,
+    },
+}
+
+impl From<io::Error> for RecordingError{
+    fn from(error:io::Error)->RecordingError{
+        RecordingError::Io(error)
+    }
+}
+
+impl fmt::Display for RecordingError{
+    fn fmt(&self,f:&mut fmt::Formatter)->fmt::Result{
+        match self{
+            RecordingError::Io(error)=>write!(f,"{}",error),
+            RecordingError::Parse{line,message}=>write!(f,"line {}: {}",line,message),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError{}
+
+/// Writes events with their timestamps as text, an event per line.
+///
+/// ```text
+/// # cat_engine events 1
+/// 20000000 MouseMove 120 45
+/// 35000000 MousePress 120 45 Left
+/// 41000000 KeyPress 65
+/// 41000000 CharacterInput 97
+/// 90000000 Quit
+/// ```
+///
+/// The timestamps are ticks (nanoseconds) of the recorder clock,
+/// keys are `VirtualKeyCode` values and characters are Unicode code points.
+/// Only window events and quit requests are recorded,
+/// the other process events are generated by the event loop itself.
+pub struct EventRecorder<W:Write,C:Clock=SystemClock>{
+    writer:W,
+    clock:C,
+}
+
+impl EventRecorder<BufWriter<File>>{
+    /// Creates a file to record to.
+    pub fn create<P:AsRef<Path>>(path:P)->io::Result<EventRecorder<BufWriter<File>>>{
+        EventRecorder::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W:Write> EventRecorder<W>{
+    /// Creates a recorder with the system clock starting now.
+    pub fn new(writer:W)->io::Result<EventRecorder<W>>{
+        EventRecorder::with_clock(writer,SystemClock::new())
+    }
+}
+
+impl<W:Write,C:Clock> EventRecorder<W,C>{
+    pub fn with_clock(mut writer:W,clock:C)->io::Result<EventRecorder<W,C>>{
+        writeln!(writer,"{}",HEADER)?;
+
+        Ok(Self{
+            writer,
+            clock,
+        })
+    }
+
+    /// Records an event at the current time of the clock.
+    ///
+    /// Returns `Ok(false)` if the event isn't recorded.
+    pub fn record(&mut self,event:&Event)->io::Result<bool>{
+        let ticks=self.clock.now();
+
+        match event{
+            Event::Window(event)=>self.write(ticks,&RecordedEvent::Window(event.clone()))?,
+            Event::Process(ProcessEvent::Quit)=>self.write(ticks,&RecordedEvent::Quit)?,
+            Event::Process(_)=>return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    /// Records an event with the given time.
+    pub fn write(&mut self,ticks:u64,event:&RecordedEvent)->io::Result<()>{
+        write!(self.writer,"{} ",ticks)?;
+
+        match event{
+            RecordedEvent::Window(event)=>match event{
+                WindowEvent::MouseMove([x,y])=>writeln!(self.writer,"MouseMove {} {}",x,y),
+                WindowEvent::MousePress{cursor_position:[x,y],button}=>{
+                    writeln!(self.writer,"MousePress {} {} {:?}",x,y,button)
+                }
+                WindowEvent::MouseRelease{cursor_position:[x,y],button}=>{
+                    writeln!(self.writer,"MouseRelease {} {} {:?}",x,y,button)
+                }
+                WindowEvent::MouseScroll(delta)=>writeln!(self.writer,"MouseScroll {}",delta),
+                WindowEvent::KeyPress(key)=>writeln!(self.writer,"KeyPress {}",*key as u8),
+                WindowEvent::KeyRelease(key)=>writeln!(self.writer,"KeyRelease {}",*key as u8),
+                WindowEvent::CharacterInput(character)=>{
+                    writeln!(self.writer,"CharacterInput {}",*character as u32)
+                }
+            }
+            RecordedEvent::Quit=>writeln!(self.writer,"Quit"),
+        }
+    }
+
+    pub fn flush(&mut self)->io::Result<()>{
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self)->W{
+        self.writer
+    }
+}
+
+/// Recorded events read back in the order of their timestamps.
+#[derive(Debug,Clone,Default)]
+pub struct EventPlayer{
+    events:VecDeque<(u64,RecordedEvent)>,
+}
+
+impl EventPlayer{
+    pub fn open<P:AsRef<Path>>(path:P)->Result<EventPlayer,RecordingError>{
+        EventPlayer::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads a recording made by an [EventRecorder].
+    ///
+    /// Empty lines and lines starting with `#` are skipped.
+    pub fn read<R:BufRead>(reader:R)->Result<EventPlayer,RecordingError>{
+        let mut events=Vec::new();
+
+        for (index,line) in reader.lines().enumerate(){
+            let line=line?;
+            let line=line.trim();
+            if line.is_empty() || line.starts_with('#'){
+                continue
+            }
+
+            let event=parse_line(line).map_err(|message|RecordingError::Parse{
+                line:index+1,
+                message,
+            })?;
+
+            events.push(event);
+        }
+
+        // Keeps the order of events with the same time
+        events.sort_by_key(|&(ticks,_)|ticks);
+
+        Ok(Self{
+            events:events.into(),
+        })
+    }
+
+    /// The number of events left.
+    pub fn len(&self)->usize{
+        self.events.len()
+    }
+
+    pub fn is_empty(&self)->bool{
+        self.events.is_empty()
+    }
+
+    /// The time of the next event.
+    pub fn next_ticks(&self)->Option<u64>{
+        self.events.front().map(|&(ticks,_)|ticks)
+    }
+
+    /// Removes and returns the next event if it's due by `ticks`.
+    pub fn next_event(&mut self,ticks:u64)->Option<RecordedEvent>{
+        if self.next_ticks()? <= ticks{
+            self.events.pop_front().map(|(_,event)|event)
+        }
+        else{
+            None
+        }
+    }
+
+    pub fn into_events(self)->Vec<(u64,RecordedEvent)>{
+        self.events.into()
+    }
+}
+
+fn parse_line(line:&str)->Result<(u64,RecordedEvent),String>{
+    let mut words=line.split_whitespace();
+
+    let ticks=parse_number(words.next())?;
+    let name=words.next().ok_or("no event name")?;
+
+    let mut position=||->Result<[u16;2],String>{
+        Ok([parse_number(words.next())?,parse_number(words.next())?])
+    };
+
+    let event=match name{
+        "Quit"=>RecordedEvent::Quit,
+        "MouseMove"=>RecordedEvent::Window(WindowEvent::MouseMove(position()?)),
+        "MousePress"|"MouseRelease"=>{
+            let cursor_position=position()?;
+            let button=match words.next(){
+                Some("Left")=>MouseButton::Left,
+                Some("Middle")=>MouseButton::Middle,
+                Some("Right")=>MouseButton::Right,
+                Some("Button4")=>MouseButton::Button4,
+                Some("Button5")=>MouseButton::Button5,
+                button=>return Err(format!("unknown mouse button {:?}",button)),
+            };
+
+            if name=="MousePress"{
+                RecordedEvent::Window(WindowEvent::MousePress{cursor_position,button})
+            }
+            else{
+                RecordedEvent::Window(WindowEvent::MouseRelease{cursor_position,button})
+            }
+        }
+        "MouseScroll"=>RecordedEvent::Window(WindowEvent::MouseScroll(parse_number(words.next())?)),
+        "KeyPress"=>RecordedEvent::Window(WindowEvent::KeyPress(parse_key(words.next())?)),
+        "KeyRelease"=>RecordedEvent::Window(WindowEvent::KeyRelease(parse_key(words.next())?)),
+        "CharacterInput"=>{
+            let code=parse_number(words.next())?;
+            let character=char::from_u32(code).ok_or(format!("invalid character {}",code))?;
+            RecordedEvent::Window(WindowEvent::CharacterInput(character))
+        }
+        name=>return Err(format!("unknown event {}",name)),
+    };
+
+    if words.next().is_some(){
+        return Err("unexpected arguments".to_string())
+    }
+
+    Ok((ticks,event))
+}
+
+fn parse_number<N:std::str::FromStr>(word:Option<&str>)->Result<N,String>{
+    let word=word.ok_or("not enough arguments")?;
+    word.parse().map_err(|_|format!("invalid number {}",word))
+}
+
+fn parse_key(word:Option<&str>)->Result<VirtualKeyCode,String>{
+    let code:u8=parse_number(word)?;
+    if code>VirtualKeyCode::OEM_CLEAR as u8{
+        return Err(format!("invalid key {}",code))
+    }
+
+    // `VirtualKeyCode` covers all the codes up to `OEM_CLEAR`
+    unsafe{
+        Ok(std::mem::transmute::<u8,VirtualKeyCode>(code))
+    }
+}
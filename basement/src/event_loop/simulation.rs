@@ -0,0 +1,109 @@
+use super::{
+    Clock,
+    ManualClock,
+    Event,
+    WindowEvent,
+    LoopControl,
+    EventSource,
+    SourceState,
+    RecordedEvent,
+    EventPlayer,
+};
+
+use std::collections::VecDeque;
+
+/// Emits scheduled events by a [ManualClock].
+///
+/// Instead of waiting the source moves the clock forward
+/// to the next event or to the end of the timeout,
+/// so the loop runs without sleeping and the same way every time.
+/// When there are no events left and the loop has nothing to wait for,
+/// the source is closed.
+pub struct SimulatedEventSource{
+    clock:ManualClock,
+    /// Sorted by time.
+    events:VecDeque<(u64,RecordedEvent)>,
+    redraw_requests:usize,
+}
+
+impl SimulatedEventSource{
+    pub fn new(clock:ManualClock)->SimulatedEventSource{
+        Self{
+            clock,
+            events:VecDeque::new(),
+            redraw_requests:0,
+        }
+    }
+
+    /// Schedules an event at `ticks`.
+    ///
+    /// Events with the same time are emitted in the order they were added.
+    pub fn push_event(&mut self,ticks:u64,event:WindowEvent){
+        self.insert(ticks,RecordedEvent::Window(event))
+    }
+
+    /// Schedules a quit request at `ticks`.
+    pub fn push_quit(&mut self,ticks:u64){
+        self.insert(ticks,RecordedEvent::Quit)
+    }
+
+    /// Schedules the events of a recording.
+    pub fn push_recording(&mut self,player:EventPlayer){
+        for (ticks,event) in player.into_events(){
+            self.insert(ticks,event)
+        }
+    }
+
+    /// The number of redraw requests made by the loop.
+    pub fn redraw_requests(&self)->usize{
+        self.redraw_requests
+    }
+
+    /// The number of events that haven't been emitted yet.
+    pub fn pending_events(&self)->usize{
+        self.events.len()
+    }
+
+    fn insert(&mut self,ticks:u64,event:RecordedEvent){
+        let index=self.events.partition_point(|&(event_ticks,_)|event_ticks<=ticks);
+        self.events.insert(index,(ticks,event))
+    }
+}
+
+impl EventSource for SimulatedEventSource{
+    fn process<F:FnMut(Event,&mut LoopControl)>(
+        &mut self,
+        timeout:Option<u64>,
+        f:&mut F,
+        control:&mut LoopControl
+    )->SourceState{
+        let current_ticks=self.clock.now();
+
+        let next=self.events.front().map(|&(ticks,_)|ticks);
+
+        if let Some(next)=next{
+            if next<=current_ticks{
+                match self.events.pop_front().unwrap().1{
+                    RecordedEvent::Window(event)=>f(Event::Window(event),control),
+                    RecordedEvent::Quit=>return SourceState::Quit,
+                }
+                return SourceState::Running
+            }
+        }
+
+        let wake_up=match (next,timeout){
+            (Some(next),Some(timeout))=>next.min(current_ticks+timeout),
+            (Some(next),None)=>next,
+            (None,Some(timeout))=>current_ticks+timeout,
+            // Nothing will ever happen
+            (None,None)=>return SourceState::Closed,
+        };
+
+        self.clock.set(wake_up);
+        SourceState::Running
+    }
+
+    fn request_redraw(&mut self){
+        self.redraw_requests+=1;
+    }
+}
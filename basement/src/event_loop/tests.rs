@@ -0,0 +1,176 @@
+use super::{
+    Clock,
+    Event,
+    EventInterval,
+    EventLoop,
+    EventLoopAttributes,
+    LoopControl,
+    ManualClock,
+    ProcessEvent,
+    SimulatedEventSource,
+    WindowEvent,
+};
+
+const MILLISECOND:u64=1_000_000;
+
+fn new_event_loop(update_interval:EventInterval,redraw_request_interval:EventInterval)->EventLoop{
+    let mut attributes=EventLoopAttributes::new();
+    attributes.update_interval=update_interval;
+    attributes.redraw_request_interval=redraw_request_interval;
+    EventLoop::new(attributes)
+}
+
+#[test]
+fn fixed_update_interval(){
+    let clock=ManualClock::new();
+    let mut source=SimulatedEventSource::new(clock.clone());
+    source.push_quit(1000*MILLISECOND);
+
+    let event_loop=new_event_loop(EventInterval::EventsPerSecond(50),EventInterval::EventsPerSecond(0));
+
+    let mut updates=Vec::new();
+    event_loop.run_with(&clock,&mut source,|event,_|{
+        if let Event::Process(ProcessEvent::Update(ticks))=event{
+            updates.push((clock.now(),ticks.as_nanoseconds()));
+        }
+    });
+
+    assert_eq!(updates.len(),50);
+    for (n,&(time,passed)) in updates.iter().enumerate(){
+        assert_eq!(time,(n as u64+1)*20*MILLISECOND);
+        assert_eq!(passed,20*MILLISECOND);
+    }
+    assert_eq!(source.redraw_requests(),0);
+}
+
+#[test]
+fn late_updates(){
+    let clock=ManualClock::new();
+    let mut source=SimulatedEventSource::new(clock.clone());
+    source.push_quit(200*MILLISECOND);
+
+    let event_loop=new_event_loop(EventInterval::EventsPerSecond(50),EventInterval::EventsPerSecond(0));
+
+    let mut updates=Vec::new();
+    event_loop.run_with(&clock,&mut source,|event,_|{
+        if let Event::Process(ProcessEvent::Update(ticks))=event{
+            updates.push((clock.now(),ticks.as_nanoseconds()/MILLISECOND));
+            match updates.len(){
+                // A slow frame is caught up with by the next one
+                1=>clock.advance(30*MILLISECOND),
+                // A stall resets the schedule
+                3=>clock.advance(100*MILLISECOND),
+                _=>{}
+            }
+        }
+    });
+
+    let times:Vec<u64>=updates.iter().map(|&(time,_)|time/MILLISECOND).collect();
+    let passed:Vec<u64>=updates.iter().map(|&(_,passed)|passed).collect();
+    assert_eq!(times,[20,50,60,160,180,200]);
+    assert_eq!(passed,[20,30,20,100,20,20]);
+}
+
+#[test]
+fn lazy_loop(){
+    let clock=ManualClock::new();
+    let mut source=SimulatedEventSource::new(clock.clone());
+    source.push_event(150*MILLISECOND,WindowEvent::CharacterInput('a'));
+    source.push_event(150*MILLISECOND,WindowEvent::CharacterInput('b'));
+    source.push_quit(1000*MILLISECOND);
+
+    let event_loop=new_event_loop(EventInterval::EventsPerSecond(50),EventInterval::EventsPerSecond(10));
+
+    let mut log=Vec::new();
+    event_loop.run_with(&clock,&mut source,|event,control|{
+        match event{
+            Event::Process(ProcessEvent::EventLoopStart)=>*control=LoopControl::Lazy,
+            Event::Process(event)=>log.push(format!("{:?}",event)),
+            Event::Window(WindowEvent::CharacterInput(character))=>{
+                log.push(format!("{} at {}",character,clock.now()/MILLISECOND))
+            }
+            Event::Window(_)=>{}
+        }
+    });
+
+    assert_eq!(log,["a at 150","b at 150","Quit","EventLoopBreak"]);
+    assert_eq!(source.redraw_requests(),10);
+
+    // Nothing to wait for
+    let mut source=SimulatedEventSource::new(clock.clone());
+    let event_loop=new_event_loop(EventInterval::EventsPerSecond(50),EventInterval::EventsPerSecond(0));
+
+    let mut log=Vec::new();
+    event_loop.run_with(&clock,&mut source,|event,control|{
+        if let Event::Process(event)=event{
+            if let ProcessEvent::EventLoopStart=event{
+                *control=LoopControl::Lazy
+            }
+            log.push(format!("{:?}",event))
+        }
+    });
+
+    assert_eq!(log,["EventLoopStart","EventLoopBreak"]);
+}
+
+#[test]
+fn recording_replay(){
+    use super::{
+        EventPlayer,
+        EventRecorder,
+        MouseButton,
+        RecordingError,
+        VirtualKeyCode,
+    };
+
+    let clock=ManualClock::new();
+    let mut recorder=EventRecorder::with_clock(Vec::new(),clock.clone()).unwrap();
+
+    let events=[
+        WindowEvent::MouseMove([120,45]),
+        WindowEvent::MousePress{cursor_position:[120,45],button:MouseButton::Right},
+        WindowEvent::MouseScroll(-1),
+        WindowEvent::KeyPress(VirtualKeyCode::A),
+        WindowEvent::CharacterInput('ё'),
+        WindowEvent::KeyRelease(VirtualKeyCode::A),
+    ];
+    for event in events.iter(){
+        clock.advance(7*MILLISECOND);
+        assert!(recorder.record(&Event::Window(event.clone())).unwrap());
+    }
+    assert!(!recorder.record(&Event::Process(ProcessEvent::EventLoopStart)).unwrap());
+    clock.advance(7*MILLISECOND);
+    recorder.record(&Event::Process(ProcessEvent::Quit)).unwrap();
+
+    let recording=recorder.into_inner();
+    let player=EventPlayer::read(recording.as_slice()).unwrap();
+    assert_eq!(player.len(),7);
+    assert_eq!(player.next_ticks(),Some(7*MILLISECOND));
+
+    // The replay gets the same events at the same time
+    let clock=ManualClock::new();
+    let mut source=SimulatedEventSource::new(clock.clone());
+    source.push_recording(player);
+
+    let mut replayed=Vec::new();
+    new_event_loop(EventInterval::EventsPerSecond(50),EventInterval::EventsPerSecond(0))
+        .run_with(&clock,&mut source,|event,_|{
+            match event{
+                Event::Window(event)=>replayed.push(format!("{} {:?}",clock.now()/MILLISECOND,event)),
+                Event::Process(ProcessEvent::Quit)=>replayed.push(format!("{} Quit",clock.now()/MILLISECOND)),
+                Event::Process(_)=>{}
+            }
+        });
+
+    let mut expected:Vec<String>=events.iter()
+        .enumerate()
+        .map(|(n,event)|format!("{} {:?}",(n+1)*7,event))
+        .collect();
+    expected.push("49 Quit".to_string());
+    assert_eq!(replayed,expected);
+
+    match EventPlayer::read("# cat_engine events 1\n10 KeyPress 300\n".as_bytes()){
+        Err(RecordingError::Parse{line,..})=>assert_eq!(line,2),
+        _=>panic!("a parse error is expected"),
+    }
+}
@@ -1,5 +1,4 @@
-#[cfg(any(windows))]
-use crate::windows::OpenGraphicsLibrary;
+use crate::graphics::core::GLLoader;
 
 use core::mem::transmute;
 
@@ -305,8 +304,7 @@ impl Buffer{
         }
     }
 
-    #[cfg(any(windows))]
-    pub fn load(&mut self,library:&OpenGraphicsLibrary){
+    pub fn load<L:GLLoader+?Sized>(&mut self,library:&L){
         unsafe{
             self.glGenBuffers=transmute(library.get_proc_address("glGenBuffers\0"));
             self.glDeleteBuffers=transmute(library.get_proc_address("glDeleteBuffers\0"));
@@ -1,5 +1,4 @@
-#[cfg(any(windows))]
-use crate::windows::OpenGraphicsLibrary;
+use crate::graphics::core::GLLoader;
 
 use core::mem::transmute;
 
@@ -95,8 +94,7 @@ impl Drawing{
         }
     }
 
-    #[cfg(any(windows))]
-    pub fn load(&mut self,library:&OpenGraphicsLibrary){
+    pub fn load<L:GLLoader+?Sized>(&mut self,library:&L){
         unsafe{
             self.glDrawArrays=transmute(library.get_proc_address("glDrawArrays\0"));
             self.glDrawElements=transmute(library.get_proc_address("glDrawElements\0"));
@@ -1,5 +1,4 @@
-#[cfg(any(windows))]
-use crate::windows::OpenGraphicsLibrary;
+use crate::graphics::core::GLLoader;
 
 use core::mem::transmute;
 
@@ -131,8 +130,7 @@ impl Framebuffer{
         }
     }
 
-    #[cfg(any(windows))]
-    pub fn load(&mut self,library:&OpenGraphicsLibrary){
+    pub fn load<L:GLLoader+?Sized>(&mut self,library:&L){
         unsafe{
             self.glGenFramebuffers=transmute(library.get_proc_address("glGenFramebuffers\0"));
             self.glDeleteFramebuffers=transmute(library.get_proc_address("glDeleteFramebuffers\0"));
@@ -1,6 +1,3 @@
-#[cfg(any(windows))]
-use crate::windows::OpenGraphicsLibrary;
-
 pub mod parameters;
 use parameters::GraphicsParameters;
 
@@ -29,10 +26,43 @@ pub mod vertex_array;
 use vertex_array::VertexArray;
 
 use std::{
-    ffi::CStr,
+    ffi::{
+        CStr,
+        c_void,
+    },
     mem::transmute,
 };
 
+/// Loads OpenGL functions.
+///
+/// Implemented for `OpenGraphicsLibrary` on Windows
+/// and for closures, so `GLCore` may be loaded
+/// with any `get_proc_address` function (EGL, GLX, OSMesa, etc.).
+///
+/// ```
+/// use cat_engine_basement::graphics::GLCore;
+/// use std::ffi::c_void;
+///
+/// # fn egl_get_proc_address(name:*const u8)->*const c_void{std::ptr::null()}
+/// unsafe{
+///     GLCore.load_functions(&|name:&str|{
+///         egl_get_proc_address(name.as_ptr())
+///     });
+/// }
+/// ```
+pub trait GLLoader{
+    /// Returns the address of the function or null if there is no such function.
+    ///
+    /// The name has the terminating null.
+    fn get_proc_address(&self,name:&str)->*const c_void;
+}
+
+impl<F:Fn(&str)->*const c_void> GLLoader for F{
+    fn get_proc_address(&self,name:&str)->*const c_void{
+        self(name)
+    }
+}
+
 const MAJOR_VERSION:u32=0x821B;
 const MINOR_VERSION:u32=0x821C;
 const VERSION:u32=0x1F02;
@@ -169,8 +199,7 @@ impl GraphicsCore{
         }
     }
 
-    #[cfg(any(windows))]
-    pub fn load_functions(&mut self,library:&OpenGraphicsLibrary){
+    pub fn load_functions<L:GLLoader+?Sized>(&mut self,library:&L){
         self.parameters.load(library);
         self.buffer.load(library);
         self.drawing.load(library);
@@ -1,7 +1,6 @@
 use crate::graphics::Colour;
 
-#[cfg(any(windows))]
-use crate::windows::OpenGraphicsLibrary;
+use crate::graphics::core::GLLoader;
 
 use core::mem::transmute;
 
@@ -189,8 +188,7 @@ impl Blend{
         }
     }
 
-    #[cfg(any(windows))]
-    pub fn load(&mut self,library:&OpenGraphicsLibrary){
+    pub fn load<L:GLLoader+?Sized>(&mut self,library:&L){
         unsafe{
             self.glBlendColor=transmute(library.get_proc_address("glBlendColor\0"));
             self.glBlendFunc=transmute(library.get_proc_address("glBlendFunc\0"));
@@ -1,5 +1,4 @@
-#[cfg(any(windows))]
-use crate::windows::OpenGraphicsLibrary;
+use crate::graphics::core::GLLoader;
 
 pub mod blend;
 use blend::Blend;
@@ -249,8 +248,7 @@ impl GraphicsParameters{
         }
     }
 
-    #[cfg(any(windows))]
-    pub fn load(&mut self,library:&OpenGraphicsLibrary){
+    pub fn load<L:GLLoader+?Sized>(&mut self,library:&L){
         self.blend.load(library);
         self.viewport.load(library);
 
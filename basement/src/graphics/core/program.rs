@@ -1,5 +1,4 @@
-#[cfg(any(windows))]
-use crate::windows::OpenGraphicsLibrary;
+use crate::graphics::core::GLLoader;
 
 use core::mem::{
     MaybeUninit,
@@ -74,8 +73,7 @@ impl Program{
         }
     }
 
-    #[cfg(any(windows))]
-    pub fn load(&mut self,library:&OpenGraphicsLibrary){
+    pub fn load<L:GLLoader+?Sized>(&mut self,library:&L){
         unsafe{
             self.glCreateProgram=transmute(library.get_proc_address("glCreateProgram\0"));
             self.glDeleteProgram=transmute(library.get_proc_address("glDeleteProgram\0"));
@@ -1,5 +1,4 @@
-#[cfg(any(windows))]
-use crate::windows::OpenGraphicsLibrary;
+use crate::graphics::core::GLLoader;
 
 use core::mem::{
     MaybeUninit,
@@ -82,8 +81,7 @@ impl Shader{
         }
     }
 
-    #[cfg(any(windows))]
-    pub fn load(&mut self,library:&OpenGraphicsLibrary){
+    pub fn load<L:GLLoader+?Sized>(&mut self,library:&L){
         unsafe{
             self.glCreateShader=transmute(library.get_proc_address("glCreateShader\0"));
             self.glDeleteShader=transmute(library.get_proc_address("glDeleteShader\0"));
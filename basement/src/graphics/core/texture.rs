@@ -1,5 +1,4 @@
-#[cfg(any(windows))]
-use crate::windows::OpenGraphicsLibrary;
+use crate::graphics::core::GLLoader;
 
 use core::mem::transmute;
 
@@ -512,8 +511,7 @@ impl Texture{
         }
     }
 
-    #[cfg(any(windows))]
-    pub fn load(&mut self,library:&OpenGraphicsLibrary){
+    pub fn load<L:GLLoader+?Sized>(&mut self,library:&L){
         unsafe{
             self.glGenTextures=transmute(library.get_proc_address("glGenTextures\0"));
             self.glDeleteTextures=transmute(library.get_proc_address("glDeleteTextures\0"));
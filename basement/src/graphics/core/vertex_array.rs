@@ -1,5 +1,4 @@
-#[cfg(any(windows))]
-use crate::windows::OpenGraphicsLibrary;
+use crate::graphics::core::GLLoader;
 
 use core::mem::transmute;
 
@@ -97,8 +96,7 @@ impl VertexArray{
         }
     }
 
-    #[cfg(any(windows))]
-    pub fn load(&mut self,library:&OpenGraphicsLibrary){
+    pub fn load<L:GLLoader+?Sized>(&mut self,library:&L){
         unsafe{
             self.glGenVertexArrays=transmute(library.get_proc_address("glGenVertexArrays\0"));
             self.glDeleteVertexArrays=transmute(library.get_proc_address("glDeleteVertexArrays\0"));
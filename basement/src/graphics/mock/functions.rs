@@ -0,0 +1,973 @@
+use super::{
+    with_state,
+    Argument,
+    MockState,
+    MockBuffer,
+    MockImage,
+    MockTexture,
+    MockShader,
+    MockProgram,
+    MockAttribute,
+    MockVertexArray,
+};
+
+use std::{
+    ffi::{
+        CStr,
+        c_void,
+    },
+    ptr::copy_nonoverlapping,
+    slice::from_raw_parts,
+};
+
+// Errors
+const INVALID_ENUM:u32=0x0500;
+const INVALID_VALUE:u32=0x0501;
+const INVALID_OPERATION:u32=0x0502;
+
+// Shader and program parameters
+const SHADER_TYPE:u32=0x8B4F;
+const DELETE_STATUS:u32=0x8B80;
+const COMPILE_STATUS:u32=0x8B81;
+const LINK_STATUS:u32=0x8B82;
+const INFO_LOG_LENGTH:u32=0x8B84;
+const SHADER_SOURCE_LENGTH:u32=0x8B88;
+const ATTACHED_SHADERS:u32=0x8B85;
+
+// Buffer parameters
+const BUFFER_SIZE:u32=0x8764;
+const BUFFER_USAGE:u32=0x8765;
+const BUFFER_MAPPED:u32=0x88BC;
+
+// Framebuffer status
+const FRAMEBUFFER_COMPLETE:u32=0x8CD5;
+
+// Pixel storage parameters
+const UNPACK_ALIGNMENT:u32=0x0CF5;
+
+const INVALID_INDEX:u32=0xFFFFFFFF;
+
+macro_rules! mock_functions{
+    ($(fn $name:ident($($argument:ident:$type:ty),*)$(->$output:ty)? $body:block)*)=>{
+        $(
+            #[allow(clippy::too_many_arguments)]
+            fn $name($($argument:$type),*)$(->$output)?{
+                with_state(|state|state.record(stringify!($name),vec![$(Argument::from($argument)),*]));
+                $body
+            }
+        )*
+
+        pub (super) fn function_address(name:&str)->*const c_void{
+            match name{
+                $(stringify!($name)=>$name as *const () as *const c_void,)*
+                _=>unsupported as *const () as *const c_void,
+            }
+        }
+    };
+}
+
+fn unsupported(){
+    panic!("The function is not supported by the mock")
+}
+
+mock_functions!{
+    // Core
+
+    fn glGetIntegerv(parameter:u32,value:*mut i32){}
+
+    fn glGetFloatv(parameter:u32,value:*mut f32){}
+
+    fn glGetString(name:u32)->*const i8{
+        c"Mock".as_ptr()
+    }
+
+    fn glClear(mask:u32){}
+
+    fn glGetError()->u32{
+        with_state(|state|std::mem::take(&mut state.error))
+    }
+
+    fn glDrawBuffer(mode:u32){}
+
+    fn glFinish(){}
+
+    fn glFlush(){}
+
+    // Parameters
+
+    fn glEnable(capability:u32){
+        with_state(|state|state.capabilities.insert(capability,true));
+    }
+
+    fn glDisable(capability:u32){
+        with_state(|state|state.capabilities.insert(capability,false));
+    }
+
+    fn glIsEnabled(capability:u32)->bool{
+        with_state(|state|state.capabilities.get(&capability).copied().unwrap_or(false))
+    }
+
+    fn glPixelStoref(parameter:u32,value:f32){
+        with_state(|state|state.pixel_store.insert(parameter,value as i32));
+    }
+
+    fn glPixelStorei(parameter:u32,value:i32){
+        with_state(|state|state.pixel_store.insert(parameter,value));
+    }
+
+    fn glLineWidth(width:f32){
+        with_state(|state|state.line_width=width)
+    }
+
+    fn glClearColor(red:f32,green:f32,blue:f32,alpha:f32){
+        with_state(|state|state.clear_colour=[red,green,blue,alpha])
+    }
+
+    fn glViewport(x:i32,y:i32,width:i32,height:i32){
+        with_state(|state|state.viewport=[x,y,width,height])
+    }
+
+    fn glBlendColor(red:f32,green:f32,blue:f32,alpha:f32){}
+
+    fn glBlendEquation(mode:u32){
+        with_state(|state|state.blend_equation=[mode,mode])
+    }
+
+    fn glBlendEquationSeparate(rgb:u32,alpha:u32){
+        with_state(|state|state.blend_equation=[rgb,alpha])
+    }
+
+    fn glBlendFunc(source:u32,destination:u32){
+        with_state(|state|state.blend_function=[source,destination,source,destination])
+    }
+
+    fn glBlendFuncSeparate(source_rgb:u32,destination_rgb:u32,source_alpha:u32,destination_alpha:u32){
+        with_state(|state|state.blend_function=[source_rgb,destination_rgb,source_alpha,destination_alpha])
+    }
+
+    // Buffers
+
+    fn glGenBuffers(n:i32,buffers:*mut u32){
+        with_state(|state|generate(state,n,buffers,|state,id|{
+            state.buffers.insert(id,MockBuffer::default());
+        }))
+    }
+
+    fn glDeleteBuffers(n:i32,buffers:*const u32){
+        with_state(|state|for id in ids(n,buffers){
+            if state.buffers.remove(&id).is_some(){
+                state.buffer_bindings.retain(|_,bound|*bound!=id);
+            }
+        })
+    }
+
+    fn glIsBuffer(buffer:u32)->bool{
+        with_state(|state|state.buffers.contains_key(&buffer))
+    }
+
+    fn glBindBuffer(target:u32,buffer:u32){
+        with_state(|state|bind_buffer(state,target,buffer))
+    }
+
+    fn glBindBufferBase(target:u32,index:u32,buffer:u32){
+        with_state(|state|bind_buffer(state,target,buffer))
+    }
+
+    fn glBindBufferRange(target:u32,index:u32,buffer:u32,offset:isize,size:isize){
+        with_state(|state|bind_buffer(state,target,buffer))
+    }
+
+    fn glBufferData(target:u32,size:isize,data:*const u8,usage:u32){
+        with_state(|state|{
+            if size<0{
+                return state.set_error(INVALID_VALUE)
+            }
+
+            if let Some(buffer)=bound_buffer(state,target){
+                buffer.data=if data.is_null(){
+                    vec![0u8;size as usize]
+                }
+                else{
+                    unsafe{from_raw_parts(data,size as usize).to_vec()}
+                };
+                buffer.usage=usage;
+                buffer.mapped=false;
+            }
+        })
+    }
+
+    fn glBufferSubData(target:u32,offset:isize,size:isize,data:*const u8){
+        with_state(|state|{
+            if let Some(range)=buffer_range(state,target,offset,size){
+                let buffer=bound_buffer(state,target).unwrap();
+                unsafe{
+                    buffer.data[range].copy_from_slice(from_raw_parts(data,size as usize))
+                }
+            }
+        })
+    }
+
+    fn glCopyBufferSubData(read_target:u32,write_target:u32,read_offset:isize,write_offset:isize,size:isize){
+        with_state(|state|{
+            if let (Some(read),Some(write))=(
+                buffer_range(state,read_target,read_offset,size),
+                buffer_range(state,write_target,write_offset,size)
+            ){
+                let data=bound_buffer(state,read_target).unwrap().data[read].to_vec();
+                bound_buffer(state,write_target).unwrap().data[write].copy_from_slice(&data)
+            }
+        })
+    }
+
+    fn glGetBufferSubData(target:u32,offset:isize,size:isize,data:*mut u8){
+        with_state(|state|{
+            if let Some(range)=buffer_range(state,target,offset,size){
+                let buffer=bound_buffer(state,target).unwrap();
+                unsafe{
+                    copy_nonoverlapping(buffer.data[range].as_ptr(),data,size as usize)
+                }
+            }
+        })
+    }
+
+    fn glMapBuffer(target:u32,access:u32)->*mut u8{
+        with_state(|state|{
+            match bound_buffer(state,target){
+                Some(buffer) if !buffer.mapped=>{
+                    buffer.mapped=true;
+                    buffer.data.as_mut_ptr()
+                }
+                _=>{
+                    state.set_error(INVALID_OPERATION);
+                    std::ptr::null_mut()
+                }
+            }
+        })
+    }
+
+    fn glMapBufferRange(target:u32,offset:isize,length:isize,access:u32)->*mut u8{
+        with_state(|state|{
+            if let Some(range)=buffer_range(state,target,offset,length){
+                let buffer=bound_buffer(state,target).unwrap();
+                if !buffer.mapped{
+                    buffer.mapped=true;
+                    return buffer.data[range].as_mut_ptr()
+                }
+                state.set_error(INVALID_OPERATION);
+            }
+            std::ptr::null_mut()
+        })
+    }
+
+    fn glUnmapBuffer(target:u32)->bool{
+        with_state(|state|{
+            match bound_buffer(state,target){
+                Some(buffer) if buffer.mapped=>{
+                    buffer.mapped=false;
+                    true
+                }
+                _=>{
+                    state.set_error(INVALID_OPERATION);
+                    false
+                }
+            }
+        })
+    }
+
+    fn glGetBufferPointerv(target:u32,parameter:u32,pointer:*mut *mut u8){
+        with_state(|state|{
+            if let Some(buffer)=bound_buffer(state,target){
+                let data=if buffer.mapped{buffer.data.as_mut_ptr()}else{std::ptr::null_mut()};
+                unsafe{*pointer=data}
+            }
+        })
+    }
+
+    fn glFlushMappedBufferRange(target:u32,offset:isize,length:isize){}
+
+    fn glGetBufferParameteriv(target:u32,parameter:u32,value:*mut i32){
+        with_state(|state|{
+            if let Some(result)=buffer_parameter(state,target,parameter){
+                unsafe{*value=result as i32}
+            }
+        })
+    }
+
+    fn glGetBufferParameteri64v(target:u32,parameter:u32,value:*mut i64){
+        with_state(|state|{
+            if let Some(result)=buffer_parameter(state,target,parameter){
+                unsafe{*value=result}
+            }
+        })
+    }
+
+    // Drawing
+
+    fn glDrawArrays(mode:u32,first:i32,count:i32){
+        with_state(|state|state.draw_call("glDrawArrays",mode,vec![(first as isize,count)],None))
+    }
+
+    fn glDrawElements(mode:u32,count:i32,index_type:u32,offset:isize){
+        with_state(|state|state.draw_call("glDrawElements",mode,vec![(offset,count)],Some(index_type)))
+    }
+
+    fn glMultiDrawArrays(mode:u32,first:*const i32,count:*const i32,draw_count:i32){
+        with_state(|state|{
+            let ranges=unsafe{
+                let first=from_raw_parts(first,draw_count.max(0) as usize);
+                let count=from_raw_parts(count,draw_count.max(0) as usize);
+                first.iter().zip(count).map(|(&first,&count)|(first as isize,count)).collect()
+            };
+            state.draw_call("glMultiDrawArrays",mode,ranges,None)
+        })
+    }
+
+    fn glMultiDrawElements(mode:u32,count:*const i32,index_type:u32,offsets:*const isize,draw_count:i32){
+        with_state(|state|{
+            let ranges=unsafe{
+                let offsets=from_raw_parts(offsets,draw_count.max(0) as usize);
+                let count=from_raw_parts(count,draw_count.max(0) as usize);
+                offsets.iter().copied().zip(count.iter().copied()).collect()
+            };
+            state.draw_call("glMultiDrawElements",mode,ranges,Some(index_type))
+        })
+    }
+
+    // Framebuffers
+
+    fn glGenFramebuffers(n:i32,framebuffers:*mut u32){
+        with_state(|state|generate(state,n,framebuffers,|state,id|{
+            state.framebuffers.insert(id);
+        }))
+    }
+
+    fn glDeleteFramebuffers(n:i32,framebuffers:*const u32){
+        with_state(|state|for id in ids(n,framebuffers){
+            if state.framebuffers.remove(&id){
+                state.framebuffer_bindings.retain(|_,bound|*bound!=id);
+            }
+        })
+    }
+
+    fn glIsFramebuffer(framebuffer:u32)->bool{
+        with_state(|state|state.framebuffers.contains(&framebuffer))
+    }
+
+    fn glBindFramebuffer(target:u32,framebuffer:u32){
+        with_state(|state|{
+            if framebuffer!=0 && !state.framebuffers.contains(&framebuffer){
+                return state.set_error(INVALID_OPERATION)
+            }
+            state.framebuffer_bindings.insert(target,framebuffer);
+        })
+    }
+
+    fn glCheckFramebufferStatus(target:u32)->u32{
+        FRAMEBUFFER_COMPLETE
+    }
+
+    fn glFramebufferTexture(target:u32,attachment:u32,texture:u32,level:i32){}
+
+    fn glFramebufferTexture1D(target:u32,attachment:u32,texture_target:u32,texture:u32,level:i32){}
+
+    fn glFramebufferTexture2D(target:u32,attachment:u32,texture_target:u32,texture:u32,level:i32){}
+
+    fn glFramebufferTexture3D(target:u32,attachment:u32,texture_target:u32,texture:u32,level:i32,layer:i32){}
+
+    fn glFramebufferTextureLayer(target:u32,attachment:u32,texture:u32,level:i32,layer:i32){}
+
+    fn glFramebufferRenderbuffer(target:u32,attachment:u32,renderbuffer_target:u32,renderbuffer:u32){}
+
+    fn glBlitFramebuffer(
+        source_x0:i32,
+        source_y0:i32,
+        source_x1:i32,
+        source_y1:i32,
+        destination_x0:i32,
+        destination_y0:i32,
+        destination_x1:i32,
+        destination_y1:i32,
+        mask:u32,
+        filter:u32
+    ){}
+
+    fn glGetFramebufferAttachmentParameteriv(target:u32,attachment:u32,parameter:u32,value:*mut i32){}
+
+    // Shaders
+
+    fn glCreateShader(shader_type:u32)->u32{
+        with_state(|state|{
+            let id=state.generate_id();
+            state.shaders.insert(id,MockShader{shader_type,..Default::default()});
+            id
+        })
+    }
+
+    fn glDeleteShader(shader:u32){
+        with_state(|state|state.shaders.remove(&shader));
+    }
+
+    fn glShaderSource(shader:u32,count:i32,strings:*const *const u8,lengths:*const i32){
+        with_state(|state|{
+            let mut source=String::new();
+            for c in 0..count.max(0) as usize{
+                unsafe{
+                    let string=*strings.add(c);
+                    let bytes=if lengths.is_null() || *lengths.add(c)<0{
+                        CStr::from_ptr(string as *const _).to_bytes()
+                    }
+                    else{
+                        from_raw_parts(string,*lengths.add(c) as usize)
+                    };
+                    source.push_str(&String::from_utf8_lossy(bytes))
+                }
+            }
+
+            match state.shaders.get_mut(&shader){
+                Some(mock)=>mock.source=source,
+                None=>state.set_error(INVALID_VALUE),
+            }
+        })
+    }
+
+    fn glCompileShader(shader:u32){
+        with_state(|state|{
+            match state.shaders.get_mut(&shader){
+                Some(mock)=>mock.compiled=true,
+                None=>state.set_error(INVALID_VALUE),
+            }
+        })
+    }
+
+    fn glGetShaderiv(shader:u32,parameter:u32,value:*mut i32){
+        with_state(|state|{
+            let result=match state.shaders.get(&shader){
+                Some(mock)=>match parameter{
+                    SHADER_TYPE=>mock.shader_type as i32,
+                    DELETE_STATUS=>0,
+                    COMPILE_STATUS=>mock.compiled as i32,
+                    INFO_LOG_LENGTH=>0,
+                    SHADER_SOURCE_LENGTH=>mock.source.len() as i32+1,
+                    _=>return state.set_error(INVALID_ENUM),
+                },
+                None=>return state.set_error(INVALID_VALUE),
+            };
+            unsafe{*value=result}
+        })
+    }
+
+    fn glGetShaderInfoLog(shader:u32,max_length:i32,length:*mut i32,log:*mut u8){
+        write_empty_log(length,log,max_length)
+    }
+
+    // Programs
+
+    fn glCreateProgram()->u32{
+        with_state(|state|{
+            let id=state.generate_id();
+            state.programs.insert(id,MockProgram::default());
+            id
+        })
+    }
+
+    fn glDeleteProgram(program:u32){
+        with_state(|state|state.programs.remove(&program));
+    }
+
+    fn glAttachShader(program:u32,shader:u32){
+        with_state(|state|{
+            if !state.shaders.contains_key(&shader){
+                return state.set_error(INVALID_VALUE)
+            }
+            match state.programs.get_mut(&program){
+                Some(mock)=>mock.shaders.push(shader),
+                None=>state.set_error(INVALID_VALUE),
+            }
+        })
+    }
+
+    fn glLinkProgram(program:u32){
+        with_state(|state|{
+            let MockState{programs,shaders,..}=state;
+            if let Some(mock)=programs.get_mut(&program){
+                let attached:Vec<&MockShader>=mock.shaders.iter().filter_map(|shader|shaders.get(shader)).collect();
+                mock.linked=attached.len()==mock.shaders.len() && attached.iter().all(|shader|shader.compiled);
+                mock.source=attached.iter().map(|shader|shader.source.as_str()).collect::<Vec<&str>>().join("\n");
+            }
+        })
+    }
+
+    fn glUseProgram(program:u32){
+        with_state(|state|{
+            if program!=0 && !state.programs.contains_key(&program){
+                return state.set_error(INVALID_VALUE)
+            }
+            state.program=program
+        })
+    }
+
+    fn glGetProgramiv(program:u32,parameter:u32,value:*mut i32){
+        with_state(|state|{
+            let result=match state.programs.get(&program){
+                Some(mock)=>match parameter{
+                    DELETE_STATUS=>0,
+                    LINK_STATUS=>mock.linked as i32,
+                    INFO_LOG_LENGTH=>0,
+                    ATTACHED_SHADERS=>mock.shaders.len() as i32,
+                    _=>return state.set_error(INVALID_ENUM),
+                },
+                None=>return state.set_error(INVALID_VALUE),
+            };
+            unsafe{*value=result}
+        })
+    }
+
+    fn glGetProgramInfoLog(program:u32,max_length:i32,length:*mut i32,log:*mut u8){
+        write_empty_log(length,log,max_length)
+    }
+
+    fn glGetUniformLocation(program:u32,name:*const u8)->i32{
+        let name=unsafe{CStr::from_ptr(name as *const _).to_string_lossy().into_owned()};
+        with_state(|state|{
+            let mock=match state.programs.get_mut(&program){
+                Some(mock)=>mock,
+                None=>return -1,
+            };
+
+            let next=mock.uniforms.len() as i32;
+            if let Some(&location)=mock.uniforms.get(&name){
+                location
+            }
+            else if declared(&mock.source,&name){
+                mock.uniforms.insert(name,next);
+                next
+            }
+            else{
+                -1
+            }
+        })
+    }
+
+    fn glGetUniformBlockIndex(program:u32,name:*const u8)->u32{
+        let name=unsafe{CStr::from_ptr(name as *const _).to_string_lossy().into_owned()};
+        with_state(|state|{
+            let mock=match state.programs.get_mut(&program){
+                Some(mock)=>mock,
+                None=>return INVALID_INDEX,
+            };
+
+            let next=mock.uniform_blocks.len() as u32;
+            if let Some(&index)=mock.uniform_blocks.get(&name){
+                index
+            }
+            else if declared(&mock.source,&name){
+                mock.uniform_blocks.insert(name,next);
+                next
+            }
+            else{
+                INVALID_INDEX
+            }
+        })
+    }
+
+    fn glUniformBlockBinding(program:u32,index:u32,binding:u32){
+        with_state(|state|{
+            if let Some(mock)=state.programs.get_mut(&program){
+                mock.uniform_block_bindings.insert(index,binding);
+            }
+        })
+    }
+
+    // Uniforms
+
+    fn glUniform1f(location:i32,v0:f32){
+        with_state(|state|set_uniform(state,location,vec![v0.into()]))
+    }
+
+    fn glUniform2f(location:i32,v0:f32,v1:f32){
+        with_state(|state|set_uniform(state,location,vec![v0.into(),v1.into()]))
+    }
+
+    fn glUniform3f(location:i32,v0:f32,v1:f32,v2:f32){
+        with_state(|state|set_uniform(state,location,vec![v0.into(),v1.into(),v2.into()]))
+    }
+
+    fn glUniform4f(location:i32,v0:f32,v1:f32,v2:f32,v3:f32){
+        with_state(|state|set_uniform(state,location,vec![v0.into(),v1.into(),v2.into(),v3.into()]))
+    }
+
+    fn glUniform1i(location:i32,v0:i32){
+        with_state(|state|set_uniform(state,location,vec![v0.into()]))
+    }
+
+    fn glUniform2i(location:i32,v0:i32,v1:i32){
+        with_state(|state|set_uniform(state,location,vec![v0.into(),v1.into()]))
+    }
+
+    fn glUniform3i(location:i32,v0:i32,v1:i32,v2:i32){
+        with_state(|state|set_uniform(state,location,vec![v0.into(),v1.into(),v2.into()]))
+    }
+
+    fn glUniform4i(location:i32,v0:i32,v1:i32,v2:i32,v3:i32){
+        with_state(|state|set_uniform(state,location,vec![v0.into(),v1.into(),v2.into(),v3.into()]))
+    }
+
+    fn glUniform1ui(location:i32,v0:u32){
+        with_state(|state|set_uniform(state,location,vec![v0.into()]))
+    }
+
+    fn glUniform2ui(location:i32,v0:u32,v1:u32){
+        with_state(|state|set_uniform(state,location,vec![v0.into(),v1.into()]))
+    }
+
+    fn glUniform3ui(location:i32,v0:u32,v1:u32,v2:u32){
+        with_state(|state|set_uniform(state,location,vec![v0.into(),v1.into(),v2.into()]))
+    }
+
+    fn glUniform4ui(location:i32,v0:u32,v1:u32,v2:u32,v3:u32){
+        with_state(|state|set_uniform(state,location,vec![v0.into(),v1.into(),v2.into(),v3.into()]))
+    }
+
+    // Textures
+
+    fn glGenTextures(n:i32,textures:*mut u32){
+        with_state(|state|generate(state,n,textures,|state,id|{
+            state.textures.insert(id,MockTexture::default());
+        }))
+    }
+
+    fn glDeleteTextures(n:i32,textures:*const u32){
+        with_state(|state|for id in ids(n,textures){
+            if state.textures.remove(&id).is_some(){
+                state.texture_bindings.retain(|_,bound|*bound!=id);
+            }
+        })
+    }
+
+    fn glBindTexture(target:u32,texture:u32){
+        with_state(|state|{
+            if texture!=0 && !state.textures.contains_key(&texture){
+                return state.set_error(INVALID_VALUE)
+            }
+            state.texture_bindings.insert(target,texture);
+        })
+    }
+
+    fn glTexParameteri(target:u32,parameter:u32,value:i32){
+        with_state(|state|{
+            if let Some(texture)=bound_texture(state,target){
+                texture.parameters.insert(parameter,value);
+            }
+        })
+    }
+
+    fn glTexImage2D(
+        target:u32,
+        level:i32,
+        internal_format:u32,
+        width:i32,
+        height:i32,
+        border:i32,
+        format:u32,
+        data_type:u32,
+        data:*const u8
+    ){
+        with_state(|state|{
+            if width<0 || height<0 || border!=0{
+                return state.set_error(INVALID_VALUE)
+            }
+
+            let alignment=unpack_alignment(state);
+
+            let pixel_size=pixel_size(format,data_type);
+
+            let texture=match bound_texture(state,target){
+                Some(texture)=>texture,
+                None=>return state.set_error(INVALID_OPERATION),
+            };
+
+            let image_data=pixel_size.map(|pixel_size|{
+                let row=width as usize*pixel_size;
+                let mut image=vec![0u8;row*height as usize];
+                if !data.is_null(){
+                    let stride=align(row,alignment);
+                    for (y,image_row) in image.chunks_exact_mut(row.max(1)).enumerate(){
+                        unsafe{
+                            copy_nonoverlapping(data.add(y*stride),image_row.as_mut_ptr(),row)
+                        }
+                    }
+                }
+                image
+            });
+
+            texture.images.insert(level,MockImage{
+                size:[width,height],
+                internal_format,
+                data:image_data,
+                pixel_size:pixel_size.unwrap_or(0),
+            });
+        })
+    }
+
+    fn glTexSubImage2D(
+        target:u32,
+        level:i32,
+        x:i32,
+        y:i32,
+        width:i32,
+        height:i32,
+        format:u32,
+        data_type:u32,
+        data:*const u8
+    ){
+        with_state(|state|{
+            let alignment=unpack_alignment(state);
+
+            let texture=match bound_texture(state,target){
+                Some(texture)=>texture,
+                None=>return state.set_error(INVALID_OPERATION),
+            };
+
+            let image=match texture.images.get_mut(&level){
+                Some(image)=>image,
+                None=>return state.set_error(INVALID_OPERATION),
+            };
+
+            if x<0 || y<0 || width<0 || height<0 || x+width>image.size[0] || y+height>image.size[1]{
+                return state.set_error(INVALID_VALUE)
+            }
+
+            if let (Some(image_data),Some(pixel_size))=(&mut image.data,pixel_size(format,data_type)){
+                if pixel_size!=image.pixel_size || data.is_null(){
+                    return
+                }
+
+                let row=width as usize*pixel_size;
+                let stride=align(row,alignment);
+                let image_row=image.size[0] as usize*pixel_size;
+
+                for c in 0..height as usize{
+                    let start=(y as usize+c)*image_row+x as usize*pixel_size;
+                    unsafe{
+                        copy_nonoverlapping(data.add(c*stride),image_data[start..start+row].as_mut_ptr(),row)
+                    }
+                }
+            }
+        })
+    }
+
+    fn glCopyTexSubImage2D(target:u32,level:i32,x_offset:i32,y_offset:i32,x:i32,y:i32,width:i32,height:i32){}
+
+    // Vertex arrays
+
+    fn glGenVertexArrays(n:i32,arrays:*mut u32){
+        with_state(|state|generate(state,n,arrays,|state,id|{
+            state.vertex_arrays.insert(id,MockVertexArray::default());
+        }))
+    }
+
+    fn glDeleteVertexArrays(n:i32,arrays:*const u32){
+        with_state(|state|for id in ids(n,arrays){
+            if state.vertex_arrays.remove(&id).is_some() && state.vertex_array==id{
+                state.vertex_array=0
+            }
+        })
+    }
+
+    fn glBindVertexArray(array:u32){
+        with_state(|state|{
+            if array!=0 && !state.vertex_arrays.contains_key(&array){
+                return state.set_error(INVALID_OPERATION)
+            }
+            state.vertex_array=array
+        })
+    }
+
+    fn glEnableVertexAttribArray(index:u32){
+        with_state(|state|{
+            if let Some(vertex_array)=state.vertex_arrays.get_mut(&state.vertex_array){
+                vertex_array.attributes.entry(index).or_default().enabled=true
+            }
+        })
+    }
+
+    fn glDisableVertexAttribArray(index:u32){
+        with_state(|state|{
+            if let Some(vertex_array)=state.vertex_arrays.get_mut(&state.vertex_array){
+                vertex_array.attributes.entry(index).or_default().enabled=false
+            }
+        })
+    }
+
+    fn glVertexAttribPointer(index:u32,size:i32,data_type:u32,normalized:bool,stride:i32,offset:isize){
+        with_state(|state|set_attribute(state,index,MockAttribute{
+            size,
+            data_type,
+            normalized,
+            integer:false,
+            stride,
+            offset,
+            ..Default::default()
+        }))
+    }
+
+    fn glVertexAttribIPointer(index:u32,size:i32,data_type:u32,stride:i32,offset:isize){
+        with_state(|state|set_attribute(state,index,MockAttribute{
+            size,
+            data_type,
+            normalized:false,
+            integer:true,
+            stride,
+            offset,
+            ..Default::default()
+        }))
+    }
+}
+
+fn generate<F:Fn(&mut MockState,u32)>(state:&mut MockState,n:i32,ids:*mut u32,insert:F){
+    if n<0{
+        return state.set_error(INVALID_VALUE)
+    }
+
+    for c in 0..n as usize{
+        let id=state.generate_id();
+        insert(state,id);
+        unsafe{*ids.add(c)=id}
+    }
+}
+
+fn ids(n:i32,ids:*const u32)->Vec<u32>{
+    if n<=0{
+        return Vec::new()
+    }
+    unsafe{from_raw_parts(ids,n as usize).to_vec()}
+}
+
+fn bind_buffer(state:&mut MockState,target:u32,buffer:u32){
+    if buffer!=0 && !state.buffers.contains_key(&buffer){
+        return state.set_error(INVALID_VALUE)
+    }
+    state.buffer_bindings.insert(target,buffer);
+}
+
+fn bound_buffer(state:&mut MockState,target:u32)->Option<&mut MockBuffer>{
+    let id=state.bound_buffer(target);
+    if id==0{
+        state.set_error(INVALID_OPERATION);
+        return None
+    }
+    state.buffers.get_mut(&id)
+}
+
+/// Checks the range of the buffer bound to `target`.
+fn buffer_range(state:&mut MockState,target:u32,offset:isize,size:isize)->Option<std::ops::Range<usize>>{
+    let length=bound_buffer(state,target)?.data.len();
+
+    if offset<0 || size<0 || (offset+size) as usize>length{
+        state.set_error(INVALID_VALUE);
+        return None
+    }
+
+    Some(offset as usize..(offset+size) as usize)
+}
+
+fn buffer_parameter(state:&mut MockState,target:u32,parameter:u32)->Option<i64>{
+    let buffer=bound_buffer(state,target)?;
+    let value=match parameter{
+        BUFFER_SIZE=>buffer.data.len() as i64,
+        BUFFER_USAGE=>buffer.usage as i64,
+        BUFFER_MAPPED=>buffer.mapped as i64,
+        _=>0,
+    };
+    Some(value)
+}
+
+fn bound_texture(state:&mut MockState,target:u32)->Option<&mut MockTexture>{
+    // Cube map faces are bound as a cube map
+    let binding=if (0x8515..=0x851A).contains(&target){0x8513}else{target};
+    let id=state.bound_texture(binding);
+    state.textures.get_mut(&id)
+}
+
+fn unpack_alignment(state:&MockState)->usize{
+    state.pixel_store.get(&UNPACK_ALIGNMENT).copied().unwrap_or(4).max(1) as usize
+}
+
+fn align(row:usize,alignment:usize)->usize{
+    row.div_ceil(alignment)*alignment
+}
+
+/// Returns the size of a pixel in bytes for the given format and type.
+fn pixel_size(format:u32,data_type:u32)->Option<usize>{
+    let components=match format{
+        // RED, RED_INTEGER
+        0x1903|0x8D94=>1,
+        // RG, RG_INTEGER
+        0x8227|0x8228=>2,
+        // RGB, BGR, RGB_INTEGER, BGR_INTEGER
+        0x1907|0x80E0|0x8D98|0x8D9A=>3,
+        // RGBA, BGRA, RGBA_INTEGER, BGRA_INTEGER
+        0x1908|0x80E1|0x8D99|0x8D9B=>4,
+        _=>return None,
+    };
+
+    let size=match data_type{
+        // BYTE, UNSIGNED_BYTE
+        0x1400|0x1401=>1,
+        // SHORT, UNSIGNED_SHORT, HALF_FLOAT
+        0x1402|0x1403|0x140B=>2,
+        // INT, UNSIGNED_INT, FLOAT
+        0x1404..=0x1406=>4,
+        _=>return None,
+    };
+
+    Some(components*size)
+}
+
+fn write_empty_log(length:*mut i32,log:*mut u8,max_length:i32){
+    unsafe{
+        if !length.is_null(){
+            *length=0
+        }
+        if !log.is_null() && max_length>0{
+            *log=0
+        }
+    }
+}
+
+/// Checks whether the name appears in the source as an identifier.
+fn declared(source:&str,name:&str)->bool{
+    let is_identifier=|c:char|c.is_alphanumeric() || c=='_';
+
+    source.match_indices(name).any(|(start,_)|{
+        let before=source[..start].chars().next_back();
+        let after=source[start+name.len()..].chars().next();
+        !before.is_some_and(is_identifier) && !after.is_some_and(is_identifier)
+    })
+}
+
+fn set_uniform(state:&mut MockState,location:i32,value:Vec<Argument>){
+    if location==-1{
+        return
+    }
+
+    match state.programs.get_mut(&state.program){
+        Some(program) if location>=0 && location<program.uniforms.len() as i32=>{
+            program.uniform_values.insert(location,value);
+        }
+        _=>state.set_error(INVALID_OPERATION),
+    }
+}
+
+fn set_attribute(state:&mut MockState,index:u32,mut attribute:MockAttribute){
+    attribute.buffer=state.bound_buffer(super::ARRAY_BUFFER);
+
+    match state.vertex_arrays.get_mut(&state.vertex_array){
+        Some(vertex_array)=>{
+            let entry=vertex_array.attributes.entry(index).or_default();
+            attribute.enabled=entry.enabled;
+            *entry=attribute;
+        }
+        None=>state.set_error(INVALID_OPERATION),
+    }
+}
@@ -0,0 +1,384 @@
+//! A recording OpenGL backend for testing.
+//!
+//! `load` fills `GLCore` with fake entry points
+//! that emulate the objects and the bound state of a context
+//! and record every issued command.
+//! The state is thread local,
+//! so tests running in parallel don't affect each other.
+//!
+//! ```
+//! use cat_engine_basement::graphics::{
+//!     mock,
+//!     level1::VertexBuffer,
+//!     core::buffer::BufferUsage,
+//! };
+//!
+//! mock::load();
+//!
+//! let buffer=VertexBuffer::<[f32;2]>::new(&[[1f32,2f32]],BufferUsage::StaticDraw).unwrap();
+//!
+//! mock::with_state(|state|{
+//!     let data=&state.buffers[&buffer.as_raw().id()].data;
+//!     assert_eq!(data.len(),8);
+//!     assert!(state.commands.iter().any(|command|command.name=="glBufferData"));
+//! });
+//! ```
+//!
+//! Functions are called through the Rust ABI (as `GraphicsCore` does),
+//! slices are received as pointers to their first elements.
+
+mod functions;
+
+use super::{
+    GLCore,
+    core::GLLoader,
+    level0::Vertex,
+};
+
+use std::{
+    cell::RefCell,
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    ffi::c_void,
+    mem::size_of,
+    sync::Once,
+};
+
+// Buffer targets
+const ARRAY_BUFFER:u32=0x8892;
+const ELEMENT_ARRAY_BUFFER:u32=0x8893;
+
+// Texture targets
+const TEXTURE_2D:u32=0x0DE1;
+
+thread_local!{
+    static STATE:RefCell<MockState>=RefCell::new(MockState::new());
+}
+
+static LOAD:Once=Once::new();
+
+/// Loads the mock functions to `GLCore` and resets the state of the current thread.
+///
+/// The functions are loaded once per process,
+/// so threads already calling them aren't raced with.
+pub fn load(){
+    reset();
+    LOAD.call_once(||unsafe{
+        GLCore.load_functions(&MockLoader);
+    });
+}
+
+/// Resets the state of the current thread.
+pub fn reset(){
+    STATE.with(|state|*state.borrow_mut()=MockState::new())
+}
+
+/// Gives access to the state of the current thread.
+///
+/// Don't call GL functions inside `f`.
+pub fn with_state<R,F:FnOnce(&mut MockState)->R>(f:F)->R{
+    STATE.with(|state|f(&mut state.borrow_mut()))
+}
+
+/// Returns and clears the recorded commands.
+pub fn take_commands()->Vec<Command>{
+    with_state(|state|std::mem::take(&mut state.commands))
+}
+
+/// Returns and clears the recorded draw calls.
+pub fn take_draw_calls()->Vec<DrawCall>{
+    with_state(|state|std::mem::take(&mut state.draw_calls))
+}
+
+/// Returns mock functions by their names.
+///
+/// Unknown functions panic when called.
+pub struct MockLoader;
+
+impl GLLoader for MockLoader{
+    fn get_proc_address(&self,name:&str)->*const c_void{
+        functions::function_address(name.trim_end_matches('\0'))
+    }
+}
+
+/// An argument of a recorded command.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum Argument{
+    Integer(i64),
+    Float(f32),
+    Pointer(usize),
+}
+
+macro_rules! argument_from{
+    (Integer:$($t:ty),*)=>{
+        $(
+            impl From<$t> for Argument{
+                fn from(value:$t)->Argument{
+                    Argument::Integer(value as i64)
+                }
+            }
+        )*
+    };
+}
+
+argument_from!(Integer:i32,u32,isize,i64,bool);
+
+impl From<f32> for Argument{
+    fn from(value:f32)->Argument{
+        Argument::Float(value)
+    }
+}
+
+impl<T> From<*const T> for Argument{
+    fn from(value:*const T)->Argument{
+        Argument::Pointer(value as usize)
+    }
+}
+
+impl<T> From<*mut T> for Argument{
+    fn from(value:*mut T)->Argument{
+        Argument::Pointer(value as usize)
+    }
+}
+
+/// A recorded command.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Command{
+    /// The name of the function, e.g. `glBindBuffer`.
+    pub name:&'static str,
+    pub arguments:Vec<Argument>,
+}
+
+/// A recorded draw call.
+#[derive(Clone,Debug,PartialEq)]
+pub struct DrawCall{
+    /// The name of the function, e.g. `glMultiDrawElements`.
+    pub name:&'static str,
+    pub primitive_type:u32,
+    /// `(first, count)` of every drawn range.
+    ///
+    /// `first` is the index of the first vertex for array draw calls
+    /// and the offset in bytes of the first index for element draw calls.
+    pub ranges:Vec<(isize,i32)>,
+    /// The type of indices for element draw calls.
+    pub index_type:Option<u32>,
+    pub program:u32,
+    pub vertex_array:u32,
+    /// The buffer of the vertex attribute 0 or the bound array buffer.
+    pub vertex_buffer:u32,
+    pub index_buffer:u32,
+    pub texture_2d:u32,
+}
+
+#[derive(Clone,Debug,Default)]
+pub struct MockBuffer{
+    pub data:Vec<u8>,
+    pub usage:u32,
+    pub mapped:bool,
+}
+
+#[derive(Clone,Debug,Default)]
+pub struct MockImage{
+    pub size:[i32;2],
+    pub internal_format:u32,
+    /// Tightly packed pixels, rows go from the bottom (`v = 0`) to the top.
+    ///
+    /// `None` if the format isn't supported by the mock.
+    pub data:Option<Vec<u8>>,
+    pub pixel_size:usize,
+}
+
+#[derive(Clone,Debug,Default)]
+pub struct MockTexture{
+    /// Images by mipmap levels.
+    pub images:HashMap<i32,MockImage>,
+    pub parameters:HashMap<u32,i32>,
+}
+
+#[derive(Clone,Debug,Default)]
+pub struct MockShader{
+    pub shader_type:u32,
+    pub source:String,
+    pub compiled:bool,
+}
+
+#[derive(Clone,Debug,Default)]
+pub struct MockProgram{
+    pub shaders:Vec<u32>,
+    pub linked:bool,
+    /// The sources of the shaders attached at the time of linking.
+    pub source:String,
+    /// Uniform locations by names.
+    ///
+    /// A location is given to any name found in the linked sources.
+    pub uniforms:HashMap<String,i32>,
+    /// The last set values by uniform locations.
+    pub uniform_values:HashMap<i32,Vec<Argument>>,
+    pub uniform_blocks:HashMap<String,u32>,
+    /// Binding points by uniform block indices.
+    pub uniform_block_bindings:HashMap<u32,u32>,
+}
+
+#[derive(Clone,Copy,Debug,Default)]
+pub struct MockAttribute{
+    pub size:i32,
+    pub data_type:u32,
+    pub normalized:bool,
+    pub integer:bool,
+    pub stride:i32,
+    pub offset:isize,
+    pub buffer:u32,
+    pub enabled:bool,
+}
+
+#[derive(Clone,Debug,Default)]
+pub struct MockVertexArray{
+    pub attributes:HashMap<u32,MockAttribute>,
+}
+
+/// The emulated context state.
+#[derive(Debug,Default)]
+pub struct MockState{
+    pub commands:Vec<Command>,
+    pub draw_calls:Vec<DrawCall>,
+
+    pub buffers:HashMap<u32,MockBuffer>,
+    pub textures:HashMap<u32,MockTexture>,
+    pub shaders:HashMap<u32,MockShader>,
+    pub programs:HashMap<u32,MockProgram>,
+    pub vertex_arrays:HashMap<u32,MockVertexArray>,
+    pub framebuffers:HashSet<u32>,
+
+    /// Bound buffers by targets.
+    pub buffer_bindings:HashMap<u32,u32>,
+    /// Bound textures by targets.
+    pub texture_bindings:HashMap<u32,u32>,
+    /// Bound framebuffers by targets.
+    pub framebuffer_bindings:HashMap<u32,u32>,
+    pub vertex_array:u32,
+    pub program:u32,
+
+    pub capabilities:HashMap<u32,bool>,
+    pub pixel_store:HashMap<u32,i32>,
+    pub viewport:[i32;4],
+    pub clear_colour:[f32;4],
+    pub blend_function:[u32;4],
+    pub blend_equation:[u32;2],
+    pub line_width:f32,
+
+    /// The error returned by the next `glGetError` call.
+    pub error:u32,
+
+    next_id:u32,
+}
+
+impl MockState{
+    pub fn new()->MockState{
+        Self{
+            blend_function:[1,0,1,0],
+            blend_equation:[0x8006,0x8006],
+            line_width:1f32,
+            next_id:1,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the error flag if it isn't set yet.
+    pub fn set_error(&mut self,error:u32){
+        if self.error==0{
+            self.error=error
+        }
+    }
+
+    pub fn bound_buffer(&self,target:u32)->u32{
+        self.buffer_bindings.get(&target).copied().unwrap_or(0)
+    }
+
+    pub fn bound_texture(&self,target:u32)->u32{
+        self.texture_bindings.get(&target).copied().unwrap_or(0)
+    }
+
+    /// Returns the indices of the vertices drawn by a draw call.
+    pub fn drawn_elements(&self,draw_call:&DrawCall)->Vec<u32>{
+        let mut elements=Vec::new();
+
+        for &(first,count) in &draw_call.ranges{
+            match draw_call.index_type{
+                None=>elements.extend((0..count.max(0) as u32).map(|i|first as u32+i)),
+
+                Some(index_type)=>{
+                    let data=match self.buffers.get(&draw_call.index_buffer){
+                        Some(buffer)=>&buffer.data,
+                        None=>continue,
+                    };
+
+                    let index_size=match index_type{
+                        0x1401=>1,
+                        0x1403=>2,
+                        _=>4,
+                    };
+
+                    for c in 0..count.max(0) as usize{
+                        let start=first as usize+c*index_size;
+                        if let Some(bytes)=data.get(start..start+index_size){
+                            let mut index=[0u8;4];
+                            index[..index_size].copy_from_slice(bytes);
+                            elements.push(u32::from_ne_bytes(index))
+                        }
+                    }
+                }
+            }
+        }
+
+        elements
+    }
+
+    /// Returns the vertices drawn by a draw call
+    /// read from the vertex buffer as values of type `V`.
+    pub fn drawn_vertices<V:Vertex>(&self,draw_call:&DrawCall)->Vec<V>{
+        let data=match self.buffers.get(&draw_call.vertex_buffer){
+            Some(buffer)=>&buffer.data,
+            None=>return Vec::new(),
+        };
+
+        self.drawn_elements(draw_call).into_iter().filter_map(|element|{
+            let start=element as usize*size_of::<V>();
+            data.get(start..start+size_of::<V>()).map(|bytes|unsafe{
+                (bytes.as_ptr() as *const V).read_unaligned()
+            })
+        }).collect()
+    }
+
+    fn generate_id(&mut self)->u32{
+        let id=self.next_id;
+        self.next_id+=1;
+        id
+    }
+
+    fn record(&mut self,name:&'static str,arguments:Vec<Argument>){
+        self.commands.push(Command{name,arguments})
+    }
+
+    fn draw_call(&mut self,name:&'static str,primitive_type:u32,ranges:Vec<(isize,i32)>,index_type:Option<u32>){
+        let vertex_buffer=self.vertex_arrays.get(&self.vertex_array)
+            .and_then(|vertex_array|vertex_array.attributes.get(&0))
+            .map(|attribute|attribute.buffer)
+            .unwrap_or_else(||self.bound_buffer(ARRAY_BUFFER));
+
+        let draw_call=DrawCall{
+            name,
+            primitive_type,
+            ranges,
+            index_type,
+            program:self.program,
+            vertex_array:self.vertex_array,
+            vertex_buffer,
+            index_buffer:self.bound_buffer(ELEMENT_ARRAY_BUFFER),
+            texture_2d:self.bound_texture(TEXTURE_2D),
+        };
+
+        self.draw_calls.push(draw_call)
+    }
+}
@@ -7,6 +7,9 @@ pub mod level1;
 
 pub mod level2;
 
+#[cfg(feature="mock")]
+pub mod mock;
+
 pub type ColourComponent=f32;
 pub type Colour=[ColourComponent;4];
 
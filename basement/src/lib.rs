@@ -18,8 +18,16 @@ pub mod support;
 #[cfg(any(feature="opengl"))]
 pub mod graphics;
 
+/// A platform-independent event loop with a pluggable clock and event source.
+pub mod event_loop;
+
 #[cfg(any(target_os="windows"))]
 #[cfg_attr(feature="nightly-docs",doc(cfg(target_os="windows")))]
 pub mod windows;
 
+#[cfg(target_os="linux")]
+#[cfg_attr(feature="nightly-docs",doc(cfg(target_os="linux")))]
+pub mod linux;
+
+#[cfg(feature="opengl")]
 pub use image;
\ No newline at end of file
@@ -0,0 +1,316 @@
+use super::{
+    X11Error,
+    window::{
+        Window,
+        WindowMessage,
+        WindowProcedureFunction,
+    },
+};
+
+use x11_dl::xlib::{
+    self,
+    Xlib,
+    Display,
+    Atom,
+};
+
+#[cfg(feature="opengl")]
+use x11_dl::glx::{
+    self,
+    Glx,
+};
+
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    collections::HashMap,
+    ffi::{
+        c_void,
+        CString,
+    },
+    mem::ManuallyDrop,
+    ptr::null,
+};
+
+thread_local!{
+    static X11:Cell<Option<&'static X11Core>>=const{Cell::new(None)};
+}
+
+/// Returns the connection of the current thread opening it at the first call.
+///
+/// The connection lives until the process terminates.
+pub fn core()->Result<&'static X11Core,X11Error>{
+    X11.with(|x11|{
+        if let Some(core)=x11.get(){
+            return Ok(core)
+        }
+
+        let core:&'static X11Core=Box::leak(Box::new(X11Core::open()?));
+        x11.set(Some(core));
+        Ok(core)
+    })
+}
+
+/// Ignores protocol errors.
+///
+/// Failed requests are detected by the results of the functions.
+unsafe extern "C" fn error_handler(_display:*mut Display,_event:*mut xlib::XErrorEvent)->i32{
+    0
+}
+
+/// Registered window data.
+pub struct WindowEntry{
+    pub procedure:WindowProcedureFunction,
+    /// `*mut W::Data`, null until `WindowProcedure::create` returns.
+    pub data:*mut c_void,
+    pub auto_redraw:bool,
+    /// A paint is requested.
+    pub redraw:bool,
+    pub minimized:bool,
+    pub client_size:[u16;2],
+    pub client_position:[i16;2],
+}
+
+/// The connection to the X server and the loaded libraries.
+pub struct X11Core{
+    pub xlib:Xlib,
+    #[cfg(feature="opengl")]
+    pub glx:Glx,
+    pub display:*mut Display,
+
+    pub wm_protocols:Atom,
+    pub wm_delete_window:Atom,
+    pub net_wm_name:Atom,
+    pub net_wm_state:Atom,
+    pub net_wm_state_above:Atom,
+    pub net_wm_state_fullscreen:Atom,
+    pub net_wm_state_maximized_vert:Atom,
+    pub net_wm_state_maximized_horz:Atom,
+    pub utf8_string:Atom,
+
+    windows:RefCell<HashMap<xlib::Window,WindowEntry>>,
+}
+
+impl X11Core{
+    fn open()->Result<X11Core,X11Error>{
+        let xlib=Xlib::open().map_err(|error|X11Error::Library(error.to_string()))?;
+
+        #[cfg(feature="opengl")]
+        let glx=Glx::open().map_err(|error|X11Error::Library(error.to_string()))?;
+
+        unsafe{
+            let display=(xlib.XOpenDisplay)(null());
+            if display.is_null(){
+                return Err(X11Error::NoDisplay)
+            }
+
+            // The default handler terminates the process
+            (xlib.XSetErrorHandler)(Some(error_handler));
+
+            let atom=|name:&str|{
+                let name=CString::new(name).unwrap();
+                (xlib.XInternAtom)(display,name.as_ptr(),xlib::False)
+            };
+
+            Ok(Self{
+                wm_protocols:atom("WM_PROTOCOLS"),
+                wm_delete_window:atom("WM_DELETE_WINDOW"),
+                net_wm_name:atom("_NET_WM_NAME"),
+                net_wm_state:atom("_NET_WM_STATE"),
+                net_wm_state_above:atom("_NET_WM_STATE_ABOVE"),
+                net_wm_state_fullscreen:atom("_NET_WM_STATE_FULLSCREEN"),
+                net_wm_state_maximized_vert:atom("_NET_WM_STATE_MAXIMIZED_VERT"),
+                net_wm_state_maximized_horz:atom("_NET_WM_STATE_MAXIMIZED_HORZ"),
+                utf8_string:atom("UTF8_STRING"),
+
+                xlib,
+                #[cfg(feature="opengl")]
+                glx,
+                display,
+
+                windows:RefCell::new(HashMap::new()),
+            })
+        }
+    }
+
+    pub fn default_screen(&self)->i32{
+        unsafe{
+            (self.xlib.XDefaultScreen)(self.display)
+        }
+    }
+
+    pub fn root_window(&self)->xlib::Window{
+        unsafe{
+            (self.xlib.XRootWindow)(self.display,self.default_screen())
+        }
+    }
+
+    pub fn flush(&self){
+        unsafe{
+            (self.xlib.XFlush)(self.display);
+        }
+    }
+
+    /// Returns a visual and a depth for new windows.
+    ///
+    /// With OpenGL the visual is chosen by GLX, so the windows are drawable by a render context.
+    pub unsafe fn choose_visual(&self)->Result<(*mut xlib::Visual,i32),X11Error>{
+        let screen=self.default_screen();
+
+        #[cfg(feature="opengl")]{
+            let mut attributes=[
+                glx::GLX_RGBA,
+                glx::GLX_DOUBLEBUFFER,
+                glx::GLX_RED_SIZE,8,
+                glx::GLX_GREEN_SIZE,8,
+                glx::GLX_BLUE_SIZE,8,
+                glx::GLX_ALPHA_SIZE,8,
+                0,
+            ];
+
+            let info=(self.glx.glXChooseVisual)(self.display,screen,attributes.as_mut_ptr());
+            if info.is_null(){
+                return Err(X11Error::NoVisual)
+            }
+
+            let visual=((*info).visual,(*info).depth);
+            (self.xlib.XFree)(info as *mut c_void);
+
+            Ok(visual)
+        }
+
+        #[cfg(not(feature="opengl"))]
+        Ok((
+            (self.xlib.XDefaultVisual)(self.display,screen),
+            (self.xlib.XDefaultDepth)(self.display,screen),
+        ))
+    }
+
+    /// Returns the client area size and the position of its upper-left corner on the screen.
+    pub fn client_rectangle(&self,window:xlib::Window)->Option<([i32;2],[u32;2])>{
+        unsafe{
+            let mut attributes:xlib::XWindowAttributes=std::mem::zeroed();
+            if (self.xlib.XGetWindowAttributes)(self.display,window,&mut attributes)==0{
+                return None
+            }
+
+            let [mut x,mut y]=[0i32;2];
+            let mut child=0;
+            (self.xlib.XTranslateCoordinates)(
+                self.display,
+                window,
+                attributes.root,
+                0,
+                0,
+                &mut x,
+                &mut y,
+                &mut child
+            );
+
+            Some(([x,y],[attributes.width as u32,attributes.height as u32]))
+        }
+    }
+
+    /// Checks whether the window manager has maximized the window.
+    pub fn is_maximized(&self,window:xlib::Window)->bool{
+        let states=self.window_states(window);
+        states.contains(&self.net_wm_state_maximized_vert)
+            && states.contains(&self.net_wm_state_maximized_horz)
+    }
+
+    fn window_states(&self,window:xlib::Window)->Vec<Atom>{
+        unsafe{
+            let mut actual_type=0;
+            let mut actual_format=0;
+            let mut items=0;
+            let mut bytes_after=0;
+            let mut data=std::ptr::null_mut();
+
+            let result=(self.xlib.XGetWindowProperty)(
+                self.display,
+                window,
+                self.net_wm_state,
+                0,
+                1024,
+                xlib::False,
+                xlib::XA_ATOM,
+                &mut actual_type,
+                &mut actual_format,
+                &mut items,
+                &mut bytes_after,
+                &mut data
+            );
+
+            if result!=xlib::Success as i32 || data.is_null(){
+                return Vec::new()
+            }
+
+            // 32-bit properties are returned as arrays of `long`
+            let states=std::slice::from_raw_parts(data as *const Atom,items as usize).to_vec();
+            (self.xlib.XFree)(data as *mut c_void);
+            states
+        }
+    }
+}
+
+/// Window registry.
+impl X11Core{
+    pub fn register_window(&self,window:xlib::Window,entry:WindowEntry){
+        self.windows.borrow_mut().insert(window,entry);
+    }
+
+    pub fn unregister_window(&self,window:xlib::Window)->Option<WindowEntry>{
+        self.windows.borrow_mut().remove(&window)
+    }
+
+    /// Gives access to the data of a registered window.
+    ///
+    /// Don't call window procedures inside `f`.
+    pub fn with_window<R,F:FnOnce(&mut WindowEntry)->R>(&self,window:xlib::Window,f:F)->Option<R>{
+        self.windows.borrow_mut().get_mut(&window).map(f)
+    }
+
+    pub fn windows(&self)->Vec<xlib::Window>{
+        self.windows.borrow().keys().copied().collect()
+    }
+
+    /// Calls the procedure of a window.
+    ///
+    /// Returns `false` if the window isn't registered.
+    pub fn dispatch(&self,window:xlib::Window,message:WindowMessage)->bool{
+        let procedure=self.with_window(window,|entry|(entry.procedure,entry.data));
+
+        if let Some((procedure,data))=procedure{
+            let window=ManuallyDrop::new(Window{handle:window});
+            unsafe{
+                procedure(&window,message,data)
+            }
+            true
+        }
+        else{
+            false
+        }
+    }
+
+    /// Paints the windows that have requested it.
+    ///
+    /// Returns `false` if there were no such windows.
+    pub fn paint_requested_windows(&self)->bool{
+        let requested:Vec<xlib::Window>=self.windows.borrow_mut()
+            .iter_mut()
+            .filter(|(_,entry)|entry.redraw)
+            .map(|(&window,entry)|{
+                entry.redraw=false;
+                window
+            })
+            .collect();
+
+        for &window in &requested{
+            self.dispatch(window,WindowMessage::Paint);
+        }
+
+        !requested.is_empty()
+    }
+}
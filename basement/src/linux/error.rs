@@ -0,0 +1,46 @@
+use std::fmt::{
+    Display,
+    Formatter,
+    Result,
+};
+
+#[derive(Clone,Debug,PartialEq)]
+pub enum X11Error{
+    /// Xlib or libGL can't be loaded.
+    Library(String),
+
+    /// The connection to the X server can't be opened.
+    /// Usually `$DISPLAY` isn't set or the server isn't running.
+    NoDisplay,
+
+    /// The server doesn't provide a visual with the requested framebuffer configuration.
+    NoVisual,
+
+    /// The window doesn't exist or belongs to another thread.
+    InvalidWindow,
+
+    /// `glXCreateContext` has failed.
+    ContextCreation,
+
+    /// `glXMakeCurrent` has failed.
+    MakeCurrent,
+
+    /// The swap control extensions aren't supported.
+    NoSwapControl,
+}
+
+impl Display for X11Error{
+    fn fmt(&self,f:&mut Formatter)->Result{
+        match self{
+            X11Error::Library(error)=>write!(f,"can't load a library: {}",error),
+            X11Error::NoDisplay=>write!(f,"can't open the X display"),
+            X11Error::NoVisual=>write!(f,"no matching visual"),
+            X11Error::InvalidWindow=>write!(f,"invalid window"),
+            X11Error::ContextCreation=>write!(f,"can't create a GLX context"),
+            X11Error::MakeCurrent=>write!(f,"can't make the GLX context current"),
+            X11Error::NoSwapControl=>write!(f,"swap control is not supported"),
+        }
+    }
+}
+
+impl std::error::Error for X11Error{}
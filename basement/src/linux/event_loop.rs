@@ -0,0 +1,351 @@
+use crate::event_loop::{
+    SystemClock,
+    EventSource,
+    SourceState,
+};
+
+use super::{
+    Event,
+    EventLoop,
+    LoopControl,
+    WindowEvent,
+    MouseButton,
+    WindowResizeType,
+    core::{
+        core,
+        X11Core,
+    },
+    window::{
+        WindowMessage,
+        take_quit_request,
+    },
+    keyboard::{
+        virtual_key,
+        unicode_keysym,
+    },
+};
+
+use x11_dl::xlib;
+
+use std::{
+    mem::zeroed,
+    time::Duration,
+};
+
+/// Events of the X server connection of the current thread.
+///
+/// Without a connection the source only waits for the timeout
+/// and closes if there is no timeout, since nothing could wake it up.
+pub struct X11EventSource{
+    core:Option<&'static X11Core>,
+}
+
+impl X11EventSource{
+    pub fn new()->X11EventSource{
+        Self{
+            core:core().ok(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl X11EventSource{
+    pub (super) fn disconnected()->X11EventSource{
+        Self{
+            core:None,
+        }
+    }
+}
+
+impl Default for X11EventSource{
+    fn default()->X11EventSource{
+        X11EventSource::new()
+    }
+}
+
+impl EventSource for X11EventSource{
+    fn process<F:FnMut(Event,&mut LoopControl)>(
+        &mut self,
+        timeout:Option<u64>,
+        f:&mut F,
+        control:&mut LoopControl
+    )->SourceState{
+        if take_quit_request().is_some(){
+            return SourceState::Quit
+        }
+
+        let core=match self.core{
+            Some(core)=>core,
+            None=>{
+                // Nothing but the clock can wake the loop up
+                return match timeout{
+                    Some(timeout)=>{
+                        std::thread::sleep(Duration::from_nanos(timeout));
+                        SourceState::Running
+                    }
+                    None=>SourceState::Closed,
+                }
+            }
+        };
+
+        process_events(core,timeout,f,control);
+        SourceState::Running
+    }
+
+    fn request_redraw(&mut self){
+        if let Some(core)=self.core{
+            for window in core.windows(){
+                core.with_window(window,|entry|entry.redraw=true);
+            }
+        }
+    }
+}
+
+impl EventLoop{
+    /// Runs an event loop.
+    ///
+    /// Window events are dispatched to the window procedures
+    /// and input events are passed to `f` as well.
+    /// Windows are painted when there are no other X events.
+    ///
+    /// Without an X server connection only process events are generated
+    /// and the loop breaks when there is no event to wait for,
+    /// e.g. in the lazy mode with disabled redraw requests.
+    pub fn run<F:FnMut(Event,&mut LoopControl)>(&self,f:F){
+        self.run_with(&SystemClock::new(),&mut X11EventSource::new(),f)
+    }
+}
+
+/// Processes one X event or paints the requested windows.
+/// If there is nothing to do, waits for an event `timeout` nanoseconds or forever.
+fn process_events<F:FnMut(Event,&mut LoopControl)>(
+    core:&X11Core,
+    timeout:Option<u64>,
+    f:&mut F,
+    control:&mut LoopControl
+){
+    unsafe{
+        if (core.xlib.XPending)(core.display)>0{
+            let mut event:xlib::XEvent=zeroed();
+            (core.xlib.XNextEvent)(core.display,&mut event);
+            event_handler(core,&mut event,f,control);
+            return
+        }
+    }
+
+    if core.paint_requested_windows(){
+        return
+    }
+
+    wait_for_events(core,timeout)
+}
+
+/// Waits until the X connection becomes readable.
+fn wait_for_events(core:&X11Core,timeout:Option<u64>){
+    core.flush();
+
+    let timeout=match timeout{
+        // Rounding up to not wake up too early
+        Some(timeout)=>timeout.div_ceil(1_000_000).min(i32::MAX as u64) as i32,
+        None=>-1,
+    };
+
+    unsafe{
+        let mut descriptor=libc::pollfd{
+            fd:(core.xlib.XConnectionNumber)(core.display),
+            events:libc::POLLIN,
+            revents:0,
+        };
+
+        libc::poll(&mut descriptor,1,timeout);
+    }
+}
+
+fn event_handler<F:FnMut(Event,&mut LoopControl)>(
+    core:&X11Core,
+    event:&mut xlib::XEvent,
+    f:&mut F,
+    control:&mut LoopControl
+){
+    unsafe{
+        let window=event.any.window;
+
+        let event=match event.get_type(){
+            xlib::ClientMessage=>{
+                let message=event.client_message;
+                if message.message_type==core.wm_protocols
+                    && message.data.get_long(0) as xlib::Atom==core.wm_delete_window
+                {
+                    core.dispatch(window,WindowMessage::CloseRequest);
+                }
+                return
+            }
+
+            // The window is destroyed not with `Window::destroy`
+            xlib::DestroyNotify=>{
+                let destroyed=event.destroy_window.window;
+                if let Some(entry)=core.unregister_window(destroyed){
+                    let window=std::mem::ManuallyDrop::new(super::Window{handle:destroyed});
+                    (entry.procedure)(&window,WindowMessage::Destroy,entry.data);
+                }
+                return
+            }
+
+            xlib::Expose=>{
+                if event.expose.count==0{
+                    core.with_window(window,|entry|entry.redraw=true);
+                }
+                return
+            }
+
+            xlib::ConfigureNotify=>{
+                if let Some((position,[width,height]))=core.client_rectangle(window){
+                    let client_size=[width as u16,height as u16];
+                    let client_position=[position[0] as i16,position[1] as i16];
+
+                    let changes=core.with_window(window,|entry|{
+                        let resized=entry.client_size!=client_size;
+                        let moved=entry.client_position!=client_position;
+                        entry.client_size=client_size;
+                        entry.client_position=client_position;
+                        (resized,moved)
+                    });
+
+                    if let Some((resized,moved))=changes{
+                        if resized{
+                            let resize_type=if core.is_maximized(window){
+                                WindowResizeType::Maximized
+                            }
+                            else{
+                                WindowResizeType::Restore
+                            };
+                            core.dispatch(window,WindowMessage::Resized(client_size,resize_type));
+                        }
+
+                        if moved{
+                            core.dispatch(window,WindowMessage::Moved(client_position));
+                        }
+                    }
+                }
+                return
+            }
+
+            xlib::MapNotify=>{
+                let restored=core.with_window(window,|entry|{
+                    let minimized=entry.minimized;
+                    entry.minimized=false;
+                    minimized.then_some(entry.client_size)
+                });
+
+                if let Some(Some(client_size))=restored{
+                    core.dispatch(window,WindowMessage::Resized(client_size,WindowResizeType::Restore));
+                }
+                return
+            }
+
+            xlib::UnmapNotify=>{
+                core.with_window(window,|entry|entry.minimized=true);
+                core.dispatch(window,WindowMessage::Resized([0,0],WindowResizeType::Minimized));
+                return
+            }
+
+            #[cfg(feature="set_cursor_event")]
+            xlib::EnterNotify=>{
+                core.dispatch(window,WindowMessage::SetCursor);
+                return
+            }
+
+            // Keyboard events
+            // События клавиатуры
+            xlib::KeyPress|xlib::KeyRelease=>{
+                let key_event=&mut event.key;
+                let key=virtual_key((core.xlib.XLookupKeysym)(key_event,0));
+
+                if event.get_type()==xlib::KeyRelease{
+                    WindowEvent::KeyRelease(key)
+                }
+                else{
+                    dispatch_event(core,window,WindowEvent::KeyPress(key),f,control);
+
+                    // Ввод символов
+                    let mut buffer=[0u8;32];
+                    let mut keysym=0;
+                    let length=(core.xlib.XLookupString)(
+                        &mut event.key,
+                        buffer.as_mut_ptr() as *mut i8,
+                        buffer.len() as i32,
+                        &mut keysym,
+                        std::ptr::null_mut()
+                    );
+
+                    if let Some(character)=unicode_keysym(keysym){
+                        dispatch_event(core,window,WindowEvent::CharacterInput(character),f,control);
+                    }
+                    else{
+                        // Latin-1
+                        for &byte in &buffer[..length.clamp(0,buffer.len() as i32) as usize]{
+                            dispatch_event(core,window,WindowEvent::CharacterInput(byte as char),f,control);
+                        }
+                    }
+                    return
+                }
+            }
+
+            // Mouse events
+            // События мыши
+            xlib::MotionNotify=>{
+                #[cfg(feature="set_cursor_event")]
+                core.dispatch(window,WindowMessage::SetCursor);
+
+                let motion=event.motion;
+                WindowEvent::MouseMove([motion.x as u16,motion.y as u16])
+            }
+
+            xlib::ButtonPress|xlib::ButtonRelease=>{
+                let button_event=event.button;
+                let cursor_position=[button_event.x as u16,button_event.y as u16];
+                let press=event.get_type()==xlib::ButtonPress;
+
+                let button=match button_event.button{
+                    xlib::Button1=>MouseButton::Left,
+                    xlib::Button2=>MouseButton::Middle,
+                    xlib::Button3=>MouseButton::Right,
+                    // The wheel is reported as buttons 4 and 5
+                    xlib::Button4|xlib::Button5=>{
+                        if press{
+                            let delta=if button_event.button==xlib::Button4{1}else{-1};
+                            dispatch_event(core,window,WindowEvent::MouseScroll(delta),f,control);
+                        }
+                        return
+                    }
+                    8=>MouseButton::Button4,
+                    9=>MouseButton::Button5,
+                    _=>return,
+                };
+
+                if press{
+                    WindowEvent::MousePress{cursor_position,button}
+                }
+                else{
+                    WindowEvent::MouseRelease{cursor_position,button}
+                }
+            }
+
+            _=>return,
+        };
+
+        dispatch_event(core,window,event,f,control)
+    }
+}
+
+/// Passes an input event to the window procedure and to the event loop handler.
+fn dispatch_event<F:FnMut(Event,&mut LoopControl)>(
+    core:&X11Core,
+    window:xlib::Window,
+    event:WindowEvent,
+    f:&mut F,
+    control:&mut LoopControl
+){
+    core.dispatch(window,WindowMessage::Event(event.clone()));
+    f(Event::Window(event),control)
+}
@@ -0,0 +1,88 @@
+use super::VirtualKeyCode;
+
+use x11_dl::keysym::*;
+
+/// Converts an unshifted keysym to a Windows virtual-key code.
+///
+/// Left and right modifier keys aren't distinguished,
+/// as `MapVirtualKeyW` in the Windows event loop doesn't do it.
+pub fn virtual_key(keysym:u64)->VirtualKeyCode{
+    let keysym=keysym as u32;
+
+    let code:u8=match keysym{
+        XK_a..=XK_z=>0x41+(keysym-XK_a) as u8,
+        XK_A..=XK_Z=>0x41+(keysym-XK_A) as u8,
+        XK_0..=XK_9=>0x30+(keysym-XK_0) as u8,
+        XK_KP_0..=XK_KP_9=>0x60+(keysym-XK_KP_0) as u8,
+        XK_F1..=XK_F24=>0x70+(keysym-XK_F1) as u8,
+
+        XK_BackSpace=>0x08,
+        XK_Tab|XK_ISO_Left_Tab=>0x09,
+        XK_Clear=>0x0C,
+        XK_Return|XK_KP_Enter=>0x0D,
+        XK_Shift_L|XK_Shift_R=>0x10,
+        XK_Control_L|XK_Control_R=>0x11,
+        XK_Alt_L|XK_Alt_R=>0x12,
+        XK_Pause=>0x13,
+        XK_Caps_Lock=>0x14,
+        XK_Escape=>0x1B,
+        XK_space=>0x20,
+        XK_Prior|XK_KP_Prior=>0x21,
+        XK_Next|XK_KP_Next=>0x22,
+        XK_End|XK_KP_End=>0x23,
+        XK_Home|XK_KP_Home=>0x24,
+        XK_Left|XK_KP_Left=>0x25,
+        XK_Up|XK_KP_Up=>0x26,
+        XK_Right|XK_KP_Right=>0x27,
+        XK_Down|XK_KP_Down=>0x28,
+        XK_Select=>0x29,
+        XK_Execute=>0x2B,
+        XK_Print=>0x2C,
+        XK_Insert|XK_KP_Insert=>0x2D,
+        XK_Delete|XK_KP_Delete=>0x2E,
+        XK_Help=>0x2F,
+        XK_Super_L=>0x5B,
+        XK_Super_R=>0x5C,
+        XK_Menu=>0x5D,
+
+        XK_KP_Multiply=>0x6A,
+        XK_KP_Add=>0x6B,
+        XK_KP_Separator=>0x6C,
+        XK_KP_Subtract=>0x6D,
+        XK_KP_Decimal=>0x6E,
+        XK_KP_Divide=>0x6F,
+
+        XK_Num_Lock=>0x90,
+        XK_Scroll_Lock=>0x91,
+
+        XK_semicolon=>0xBA,
+        XK_equal=>0xBB,
+        XK_comma=>0xBC,
+        XK_minus=>0xBD,
+        XK_period=>0xBE,
+        XK_slash=>0xBF,
+        XK_grave=>0xC0,
+        XK_bracketleft=>0xDB,
+        XK_backslash=>0xDC,
+        XK_bracketright=>0xDD,
+        XK_apostrophe=>0xDE,
+        XK_less=>0xE2,
+
+        _=>0x00,
+    };
+
+    // The codes above are in the range of `VirtualKeyCode`
+    unsafe{
+        std::mem::transmute::<u8,VirtualKeyCode>(code)
+    }
+}
+
+/// Converts a keysym to a character if it's a Unicode keysym.
+pub fn unicode_keysym(keysym:u64)->Option<char>{
+    if keysym&0xFF00_0000==0x0100_0000{
+        char::from_u32((keysym&0x00FF_FFFF) as u32)
+    }
+    else{
+        None
+    }
+}
@@ -0,0 +1,61 @@
+//! An X11 backend with the same interface as the `windows` module.
+//!
+//! Xlib and libGL are loaded at run time,
+//! so the crate builds without the X11 development packages.
+//! Windows, their procedures and the event loop belong to the thread that created them,
+//! every thread opens its own connection to the X server (`$DISPLAY`).
+
+mod core;
+
+mod error;
+pub use error::X11Error;
+
+pub use crate::event_loop::{
+    Event,
+    ProcessEvent,
+    WindowEvent,
+    MouseButton,
+    VirtualKeyCode,
+};
+
+mod keyboard;
+
+#[cfg(feature="opengl")]
+mod opengl;
+#[cfg(feature="opengl")]
+pub use opengl::{
+    OpenGraphicsLibrary,
+    OpenGLRenderContext,
+    OpenGLRenderContextAttributes,
+};
+
+mod monitor;
+pub use monitor::Monitor;
+
+mod window;
+pub use window::{
+    WindowProcedure,
+    Fullscreen,
+    Window,
+    WindowAttributes,
+    WindowClass,
+    WindowClassAttributes,
+    WindowResizeType,
+    // functions
+    quit
+};
+
+mod event_loop;
+pub use event_loop::X11EventSource;
+pub use crate::event_loop::{
+    Ticks,
+    EventLoop,
+    LoopControl,
+    EventInterval,
+    EventLoopAttributes,
+};
+
+pub use x11_dl;
+
+#[cfg(test)]
+mod tests;
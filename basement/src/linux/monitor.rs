@@ -0,0 +1,87 @@
+use super::{
+    Window,
+    core::core,
+};
+
+use x11_dl::xinerama::Xlib as Xinerama;
+
+/// A monitor rectangle.
+///
+/// Monitors are queried with Xinerama,
+/// without it the whole screen is a single monitor.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Monitor{
+    rectangle:[i32;4],
+}
+
+impl Monitor{
+    pub fn get_primary_monitor()->Monitor{
+        Self{
+            rectangle:monitors().first().copied().unwrap_or([0;4])
+        }
+    }
+
+    /// Returns the nearest to the window monitor.
+    ///
+    /// Возращает ближайщий к окну монитор.
+    pub fn get_monitor_from_window(window:&Window)->Monitor{
+        let [x1,y1,x2,y2]=window.rectangle();
+        let center=[(x1+x2)/2,(y1+y2)/2];
+
+        let distance=|[mx1,my1,mx2,my2]:[i32;4]|{
+            let dx=(mx1-center[0]).max(center[0]-mx2).max(0) as i64;
+            let dy=(my1-center[1]).max(center[1]-my2).max(0) as i64;
+            dx*dx+dy*dy
+        };
+
+        Self{
+            rectangle:monitors().into_iter().min_by_key(|&rectangle|distance(rectangle)).unwrap_or([0;4])
+        }
+    }
+
+    /// Returns the coordinates of the upper-left and lower-right corners.
+    ///
+    /// [x1, y1, x2, y2]
+    pub fn rectangle(&self)->[i32;4]{
+        self.rectangle
+    }
+}
+
+/// Returns the rectangles of all monitors, the primary one is the first.
+fn monitors()->Vec<[i32;4]>{
+    let core=match core(){
+        Ok(core)=>core,
+        Err(_)=>return Vec::new(),
+    };
+
+    unsafe{
+        if let Ok(xinerama)=Xinerama::open(){
+            if (xinerama.XineramaIsActive)(core.display)!=0{
+                let mut number=0;
+                let screens=(xinerama.XineramaQueryScreens)(core.display,&mut number);
+
+                if !screens.is_null(){
+                    let monitors=std::slice::from_raw_parts(screens,number.max(0) as usize)
+                        .iter()
+                        .map(|screen|{
+                            let [x,y]=[screen.x_org as i32,screen.y_org as i32];
+                            [x,y,x+screen.width as i32,y+screen.height as i32]
+                        })
+                        .collect();
+
+                    (core.xlib.XFree)(screens as *mut std::ffi::c_void);
+
+                    return monitors
+                }
+            }
+        }
+
+        let screen=core.default_screen();
+        vec![[
+            0,
+            0,
+            (core.xlib.XDisplayWidth)(core.display,screen),
+            (core.xlib.XDisplayHeight)(core.display,screen),
+        ]]
+    }
+}
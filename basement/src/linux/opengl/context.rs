@@ -0,0 +1,202 @@
+use crate::linux::{
+    Window,
+    X11Error,
+    core::{
+        core,
+        X11Core,
+    },
+};
+
+use x11_dl::{
+    xlib,
+    glx::GLXContext,
+};
+
+use std::{
+    ffi::CStr,
+    mem::zeroed,
+    ptr::null_mut,
+};
+
+pub struct OpenGLRenderContext{
+    core:&'static X11Core,
+    window:xlib::Window,
+    render_context:GLXContext,
+    swap_control:SwapControl,
+}
+
+impl OpenGLRenderContext{
+    /// Creates a context for the visual of the window and makes it current.
+    ///
+    /// The vsync setting is ignored if the swap control extensions aren't supported.
+    pub fn new(
+        window:&Window,
+        attributes:OpenGLRenderContextAttributes
+    )->Result<OpenGLRenderContext,X11Error>{
+        let core=core()?;
+
+        unsafe{
+            let mut window_attributes:xlib::XWindowAttributes=zeroed();
+            if (core.xlib.XGetWindowAttributes)(core.display,window.handle,&mut window_attributes)==0{
+                return Err(X11Error::InvalidWindow)
+            }
+
+            let mut template:xlib::XVisualInfo=zeroed();
+            template.visualid=(core.xlib.XVisualIDFromVisual)(window_attributes.visual);
+
+            let mut count=0;
+            let visual_info=(core.xlib.XGetVisualInfo)(
+                core.display,
+                xlib::VisualIDMask,
+                &mut template,
+                &mut count
+            );
+            if visual_info.is_null(){
+                return Err(X11Error::NoVisual)
+            }
+
+            let render_context=(core.glx.glXCreateContext)(core.display,visual_info,null_mut(),xlib::True);
+            (core.xlib.XFree)(visual_info as *mut std::ffi::c_void);
+
+            if render_context.is_null(){
+                return Err(X11Error::ContextCreation)
+            }
+
+            let context=Self{
+                core,
+                window:window.handle,
+                render_context,
+                swap_control:SwapControl::load(core),
+            };
+
+            context.make_current(true)?;
+
+            // vsync
+            let _=context.set_vsync(attributes.vsync);
+
+            Ok(context)
+        }
+    }
+
+    pub fn render_context(&self)->GLXContext{
+        self.render_context
+    }
+
+    /// Makes a specified OpenGL rendering context the calling thread's current rendering context.
+    pub fn make_current(&self,current:bool)->Result<(),X11Error>{
+        unsafe{
+            let result=if current{
+                (self.core.glx.glXMakeCurrent)(self.core.display,self.window,self.render_context)
+            }
+            else{
+                (self.core.glx.glXMakeCurrent)(self.core.display,0,null_mut())
+            };
+
+            if result!=0{
+                Ok(())
+            }
+            else{
+                Err(X11Error::MakeCurrent)
+            }
+        }
+    }
+
+    pub fn swap_buffers(&self)->Result<(),X11Error>{
+        unsafe{
+            (self.core.glx.glXSwapBuffers)(self.core.display,self.window);
+        }
+        Ok(())
+    }
+
+    pub fn set_vsync(&self,enabled:bool)->Result<(),X11Error>{
+        unsafe{
+            match self.swap_control{
+                SwapControl::Ext(glXSwapIntervalEXT)=>{
+                    glXSwapIntervalEXT(self.core.display,self.window,enabled as i32);
+                    Ok(())
+                }
+                SwapControl::Mesa(glXSwapIntervalMESA)=>{
+                    if glXSwapIntervalMESA(enabled as u32)==0{
+                        Ok(())
+                    }
+                    else{
+                        Err(X11Error::NoSwapControl)
+                    }
+                }
+                SwapControl::None=>Err(X11Error::NoSwapControl),
+            }
+        }
+    }
+}
+
+impl Drop for OpenGLRenderContext{
+    fn drop(&mut self){
+        unsafe{
+            (self.core.glx.glXMakeCurrent)(self.core.display,0,null_mut());
+            (self.core.glx.glXDestroyContext)(self.core.display,self.render_context);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct OpenGLRenderContextAttributes{
+    pub vsync:bool,
+}
+
+impl OpenGLRenderContextAttributes{
+    pub fn new()->OpenGLRenderContextAttributes{
+        Self{
+            vsync:true,
+        }
+    }
+}
+
+impl Default for OpenGLRenderContextAttributes{
+    fn default()->OpenGLRenderContextAttributes{
+        OpenGLRenderContextAttributes::new()
+    }
+}
+
+/// Type for [glXSwapIntervalEXT](https://www.khronos.org/registry/OpenGL/extensions/EXT/EXT_swap_control.txt)
+pub type glXSwapIntervalEXT_t=unsafe extern "C" fn(display:*mut xlib::Display,drawable:xlib::XID,interval:i32);
+/// Type for [glXSwapIntervalMESA](https://www.khronos.org/registry/OpenGL/extensions/MESA/GLX_MESA_swap_control.txt)
+pub type glXSwapIntervalMESA_t=unsafe extern "C" fn(interval:u32)->i32;
+
+#[derive(Clone,Copy)]
+enum SwapControl{
+    Ext(glXSwapIntervalEXT_t),
+    Mesa(glXSwapIntervalMESA_t),
+    None,
+}
+
+impl SwapControl{
+    /// `glXGetProcAddress` may return stubs for unsupported functions,
+    /// so the extension string is checked first.
+    fn load(core:&X11Core)->SwapControl{
+        unsafe{
+            let extensions=(core.glx.glXQueryExtensionsString)(core.display,core.default_screen());
+            if extensions.is_null(){
+                return SwapControl::None
+            }
+
+            let extensions=CStr::from_ptr(extensions).to_string_lossy();
+            let supported=|name:&str|extensions.split(' ').any(|extension|extension==name);
+
+            let function=|name:&str|(core.glx.glXGetProcAddress)(name.as_ptr());
+
+            if supported("GLX_EXT_swap_control"){
+                if let Some(function)=function("glXSwapIntervalEXT\0"){
+                    return SwapControl::Ext(std::mem::transmute::<unsafe extern "C" fn(),glXSwapIntervalEXT_t>(function))
+                }
+            }
+
+            if supported("GLX_MESA_swap_control"){
+                if let Some(function)=function("glXSwapIntervalMESA\0"){
+                    return SwapControl::Mesa(std::mem::transmute::<unsafe extern "C" fn(),glXSwapIntervalMESA_t>(function))
+                }
+            }
+
+            SwapControl::None
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::graphics::{
+    GLCore,
+    core::GLLoader,
+};
+
+use super::X11Error;
+
+use x11_dl::glx::Glx;
+
+use std::ffi::c_void;
+
+mod context;
+pub use context::{
+    OpenGLRenderContext,
+    OpenGLRenderContextAttributes,
+};
+
+pub struct OpenGraphicsLibrary{
+    glx:Glx,
+}
+
+impl OpenGraphicsLibrary{
+    /// Loads libGL.
+    pub fn new()->Result<OpenGraphicsLibrary,X11Error>{
+        let glx=Glx::open().map_err(|error|X11Error::Library(error.to_string()))?;
+
+        Ok(Self{
+            glx,
+        })
+    }
+
+    /// Needs the terminating null
+    pub fn get_proc_address(&self,name:&str)->*const c_void{
+        unsafe{
+            match (self.glx.glXGetProcAddress)(name.as_ptr()){
+                Some(function)=>function as *const c_void,
+                None=>std::ptr::null(),
+            }
+        }
+    }
+
+    pub fn load_functions(&self){
+        unsafe{
+            GLCore.load_functions(self);
+        }
+    }
+}
+
+impl GLLoader for OpenGraphicsLibrary{
+    fn get_proc_address(&self,name:&str)->*const c_void{
+        OpenGraphicsLibrary::get_proc_address(self,name)
+    }
+}
@@ -0,0 +1,152 @@
+//! The window tests need an X server with GLX and are ignored by default,
+//! run them with a virtual one: `xvfb-run cargo test -- --ignored`.
+
+use super::{
+    Event,
+    EventLoop,
+    EventLoopAttributes,
+    EventInterval,
+    LoopControl,
+    ProcessEvent,
+    VirtualKeyCode,
+    Window,
+    WindowAttributes,
+    WindowClass,
+    WindowClassAttributes,
+    WindowEvent,
+    WindowProcedure,
+    WindowResizeType,
+    X11Error,
+    X11EventSource,
+    quit,
+    keyboard::virtual_key,
+};
+
+use crate::event_loop::SystemClock;
+
+use x11_dl::keysym;
+
+use std::cell::RefCell;
+
+thread_local!{
+    static CALLS:RefCell<Vec<&'static str>>=const{RefCell::new(Vec::new())};
+}
+
+fn record(call:&'static str){
+    CALLS.with(|calls|calls.borrow_mut().push(call))
+}
+
+fn calls(call:&str)->usize{
+    CALLS.with(|calls|calls.borrow().iter().filter(|&&c|c==call).count())
+}
+
+struct Recorder;
+
+impl WindowProcedure for Recorder{
+    type CreateParameters=();
+    type Data=();
+
+    fn create(_window:&Window,_parameters:&mut ())->Result<(),X11Error>{
+        record("create");
+        Ok(())
+    }
+
+    fn close_request(_window:&Window,_data:()){
+        record("close_request")
+    }
+
+    fn destroy(_window:&Window,_data:()){
+        record("destroy")
+    }
+
+    fn paint(_window:&Window,_data:()){
+        record("paint")
+    }
+
+    #[cfg(feature="set_cursor_event")]
+    fn set_cursor(_window:&Window,_data:()){}
+
+    fn resized(_client_size:[u16;2],_resize_type:WindowResizeType,_window:&Window,_data:()){
+        record("resized")
+    }
+
+    fn moved(_client_position:[i16;2],_window:&Window,_data:()){}
+
+    fn handle(_event:WindowEvent,_window:&Window,_data:()){}
+
+    #[cfg(feature="wnd_proc_catch_panic")]
+    fn catch_panic(_window:&Window,_data:(),_error:Box<dyn std::any::Any+Send>){}
+}
+
+#[test]
+fn key_mapping(){
+    assert!(matches!(virtual_key(keysym::XK_a as u64),VirtualKeyCode::A));
+    assert!(matches!(virtual_key(keysym::XK_Z as u64),VirtualKeyCode::Z));
+    assert!(matches!(virtual_key(keysym::XK_5 as u64),VirtualKeyCode::Five));
+    assert!(matches!(virtual_key(keysym::XK_F12 as u64),VirtualKeyCode::F12));
+    assert!(matches!(virtual_key(keysym::XK_Escape as u64),VirtualKeyCode::ESCAPE));
+    assert!(matches!(virtual_key(keysym::XK_Shift_R as u64),VirtualKeyCode::SHIFT));
+    assert!(matches!(virtual_key(keysym::XK_KP_7 as u64),VirtualKeyCode::NUMPAD7));
+}
+
+#[test]
+fn lazy_loop_without_connection(){
+    let event_loop=EventLoop::new(EventLoopAttributes::new());
+
+    let mut process_events=Vec::new();
+    event_loop.run_with(&SystemClock::new(),&mut X11EventSource::disconnected(),|event,control|{
+        if let Event::Process(event)=event{
+            if let ProcessEvent::EventLoopStart=event{
+                *control=LoopControl::Lazy
+            }
+            process_events.push(format!("{:?}",event));
+        }
+    });
+
+    // Nothing can wake the loop up, so it breaks instead of waiting forever
+    assert_eq!(process_events,["EventLoopStart","EventLoopBreak"]);
+}
+
+#[test]
+#[ignore="needs an X server, run with `xvfb-run cargo test -- --ignored`"]
+fn window_life_cycle(){
+    let class=WindowClass::new(WindowClassAttributes::new("CatEngineTest")).unwrap();
+    let mut attributes=WindowAttributes::new("CatEngineTest");
+    attributes.size=Some([200,100]);
+
+    let window=Window::new::<Recorder>(&class,attributes,&mut ()).unwrap();
+
+    let mut event_loop_attributes=EventLoopAttributes::new();
+    event_loop_attributes.update_interval=EventInterval::EventsPerSecond(100);
+    let event_loop=EventLoop::new(event_loop_attributes);
+
+    let mut updates=0;
+    let mut process_events=Vec::new();
+
+    event_loop.run(|event,control|{
+        if let Event::Process(event)=event{
+            match event{
+                ProcessEvent::Update(_)=>{
+                    updates+=1;
+                    if updates==20{
+                        window.destroy().unwrap();
+                        quit(0)
+                    }
+                }
+                ProcessEvent::EventLoopStart=>*control=LoopControl::Run,
+                _=>{}
+            }
+            process_events.push(format!("{:?}",event));
+        }
+    });
+
+    assert_eq!(updates,20);
+    assert_eq!(process_events.first().map(String::as_str),Some("EventLoopStart"));
+    assert_eq!(&process_events[process_events.len()-2..],["Quit","EventLoopBreak"]);
+
+    assert_eq!(calls("create"),1);
+    assert!(calls("paint")>=1);
+    assert_eq!(calls("destroy"),1);
+    // The window is already destroyed
+    assert_eq!(window.destroy(),Err(X11Error::InvalidWindow));
+}
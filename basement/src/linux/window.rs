@@ -0,0 +1,669 @@
+use super::{
+    X11Error,
+    Monitor,
+    WindowEvent,
+    core::{
+        core,
+        WindowEntry,
+    },
+};
+
+use x11_dl::xlib;
+
+use std::{
+    any::Any,
+    cell::Cell,
+    ffi::{
+        c_void,
+        CString,
+    },
+    mem::zeroed,
+    ptr::null_mut,
+};
+
+thread_local!{
+    static QUIT_REQUEST:Cell<Option<i32>>=const{Cell::new(None)};
+}
+
+/// Defines window's behavior.
+pub trait WindowProcedure{
+    type CreateParameters;
+    type Data:Copy;
+
+    /// Called when an application requests that a window be created.
+    fn create(window:&Window,parameters:&mut Self::CreateParameters)->Result<Self::Data,X11Error>;
+
+    /// Called as a signal that a window or an application should terminate.
+    fn close_request(window:&Window,data:Self::Data);
+
+    /// Called when a window is being destroyed,
+    /// before the window is removed from the screen.
+    fn destroy(window:&Window,data:Self::Data);
+
+    /// Called when the system or another application
+    /// makes a request to paint a portion of an application's window.
+    fn paint(window:&Window,data:Self::Data);
+
+    /// Called if the mouse causes the cursor to move within a window.
+    ///
+    /// Note that you have to set up cursor manually each time the function is called.
+    #[cfg(feature="set_cursor_event")]
+    fn set_cursor(window:&Window,data:Self::Data);
+
+    /// Called after window's size has changed.
+    ///
+    /// `client_size` specifies the new width of the client area.
+    fn resized(client_size:[u16;2],resize_type:WindowResizeType,window:&Window,data:Self::Data);
+
+    /// Called after a window has been moved.
+    ///
+    /// `client_position` contains coordinates of the upper-left corner of the client area of the window.
+    fn moved(client_position:[i16;2],window:&Window,data:Self::Data);
+
+    /// Called in other cases.
+    fn handle(event:WindowEvent,window:&Window,data:Self::Data);
+
+    /// Called when one of the functions above panics.
+    ///
+    /// The panic is caught to keep the behaviour of the Windows backend,
+    /// the event loop continues processing events.
+    ///
+    /// You can remove panic capturing with disabling the `wnd_proc_catch_panic` feature.
+    #[cfg(feature="wnd_proc_catch_panic")]
+    fn catch_panic(window:&Window,data:Self::Data,error:Box<dyn Any+Send>);
+}
+
+/// Indicates to the event loop that it's thread has made a request to close.
+pub fn quit(exit_code:i32){
+    QUIT_REQUEST.with(|request|request.set(Some(exit_code)))
+}
+
+/// Returns and clears the exit code passed to `quit`.
+pub (crate) fn take_quit_request()->Option<i32>{
+    QUIT_REQUEST.with(|request|request.take())
+}
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum WindowResizeType{
+    /// Message is sent to all pop-up windows
+    /// when some other window is maximized.
+    ///
+    /// Not used by X11.
+    MaximizedHide,
+
+    /// The window has been maximized.
+    Maximized,
+
+    /// Message is sent to all pop-up windows
+    /// when some other window has been restored to its former size.
+    ///
+    /// Not used by X11.
+    MaximizedShow,
+
+    /// The window has been minimized (unmapped).
+    Minimized,
+
+    /// The window has been resized,
+    /// but neither the `WindowMessageResize::Minimized` nor `WindowMessageResize::Maximized` value applies.
+    Restore,
+}
+
+/// Messages passed to window procedures.
+pub (crate) enum WindowMessage{
+    CloseRequest,
+    Destroy,
+    Paint,
+    #[cfg(feature="set_cursor_event")]
+    SetCursor,
+    Resized([u16;2],WindowResizeType),
+    Moved([i16;2]),
+    Event(WindowEvent),
+}
+
+pub (crate) type WindowProcedureFunction=unsafe fn(&Window,WindowMessage,*mut c_void);
+
+/// Calls a window procedure catching panics if `wnd_proc_catch_panic` is enabled.
+unsafe fn window_procedure<W:WindowProcedure>(window:&Window,message:WindowMessage,data:*mut c_void){
+    // Events may come before `WindowProcedure::create` returns
+    if data.is_null(){
+        return
+    }
+
+    let data_ptr=data as *mut W::Data;
+    let data=*data_ptr;
+
+    let destroy=matches!(message,WindowMessage::Destroy);
+
+    #[cfg(feature="wnd_proc_catch_panic")]{
+        let result=std::panic::catch_unwind(std::panic::AssertUnwindSafe(||{
+            wrap_window_procedure::<W>(window,message,data)
+        }));
+
+        if let Err(error)=result{
+            W::catch_panic(window,data,error)
+        }
+    }
+
+    #[cfg(not(feature="wnd_proc_catch_panic"))]
+    wrap_window_procedure::<W>(window,message,data);
+
+    if destroy{
+        drop(Box::from_raw(data_ptr))
+    }
+}
+
+fn wrap_window_procedure<W:WindowProcedure>(window:&Window,message:WindowMessage,data:W::Data){
+    match message{
+        WindowMessage::CloseRequest=>W::close_request(window,data),
+
+        WindowMessage::Destroy=>W::destroy(window,data),
+
+        WindowMessage::Paint=>{
+            W::paint(window,data);
+
+            if window.auto_redraw(){
+                window.redraw()
+            }
+        }
+
+        #[cfg(feature="set_cursor_event")]
+        WindowMessage::SetCursor=>W::set_cursor(window,data),
+
+        WindowMessage::Resized(client_size,resize_type)=>W::resized(client_size,resize_type,window,data),
+
+        WindowMessage::Moved(client_position)=>W::moved(client_position,window,data),
+
+        WindowMessage::Event(event)=>W::handle(event,window,data),
+    }
+}
+
+pub enum Fullscreen{
+    None,
+    Monitor(Monitor)
+}
+
+/// X11 has no window classes,
+/// the name is set as the `WM_CLASS` property of windows.
+pub struct WindowClass{
+    name:CString,
+}
+
+impl WindowClass{
+    pub fn new(attributes:WindowClassAttributes)->Result<WindowClass,X11Error>{
+        // A name with a null byte is cut
+        let name=attributes.name.split('\0').next().unwrap_or("");
+
+        Ok(Self{
+            name:CString::new(name).unwrap(),
+        })
+    }
+
+    pub fn name(&self)->&str{
+        self.name.to_str().unwrap_or("")
+    }
+}
+
+pub struct WindowClassAttributes{
+    /// The class name.
+    pub name:String,
+}
+
+impl WindowClassAttributes{
+    pub fn new(class_name:&str)->WindowClassAttributes{
+        Self{
+            name:String::from(class_name),
+        }
+    }
+}
+
+/// A window handle.
+///
+/// The window belongs to the thread that has created it.
+#[repr(transparent)]
+pub struct Window{
+    pub (crate) handle:xlib::Window,
+}
+
+impl Window{
+    pub fn new<W:WindowProcedure>(
+        class:&WindowClass,
+        attributes:WindowAttributes,
+        create_parameters:&mut W::CreateParameters,
+    )->Result<Window,X11Error>{
+        let core=core()?;
+
+        // The window manager places windows without the position
+        let [mut x,mut y]=attributes.position.unwrap_or([0,0]);
+        let [mut width,mut height]=attributes.size.unwrap_or([800,600]);
+
+        let fullscreen=if let Fullscreen::Monitor(monitor)=&attributes.fullscreen{
+            let [x1,y1,x2,y2]=monitor.rectangle();
+            x=x1;
+            y=y1;
+            width=x2-x1;
+            height=y2-y1;
+            true
+        }
+        else{
+            false
+        };
+
+        unsafe{
+            let display=core.display;
+            let root=core.root_window();
+
+            let (visual,depth)=core.choose_visual()?;
+
+            let mut window_attributes:xlib::XSetWindowAttributes=zeroed();
+            window_attributes.colormap=(core.xlib.XCreateColormap)(display,root,visual,xlib::AllocNone);
+            window_attributes.event_mask=xlib::ExposureMask
+                |xlib::StructureNotifyMask
+                |xlib::KeyPressMask
+                |xlib::KeyReleaseMask
+                |xlib::ButtonPressMask
+                |xlib::ButtonReleaseMask
+                |xlib::PointerMotionMask
+                |xlib::EnterWindowMask;
+
+            let handle=(core.xlib.XCreateWindow)(
+                display,
+                root,
+                x,
+                y,
+                width.max(1) as u32,
+                height.max(1) as u32,
+                0,
+                depth,
+                xlib::InputOutput as u32,
+                visual,
+                xlib::CWColormap|xlib::CWEventMask|xlib::CWBorderPixel,
+                &mut window_attributes
+            );
+
+            if handle==0{
+                return Err(X11Error::InvalidWindow)
+            }
+
+            // The title
+            let name=attributes.name.split('\0').next().unwrap_or("");
+            let c_name=CString::new(name).unwrap();
+            (core.xlib.XStoreName)(display,handle,c_name.as_ptr());
+            (core.xlib.XChangeProperty)(
+                display,
+                handle,
+                core.net_wm_name,
+                core.utf8_string,
+                8,
+                xlib::PropModeReplace,
+                name.as_ptr(),
+                name.len() as i32
+            );
+
+            // The class
+            let mut class_hint=xlib::XClassHint{
+                res_name:class.name.as_ptr() as *mut _,
+                res_class:class.name.as_ptr() as *mut _,
+            };
+            (core.xlib.XSetClassHint)(display,handle,&mut class_hint);
+
+            // Getting `WM_DELETE_WINDOW` instead of being killed
+            let mut protocols=[core.wm_delete_window];
+            (core.xlib.XSetWMProtocols)(display,handle,protocols.as_mut_ptr(),1);
+
+            if attributes.position.is_some() || attributes.size.is_some() || fullscreen{
+                let mut hints:xlib::XSizeHints=zeroed();
+                hints.flags=xlib::USPosition|xlib::USSize;
+                hints.x=x;
+                hints.y=y;
+                hints.width=width;
+                hints.height=height;
+                (core.xlib.XSetWMNormalHints)(display,handle,&mut hints);
+            }
+
+            // The window manager reads the state when the window is mapped
+            let mut states=Vec::new();
+            if attributes.topmost{
+                states.push(core.net_wm_state_above);
+            }
+            if fullscreen{
+                states.push(core.net_wm_state_fullscreen);
+            }
+            if !states.is_empty(){
+                (core.xlib.XChangeProperty)(
+                    display,
+                    handle,
+                    core.net_wm_state,
+                    xlib::XA_ATOM,
+                    32,
+                    xlib::PropModeReplace,
+                    states.as_ptr() as *const u8,
+                    states.len() as i32
+                );
+            }
+
+            core.register_window(handle,WindowEntry{
+                procedure:window_procedure::<W>,
+                data:null_mut(),
+                auto_redraw:attributes.auto_redraw,
+                redraw:false,
+                minimized:false,
+                client_size:[width as u16,height as u16],
+                client_position:[x as i16,y as i16],
+            });
+
+            let window=Window{handle};
+
+            match W::create(&window,create_parameters){
+                Ok(data)=>{
+                    let data=Box::leak(Box::new(data)) as *mut W::Data as *mut c_void;
+                    core.with_window(handle,|entry|entry.data=data);
+                }
+                Err(error)=>{
+                    core.unregister_window(handle);
+                    (core.xlib.XDestroyWindow)(display,handle);
+                    core.flush();
+                    // already destroyed
+                    std::mem::forget(window);
+                    return Err(error)
+                }
+            }
+
+            if attributes.visible{
+                (core.xlib.XMapWindow)(display,handle);
+            }
+
+            core.flush();
+
+            Ok(window)
+        }
+    }
+
+    pub fn handle(&self)->xlib::Window{
+        self.handle
+    }
+
+    /// Returns the connection of the current thread.
+    pub fn display(&self)->*mut xlib::Display{
+        core().map(|core|core.display).unwrap_or(null_mut())
+    }
+}
+
+/// Requests and sending events.
+impl Window{
+    /// Requests a paint.
+    ///
+    /// Windows are painted when there are no other events to process.
+    pub fn redraw(&self){
+        if let Ok(core)=core(){
+            core.with_window(self.handle,|entry|entry.redraw=true);
+        }
+    }
+
+    pub fn destroy(&self)->Result<(),X11Error>{
+        let core=core()?;
+
+        let entry=core.unregister_window(self.handle).ok_or(X11Error::InvalidWindow)?;
+
+        unsafe{
+            (entry.procedure)(self,WindowMessage::Destroy,entry.data);
+
+            (core.xlib.XDestroyWindow)(core.display,self.handle);
+        }
+
+        core.flush();
+
+        Ok(())
+    }
+}
+
+/// Window sizes and positions.
+///
+/// The decorations are drawn by the window manager,
+/// so the window and its client area are the same.
+impl Window{
+    /// Returns the window size.
+    ///
+    /// [width, height]
+    pub fn size(&self)->[u32;2]{
+        self.client_size()
+    }
+
+    /// Returns coordinates of window's upper-left corner.
+    ///
+    /// [x, y]
+    pub fn position(&self)->[i32;2]{
+        core().ok()
+            .and_then(|core|core.client_rectangle(self.handle))
+            .map(|(position,_)|position)
+            .unwrap_or([0;2])
+    }
+
+    /// Returns the window rectangle with the coordinates of it's upper-left and lower-right corners.
+    ///
+    /// [x1, y1, x2, y2]
+    pub fn rectangle(&self)->[i32;4]{
+        let [x,y]=self.position();
+        let [width,height]=self.size();
+        [x,y,x+width as i32,y+height as i32]
+    }
+
+    /// Returns window's client area size.
+    ///
+    /// [width, height]
+    pub fn client_size(&self)->[u32;2]{
+        core().ok()
+            .and_then(|core|core.client_rectangle(self.handle))
+            .map(|(_,size)|size)
+            .unwrap_or([0;2])
+    }
+}
+
+/// Styles and positioning.
+impl Window{
+    /// Asks the window manager to change the fullscreen state.
+    pub fn set_fullscreen(&self,fullscreen:Fullscreen){
+        let core=match core(){
+            Ok(core)=>core,
+            Err(_)=>return,
+        };
+
+        let enabled=match fullscreen{
+            Fullscreen::None=>false,
+            Fullscreen::Monitor(monitor)=>{
+                // The window is made fullscreen on the monitor it's placed on
+                let [x1,y1,x2,y2]=monitor.rectangle();
+                unsafe{
+                    self.set_window_position([x1,y1,x2-x1,y2-y1])
+                }
+                true
+            }
+        };
+
+        unsafe{
+            let mut event:xlib::XClientMessageEvent=zeroed();
+            event.type_=xlib::ClientMessage;
+            event.window=self.handle;
+            event.message_type=core.net_wm_state;
+            event.format=32;
+            // _NET_WM_STATE_REMOVE = 0, _NET_WM_STATE_ADD = 1
+            event.data.set_long(0,enabled as i64);
+            event.data.set_long(1,core.net_wm_state_fullscreen as i64);
+            // Normal application
+            event.data.set_long(3,1);
+
+            let mut event=xlib::XEvent{client_message:event};
+
+            (core.xlib.XSendEvent)(
+                core.display,
+                core.root_window(),
+                xlib::False,
+                xlib::SubstructureRedirectMask|xlib::SubstructureNotifyMask,
+                &mut event
+            );
+        }
+
+        core.flush()
+    }
+
+    /// Moves and resizes the window.
+    ///
+    /// # Safety
+    ///
+    /// The window manager may ignore the request or change the values,
+    /// `resized` and `moved` report the result.
+    pub unsafe fn set_window_position(&self,[x,y,width,height]:[i32;4]){
+        if let Ok(core)=core(){
+            (core.xlib.XMoveResizeWindow)(core.display,self.handle,x,y,width.max(1) as u32,height.max(1) as u32);
+            core.flush()
+        }
+    }
+}
+
+/// Special functions.
+impl Window{
+    pub fn set_auto_redraw(&self,enabled:bool){
+        if let Ok(core)=core(){
+            core.with_window(self.handle,|entry|entry.auto_redraw=enabled);
+        }
+    }
+
+    pub fn auto_redraw(&self)->bool{
+        core().ok()
+            .and_then(|core|core.with_window(self.handle,|entry|entry.auto_redraw))
+            .unwrap_or(false)
+    }
+
+    /// Replaces the window procedure.
+    ///
+    /// # Safety
+    ///
+    /// `W::Data` must be the same as the data of the current procedure.
+    pub unsafe fn set_window_handle<W:WindowProcedure>(&self){
+        if let Ok(core)=core(){
+            core.with_window(self.handle,|entry|entry.procedure=window_procedure::<W>);
+        }
+    }
+}
+
+/// Cursor functions.
+impl Window{
+    /// Returns window's cursor position.
+    pub fn cursor_position(&self)->[i32;2]{
+        let core=match core(){
+            Ok(core)=>core,
+            Err(_)=>return [0;2],
+        };
+
+        unsafe{
+            let [mut root,mut child]=[0;2];
+            let [mut root_x,mut root_y,mut x,mut y]=[0i32;4];
+            let mut mask=0;
+
+            (core.xlib.XQueryPointer)(
+                core.display,
+                self.handle,
+                &mut root,
+                &mut child,
+                &mut root_x,
+                &mut root_y,
+                &mut x,
+                &mut y,
+                &mut mask
+            );
+
+            [x,y]
+        }
+    }
+
+    /// Sets window's cursor position.
+    pub fn set_cursor_position(&self,[x,y]:[i32;2]){
+        if let Ok(core)=core(){
+            unsafe{
+                (core.xlib.XWarpPointer)(core.display,0,self.handle,0,0,0,0,x,y);
+            }
+            core.flush()
+        }
+    }
+
+    pub fn show_cursor(&self,show:bool){
+        let core=match core(){
+            Ok(core)=>core,
+            Err(_)=>return,
+        };
+
+        unsafe{
+            if show{
+                (core.xlib.XUndefineCursor)(core.display,self.handle);
+            }
+            else{
+                // An empty 1x1 cursor
+                let data=[0i8;1];
+                let pixmap=(core.xlib.XCreateBitmapFromData)(core.display,self.handle,data.as_ptr(),1,1);
+                let mut colour:xlib::XColor=zeroed();
+                let cursor=(core.xlib.XCreatePixmapCursor)(core.display,pixmap,pixmap,&mut colour,&mut colour,0,0);
+
+                (core.xlib.XDefineCursor)(core.display,self.handle,cursor);
+
+                (core.xlib.XFreeCursor)(core.display,cursor);
+                (core.xlib.XFreePixmap)(core.display,pixmap);
+            }
+        }
+
+        core.flush()
+    }
+}
+
+impl Drop for Window{
+    fn drop(&mut self){
+        let _=self.destroy();
+    }
+}
+
+
+pub struct WindowAttributes{
+    /// The window name and title.
+    pub name:String,
+
+    /// The window size.
+    ///
+    /// The default is `None` (800x600).
+    pub size:Option<[i32;2]>,
+
+    /// The window position.
+    pub position:Option<[i32;2]>,
+
+    /// The default is `true`.
+    pub visible:bool,
+
+    /// The window should be placed above all non-topmost windows
+    /// and should stay above them,
+    /// even when the window is deactivated.
+    ///
+    /// The default is `false`.
+    pub topmost:bool,
+
+    /// Defines whether a new redraw event is requested
+    /// directly after processing the last one.
+    ///
+    /// The default is `true`.
+    pub auto_redraw:bool,
+
+    /// If `Fullscreen::Monitor` is set the size and position are ignored.
+    ///
+    /// The default is `false`.
+    pub fullscreen:Fullscreen,
+}
+
+impl WindowAttributes{
+    pub fn new(name:&str)->WindowAttributes{
+        Self{
+            name:String::from(name),
+            size:None,
+            position:None,
+            visible:true,
+            topmost:false,
+            auto_redraw:true,
+            fullscreen:Fullscreen::None,
+        }
+    }
+}
@@ -1,7 +1,14 @@
+use crate::event_loop::{
+    SystemClock,
+    EventSource,
+    SourceState,
+};
+
 use crate::windows::{
     Window,
     Event,
-    ProcessEvent,
+    EventLoop,
+    LoopControl,
     WindowEvent,
     MouseButton,
 };
@@ -14,10 +21,12 @@ use winapi::um::{
         TranslateMessage,
         DispatchMessageW,
         MapVirtualKeyW,
+        MsgWaitForMultipleObjects,
 
         WHEEL_DELTA,
         MAPVK_VSC_TO_VK,
         PM_REMOVE,
+        QS_ALLINPUT,
         WM_NULL,
         WM_CREATE,
         WM_DESTROY,
@@ -268,10 +277,6 @@ use winapi::um::{
         WM_APP,
         WM_USER,
     },
-    profileapi::{
-        QueryPerformanceCounter,
-        QueryPerformanceFrequency,
-    },
 };
 
 use std::{
@@ -279,248 +284,88 @@ use std::{
         transmute,
         zeroed
     },
-    ptr::null_mut,
+    ptr::{
+        null,
+        null_mut,
+    },
 };
 
-#[derive(Clone,Debug)]
-pub enum LoopControl{
-    /// The loop is running with the defeault settings.
-    Run,
-
-    /// Updates are disabled.
-    Lazy,
-
-    /// The loop will be closed.
-    Break,
-}
-
-unsafe impl Sync for LoopControl{}
-unsafe impl Send for LoopControl{}
-
-// pub struct Control{
-//     state:LoopControl,
-//     update_interval:i64,
-//     redraw_request_interval:i64,
-// }
-
-/// Represents interval between events or an event rate.
-/// 
-/// The event is disabled if an interval or rate is `0`.
-#[derive(Clone,Copy,Debug)]
-pub enum EventInterval{
-    Ticks(u32),
-    EventsPerSecond(u32),
-    NanoSeconds(u32),
-    Seconds(u32),
+/// Messages of the current thread.
+pub struct WindowsEventSource{
+    message:MSG,
 }
 
-impl EventInterval{
-    pub fn into_ticks(self)->i64{
-        let mut frequency=0i64;
+impl WindowsEventSource{
+    pub fn new()->WindowsEventSource{
         unsafe{
-            QueryPerformanceFrequency(transmute(&mut frequency));
-        }
-
-        match self{
-            EventInterval::Ticks(ticks)=>ticks as i64,
-            EventInterval::EventsPerSecond(updates)=>{
-                if updates==0{
-                    0i64
-                }
-                else{
-                    frequency/updates as i64
-                }
-            }
-            EventInterval::NanoSeconds(nanoseconds)=>{
-                (nanoseconds as i64*frequency)/1_000_000_000i64
-            }
-            EventInterval::Seconds(nanoseconds)=>{
-                nanoseconds as i64*frequency
+            Self{
+                message:zeroed(),
             }
         }
     }
 }
 
-#[derive(Debug,Clone,Copy)]
-pub struct Ticks(pub u64);
-
-impl Ticks{
-    pub fn as_seconds(self)->u64{
-        let mut frequency=0u64;
-        unsafe{
-            QueryPerformanceFrequency(transmute(&mut frequency));
-        }
-        self.0/frequency
+impl Default for WindowsEventSource{
+    fn default()->WindowsEventSource{
+        WindowsEventSource::new()
     }
-
-    pub fn as_nanoseconds(self)->u64{
-        let mut frequency=0u64;
-        unsafe{
-            QueryPerformanceFrequency(transmute(&mut frequency));
-        }
-        (self.0*1_000_000_000u64)/frequency
-    }
-}
-
-// Mister Programmer, may I have some loops?
-//      /\_____/\
-//     /  o   o  \
-//    ( ==  ^  == )
-//     )         (
-//    (           )
-//   ( (  )   (  ) )
-//  (__(__)___(__)__)
-
-pub struct EventLoop{
-    // в тактах
-    update_interval:i64,
-    redraw_request_interval:i64,
 }
 
-impl EventLoop{
-    pub fn new(attributes:EventLoopAttributes)->EventLoop{
-
-        let update_interval=attributes.update_interval.into_ticks();
-
-        let redraw_request_interval=attributes.redraw_request_interval.into_ticks();
-
-        Self{
-            update_interval,
-            redraw_request_interval,
-        }
-    }
-
-    /// Runs an event loop.
-    /// 
-    /// Запускает цикл событий.
-    pub fn run<F:FnMut(Event,&mut LoopControl)>(&self,mut f:F){
+impl EventSource for WindowsEventSource{
+    /// With the `LoopControl::Run` mode messages are polled without waiting
+    /// to keep the updates precise (the system timer is too coarse for them).
+    fn process<F:FnMut(Event,&mut LoopControl)>(
+        &mut self,
+        timeout:Option<u64>,
+        f:&mut F,
+        control:&mut LoopControl
+    )->SourceState{
         unsafe{
-            let mut message:MSG=zeroed();
-
-            // Флаг управления циклом
-            let mut loop_control=LoopControl::Run;
-
-            // Время последнего события обновления в тактах
-            let mut last_update=0i64;
-            let mut current_ticks=0i64;
-            QueryPerformanceCounter(transmute(&mut last_update));
-            let mut last_redraw_request=last_update;
-
-            // Начальное событие
-            f(Event::Process(ProcessEvent::EventLoopStart),&mut loop_control);
-
-            loop{
-                match loop_control{
-                    LoopControl::Run=>{
-                        if PeekMessageW(&mut message,null_mut(),0,0,PM_REMOVE)==1{
-                            match message.message{
-                                WM_QUIT=>{
-                                    f(Event::Process(ProcessEvent::Quit),&mut loop_control);
-                                    break
-                                }
-
-                                _=>event_handler(&message,&mut f,&mut loop_control),
-                            }
-                        }
-
-                        // если интервал не нулевой (событие включёно)
-                        if self.update_interval!=0{
-                            // Текущее время в тактах
-                            QueryPerformanceCounter(transmute(&mut current_ticks));
-                            // проверка интервала события обновления
-                            let ticks_passed=current_ticks-last_update;
-                            if ticks_passed>=self.update_interval{
-                                if ticks_passed<self.update_interval<<1{
-                                    last_update+=self.update_interval;
-                                }
-                                else{
-                                    last_update=current_ticks;
-                                }
-
-                                f(
-                                    Event::Process(ProcessEvent::Update(Ticks(ticks_passed as u64))),
-                                    &mut loop_control
-                                );
-                            }
+            match timeout{
+                Some(timeout)=>{
+                    if PeekMessageW(&mut self.message,null_mut(),0,0,PM_REMOVE)==0{
+                        if let LoopControl::Lazy=control{
+                            // Rounding up to not wake up too early
+                            let milliseconds=timeout.div_ceil(1_000_000).min(u32::MAX as u64-1);
+                            MsgWaitForMultipleObjects(0,null(),0,milliseconds as u32,QS_ALLINPUT);
                         }
-                    },
-
-                    LoopControl::Lazy=>
-                        match GetMessageW(&mut message,null_mut(),0,0){
-                            -1=>break,
-                            0=>{
-                                f(Event::Process(ProcessEvent::Quit),&mut loop_control);
-                                break
-                            }
-
-                            _=>event_handler(&message,&mut f,&mut loop_control),
-                        }
-
-                    LoopControl::Break=>break,
+                        return SourceState::Running
+                    }
                 }
 
-                if !message.hwnd.is_null(){
-                    // если интервал не нулевой (событие включёно)
-                    if self.redraw_request_interval!=0{
-                        // текущее время в тактах
-                        QueryPerformanceCounter(transmute(&mut current_ticks));
-                        // проверка интервала события отрисовки
-                        let ticks_passed=current_ticks-last_redraw_request;
-                        if ticks_passed>=self.redraw_request_interval{
-                            if ticks_passed<self.redraw_request_interval<<1{
-                                last_redraw_request+=self.redraw_request_interval;
-                            }
-                            else{
-                                last_redraw_request=current_ticks;
-                            }
-
-                            let window:&Window=transmute(&message.hwnd);
-                            window.redraw();
-                        }
-                    }
+                None=>match GetMessageW(&mut self.message,null_mut(),0,0){
+                    -1=>return SourceState::Closed,
+                    0=>return SourceState::Quit,
+                    _=>{}
                 }
             }
 
-            // Завершение цикла
-            f(Event::Process(ProcessEvent::EventLoopBreak),&mut loop_control);
-        }
-    }
-}
+            if self.message.message==WM_QUIT{
+                return SourceState::Quit
+            }
 
-impl EventLoop{
-    pub fn set_update_interval(&mut self,interval:EventInterval){
-        self.update_interval=interval.into_ticks()
+            event_handler(&self.message,f,control);
+            SourceState::Running
+        }
     }
 
-    pub fn set_redraw_request_interval(&mut self,interval:EventInterval){
-        self.redraw_request_interval=interval.into_ticks()
+    /// Requests a redraw of the window of the last message.
+    fn request_redraw(&mut self){
+        if !self.message.hwnd.is_null(){
+            unsafe{
+                let window:&Window=transmute(&self.message.hwnd);
+                window.redraw();
+            }
+        }
     }
 }
 
-#[derive(Clone,Debug)]
-pub struct EventLoopAttributes{
-    /// Defines an interval between `Process::Update` events.
-    /// 
-    /// The event is disabled if an interval or rate is `0`.
-    /// 
-    /// The default is `EventInteval::UpdatesPerSecond(50u32)`.
-    pub update_interval:EventInterval,
-
-    /// Defines an interval between `Window::Redraw` requests.
-    /// 
-    /// The event is disabled if an interval or rate is `0`.
+impl EventLoop{
+    /// Runs an event loop.
     /// 
-    /// The default is `EventInteval::UpdatesPerSecond(0u32)`
-    /// (disabled).
-    pub redraw_request_interval:EventInterval,
-}
-
-impl EventLoopAttributes{
-    pub fn new()->EventLoopAttributes{
-        Self{
-            update_interval:EventInterval::EventsPerSecond(50u32),
-            redraw_request_interval:EventInterval::EventsPerSecond(0u32),
-        }
+    /// Запускает цикл событий.
+    pub fn run<F:FnMut(Event,&mut LoopControl)>(&self,f:F){
+        self.run_with(&SystemClock::new(),&mut WindowsEventSource::new(),f)
     }
 }
 
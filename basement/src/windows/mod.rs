@@ -25,8 +25,7 @@ pub use opengl::{
     OpenGLRenderContextAttributes,
 };
 
-mod event;
-pub use event::{
+pub use crate::event_loop::{
     Event,
     ProcessEvent,
     WindowEvent,
@@ -55,7 +54,8 @@ pub use window::{
 };
 
 mod event_loop;
-pub use event_loop::{
+pub use event_loop::WindowsEventSource;
+pub use crate::event_loop::{
     Ticks,
     EventLoop,
     LoopControl,
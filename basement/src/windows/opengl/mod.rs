@@ -1,4 +1,9 @@
-use crate::graphics::GLCore;
+use crate::graphics::{
+    GLCore,
+    core::GLLoader,
+};
+
+use std::ffi::c_void;
 
 mod context;
 pub use context::{
@@ -52,6 +57,12 @@ impl OpenGraphicsLibrary{
     }
 }
 
+impl GLLoader for OpenGraphicsLibrary{
+    fn get_proc_address(&self,name:&str)->*const c_void{
+        get_proc_address(self.module,name) as *const c_void
+    }
+}
+
 pub fn get_proc_address(module:HMODULE,name:&str)->PROC{
     unsafe{
         let ptr=wglGetProcAddress(name.as_ptr() as *const i8);
@@ -0,0 +1,230 @@
+use super::{
+    AudioEffect,
+    mix_block_frames,
+    effects::{
+        EffectChain,
+        set_chain_sample_rate,
+    },
+};
+
+/// Шина сведения - группа треков со своей громкостью,
+/// выключением, соло, эффектами и плавным изменением громкости.
+///
+/// Сведённая шина добавляется в родительскую шину или в общую.
+pub (crate) struct Bus{
+    /// Родительская шина, `None` - общая шина.
+    ///
+    /// Номер родительской шины всегда меньше номера шины,
+    /// так что шины сводятся с конца.
+    pub parent:Option<usize>,
+
+    volume:f32,
+    mute:bool,
+    solo:bool,
+
+    /// Цепочки эффектов каналов вывода.
+    pub effects:Vec<EffectChain>,
+
+    /// Уровень плавного изменения громкости (множитель громкости).
+    fade:f32,
+    /// Уровень в конце плавного изменения.
+    fade_target:f32,
+    /// Изменение уровня за кадр.
+    fade_step:f32,
+    /// Кадров плавного изменения осталось.
+    fade_left:u32,
+
+    /// Громкость в конце последнего блока.
+    gain:f32,
+
+    /// Сведённый блок шины (каналы чередуются).
+    pub block:Vec<f32>,
+}
+
+impl Bus{
+    pub fn new(parent:Option<usize>,channels:usize)->Bus{
+        Self{
+            parent,
+
+            volume:1f32,
+            mute:false,
+            solo:false,
+
+            effects:(0..channels).map(|_|Vec::new()).collect(),
+
+            fade:1f32,
+            fade_target:1f32,
+            fade_step:0f32,
+            fade_left:0u32,
+
+            gain:1f32,
+
+            block:vec![0f32;mix_block_frames*channels],
+        }
+    }
+
+    /// Устанавливает количество каналов.
+    ///
+    /// Эффекты удалённых каналов удаляются.
+    pub fn set_channels(&mut self,channels:usize){
+        self.block.resize(mix_block_frames*channels,0f32);
+        self.effects.resize_with(channels,Vec::new);
+    }
+
+    pub fn set_sample_rate(&mut self,sample_rate:u32){
+        for chain in &mut self.effects{
+            set_chain_sample_rate(chain,sample_rate)
+        }
+    }
+
+    pub fn set_volume(&mut self,volume:f32){
+        self.volume=volume
+    }
+
+    pub fn set_mute(&mut self,mute:bool){
+        self.mute=mute
+    }
+
+    pub fn set_solo(&mut self,solo:bool){
+        self.solo=solo
+    }
+
+    pub fn solo(&self)->bool{
+        self.solo
+    }
+
+    /// Добавляет эффект в конец цепочки канала.
+    ///
+    /// Если нет такого канала, ничего не происходит.
+    pub fn add_effect(&mut self,channel:usize,mut effect:Box<dyn AudioEffect>,sample_rate:u32){
+        if let Some(chain)=self.effects.get_mut(channel){
+            effect.set_sample_rate(sample_rate);
+            chain.push(effect)
+        }
+    }
+
+    pub fn clear_effects(&mut self){
+        for chain in &mut self.effects{
+            chain.clear()
+        }
+    }
+
+    /// Плавно изменяет уровень громкости до `level` за `frames` кадров.
+    pub fn fade(&mut self,level:f32,frames:u32){
+        if frames==0{
+            self.fade=level;
+            self.fade_left=0;
+        }
+        else{
+            self.fade_step=(level-self.fade)/frames as f32;
+            self.fade_left=frames;
+        }
+        self.fade_target=level;
+    }
+
+    /// Возвращает громкости шины в начале и в конце блока
+    /// и продвигает плавное изменение громкости на `frames` кадров.
+    pub fn next_gains(&mut self,frames:usize,audible:bool)->(f32,f32){
+        if self.fade_left as usize<=frames{
+            self.fade=self.fade_target;
+            self.fade_left=0;
+        }
+        else{
+            self.fade+=self.fade_step*frames as f32;
+            self.fade_left-=frames as u32;
+        }
+
+        let start=self.gain;
+        self.gain=if audible{self.volume*self.fade}else{0f32};
+
+        (start,self.gain)
+    }
+}
+
+/// Проверяет, слышна ли шина с учётом выключения и соло.
+///
+/// Если есть шины с соло, слышны только они,
+/// их дочерние шины и родительские шины (через них проходит звук).
+pub (crate) fn bus_audible(buses:&[Bus],index:usize,any_solo:bool)->bool{
+    if buses[index].mute{
+        return false
+    }
+
+    if !any_solo{
+        return true
+    }
+
+    // Соло у шины или у родительских шин
+    let mut bus=Some(index);
+    while let Some(b)=bus{
+        if buses[b].solo{
+            return true
+        }
+        bus=buses[b].parent;
+    }
+
+    // Соло у дочерних шин (их номера больше)
+    for child in index+1..buses.len(){
+        if buses[child].solo{
+            let mut bus=buses[child].parent;
+            while let Some(b)=bus{
+                if b==index{
+                    return true
+                }
+                bus=buses[b].parent;
+            }
+        }
+    }
+
+    false
+}
+
+/// Добавляет блок `source` в блок `target` (каналы чередуются)
+/// с громкостью, плавно изменяющейся от `start` до `end`.
+pub (crate) fn mix_with_ramp(source:&[f32],target:&mut [f32],channels:usize,start:f32,end:f32){
+    if channels==0{
+        return
+    }
+
+    if start==end{
+        if end!=0f32{
+            for (t,&s) in target.iter_mut().zip(source.iter()){
+                *t+=s*end;
+            }
+        }
+        return
+    }
+
+    let step=(end-start)/(source.len()/channels) as f32;
+    for (n,(t,s)) in target.chunks_exact_mut(channels).zip(source.chunks_exact(channels)).enumerate(){
+        let gain=start+step*(n+1) as f32;
+        for (t,&s) in t.iter_mut().zip(s.iter()){
+            *t+=s*gain;
+        }
+    }
+}
+
+/// Умножает блок (каналы чередуются) на громкость,
+/// плавно изменяющуюся от `start` до `end`.
+pub (crate) fn apply_ramp(block:&mut [f32],channels:usize,start:f32,end:f32){
+    if channels==0{
+        return
+    }
+
+    if start==end{
+        if end!=1f32{
+            for sample in block.iter_mut(){
+                *sample*=end;
+            }
+        }
+        return
+    }
+
+    let step=(end-start)/(block.len()/channels) as f32;
+    for (n,frame) in block.chunks_exact_mut(channels).enumerate(){
+        let gain=start+step*(n+1) as f32;
+        for sample in frame{
+            *sample*=gain;
+        }
+    }
+}
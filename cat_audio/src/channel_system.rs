@@ -1,14 +1,37 @@
 use super::{
     MonoTrack,
+    TrackSet,
+    semitones_to_rate,
+    LoopRegion,
+    TrackPosition,
+    StreamReceiver,
+    SoundSource,
     TrackIter,
+    ResamplerQuality,
+    PanLaw,
+    Listener,
+    TrackPlacement,
+    AudioEvent,
+    EventSender,
+    bus::{
+        Bus,
+        bus_audible,
+        mix_with_ramp,
+        apply_ramp,
+    },
+    effects::{
+        AudioEffect,
+        EffectChain,
+        process_chain,
+        set_chain_sample_rate,
+    },
 };
 
 use cat_engine_basement::support::SyncRawMutPtr;
 
-use std::{
-    ops::AddAssign,
-    cmp::Ordering
-};
+/// Наибольшее количество кадров, сводимых за раз.
+/// Эффекты обрабатывают блоки не больше этого размера.
+pub (crate) const mix_block_frames:usize=512;
 
 
 /// Система распределения треков по каналам.
@@ -16,8 +39,30 @@ pub struct ChannelSystem{
     /// Выходящая (системная) частота дискретизации.
     sample_rate:u32,
 
-    /// Массива каналов.
-    channel_frame:Vec<f32>,
+    /// Качество перевода частоты треков по умолчанию.
+    resampler_quality:ResamplerQuality,
+
+    /// Количество каналов вывода.
+    system_channels:usize,
+
+    /// Сведённый блок кадров (каналы чередуются).
+    block:Vec<f32>,
+
+    /// Блок значений трека (для эффектов трека).
+    track_block:Vec<f32>,
+
+    /// Блок значений канала (для эффектов канала).
+    channel_block:Vec<f32>,
+
+    /// Цепочки эффектов каналов вывода.
+    channel_effects:Vec<EffectChain>,
+
+    /// Шины сведения.
+    buses:Vec<Bus>,
+
+    /// Громкость треков без шины в конце последнего блока
+    /// (они не слышны, если у какой-то шины включено соло).
+    direct_gain:f32,
 
 
 
@@ -33,7 +78,9 @@ pub struct ChannelSystem{
     /// 
     /// первый индекс - индекс трека в хралище,
     /// второй - индекс номера его итератора
-    track_owners:Vec<(usize,usize)>,
+    /// 
+    /// `None` для потоковых треков, которых нет в хранилище.
+    track_owners:Vec<Option<(usize,usize)>>,
 
 
 
@@ -44,6 +91,26 @@ pub struct ChannelSystem{
     /// Матрица (нет) распределения треков по каналам.
     channels:Vec<Vec<usize>>,
 
+    /// Размещение треков между их каналами.
+    placements:Vec<TrackPlacement>,
+
+    /// Шины треков.
+    track_buses:Vec<Option<usize>>,
+
+    /// Громкости каналов треков в конце последнего блока.
+    gains:Vec<Vec<f32>>,
+
+    /// Громкости каналов трека в конце текущего блока.
+    target_gains:Vec<f32>,
+
+    /// Слушатель для размещённых на плоскости треков.
+    listener:Listener,
+
+    pan_law:PanLaw,
+
+    /// Состояние генератора случайных чисел (для отклонений высоты звука).
+    random:u32,
+
     /// Свободные ячейки в буфере треков.
     free_slots:Vec<usize>,
 
@@ -57,7 +124,10 @@ impl ChannelSystem{
         iter_indices:SyncRawMutPtr<Vec<Vec<usize>>>,
         sample_rate:u32,
         tracks_cap:usize,
-        channels:usize
+        channels:usize,
+        resampler_quality:ResamplerQuality,
+        pan_law:PanLaw,
+        listener:Listener
     )->ChannelSystem{
         // Индексы треков в хранилище, на которые ссылаются треки из буфера
         let mut track_owners=Vec::with_capacity(tracks_cap);
@@ -66,7 +136,7 @@ impl ChannelSystem{
         let mut free_slots=Vec::with_capacity(tracks_cap);
 
         for c in 0..tracks_cap{
-            track_owners.push((0usize,0usize));
+            track_owners.push(None);
             play_buffer.push(TrackIter::empty());
             free_slots.push(c);
         }
@@ -76,6 +146,8 @@ impl ChannelSystem{
 
             sample_rate,
 
+            resampler_quality,
+
             track_owners,
 
             play_buffer,
@@ -85,7 +157,33 @@ impl ChannelSystem{
 
             channels:Vec::with_capacity(tracks_cap),
 
-            channel_frame:vec![0f32;channels],
+            placements:Vec::with_capacity(tracks_cap),
+
+            track_buses:Vec::with_capacity(tracks_cap),
+
+            gains:Vec::with_capacity(tracks_cap),
+
+            target_gains:Vec::new(),
+
+            listener,
+
+            pan_law,
+
+            system_channels:channels,
+
+            block:vec![0f32;mix_block_frames*channels],
+
+            track_block:vec![0f32;mix_block_frames],
+
+            channel_block:vec![0f32;mix_block_frames],
+
+            channel_effects:(0..channels).map(|_|Vec::new()).collect(),
+
+            buses:Vec::new(),
+
+            direct_gain:1f32,
+
+            random:random_seed(),
         }
     }
 
@@ -125,6 +223,9 @@ impl ChannelSystem{
         for iters in self.iter_indices.as_mut(){
             iters.clear()
         }
+        for &iter in &self.playlist{
+            self.play_buffer[iter].release()
+        }
         // Занесение пустых слотов в очередь
         unsafe{
             self.free_slots.set_len(self.free_slots.capacity());
@@ -134,6 +235,9 @@ impl ChannelSystem{
         }
         self.playlist.clear();
         self.channels.clear();
+        self.placements.clear();
+        self.track_buses.clear();
+        self.gains.clear();
     }
 }
 
@@ -141,27 +245,43 @@ impl ChannelSystem{
 impl ChannelSystem{
     /// Добавляет моно-канальный трек для проигрывания.
     /// 
-    /// Если плейлист переполнен, то ничего не происходит.
-    pub fn add_track(
-        &mut self,
-        owner_index:usize,
-        track:&MonoTrack,
-        channels:Vec<usize>,
-        repeats:u32,
-        volume:f32
-    ){
+    /// Если плейлист переполнен, то ничего не происходит
+    /// и возвращается `false`.
+    pub fn add_track(&mut self,track:&MonoTrack,set:TrackSet)->bool{
+        let TrackSet{index:owner_index,channels,repeats,volume,quality,effects,placement,fade_in,loop_region,bus,rate,pitch_variation}=set;
+
+        // Случайное отклонение высоты звука
+        let rate=if pitch_variation!=0f32{
+            rate*semitones_to_rate(pitch_variation*self.next_random())
+        }
+        else{
+            rate
+        };
+
         // Выбор свободного слота (номер итератора в буфере)
         if let Some(iter_index)=self.free_slots.pop(){
             // Ссылка на итератор
             let iter=&mut self.play_buffer[iter_index];
             // Установка трека в итератор
-            iter.set_track(track,self.sample_rate,repeats,volume);
+            let quality=quality.unwrap_or(self.resampler_quality);
+            iter.set_track(track,self.sample_rate,repeats,volume,quality,loop_region);
+            if rate!=1f32{
+                iter.set_rate(rate);
+            }
+            for effect in effects{
+                iter.add_effect(effect,self.sample_rate);
+            }
+            // Плавное появление
+            if fade_in>0f32{
+                iter.set_fade(0f32);
+                iter.fade(1f32,seconds_to_frames(fade_in,self.sample_rate),false);
+            }
 
             // Ссылка на список номеров итераторов
             let iter_indices=&mut self.iter_indices.as_mut()[owner_index];
 
             // Запись индекса трека в хранилище и индекса номера его итератора
-            self.track_owners[iter_index]=(owner_index,iter_indices.len());
+            self.track_owners[iter_index]=Some((owner_index,iter_indices.len()));
 
             // Добавление номера итератора в список номеров итераторов
             iter_indices.push(iter_index);
@@ -169,8 +289,81 @@ impl ChannelSystem{
             // Добавление индекса трека в буфере
             self.playlist.push(iter_index);
             // Добавление каналов трека
-            self.channels.push(channels);
+            self.push_channels(channels,placement,bus);
+            true
+        }
+        else{
+            false
+        }
+    }
+
+    /// Добавляет канал потокового трека для проигрывания.
+    /// 
+    /// Если плейлист переполнен, то ничего не происходит
+    /// (канал удаляется) и возвращается `false`.
+    pub fn add_stream(&mut self,stream:StreamReceiver,channels:Vec<usize>,volume:f32)->bool{
+        if let Some(iter_index)=self.free_slots.pop(){
+            self.play_buffer[iter_index].set_stream(stream,self.sample_rate,volume,self.resampler_quality);
+
+            // Потоковых треков нет в хранилище
+            self.track_owners[iter_index]=None;
+
+            self.playlist.push(iter_index);
+            self.push_channels(channels,TrackPlacement::Fixed,None);
+            true
+        }
+        else{
+            false
+        }
+    }
+
+    /// Добавляет каналы потокового трека для проигрывания.
+    /// 
+    /// Каналы добавляются все вместе: если для них не хватает места в плейлисте,
+    /// то ничего не происходит (каналы удаляются) и возвращается `false`.
+    pub fn add_streams(&mut self,streams:Vec<(StreamReceiver,Vec<usize>)>,volume:f32)->bool{
+        if self.free_slots.len()<streams.len(){
+            return false
         }
+
+        for (stream,channels) in streams{
+            self.add_stream(stream,channels,volume);
+        }
+
+        true
+    }
+
+    /// Добавляет источник звука для проигрывания.
+    /// 
+    /// Если плейлист переполнен, то ничего не происходит
+    /// (источник удаляется) и возвращается `false`.
+    pub fn add_source(&mut self,source:Box<dyn SoundSource>,channels:Vec<usize>,volume:f32)->bool{
+        if let Some(iter_index)=self.free_slots.pop(){
+            self.play_buffer[iter_index].set_source(source,self.sample_rate,volume,self.resampler_quality);
+
+            // Источников нет в хранилище
+            self.track_owners[iter_index]=None;
+
+            self.playlist.push(iter_index);
+            self.push_channels(channels,TrackPlacement::Fixed,None);
+            true
+        }
+        else{
+            false
+        }
+    }
+
+    /// Добавляет каналы трека с начальными громкостями и шину трека.
+    /// 
+    /// Если нет такой шины, трек сводится сразу в общую шину.
+    fn push_channels(&mut self,channels:Vec<usize>,placement:TrackPlacement,bus:Option<usize>){
+        let mut gains=vec![0f32;channels.len()];
+        placement.gains(&self.listener,self.pan_law,&mut gains);
+
+        self.channels.push(channels);
+        self.placements.push(placement);
+        self.track_buses.push(bus.filter(|&bus|bus<self.buses.len()));
+        self.gains.push(gains);
     }
 
     /// Удаляет трек из плейлиста.
@@ -183,24 +376,32 @@ impl ChannelSystem{
 
             // Удаление каналов распределения
             self.channels.remove(index);
+            self.placements.remove(index);
+            self.track_buses.remove(index);
+            self.gains.remove(index);
 
             // Добавление свободного номера в очередь
             self.free_slots.push(iter_index);
 
-            // Номер трека в хранилище и индекс номера итератора
-            let (track_owner,iter_index)=self.track_owners[iter_index];
-
-            // Ссылка на список номеров итераторов
-            let iter_indices=&mut self.iter_indices.as_mut()[track_owner];
+            // Освобождение канала потокового трека
+            self.play_buffer[iter_index].release();
 
-            // Удаление номера итератора
-            iter_indices.remove(iter_index);
-
-            if iter_index<iter_indices.len(){
-                // Уменьшение индексов номеров итераторов из списка,
-                // находящихся выше
-                for &i in &iter_indices[iter_index..]{
-                    self.track_owners[i].1-=1;
+            // Номер трека в хранилище и индекс номера итератора
+            if let Some((track_owner,iter_index))=self.track_owners[iter_index]{
+                // Ссылка на список номеров итераторов
+                let iter_indices=&mut self.iter_indices.as_mut()[track_owner];
+
+                // Удаление номера итератора
+                iter_indices.remove(iter_index);
+
+                if iter_index<iter_indices.len(){
+                    // Уменьшение индексов номеров итераторов из списка,
+                    // находящихся выше
+                    for &i in &iter_indices[iter_index..]{
+                        if let Some(owner)=&mut self.track_owners[i]{
+                            owner.1-=1;
+                        }
+                    }
                 }
             }
         }
@@ -210,37 +411,62 @@ impl ChannelSystem{
 /// Установка параметров.
 impl ChannelSystem{
     /// Устанавливает новую частоту системы.
-    /// Обновляет все треки.
+    /// Обновляет все треки и эффекты.
     pub fn set_system_sample_rate(&mut self,sample_rate:u32){
         self.sample_rate=sample_rate;
 
         for &track in &self.playlist{
             self.play_buffer[track].set_system_sample_rate(sample_rate)
         }
+
+        for chain in &mut self.channel_effects{
+            set_chain_sample_rate(chain,sample_rate)
+        }
+
+        for bus in &mut self.buses{
+            bus.set_sample_rate(sample_rate)
+        }
     }
 
     /// Устанавливает нового количество каналов.
+    /// 
+    /// Эффекты удалённых каналов удаляются.
     pub fn set_system_channels(&mut self,channels:u16){
-        // Текущее количество каналов
-        let current_channels=self.channel_frame.len();
+        let channels=channels as usize;
 
-        match current_channels.cmp(&(channels as usize)){
-            Ordering::Equal=>{} // Количество каналов не изменилось
+        self.system_channels=channels;
+        self.block.resize(mix_block_frames*channels,0f32);
+        self.channel_effects.resize_with(channels,Vec::new);
 
-            Ordering::Greater=>unsafe{ // Количество каналов уменьшилось
-                self.channel_frame.set_len(channels as usize);
-            }
+        for bus in &mut self.buses{
+            bus.set_channels(channels)
+        }
+    }
 
-            Ordering::Less=>{ // Количество каналов увеличилось
-                let add=channels as usize-current_channels;
-                self.channel_frame.reserve_exact(add);
-                for _ in 0..add{
-                    self.channel_frame.push(0f32);
-                }
-            }
+    /// Устанавливает размещение трека.
+    /// 
+    /// Если нет такого трека, ничего не происходит.
+    pub fn set_track_placement(&mut self,index:usize,placement:TrackPlacement){
+        if let Some(current)=self.placements.get_mut(index){
+            *current=placement
+        }
+    }
+
+    /// Перемещает размещённый на плоскости трек.
+    /// 
+    /// Если нет такого трека или он не размещён на плоскости,
+    /// ничего не происходит.
+    pub fn set_track_position(&mut self,index:usize,position:[f32;2]){
+        if let Some(TrackPlacement::Spatial(source))=self.placements.get_mut(index){
+            source.position=position
         }
     }
 
+    /// Устанавливает слушателя.
+    pub fn set_listener(&mut self,listener:Listener){
+        self.listener=listener
+    }
+
     /// Устанавливает громкость трека.
     /// 
     /// Если нет такого трека, ничего не происходит.
@@ -249,45 +475,363 @@ impl ChannelSystem{
             self.play_buffer[track].set_volume(volume)
         }
     }
+
+    /// Устанавливает скорость проигрывания трека.
+    /// 
+    /// Если нет такого трека, ничего не происходит.
+    pub fn set_track_rate(&mut self,index:usize,rate:f32){
+        if let Some(&track)=self.playlist.get(index){
+            self.play_buffer[track].set_rate(rate)
+        }
+    }
+
+    /// Возвращает случайное число из [-1; 1] (xorshift).
+    fn next_random(&mut self)->f32{
+        let mut x=self.random;
+        x^=x<<13;
+        x^=x>>17;
+        x^=x<<5;
+        self.random=x;
+
+        (x as f64/u32::MAX as f64*2f64-1f64) as f32
+    }
+}
+
+/// Начальное состояние генератора случайных чисел (не нулевое).
+fn random_seed()->u32{
+    let nanos=std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|time|time.subsec_nanos())
+        .unwrap_or(0);
+
+    nanos|1
+}
+
+/// Положение в треках.
+impl ChannelSystem{
+    /// Переходит к данному значению трека из плейлиста.
+    /// 
+    /// Если нет такого трека или он потоковый, ничего не происходит.
+    pub fn seek_track(&mut self,index:usize,frame:usize){
+        if let Some(&track)=self.playlist.get(index){
+            self.play_buffer[track].seek(frame)
+        }
+    }
+
+    /// Устанавливает повторяемую часть трека из плейлиста.
+    /// 
+    /// Если нет такого трека, ничего не происходит.
+    pub fn set_track_loop(&mut self,index:usize,loop_region:Option<LoopRegion>){
+        if let Some(&track)=self.playlist.get(index){
+            self.play_buffer[track].set_loop(loop_region)
+        }
+    }
+
+    /// Возвращает положение в треке из плейлиста.
+    pub fn track_position(&self,index:usize)->Option<TrackPosition>{
+        self.playlist.get(index).map(|&track|self.play_buffer[track].position())
+    }
+}
+
+/// Плавное изменение громкости.
+impl ChannelSystem{
+    /// Плавно изменяет уровень громкости трека из плейлиста
+    /// за `seconds` секунд и останавливает трек, если `stop`.
+    /// 
+    /// Если нет такого трека, ничего не происходит.
+    pub fn fade_track(&mut self,index:usize,level:f32,seconds:f32,stop:bool){
+        if let Some(&track)=self.playlist.get(index){
+            let frames=seconds_to_frames(seconds,self.sample_rate);
+            self.play_buffer[track].fade(level,frames,stop)
+        }
+    }
+
+    /// Плавно убирает трек `from` и проявляет трек `to` из плейлиста.
+    /// 
+    /// Если нет таких треков, ничего не происходит.
+    pub fn crossfade_tracks(&mut self,from:usize,to:usize,seconds:f32){
+        if from==to || from>=self.playlist.len() || to>=self.playlist.len(){
+            return
+        }
+
+        let frames=seconds_to_frames(seconds,self.sample_rate);
+
+        self.play_buffer[self.playlist[from]].fade(0f32,frames,true);
+
+        let to=&mut self.play_buffer[self.playlist[to]];
+        to.set_fade(0f32);
+        to.fade(1f32,frames,false);
+    }
+
+    /// Плавно изменяет уровень громкости итератора из буфера.
+    /// 
+    /// Если такого итератора нет,
+    /// ничего не происходит.
+    pub fn fade_buffer_iter(&mut self,index:usize,level:f32,seconds:f32,stop:bool){
+        let frames=seconds_to_frames(seconds,self.sample_rate);
+        if let Some(iter)=self.play_buffer.get_mut(index){
+            iter.fade(level,frames,stop)
+        }
+    }
+}
+
+/// Шины.
+impl ChannelSystem{
+    /// Добавляет шину, сводимую в родительскую шину.
+    /// 
+    /// Если нет такой родительской шины, шина сводится в общую.
+    pub fn add_bus(&mut self,parent:Option<usize>){
+        let parent=parent.filter(|&parent|parent<self.buses.len());
+        self.buses.push(Bus::new(parent,self.system_channels))
+    }
+
+    /// Устанавливает громкость шины.
+    /// 
+    /// Если нет такой шины, ничего не происходит.
+    pub fn set_bus_volume(&mut self,bus:usize,volume:f32){
+        if let Some(bus)=self.buses.get_mut(bus){
+            bus.set_volume(volume)
+        }
+    }
+
+    /// Выключает или включает шину.
+    /// 
+    /// Если нет такой шины, ничего не происходит.
+    pub fn set_bus_mute(&mut self,bus:usize,mute:bool){
+        if let Some(bus)=self.buses.get_mut(bus){
+            bus.set_mute(mute)
+        }
+    }
+
+    /// Включает или выключает соло шины.
+    /// 
+    /// Если нет такой шины, ничего не происходит.
+    pub fn set_bus_solo(&mut self,bus:usize,solo:bool){
+        if let Some(bus)=self.buses.get_mut(bus){
+            bus.set_solo(solo)
+        }
+    }
+
+    /// Плавно изменяет уровень громкости шины за `seconds` секунд.
+    /// 
+    /// Если нет такой шины, ничего не происходит.
+    pub fn fade_bus(&mut self,bus:usize,level:f32,seconds:f32){
+        let frames=seconds_to_frames(seconds,self.sample_rate);
+        if let Some(bus)=self.buses.get_mut(bus){
+            bus.fade(level,frames)
+        }
+    }
+
+    /// Добавляет эффект в конец цепочки канала шины.
+    /// 
+    /// Если нет такой шины или канала, ничего не происходит.
+    pub fn add_bus_effect(&mut self,bus:usize,channel:usize,effect:Box<dyn AudioEffect>){
+        if let Some(bus)=self.buses.get_mut(bus){
+            bus.add_effect(channel,effect,self.sample_rate)
+        }
+    }
+
+    /// Удаляет все эффекты шины.
+    /// 
+    /// Если нет такой шины, ничего не происходит.
+    pub fn clear_bus_effects(&mut self,bus:usize){
+        if let Some(bus)=self.buses.get_mut(bus){
+            bus.clear_effects()
+        }
+    }
+}
+
+/// Количество кадров за данное время.
+fn seconds_to_frames(seconds:f32,sample_rate:u32)->u32{
+    (seconds.max(0f32)*sample_rate as f32).round() as u32
+}
+
+/// Эффекты.
+impl ChannelSystem{
+    /// Добавляет эффект в конец цепочки трека из плейлиста.
+    /// 
+    /// Если нет такого трека, ничего не происходит.
+    pub fn add_track_effect(&mut self,index:usize,effect:Box<dyn AudioEffect>){
+        if let Some(&track)=self.playlist.get(index){
+            self.play_buffer[track].add_effect(effect,self.sample_rate)
+        }
+    }
+
+    /// Удаляет все эффекты трека из плейлиста.
+    /// 
+    /// Если нет такого трека, ничего не происходит.
+    pub fn clear_track_effects(&mut self,index:usize){
+        if let Some(&track)=self.playlist.get(index){
+            self.play_buffer[track].clear_effects()
+        }
+    }
+
+    /// Добавляет эффект в конец цепочки канала вывода.
+    /// 
+    /// Если нет такого канала, ничего не происходит.
+    pub fn add_channel_effect(&mut self,channel:usize,mut effect:Box<dyn AudioEffect>){
+        if let Some(chain)=self.channel_effects.get_mut(channel){
+            effect.set_sample_rate(self.sample_rate);
+            chain.push(effect)
+        }
+    }
+
+    /// Удаляет все эффекты канала вывода.
+    /// 
+    /// Если нет такого канала, ничего не происходит.
+    pub fn clear_channel_effects(&mut self,channel:usize){
+        if let Some(chain)=self.channel_effects.get_mut(channel){
+            chain.clear()
+        }
+    }
 }
 
 /// Итерации.
 impl ChannelSystem{
-    /// Возвращает фрейм каналов.
-    /// Распределяет все треки по каналам.
-    pub fn next_frame(&mut self)->&mut Vec<f32>{
-        // Отчистка фрейма каналов
-        for channel in &mut self.channel_frame{
-            *channel=0f32;
+    /// Возвращает блок из `frames` кадров (каналы чередуются),
+    /// `frames` не больше `mix_block_frames`.
+    /// Распределяет все треки по каналам и применяет эффекты.
+    /// Отправляет уведомления о повторениях и завершении треков.
+    pub fn next_block(&mut self,frames:usize,events:&EventSender)->&mut [f32]{
+        let channels=self.system_channels;
+        let frames=frames.min(mix_block_frames);
+
+        // Отчистка блоков
+        for sample in &mut self.block[..frames*channels]{
+            *sample=0f32;
+        }
+        for bus in &mut self.buses{
+            for sample in &mut bus.block[..frames*channels]{
+                *sample=0f32;
+            }
         }
 
         // Перебор треков
         let mut c=0usize;
-        'playlist:while c<self.playlist.len(){
+        while c<self.playlist.len(){
             // Трек из плейлиста (итератор)
             let track=&mut self.play_buffer[self.playlist[c]];
 
-            // Следующее значение трека (итератора)
-            if let Some(sample)=track.next(){
-                // Перебор индексов каналов для вывода трека
-                for &channel in &self.channels[c]{
-                    // Добавление значения трека в канал, если такой есть
-                    if let Some(channel)=self.channel_frame.get_mut(channel){
-                        channel.add_assign(sample);
+            let track_block=&mut self.track_block[..frames];
+
+            // Заполнение блока трека,
+            // после конца трека - тишина
+            let mut finished=false;
+            for sample in track_block.iter_mut(){
+                *sample=match track.next(){
+                    Some(sample)=>sample,
+                    None=>{
+                        finished=true;
+                        0f32
+                    }
+                };
+            }
+
+            track.process_effects(track_block);
+
+            // Номер трека в хранилище
+            let owner=self.track_owners[self.playlist[c]].map(|(owner,_)|owner);
+
+            if let Some(owner)=owner{
+                for _ in 0..track.take_loops(){
+                    events.send(AudioEvent::LoopCompleted(owner))
+                }
+            }
+
+            // Громкости каналов к концу блока
+            let track_channels=&self.channels[c];
+            self.target_gains.resize(track_channels.len(),0f32);
+            self.placements[c].gains(&self.listener,self.pan_law,&mut self.target_gains);
+
+            // Блок шины трека или общий
+            let block=match self.track_buses[c]{
+                Some(bus)=>&mut self.buses[bus].block,
+                None=>&mut self.block,
+            };
+
+            // Перебор индексов каналов для вывода трека
+            for ((&channel,gain),&target) in track_channels.iter().zip(self.gains[c].iter_mut()).zip(self.target_gains.iter()){
+                // Добавление значений трека в канал, если такой есть
+                if channel<channels{
+                    if *gain==target{
+                        for (frame,&sample) in block.chunks_exact_mut(channels).zip(track_block.iter()){
+                            frame[channel]+=sample*target;
+                        }
+                    }
+                    else{
+                        // Плавное изменение громкости в течение блока
+                        let step=(target-*gain)/frames as f32;
+                        for (n,(frame,&sample)) in block.chunks_exact_mut(channels).zip(track_block.iter()).enumerate(){
+                            frame[channel]+=sample*(*gain+step*(n+1) as f32);
+                        }
                     }
                 }
+
+                *gain=target;
             }
-            else{
+
+            if finished{
                 // Удаление завершённых треков
                 // (полностью проигранных)
                 self.remove_track(c);
-                continue 'playlist
+                events.send(AudioEvent::TrackFinished(owner));
+            }
+            else{
+                c+=1;
             }
+        }
+
+        let any_solo=self.buses.iter().any(|bus|bus.solo());
+
+        // Треки без шины
+        let direct_gain=if any_solo{0f32}else{1f32};
+        apply_ramp(&mut self.block[..frames*channels],channels,self.direct_gain,direct_gain);
+        self.direct_gain=direct_gain;
+
+        // Сведение шин с конца - дочерние шины раньше родительских
+        for b in (0..self.buses.len()).rev(){
+            let audible=bus_audible(&self.buses,b,any_solo);
 
-            c+=1;
+            let (parents,buses)=self.buses.split_at_mut(b);
+            let bus=&mut buses[0];
+
+            let (start,end)=bus.next_gains(frames,audible);
+
+            let bus_block=&mut bus.block[..frames*channels];
+            process_channel_chains(&mut bus.effects,bus_block,channels,&mut self.channel_block[..frames]);
+
+            let target=match bus.parent{
+                Some(parent)=>&mut parents[parent].block,
+                None=>&mut self.block,
+            };
+            mix_with_ramp(bus_block,&mut target[..frames*channels],channels,start,end);
+        }
+
+        let block=&mut self.block[..frames*channels];
+
+        // Эффекты каналов
+        process_channel_chains(&mut self.channel_effects,block,channels,&mut self.channel_block[..frames]);
+
+        block
+    }
+}
+
+/// Обрабатывает каналы блока (каналы чередуются) их цепочками эффектов.
+fn process_channel_chains(chains:&mut [EffectChain],block:&mut [f32],channels:usize,channel_block:&mut [f32]){
+    for (channel,chain) in chains.iter_mut().enumerate(){
+        if chain.is_empty(){
+            continue
         }
 
-        &mut self.channel_frame
+        for (sample,frame) in channel_block.iter_mut().zip(block.chunks_exact(channels)){
+            *sample=frame[channel];
+        }
+
+        process_chain(chain,channel_block);
+
+        for (&sample,frame) in channel_block.iter().zip(block.chunks_exact_mut(channels)){
+            frame[channel]=sample;
+        }
     }
 }
 
@@ -0,0 +1,76 @@
+use cpal::{
+    Host,
+    Device,
+    Format,
+    SupportedFormat,
+    traits::{
+        HostTrait,
+        DeviceTrait,
+    },
+};
+
+/// Описание устройства вывода.
+///
+/// Description of an output device.
+#[derive(Clone,Debug,PartialEq)]
+pub struct OutputDevice{
+    /// Имя устройства, используется для переключения (`Audio::switch_device`).
+    ///
+    /// The device name, used for switching (`Audio::switch_device`).
+    pub name:String,
+
+    /// Устройство по умолчанию.
+    ///
+    /// The default device.
+    pub default:bool,
+
+    /// Формат по умолчанию, `None` - не удалось получить.
+    ///
+    /// The default format, `None` - failed to get.
+    pub default_format:Option<Format>,
+
+    /// Поддерживаемые форматы.
+    ///
+    /// Supported formats.
+    pub formats:Vec<SupportedFormat>,
+}
+
+/// Возвращает доступные устройства вывода хоста.
+///
+/// Устройства без имени пропускаются.
+///
+/// Returns available output devices of the host.
+///
+/// Devices without a name are skipped.
+pub fn output_devices(host:&Host)->Vec<OutputDevice>{
+    let default_name=host.default_output_device().and_then(|device|device.name().ok());
+
+    let devices=match host.output_devices(){
+        Ok(devices)=>devices,
+        Err(_)=>return Vec::new(),
+    };
+
+    devices.filter_map(|device|{
+        let name=device.name().ok()?;
+
+        Some(OutputDevice{
+            default:default_name.as_ref()==Some(&name),
+            default_format:device.default_output_format().ok(),
+            formats:match device.supported_output_formats(){
+                Ok(formats)=>formats.collect(),
+                Err(_)=>Vec::new(),
+            },
+            name,
+        })
+    }).collect()
+}
+
+/// Ищет устройство вывода по имени, `None` - устройство по умолчанию.
+pub (crate) fn find_output_device(host:&Host,name:Option<&str>)->Option<Device>{
+    match name{
+        Some(name)=>host.output_devices().ok()?
+                .find(|device|device.name().map(|n|n==name).unwrap_or(false)),
+
+        None=>host.default_output_device(),
+    }
+}
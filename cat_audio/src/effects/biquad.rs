@@ -0,0 +1,133 @@
+use super::AudioEffect;
+
+use std::f32::consts::PI;
+
+/// Тип фильтра.
+/// 
+/// A filter kind.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum BiquadKind{
+    /// Пропускает частоты ниже заданной.
+    /// 
+    /// Passes frequencies below the given one.
+    LowPass,
+
+    /// Пропускает частоты выше заданной.
+    /// 
+    /// Passes frequencies above the given one.
+    HighPass,
+
+    /// Пропускает частоты около заданной.
+    /// 
+    /// Passes frequencies around the given one.
+    BandPass,
+}
+
+/// Биквадратный фильтр второго порядка.
+/// 
+/// Например, фильтр нижних частот
+/// даёт эффект звука под водой или за стеной.
+/// 
+/// A second order biquad filter.
+/// 
+/// For example, a low-pass filter
+/// makes a sound muffled as if it's under water or behind a wall.
+#[derive(Clone,Debug)]
+pub struct Biquad{
+    kind:BiquadKind,
+    frequency:f32,
+    q:f32,
+
+    // Коэффициенты, нормированные по `a0`
+    b0:f32,
+    b1:f32,
+    b2:f32,
+    a1:f32,
+    a2:f32,
+
+    // Состояние (транспонированная вторая прямая форма)
+    z1:f32,
+    z2:f32,
+}
+
+impl Biquad{
+    /// Создаёт фильтр с частотой среза (или центральной частотой для полосового) в герцах
+    /// и добротностью (`0.7071` - без подъёма на частоте среза).
+    /// 
+    /// Creates a filter with the cutoff frequency (or the center one for band-pass) in hertz
+    /// and the quality factor (`0.7071` - no peak at the cutoff frequency).
+    pub fn new(kind:BiquadKind,frequency:f32,q:f32)->Biquad{
+        Self{
+            kind,
+            frequency,
+            q:q.max(0.01f32),
+
+            // Без обработки до установки частоты дискретизации
+            b0:1f32,
+            b1:0f32,
+            b2:0f32,
+            a1:0f32,
+            a2:0f32,
+
+            z1:0f32,
+            z2:0f32,
+        }
+    }
+
+    pub fn low_pass(frequency:f32)->Biquad{
+        Biquad::new(BiquadKind::LowPass,frequency,std::f32::consts::FRAC_1_SQRT_2)
+    }
+
+    pub fn high_pass(frequency:f32)->Biquad{
+        Biquad::new(BiquadKind::HighPass,frequency,std::f32::consts::FRAC_1_SQRT_2)
+    }
+
+    pub fn band_pass(frequency:f32,q:f32)->Biquad{
+        Biquad::new(BiquadKind::BandPass,frequency,q)
+    }
+
+    pub fn kind(&self)->BiquadKind{
+        self.kind
+    }
+
+    pub fn frequency(&self)->f32{
+        self.frequency
+    }
+}
+
+impl AudioEffect for Biquad{
+    fn set_sample_rate(&mut self,sample_rate:u32){
+        let nyquist=sample_rate as f32*0.5f32;
+        let frequency=self.frequency.clamp(1f32,nyquist*0.99f32);
+
+        let w0=2f32*PI*frequency/sample_rate as f32;
+        let cos=w0.cos();
+        let alpha=w0.sin()/(2f32*self.q);
+
+        let (b0,b1,b2)=match self.kind{
+            BiquadKind::LowPass=>((1f32-cos)*0.5f32,1f32-cos,(1f32-cos)*0.5f32),
+            BiquadKind::HighPass=>((1f32+cos)*0.5f32,-(1f32+cos),(1f32+cos)*0.5f32),
+            BiquadKind::BandPass=>(alpha,0f32,-alpha),
+        };
+
+        let a0=1f32+alpha;
+
+        self.b0=b0/a0;
+        self.b1=b1/a0;
+        self.b2=b2/a0;
+        self.a1=-2f32*cos/a0;
+        self.a2=(1f32-alpha)/a0;
+    }
+
+    fn process(&mut self,samples:&mut [f32]){
+        for sample in samples{
+            let x=*sample;
+            let y=self.b0*x+self.z1;
+
+            self.z1=self.b1*x-self.a1*y+self.z2;
+            self.z2=self.b2*x-self.a2*y;
+
+            *sample=y;
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use super::AudioEffect;
+
+/// Задержка с обратной связью (эхо).
+/// 
+/// A feedback delay (echo).
+#[derive(Clone,Debug)]
+pub struct Delay{
+    time:f32,
+    feedback:f32,
+    mix:f32,
+
+    buffer:Vec<f32>,
+    position:usize,
+}
+
+impl Delay{
+    /// time - задержка в секундах,
+    /// feedback - доля повторения, идущая на следующее повторение ([0; 1)),
+    /// mix - доля обработанного звука ([0; 1]).
+    /// 
+    /// time - the delay in seconds,
+    /// feedback - the part of a repeat going to the next repeat ([0; 1)),
+    /// mix - the part of the processed sound ([0; 1]).
+    pub fn new(time:f32,feedback:f32,mix:f32)->Delay{
+        Self{
+            time:time.max(0f32),
+            feedback:feedback.clamp(0f32,0.99f32),
+            mix:mix.clamp(0f32,1f32),
+
+            buffer:Vec::new(),
+            position:0,
+        }
+    }
+
+    pub fn time(&self)->f32{
+        self.time
+    }
+}
+
+impl AudioEffect for Delay{
+    fn set_sample_rate(&mut self,sample_rate:u32){
+        let len=((self.time*sample_rate as f32).round() as usize).max(1);
+
+        self.buffer.clear();
+        self.buffer.resize(len,0f32);
+        self.position=0;
+    }
+
+    fn process(&mut self,samples:&mut [f32]){
+        if self.buffer.is_empty(){
+            return
+        }
+
+        for sample in samples{
+            let delayed=self.buffer[self.position];
+
+            self.buffer[self.position]=*sample+delayed*self.feedback;
+            self.position=(self.position+1)%self.buffer.len();
+
+            *sample=*sample*(1f32-self.mix)+delayed*self.mix;
+        }
+    }
+}
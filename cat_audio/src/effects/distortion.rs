@@ -0,0 +1,38 @@
+use super::AudioEffect;
+
+/// Мягкое ограничение (перегрузка).
+/// 
+/// Soft clipping (overdrive).
+#[derive(Clone,Debug)]
+pub struct Distortion{
+    drive:f32,
+    mix:f32,
+}
+
+impl Distortion{
+    /// drive - усиление перед ограничением (от 1),
+    /// mix - доля обработанного звука ([0; 1]).
+    /// 
+    /// drive - the gain before clipping (from 1),
+    /// mix - the part of the processed sound ([0; 1]).
+    pub fn new(drive:f32,mix:f32)->Distortion{
+        Self{
+            drive:drive.max(1f32),
+            mix:mix.clamp(0f32,1f32),
+        }
+    }
+}
+
+impl AudioEffect for Distortion{
+    fn set_sample_rate(&mut self,_sample_rate:u32){}
+
+    fn process(&mut self,samples:&mut [f32]){
+        // Полная амплитуда остаётся полной
+        let scale=1f32/self.drive.tanh();
+
+        for sample in samples{
+            let distorted=(*sample*self.drive).tanh()*scale;
+            *sample=*sample*(1f32-self.mix)+distorted*self.mix;
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Эффекты обработки звука.
+//! 
+//! Цепочки эффектов подключаются к играющим трекам
+//! и к каналам вывода.
+//! 
+//! #
+//! 
+//! Sound processing effects.
+//! 
+//! Effect chains are attached to playing tracks
+//! and to output channels.
+
+mod biquad;
+pub use biquad::{
+    Biquad,
+    BiquadKind,
+};
+
+mod delay;
+pub use delay::Delay;
+
+mod reverb;
+pub use reverb::Reverb;
+
+mod distortion;
+pub use distortion::Distortion;
+
+#[cfg(test)]
+mod tests;
+
+/// Эффект, обрабатывающий блоки значений одного канала.
+/// 
+/// Эффекты выполняются в аудио потоке,
+/// так что `process` не должна блокироваться.
+/// 
+/// An effect processing blocks of samples of a single channel.
+/// 
+/// Effects run in the audio thread,
+/// so `process` shouldn't block.
+pub trait AudioEffect:Send{
+    /// Устанавливает частоту дискретизации обрабатываемых значений.
+    /// 
+    /// Вызывается перед первой обработкой и при смене частоты системы.
+    /// 
+    /// Sets the sample rate of the processed samples.
+    /// 
+    /// Called before the first processing and when the system sample rate changes.
+    fn set_sample_rate(&mut self,sample_rate:u32);
+
+    /// Обрабатывает блок значений.
+    /// 
+    /// Processes a block of samples in place.
+    fn process(&mut self,samples:&mut [f32]);
+}
+
+/// Цепочка эффектов - эффекты выполняются по порядку.
+pub (crate) type EffectChain=Vec<Box<dyn AudioEffect>>;
+
+pub (crate) fn process_chain(chain:&mut EffectChain,samples:&mut [f32]){
+    for effect in chain{
+        effect.process(samples)
+    }
+}
+
+pub (crate) fn set_chain_sample_rate(chain:&mut EffectChain,sample_rate:u32){
+    for effect in chain{
+        effect.set_sample_rate(sample_rate)
+    }
+}
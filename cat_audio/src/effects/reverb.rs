@@ -0,0 +1,121 @@
+use super::AudioEffect;
+
+/// Длины гребенчатых фильтров при 44100 Гц.
+const comb_tunings:[usize;4]=[1116,1188,1277,1356];
+
+/// Длины всепропускающих фильтров при 44100 Гц.
+const allpass_tunings:[usize;2]=[556,441];
+
+/// Усиление входа - сумма фильтров не должна перегружать выход.
+const input_gain:f32=0.03f32;
+
+#[derive(Clone,Debug)]
+struct Comb{
+    buffer:Vec<f32>,
+    position:usize,
+    /// Значение фильтра нижних частот в обратной связи.
+    filtered:f32,
+}
+
+impl Comb{
+    fn process(&mut self,input:f32,feedback:f32,damping:f32)->f32{
+        let output=self.buffer[self.position];
+
+        self.filtered=output*(1f32-damping)+self.filtered*damping;
+        self.buffer[self.position]=input+self.filtered*feedback;
+        self.position=(self.position+1)%self.buffer.len();
+
+        output
+    }
+}
+
+#[derive(Clone,Debug)]
+struct Allpass{
+    buffer:Vec<f32>,
+    position:usize,
+}
+
+impl Allpass{
+    fn process(&mut self,input:f32)->f32{
+        let delayed=self.buffer[self.position];
+
+        self.buffer[self.position]=input+delayed*0.5f32;
+        self.position=(self.position+1)%self.buffer.len();
+
+        delayed-input
+    }
+}
+
+/// Простая реверберация (упрощённый Freeverb).
+/// 
+/// A simple reverb (a simplified Freeverb).
+#[derive(Clone,Debug)]
+pub struct Reverb{
+    room_size:f32,
+    damping:f32,
+    mix:f32,
+
+    combs:Vec<Comb>,
+    allpasses:Vec<Allpass>,
+}
+
+impl Reverb{
+    /// room_size - размер помещения ([0; 1]),
+    /// damping - поглощение высоких частот ([0; 1]),
+    /// mix - доля обработанного звука ([0; 1]).
+    /// 
+    /// room_size - the room size ([0; 1]),
+    /// damping - absorption of high frequencies ([0; 1]),
+    /// mix - the part of the processed sound ([0; 1]).
+    pub fn new(room_size:f32,damping:f32,mix:f32)->Reverb{
+        Self{
+            room_size:room_size.clamp(0f32,1f32),
+            damping:damping.clamp(0f32,1f32),
+            mix:mix.clamp(0f32,1f32),
+
+            combs:Vec::new(),
+            allpasses:Vec::new(),
+        }
+    }
+}
+
+impl AudioEffect for Reverb{
+    fn set_sample_rate(&mut self,sample_rate:u32){
+        let scale=|tuning:usize|((tuning as u64*sample_rate as u64/44100) as usize).max(1);
+
+        self.combs=comb_tunings.iter().map(|&tuning|Comb{
+            buffer:vec![0f32;scale(tuning)],
+            position:0,
+            filtered:0f32,
+        }).collect();
+
+        self.allpasses=allpass_tunings.iter().map(|&tuning|Allpass{
+            buffer:vec![0f32;scale(tuning)],
+            position:0,
+        }).collect();
+    }
+
+    fn process(&mut self,samples:&mut [f32]){
+        if self.combs.is_empty(){
+            return
+        }
+
+        let feedback=0.7f32+self.room_size*0.28f32;
+        let damping=self.damping*0.4f32;
+
+        for sample in samples{
+            let input=*sample*input_gain;
+
+            let mut wet=0f32;
+            for comb in &mut self.combs{
+                wet+=comb.process(input,feedback,damping);
+            }
+
+            for allpass in &mut self.allpasses{
+                wet=allpass.process(wet);
+            }
+
+            *sample=*sample*(1f32-self.mix)+wet*self.mix;
+        }
+    }
+}
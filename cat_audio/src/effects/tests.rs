@@ -0,0 +1,96 @@
+use super::{
+    AudioEffect,
+    Biquad,
+    Delay,
+    Distortion,
+    Reverb,
+};
+
+use std::f32::consts::PI;
+
+const sample_rate:u32=48000;
+
+fn sine(frequency:f32,len:usize)->Vec<f32>{
+    (0..len).map(|n|(2f32*PI*frequency*n as f32/sample_rate as f32).sin()).collect()
+}
+
+/// Амплитуда синусоиды после фильтра (без переходного процесса).
+fn filtered_amplitude(mut filter:Biquad,frequency:f32)->f32{
+    filter.set_sample_rate(sample_rate);
+
+    let mut samples=sine(frequency,sample_rate as usize/2);
+    // Обработка блоками разного размера
+    for block in samples.chunks_mut(300){
+        filter.process(block);
+    }
+
+    samples[samples.len()/2..].iter().fold(0f32,|max,&sample|max.max(sample.abs()))
+}
+
+#[test]
+fn biquad_filters(){
+    assert!(filtered_amplitude(Biquad::low_pass(1000f32),100f32)>0.95f32);
+    assert!(filtered_amplitude(Biquad::low_pass(1000f32),10000f32)<0.02f32);
+
+    assert!(filtered_amplitude(Biquad::high_pass(1000f32),10000f32)>0.95f32);
+    assert!(filtered_amplitude(Biquad::high_pass(1000f32),100f32)<0.02f32);
+
+    assert!(filtered_amplitude(Biquad::band_pass(1000f32,2f32),1000f32)>0.95f32);
+    assert!(filtered_amplitude(Biquad::band_pass(1000f32,2f32),100f32)<0.1f32);
+    assert!(filtered_amplitude(Biquad::band_pass(1000f32,2f32),10000f32)<0.1f32);
+}
+
+#[test]
+fn delay_echo(){
+    let mut delay=Delay::new(0.01f32,0.5f32,0.5f32);
+    delay.set_sample_rate(sample_rate);
+
+    let mut samples=vec![0f32;2000];
+    samples[0]=1f32;
+    delay.process(&mut samples);
+
+    // Задержка 480 значений
+    assert_eq!(samples[0],0.5f32);
+    assert_eq!(samples[480],0.5f32);
+    assert_eq!(samples[960],0.25f32);
+    assert_eq!(samples[1440],0.125f32);
+    assert_eq!(samples[1920],0.0625f32);
+    assert_eq!(samples.iter().filter(|&&sample|sample!=0f32).count(),5);
+}
+
+#[test]
+fn distortion_bounds(){
+    let mut distortion=Distortion::new(10f32,1f32);
+    distortion.set_sample_rate(sample_rate);
+
+    let mut samples=vec![-1f32,-0.1f32,0f32,0.1f32,1f32];
+    distortion.process(&mut samples);
+
+    assert!((samples[4]-1f32).abs()<1e-6);
+    assert!((samples[0]+1f32).abs()<1e-6);
+    assert_eq!(samples[2],0f32);
+    // Тихие значения усиливаются
+    assert!(samples[3]>0.7f32);
+    assert_eq!(samples[1],-samples[3]);
+}
+
+#[test]
+fn reverb_tail(){
+    let mut reverb=Reverb::new(0.8f32,0.5f32,1f32);
+    reverb.set_sample_rate(sample_rate);
+
+    let mut samples=vec![0f32;sample_rate as usize];
+    for sample in &mut samples[..480]{
+        *sample=0.5f32;
+    }
+    reverb.process(&mut samples);
+
+    // Звук продолжается после окончания сигнала и затухает
+    let energy=|samples:&[f32]|samples.iter().map(|sample|sample*sample).sum::<f32>();
+    let early=energy(&samples[4800..14400]);
+    let late=energy(&samples[38400..48000]);
+
+    assert!(early>0f32);
+    assert!(late<early);
+    assert!(samples.iter().all(|sample|sample.abs()<1f32));
+}
@@ -1,4 +1,19 @@
-use super::{MonoTrack,TrackSet};
+use super::{
+    MonoTrack,
+    TrackSet,
+    StreamReceiver,
+    SoundSource,
+    AudioEffect,
+    MasterBusSettings,
+    TrackPlacement,
+    Listener,
+    LoopRegion,
+    TrackPosition,
+};
+
+use cpal::Format;
+
+use std::sync::mpsc::Sender;
 
 /// Команды аудио системы.
 /// 
@@ -104,6 +119,24 @@ pub enum AudioEngineCommand{
     /// attached to tracks from the storage.
     SetMonosVolumesFromStorage(Vec<(usize,f32)>),
 
+    /// Плавно изменяет уровень громкости треков из плейлиста,
+    /// привязанных к трекам из хранилища,
+    /// за данное время в секундах.
+    /// 
+    /// Smoothly changes the volume level of tracks from the playlist
+    /// attached to tracks from the storage
+    /// during the given time in seconds.
+    FadeMonosFromStorage(Vec<usize>,f32,f32),
+
+    /// Плавно убирает и останавливает треки из плейлиста,
+    /// привязанные к трекам из хранилища,
+    /// за данное время в секундах.
+    /// 
+    /// Fades out and stops tracks from the playlist
+    /// attached to tracks from the storage
+    /// during the given time in seconds.
+    FadeOutMonosFromStorage(Vec<usize>,f32),
+
 // ПЛЕЙЛИСТ \\
     /// Проигрывает одноканальный трек на данных каналах.
     /// 
@@ -115,6 +148,34 @@ pub enum AudioEngineCommand{
     /// Plays some mono-channel tracks on the given channels.
     PlayMonosOnChannels(Vec<TrackSet>),
 
+    /// Проигрывает каналы потокового трека на данных каналах
+    /// с данной громкостью.
+    /// 
+    /// Каждый канал трека занимает отдельное место в плейлисте
+    /// и управляется как одноканальный трек.
+    /// Каналы добавляются все вместе или не добавляются.
+    /// 
+    /// Plays channels of a streaming track on the given channels
+    /// with the given volume.
+    /// 
+    /// Each channel of the track takes a separate place in the playlist
+    /// and is operated as a mono-channel track.
+    /// The channels are added all together or not at all.
+    PlayStreamOnChannels(Vec<(StreamReceiver,Vec<usize>)>,f32),
+
+    /// Проигрывает источник звука на данных каналах
+    /// с данной громкостью.
+    /// 
+    /// Источник занимает место в плейлисте
+    /// и управляется как одноканальный трек.
+    /// 
+    /// Plays a sound source on the given channels
+    /// with the given volume.
+    /// 
+    /// The source takes a place in the playlist
+    /// and is operated as a mono-channel track.
+    PlaySourceOnChannels(Box<dyn SoundSource>,Vec<usize>,f32),
+
     /// Снимает с паузы трек из плейлиста.
     /// 
     /// Если уже проигрывается, ничего не происходит.
@@ -160,11 +221,174 @@ pub enum AudioEngineCommand{
     /// Sets volumes to tracks in the playlist.
     SetMonosVolumes(Vec<(usize,f32)>),
 
+    /// Устанавливает скорость проигрывания трека в плейлисте
+    /// (изменяет и высоту звука).
+    /// 
+    /// Sets the playback rate of a track in the playlist
+    /// (changes the pitch too).
+    SetMonoRate(usize,f32),
+
+    /// Переходит к данному значению трека в плейлисте.
+    /// 
+    /// Для потоковых треков ничего не происходит.
+    /// 
+    /// Seeks a track in the playlist to the given sample.
+    /// 
+    /// Nothing happens for streaming tracks.
+    SeekMono(usize,usize),
+
+    /// Устанавливает повторяемую часть трека в плейлисте,
+    /// `None` - весь трек.
+    /// 
+    /// Sets the repeated part of a track in the playlist,
+    /// `None` - the whole track.
+    SetMonoLoop(usize,Option<LoopRegion>),
+
+    /// Отправляет положение в треке из плейлиста,
+    /// `None` - нет такого трека.
+    /// 
+    /// Sends the position in a track from the playlist,
+    /// `None` - no such track.
+    GetMonoPosition(usize,Sender<Option<TrackPosition>>),
+
+    /// Плавно изменяет уровень громкости трека в плейлисте
+    /// за данное время в секундах.
+    /// 
+    /// Уровень умножается на громкость трека.
+    /// 
+    /// Smoothly changes the volume level of a track in the playlist
+    /// during the given time in seconds.
+    /// 
+    /// The level is multiplied by the track volume.
+    FadeMono(usize,f32,f32),
+
+    /// Плавно убирает и останавливает трек в плейлисте
+    /// за данное время в секундах.
+    /// 
+    /// Fades out and stops a track in the playlist
+    /// during the given time in seconds.
+    FadeOutMono(usize,f32),
+
+    /// Плавно убирает и останавливает первый трек в плейлисте
+    /// и проявляет второй за данное время в секундах.
+    /// 
+    /// Fades out and stops the first track in the playlist
+    /// and fades in the second one during the given time in seconds.
+    CrossfadeMonos(usize,usize,f32),
+
+    /// Устанавливает размещение трека в плейлисте.
+    /// 
+    /// Sets a placement of a track in the playlist.
+    SetMonoPlacement(usize,TrackPlacement),
+
+    /// Перемещает размещённый на плоскости трек в плейлисте.
+    /// 
+    /// Moves a track in the playlist placed on the plane.
+    SetMonoPosition(usize,[f32;2]),
+
+    /// Перемещает размещённые на плоскости треки в плейлисте.
+    /// 
+    /// Moves tracks in the playlist placed on the plane.
+    SetMonosPositions(Vec<(usize,[f32;2])>),
+
+    /// Устанавливает слушателя для размещённых на плоскости треков.
+    /// 
+    /// Sets the listener for tracks placed on the plane.
+    SetListener(Listener),
+
     /// Устанавливает общую громкость.
     /// 
     /// Sets the general volume.
     SetGeneralVolume(f32),
 
+    /// Устанавливает обработку общей шины.
+    /// 
+    /// Sets the master bus processing.
+    SetMasterBus(MasterBusSettings),
+
+// Шины \\
+    /// Добавляет шину, сводимую в данную родительскую шину,
+    /// `None` - в общую шину.
+    /// 
+    /// Шины нумеруются по порядку добавления.
+    /// Если нет такой родительской шины, шина сводится в общую.
+    /// 
+    /// Adds a bus mixed into the given parent bus,
+    /// `None` - into the master bus.
+    /// 
+    /// Buses are numbered in the order of adding.
+    /// If there is no such parent bus, the bus is mixed into the master one.
+    AddBus(Option<usize>),
+
+    /// Устанавливает громкость шины.
+    /// 
+    /// Sets a volume of a bus.
+    SetBusVolume(usize,f32),
+
+    /// Выключает (`true`) или включает шину.
+    /// 
+    /// Mutes (`true`) or unmutes a bus.
+    SetBusMute(usize,bool),
+
+    /// Включает (`true`) или выключает соло шины.
+    /// 
+    /// Enables (`true`) or disables solo of a bus.
+    SetBusSolo(usize,bool),
+
+    /// Плавно изменяет уровень громкости шины
+    /// за данное время в секундах.
+    /// 
+    /// Уровень умножается на громкость шины.
+    /// 
+    /// Smoothly changes the volume level of a bus
+    /// during the given time in seconds.
+    /// 
+    /// The level is multiplied by the bus volume.
+    FadeBus(usize,f32,f32),
+
+    /// Добавляет эффект в конец цепочки канала шины.
+    /// 
+    /// Adds an effect to the end of the chain of a bus channel.
+    AddBusEffect(usize,usize,Box<dyn AudioEffect>),
+
+    /// Удаляет все эффекты шины.
+    /// 
+    /// Removes all the effects of a bus.
+    ClearBusEffects(usize),
+
+// Эффекты \\
+    /// Добавляет эффект в конец цепочки трека в плейлисте.
+    /// 
+    /// Adds an effect to the end of the chain of a track in the playlist.
+    AddMonoEffect(usize,Box<dyn AudioEffect>),
+
+    /// Удаляет все эффекты трека в плейлисте.
+    /// 
+    /// Removes all the effects of a track in the playlist.
+    ClearMonoEffects(usize),
+
+    /// Добавляет эффект в конец цепочки канала вывода.
+    /// 
+    /// Adds an effect to the end of the chain of an output channel.
+    AddChannelEffect(usize,Box<dyn AudioEffect>),
+
+    /// Удаляет все эффекты канала вывода.
+    /// 
+    /// Removes all the effects of an output channel.
+    ClearChannelEffects(usize),
+
+// Устройства \\
+    /// Переключает вывод на устройство с данным именем
+    /// (`None` - по умолчанию) и данным форматом (`None` - по умолчанию).
+    /// 
+    /// Хранилище и плейлист сохраняются.
+    /// 
+    /// Switches the output to the device with the given name
+    /// (`None` - the default one) and the given format (`None` - the default one).
+    /// 
+    /// The storage and the playlist are kept.
+    SwitchDevice(Option<String>,Option<Format>),
+
 // Остальное \\
     /// Закрывает аудио поток.
     /// 
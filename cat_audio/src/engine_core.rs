@@ -1,7 +1,13 @@
 use super::{
     ChannelSystem,
+    mix_block_frames,
     AudioEngineCommand,
+    AudioSettings,
     AudioSystemSettings,
+    AudioEvent,
+    EventSender,
+    master_bus::MasterBus,
+    devices::find_output_device,
     tracks::*,
     sample::SampleTransform,
 };
@@ -10,8 +16,8 @@ use cat_engine_basement::support::SyncRawMutPtr;
 
 use cpal::{
     Host,
+    Format,
     traits::{
-        HostTrait,
         DeviceTrait,
         EventLoopTrait
     },
@@ -23,10 +29,14 @@ use cpal::{
     OutputBuffer,
 };
 
-use std::sync::{
-    Arc,
-    Mutex,
-    mpsc::Receiver,
+use std::{
+    thread::sleep,
+    time::Duration,
+    sync::{
+        Arc,
+        Mutex,
+        mpsc::Receiver,
+    },
 };
 
 // Хранилище (хранилище треков, `track_storage`) - массив треков,
@@ -36,390 +46,774 @@ use std::sync::{
 // на которые индексно ссылаются плейлист (`playlist`)
 // и матрица распределения итераторов (`iter_indices`).
 
+/// Интервал попыток открыть устройство после потери прежнего.
+const device_retry_interval:Duration=Duration::from_millis(500);
+
 /// Создание и запуск потока обработки.
 pub (crate) fn event_loop_handler(
     host:Arc<Host>,
     playing_flag:Arc<Mutex<bool>>,
-    mut settings:AudioSystemSettings,
+    settings:AudioSystemSettings,
     main_stream:Arc<Mutex<Option<StreamId>>>,
     event_loop:Arc<EventLoop>,
     receiver:Receiver<AudioEngineCommand>,
+    events:EventSender,
 )->!{
-    // Локальное хранилище одноканальных треков,
-    // не должно превышать заданного размера
-    // При изменении размера (скорее всего с перемещением)
-    // придётся переопределить все треки в буфере,
-    // так как они прямо ссылаются на ячейки в хранилище
-    let mut track_storage=Vec::<MonoTrack>::with_capacity(settings.track_storage_capacity);
-
-    // Матрица распределения треков по буферу
-    // Номера итераторов
-    let mut iter_indices=Vec::<Vec<usize>>::with_capacity(settings.track_storage_capacity);
-
-    // Подготовка
-    for _ in 0..settings.track_storage_capacity{
-        track_storage.push(MonoTrack{data:Vec::new(),sample_rate:0u32});
-        iter_indices.push(Vec::with_capacity(1));
-    }
-
-    // Ссылки нужны, чтобы передать их потоку,
-    // в ином случае данные могут копироваться или передвигаться
-    // (в случае с `iter_indices` точно, так как она используется ещё и в `channel_system`)
-    // из-за того, что функция `run` (находится ниже) забирает все права на переменные
-    let mut track_storage_ref=SyncRawMutPtr::new(&mut track_storage);
-    let mut iter_indices_ref=SyncRawMutPtr::new(&mut iter_indices);
-
-    // Создание системы распределения треков и каналов
-    let mut channel_system=ChannelSystem::new(
-        iter_indices_ref.clone(),
+    let mut mixer=Mixer::new(
+        &settings.mixer,
         settings.format.sample_rate.0,
-        settings.track_playlist,
-        settings.format.channels as usize
+        settings.format.channels,
+        events.clone(),
     );
 
-    event_loop.clone().run(move|_,result|{
+    let stream=main_stream.lock().unwrap().clone();
+
+    let mut output=Output{
+        devices:CpalDevices{
+            host,
+            event_loop:event_loop.clone(),
+            playing_flag,
+            stream,
+            main_stream,
+        },
+        format:settings.format,
+    };
+
+    event_loop.run(move|stream,result|{
         // Обработчик команд
         match receiver.try_recv(){
-            Ok(command)=>match command{
-            // ХРАНИЛИЩЕ \\
-                // Добавление трека в ячейку хранилища
-                // Если нет такой ячейки, то ничего не происходит
-                AudioEngineCommand::AddMono(track,index)=>
-                    if let Some(slot)=track_storage_ref.as_mut().get_mut(index){
+            // Закрывает поток
+            Ok(AudioEngineCommand::Close)=> // Поток умер :)
+                panic!("Closing CatEngine's audio thread"),
+
+            // Переключение устройства
+            // Если не удалось, вывод остаётся на прежнем устройстве
+            Ok(AudioEngineCommand::SwitchDevice(device,format))=>
+                output.switch_device(device.as_deref(),format,&mut mixer,&events),
+
+            Ok(command)=>mixer.execute(command),
+
+            Err(_)=>{
+                // Ошибки игнорируются,
+                // так как `TryRecvError::Empty` означает, что команд нет,
+                // а `TryRecvError::Disconnected` во всех случаях перехватывается деконструктором,
+                // и вызывается паника
+            }
+        }
+
+        // Прежние потоки вывода игнорируются
+        if output.devices.stream.as_ref()!=Some(&stream){
+            return
+        }
+
+        // Вывод звука
+        match result{
+            Ok(data)=>{
+                match data{
+                    StreamData::Output{buffer:UnknownTypeOutputBuffer::I16(buffer)}
+                    =>output_block(
+                        &mut mixer,
+                        output.format.channels,
+                        buffer
+                    ),
+
+                    StreamData::Output{buffer:UnknownTypeOutputBuffer::U16(buffer)}
+                    =>output_block(
+                        &mut mixer,
+                        output.format.channels,
+                        buffer
+                    ),
+
+                    StreamData::Output{buffer:UnknownTypeOutputBuffer::F32(buffer)}
+                    =>output_block(
+                        &mut mixer,
+                        output.format.channels,
+                        buffer
+                    ),
+
+                    _=>{}
+                }
+            }
+
+            Err(error)=>{
+                match error{
+                    // Выбор нового устройства, если прежнее не доступно
+                    StreamError::DeviceNotAvailable=>output.recover(
+                        &receiver,
+                        &mut mixer,
+                        &events,
+                        ||sleep(device_retry_interval)
+                    ),
+                    // Паникует, если какая-то другая ошибка
+                    // (пока не знаю, как нормально обработать)
+                    StreamError::BackendSpecific{err}=>{
+                        panic!("{}",err)
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Устройства, на которых строится поток вывода.
+pub (crate) trait OutputDevices{
+    /// Строит поток вывода на устройстве с данным именем
+    /// (`None` - по умолчанию) и данным форматом (`None` - по умолчанию)
+    /// и заменяет им текущий поток.
+    /// 
+    /// Возвращает формат нового потока или `None`,
+    /// если устройство не найдено или поток не построен,
+    /// текущий поток при этом не изменяется.
+    fn open(&mut self,device:Option<&str>,format:Option<Format>)->Option<Format>;
+}
+
+/// Устройства и потоки `cpal`.
+struct CpalDevices{
+    host:Arc<Host>,
+    event_loop:Arc<EventLoop>,
+    playing_flag:Arc<Mutex<bool>>,
+    /// Поток вывода, общий с управляющим потоком.
+    main_stream:Arc<Mutex<Option<StreamId>>>,
+    /// Копия текущего потока вывода.
+    stream:Option<StreamId>,
+}
+
+impl OutputDevices for CpalDevices{
+    fn open(&mut self,device:Option<&str>,format:Option<Format>)->Option<Format>{
+        let device=find_output_device(&self.host,device)?;
+
+        let format=match format{
+            Some(format)=>format,
+            None=>device.default_output_format().ok()?,
+        };
+
+        let mut stream_lock=self.main_stream.lock().unwrap();
+
+        let new_stream=self.event_loop.build_output_stream(&device,&format).ok()?;
+
+        let started=if *self.playing_flag.lock().unwrap(){
+            self.event_loop.play_stream(new_stream.clone()).is_ok()
+        }
+        else{
+            self.event_loop.pause_stream(new_stream.clone()).is_ok()
+        };
+
+        if !started{
+            self.event_loop.destroy_stream(new_stream);
+            return None
+        }
+
+        if let Some(old_stream)=stream_lock.replace(new_stream.clone()){
+            self.event_loop.destroy_stream(old_stream);
+        }
+
+        self.stream=Some(new_stream);
+
+        Some(format)
+    }
+}
+
+/// Поток вывода и его устройство -
+/// переключение устройств и восстановление после потери устройства.
+pub (crate) struct Output<D:OutputDevices>{
+    pub devices:D,
+    /// Формат текущего потока вывода.
+    pub format:Format,
+}
+
+impl<D:OutputDevices> Output<D>{
+    /// Заменяет текущий поток потоком на данном устройстве.
+    /// 
+    /// Возвращает `false`, если устройство не найдено или поток не построен,
+    /// текущий поток при этом не изменяется.
+    fn switch(&mut self,device:Option<&str>,format:Option<Format>)->bool{
+        match self.devices.open(device,format){
+            Some(format)=>{
+                self.format=format;
+                true
+            }
+            None=>false,
+        }
+    }
+
+    /// Устанавливает формат потока вывода для сведения и уведомляет о нём.
+    fn apply_format(&self,mixer:&mut Mixer,events:&EventSender){
+        mixer.set_format(self.format.sample_rate.0,self.format.channels);
+
+        events.send(AudioEvent::FormatChanged{
+            sample_rate:self.format.sample_rate.0,
+            channels:self.format.channels,
+        });
+    }
+
+    /// Выполняет команду переключения устройства.
+    /// 
+    /// Если не удалось, вывод остаётся на прежнем устройстве
+    /// и отправляется `AudioEvent::DeviceUnavailable`.
+    pub fn switch_device(
+        &mut self,
+        device:Option<&str>,
+        format:Option<Format>,
+        mixer:&mut Mixer,
+        events:&EventSender
+    ){
+        if self.switch(device,format){
+            self.apply_format(mixer,events)
+        }
+        else{
+            events.send(AudioEvent::DeviceUnavailable)
+        }
+    }
+
+    /// Восстанавливает вывод после потери устройства.
+    /// 
+    /// Отправляет `AudioEvent::DeviceLost` и ждёт устройства по умолчанию
+    /// или успешного переключения, вызывая `wait` между попытками.
+    /// Команды при этом выполняются, хранилище и плейлист сохраняются.
+    pub fn recover<W:FnMut()>(
+        &mut self,
+        receiver:&Receiver<AudioEngineCommand>,
+        mixer:&mut Mixer,
+        events:&EventSender,
+        mut wait:W
+    ){
+        events.send(AudioEvent::DeviceLost);
+
+        while !self.switch(None,None){
+            let mut switched=false;
+
+            while let Ok(command)=receiver.try_recv(){
+                match command{
+                    AudioEngineCommand::Close=>
+                        panic!("Closing CatEngine's audio thread"),
+
+                    AudioEngineCommand::SwitchDevice(device,format)=>
+                        if self.switch(device.as_deref(),format){
+                            switched=true;
+                            break
+                        }
+                        else{
+                            events.send(AudioEvent::DeviceUnavailable)
+                        }
+
+                    command=>mixer.execute(command),
+                }
+            }
+
+            if switched{
+                break
+            }
+
+            wait();
+        }
+
+        // Установка новой частоты дискретизации и количества каналов
+        self.apply_format(mixer,events);
+    }
+}
+
+/// Вывод звука и постобработка (`feature="audio_post_processing"`).
+fn output_block<
+    S:SampleTransform,
+    #[cfg(feature="audio_post_processing")]P:FnMut(&mut [f32])
+>(
+    mixer:&mut Mixer,
+    channels:u16,
+    mut buffer:OutputBuffer<S>,
+    #[cfg(feature="audio_post_processing")]mut post_processing:P,
+){
+    let channels=channels as usize;
+
+    // Буфер заполняется блоками
+    for chunk in buffer.chunks_mut(mix_block_frames*channels){
+        let block=mixer.next_output_block(chunk.len()/channels);
+
+        #[cfg(feature="audio_post_processing")]
+        for frame in block.chunks_exact_mut(channels){
+            post_processing(frame);
+        }
+
+        for (b,&sample) in chunk.iter_mut().zip(block.iter()){
+            *b=SampleTransform::from(sample,1f32);
+        }
+    }
+}
+
+/// Хранилище треков и система распределения треков по каналам -
+/// выполняет команды и сводит треки.
+/// 
+/// Используется аудио потоком и `OfflineRenderer`.
+pub (crate) struct Mixer{
+    /// Локальное хранилище одноканальных треков,
+    /// не должно превышать заданного размера
+    /// При изменении размера (скорее всего с перемещением)
+    /// придётся переопределить все треки в буфере,
+    /// так как они прямо ссылаются на ячейки в хранилище
+    track_storage:Vec<MonoTrack>,
+
+    /// Матрица распределения треков по буферу (номера итераторов).
+    /// 
+    /// Находится в куче, так как на неё ссылается `channel_system`.
+    #[allow(clippy::box_collection)]
+    iter_indices:Box<Vec<Vec<usize>>>,
+
+    channel_system:ChannelSystem,
+
+    /// Общая громкость.
+    general_volume:f32,
+
+    /// Обработка общей шины.
+    master_bus:MasterBus,
+
+    /// Уведомления управляющему потоку.
+    events:EventSender,
+}
+
+impl Mixer{
+    pub fn new(settings:&AudioSettings,sample_rate:u32,channels:u16,events:EventSender)->Mixer{
+        let track_storage_capacity=settings.track_storage_capacity;
+
+        let mut track_storage=Vec::<MonoTrack>::with_capacity(track_storage_capacity);
+
+        let mut iter_indices=Box::new(Vec::<Vec<usize>>::with_capacity(track_storage_capacity));
+
+        // Подготовка
+        for _ in 0..track_storage_capacity{
+            track_storage.push(MonoTrack{data:Vec::new(),sample_rate:0u32});
+            iter_indices.push(Vec::with_capacity(1));
+        }
+
+        // Создание системы распределения треков и каналов
+        let channel_system=ChannelSystem::new(
+            SyncRawMutPtr::new(iter_indices.as_mut()),
+            sample_rate,
+            settings.track_playlist_capacity,
+            channels as usize,
+            settings.resampler_quality,
+            settings.pan_law,
+            settings.listener,
+        );
+
+        Self{
+            track_storage,
+            iter_indices,
+            channel_system,
+            general_volume:settings.general_volume,
+            master_bus:MasterBus::new(settings.master_bus,sample_rate,channels),
+            events,
+        }
+    }
+
+    /// Устанавливает новые частоту дискретизации и количество каналов вывода.
+    pub fn set_format(&mut self,sample_rate:u32,channels:u16){
+        self.channel_system.set_system_sample_rate(sample_rate);
+        self.channel_system.set_system_channels(channels);
+        self.master_bus.set_format(sample_rate,channels);
+    }
+
+    /// Возвращает следующий блок из `frames` кадров
+    /// с общей громкостью и обработкой общей шины
+    /// (`frames` не больше `mix_block_frames`).
+    pub fn next_output_block(&mut self,frames:usize)->&mut [f32]{
+        let block=self.channel_system.next_block(frames,&self.events);
+
+        for sample in block.iter_mut(){
+            *sample*=self.general_volume
+        }
+
+        self.master_bus.process(block);
+
+        block
+    }
+
+    /// Выполняет команду.
+    /// 
+    /// `AudioEngineCommand::Close` и `AudioEngineCommand::SwitchDevice`
+    /// обрабатываются владельцем.
+    pub fn execute(&mut self,command:AudioEngineCommand){
+        match command{
+        // ХРАНИЛИЩЕ \\
+            // Добавление трека в ячейку хранилища
+            // Если нет такой ячейки, то ничего не происходит
+            AudioEngineCommand::AddMono(track,index)=>
+                if let Some(slot)=self.track_storage.get_mut(index){
+                    // Установка трека
+                    *slot=track;
+                    // Очистка списка итераторов
+                    self.iter_indices[index].clear();
+                }
+                else{
+                    self.events.send(AudioEvent::StorageOverflow(index))
+                }
+
+            // Добавление треков в ячейки хранилища
+            // Если нет таких ячеек, то ничего не происходит
+            AudioEngineCommand::AddMonos(tracks)=>
+                for (track,index) in tracks{
+                    if let Some(slot)=self.track_storage.get_mut(index){
                         // Установка трека
                         *slot=track;
                         // Очистка списка итераторов
-                        iter_indices_ref.as_mut()[index].clear();
+                        self.iter_indices[index].clear();
                     }
+                    else{
+                        self.events.send(AudioEvent::StorageOverflow(index))
+                    }
+                }
 
-                // Добавление треков в ячейки хранилища
-                // Если нет таких ячеек, то ничего не происходит
-                AudioEngineCommand::AddMonos(tracks)=>
-                    for (track,index) in tracks{
-                        if let Some(slot)=track_storage_ref.as_mut().get_mut(index){
-                            // Установка трека
-                            *slot=track;
-                            // Очистка списка итераторов
-                            iter_indices_ref.as_mut()[index].clear();
-                        }
+            // Удаление трека из хранилища
+            // Если такого трека нет, то ничего не происходит
+            AudioEngineCommand::RemoveMono(index)=>
+                if let Some(iters)=self.iter_indices.get_mut(index){
+                    // Остановка итераторов (установка флагов PlayType::None)
+                    for &mut i in iters{
+                        self.channel_system.stop_iter(i);
                     }
+                }
 
-                // Удаление трека из хранилища
-                // Если такого трека нет, то ничего не происходит
-                AudioEngineCommand::RemoveMono(index)=>
-                    if let Some(iters)=iter_indices_ref.as_mut().get_mut(index){
+
+            // Удаление треков из хранилища
+            // Если таких треков нет, то ничего не происходит
+            AudioEngineCommand::RemoveMonos(indices)=>
+                for index in indices.into_iter(){
+                    if let Some(iters)=self.iter_indices.get_mut(index){
                         // Остановка итераторов (установка флагов PlayType::None)
                         for &mut i in iters{
-                            channel_system.stop_iter(i);
+                            self.channel_system.stop_iter(i);
                         }
                     }
+                }
 
-
-                // Удаление треков из хранилища
-                // Если таких треков нет, то ничего не происходит
-                AudioEngineCommand::RemoveMonos(indices)=>
-                    for index in indices.into_iter(){
-                        if let Some(iters)=iter_indices_ref.as_mut().get_mut(index){
-                            // Остановка итераторов (установка флагов PlayType::None)
-                            for &mut i in iters{
-                                channel_system.stop_iter(i);
-                            }
-                        }
+            // Снимает паузу с треков из плейлиста,
+            // привязанных к треку из хранилища
+            AudioEngineCommand::UnpauseMonoFromStorage(index)=>
+                if let Some(iters)=self.iter_indices.get(index){
+                    for &iter in iters{
+                        self.channel_system.unpause_buffer_iter(iter)
                     }
+                }
 
-                // Снимает паузу с треков из плейлиста,
-                // привязанных к треку из хранилища
-                AudioEngineCommand::UnpauseMonoFromStorage(index)=>
-                    if let Some(iters)=iter_indices_ref.as_ref().get(index){
+            // Снимает паузу с треков из плейлиста,
+            // привязанных к трекам из хранилища
+            AudioEngineCommand::UnpauseMonosFromStorage(indices)=>
+                for index in indices{
+                    if let Some(iters)=self.iter_indices.get(index){
                         for &iter in iters{
-                            channel_system.unpause_buffer_iter(iter)
+                            self.channel_system.unpause_buffer_iter(iter)
                         }
                     }
+                }
 
-                // Снимает паузу с треков из плейлиста,
-                // привязанных к трекам из хранилища
-                AudioEngineCommand::UnpauseMonosFromStorage(indices)=>
-                    for index in indices{
-                        if let Some(iters)=iter_indices_ref.as_ref().get(index){
-                            for &iter in iters{
-                                channel_system.unpause_buffer_iter(iter)
-                            }
-                        }
+            // Ставит на паузу треки из плейлиста,
+            // привязанные к треку из хранилища
+            AudioEngineCommand::PauseMonoFromStorage(index)=>
+                if let Some(iters)=self.iter_indices.get(index){
+                    for &iter in iters{
+                        self.channel_system.pause_buffer_iter(iter)
                     }
+                }
 
-                // Ставит на паузу треки из плейлиста,
-                // привязанные к треку из хранилища
-                AudioEngineCommand::PauseMonoFromStorage(index)=>
-                    if let Some(iters)=iter_indices_ref.as_ref().get(index){
+            // Ставит на паузу треки из плейлиста,
+            // привязанные к трекам из хранилища
+            AudioEngineCommand::PauseMonosFromStorage(indices)=>
+                for index in indices{
+                    if let Some(iters)=self.iter_indices.get(index){
                         for &iter in iters{
-                            channel_system.pause_buffer_iter(iter)
+                            self.channel_system.pause_buffer_iter(iter)
                         }
                     }
+                }
 
-                // Ставит на паузу треки из плейлиста,
-                // привязанные к трекам из хранилища
-                AudioEngineCommand::PauseMonosFromStorage(indices)=>
-                    for index in indices{
-                        if let Some(iters)=iter_indices_ref.as_ref().get(index){
-                            for &iter in iters{
-                                channel_system.pause_buffer_iter(iter)
-                            }
-                        }
+            // Останавливает треки из плейлиста,
+            // привязанные к треку из хранилища
+            AudioEngineCommand::StopMonoFromStorage(index)=>
+                if let Some(iters)=self.iter_indices.get(index){
+                    for &iter in iters{
+                        self.channel_system.stop_buffer_iter(iter)
                     }
+                }
 
-                // Останавливает треки из плейлиста,
-                // привязанные к треку из хранилища
-                AudioEngineCommand::StopMonoFromStorage(index)=>
-                    if let Some(iters)=iter_indices_ref.as_ref().get(index){
+            // Останавливает треки из плейлиста,
+            // привязанные к трекам из хранилища
+            AudioEngineCommand::StopMonosFromStorage(indices)=>
+                for index in indices{
+                    if let Some(iters)=self.iter_indices.get(index){
                         for &iter in iters{
-                            channel_system.stop_buffer_iter(iter)
+                            self.channel_system.stop_buffer_iter(iter)
                         }
                     }
+                }
 
-                // Останавливает треки из плейлиста,
-                // привязанные к трекам из хранилища
-                AudioEngineCommand::StopMonosFromStorage(indices)=>
-                    for index in indices{
-                        if let Some(iters)=iter_indices_ref.as_ref().get(index){
-                            for &iter in iters{
-                                channel_system.stop_buffer_iter(iter)
-                            }
+            // Плавно изменяет громкость треков из плейлиста,
+            // привязанных к трекам из хранилища
+            AudioEngineCommand::FadeMonosFromStorage(indices,level,seconds)=>
+                for index in indices{
+                    if let Some(iters)=self.iter_indices.get(index){
+                        for &iter in iters{
+                            self.channel_system.fade_buffer_iter(iter,level,seconds,false)
                         }
                     }
+                }
 
-                // Устанавливает громкость треков из плейлиста,
-                // привязанных к треку из хранилища
-                AudioEngineCommand::SetMonoVolumeFromStorage(index,volume)=>
-                    if let Some(iters)=iter_indices_ref.as_ref().get(index){
+            // Плавно убирает и останавливает треки из плейлиста,
+            // привязанные к трекам из хранилища
+            AudioEngineCommand::FadeOutMonosFromStorage(indices,seconds)=>
+                for index in indices{
+                    if let Some(iters)=self.iter_indices.get(index){
                         for &iter in iters{
-                            channel_system.set_volume_buffer_iter(iter,volume)
+                            self.channel_system.fade_buffer_iter(iter,0f32,seconds,true)
                         }
                     }
+                }
 
-                // Устанавливает громкость треков из плейлиста,
-                // привязанных к трекам из хранилища
-                AudioEngineCommand::SetMonosVolumeFromStorage(indices,volume)=>
-                    for index in indices{
-                        if let Some(iters)=iter_indices_ref.as_ref().get(index){
-                            for &iter in iters{
-                                channel_system.set_volume_buffer_iter(iter,volume)
-                            }
-                        }
+            // Устанавливает громкость треков из плейлиста,
+            // привязанных к треку из хранилища
+            AudioEngineCommand::SetMonoVolumeFromStorage(index,volume)=>
+                if let Some(iters)=self.iter_indices.get(index){
+                    for &iter in iters{
+                        self.channel_system.set_volume_buffer_iter(iter,volume)
                     }
+                }
 
-                // Устанавливает громкости треков из плейлиста,
-                // привязанных к трекам из хранилища
-                AudioEngineCommand::SetMonosVolumesFromStorage(sets)=>
-                    for (index,volume) in sets{
-                        if let Some(iters)=iter_indices_ref.as_ref().get(index){
-                            for &iter in iters{
-                                channel_system.set_volume_buffer_iter(iter,volume)
-                            }
+            // Устанавливает громкость треков из плейлиста,
+            // привязанных к трекам из хранилища
+            AudioEngineCommand::SetMonosVolumeFromStorage(indices,volume)=>
+                for index in indices{
+                    if let Some(iters)=self.iter_indices.get(index){
+                        for &iter in iters{
+                            self.channel_system.set_volume_buffer_iter(iter,volume)
                         }
                     }
+                }
 
-            // ПЛЕЙЛИСТ \\
-                // Добавление трека в плейлист
-                // Если плейлист переполнен, то ничего не происходит
-                AudioEngineCommand::PlayMonoOnChannels(TrackSet{index,channels,repeats,volume})=>
-                    // Получение номера трека в хранилище
-                    if let Some(track)=track_storage_ref.as_ref().get(index){
-                        // Здесь проверка не нужна, так как уже есть внутреняя -
-                        // переполнения плейлиста не должно быть
-                        channel_system.add_track(index,track,channels,repeats,volume);
-                    }
-
-                // Удаление трека из плейлиста
-                // Если нет такого трека, ничего не происходит
-                AudioEngineCommand::RemoveMonoFromPlaylist(index)=>
-                    // Проверка не нужна, так как она проводится внутри
-                    channel_system.remove_track(index),
-
-                // Добавление множества треков в плейлист
-                // Если плейлист переполнен, то ничего не происходит
-                AudioEngineCommand::PlayMonosOnChannels(sets)=>
-                    for TrackSet{index,channels,repeats,volume} in sets{
-                        if let Some(track)=track_storage_ref.as_ref().get(index){
-                            // Здесь проверка не нужна, так как уже есть внутреняя -
-                            // переполнения плейлиста не должно быть
-                            channel_system.add_track(index,track,channels,repeats,volume);
+            // Устанавливает громкости треков из плейлиста,
+            // привязанных к трекам из хранилища
+            AudioEngineCommand::SetMonosVolumesFromStorage(sets)=>
+                for (index,volume) in sets{
+                    if let Some(iters)=self.iter_indices.get(index){
+                        for &iter in iters{
+                            self.channel_system.set_volume_buffer_iter(iter,volume)
                         }
                     }
+                }
 
-                // Удаление треков из плейлиста
-                // Если нет таких треков, ничего не происходит
-                AudioEngineCommand::RemoveMonosFromPlaylist(indices)=>
-                    for index in indices.into_iter().rev(){
-                        // Проверка не нужна, так как она проводится внутри
-                        channel_system.remove_track(index)
-                    }
+        // ПЛЕЙЛИСТ \\
+            // Добавление трека в плейлист
+            // Если плейлист переполнен, то ничего не происходит
+            AudioEngineCommand::PlayMonoOnChannels(set)=>
+                self.play_track(set),
+
+            // Удаление трека из плейлиста
+            // Если нет такого трека, ничего не происходит
+            AudioEngineCommand::RemoveMonoFromPlaylist(index)=>
+                // Проверка не нужна, так как она проводится внутри
+                self.channel_system.remove_track(index),
+
+            // Добавление множества треков в плейлист
+            // Если плейлист переполнен, то ничего не происходит
+            AudioEngineCommand::PlayMonosOnChannels(sets)=>
+                for set in sets{
+                    self.play_track(set)
+                }
 
-                // Ставит трек из плейлиста проигрываться
-                // Если уже проигрывается или нет такого трека,
-                // ничего не происходит
-                AudioEngineCommand::UnpauseMonoFromPlaylist(index)=>
-                    channel_system.unpause(index),
+            // Добавление каналов потокового трека в плейлист
+            // Если для всех каналов нет места, то трек удаляется
+            AudioEngineCommand::PlayStreamOnChannels(streams,volume)=>
+                if !self.channel_system.add_streams(streams,volume){
+                    self.events.send(AudioEvent::PlaylistOverflow(None))
+                }
 
-                // Ставит треки из плейлиста проигрываться
-                // Если уже проигрывается или нет таких треков,
-                // ничего не происходит
-                AudioEngineCommand::UnpauseMonosFromPlaylist(indices)=>
-                for index in indices{
-                    channel_system.unpause(index)
+            // Добавление источника звука в плейлист
+            // Если плейлист переполнен, то источник удаляется
+            AudioEngineCommand::PlaySourceOnChannels(source,channels,volume)=>
+                if !self.channel_system.add_source(source,channels,volume){
+                    self.events.send(AudioEvent::PlaylistOverflow(None))
                 }
 
-                // Ставит трек из плейлиста на паузу
-                // Если уже проигрывается или нет такого трека,
-                // ничего не происходит
-                AudioEngineCommand::PauseMonoFromPlaylist(index)=>
-                    channel_system.pause(index),
-
-                // Ставит треки из плейлиста на паузу
-                // Если уже на паузе или нет таких треков,
-                // ничего не происходит
-                AudioEngineCommand::PauseMonosFromPlaylist(indices)=>
-                    for index in indices{
-                        channel_system.pause(index)
-                    }
+            // Удаление треков из плейлиста
+            // Если нет таких треков, ничего не происходит
+            AudioEngineCommand::RemoveMonosFromPlaylist(indices)=>
+                for index in indices.into_iter().rev(){
+                    // Проверка не нужна, так как она проводится внутри
+                    self.channel_system.remove_track(index)
+                }
 
-                // Очищает весь плейлист и
-                // очищает списки итераторов (`iter_indices`)
-                AudioEngineCommand::ClearPlaylist=>
-                    channel_system.clear_playlist(),
+            // Ставит трек из плейлиста проигрываться
+            // Если уже проигрывается или нет такого трека,
+            // ничего не происходит
+            AudioEngineCommand::UnpauseMonoFromPlaylist(index)=>
+                self.channel_system.unpause(index),
+
+            // Ставит треки из плейлиста проигрываться
+            // Если уже проигрывается или нет таких треков,
+            // ничего не происходит
+            AudioEngineCommand::UnpauseMonosFromPlaylist(indices)=>
+            for index in indices{
+                self.channel_system.unpause(index)
+            }
 
-                // Устанавливает громкость трека
-                // Если нет такого трека, ничего не происходит
-                AudioEngineCommand::SetMonoVolume(index,volume)=>
-                    channel_system.set_track_volume(index,volume),
+            // Ставит трек из плейлиста на паузу
+            // Если уже проигрывается или нет такого трека,
+            // ничего не происходит
+            AudioEngineCommand::PauseMonoFromPlaylist(index)=>
+                self.channel_system.pause(index),
 
-                AudioEngineCommand::SetMonosVolume(indices,volume)=>{
-                    for index in indices{
-                        channel_system.set_track_volume(index,volume)
-                    }
+            // Ставит треки из плейлиста на паузу
+            // Если уже на паузе или нет таких треков,
+            // ничего не происходит
+            AudioEngineCommand::PauseMonosFromPlaylist(indices)=>
+                for index in indices{
+                    self.channel_system.pause(index)
                 }
 
-                AudioEngineCommand::SetMonosVolumes(sets)=>{
-                    for set in sets{
-                        channel_system.set_track_volume(set.0,set.1)
-                    }
+            // Очищает весь плейлист и
+            // очищает списки итераторов (`iter_indices`)
+            AudioEngineCommand::ClearPlaylist=>
+                self.channel_system.clear_playlist(),
+
+            // Устанавливает громкость трека
+            // Если нет такого трека, ничего не происходит
+            AudioEngineCommand::SetMonoVolume(index,volume)=>
+                self.channel_system.set_track_volume(index,volume),
+
+            AudioEngineCommand::SetMonosVolume(indices,volume)=>{
+                for index in indices{
+                    self.channel_system.set_track_volume(index,volume)
                 }
+            }
 
-                // Устанавливает общую громкость
-                AudioEngineCommand::SetGeneralVolume(v)=>
-                    settings.general_volume=v,
-            // ОСТАЛЬНОЕ \\
-                // Закрывает поток
-                AudioEngineCommand::Close=> // Поток умер :)
-                    panic!("Closing CatEngine's audio thread"),
+            AudioEngineCommand::SetMonosVolumes(sets)=>{
+                for set in sets{
+                    self.channel_system.set_track_volume(set.0,set.1)
+                }
             }
-            Err(_)=>{
-                // Ошибки игнорируются,
-                // так как `TryRecvError::Empty` означает, что команд нет,
-                // а `TryRecvError::Disconnected` во всех случаях перехватывается деконструктором,
-                // и вызывается паника
+
+            // Устанавливает скорость проигрывания трека
+            // Если нет такого трека, ничего не происходит
+            AudioEngineCommand::SetMonoRate(index,rate)=>
+                self.channel_system.set_track_rate(index,rate),
+
+            // Переход к значению трека
+            // Если нет такого трека, ничего не происходит
+            AudioEngineCommand::SeekMono(index,frame)=>
+                self.channel_system.seek_track(index,frame),
+
+            // Устанавливает повторяемую часть трека
+            // Если нет такого трека, ничего не происходит
+            AudioEngineCommand::SetMonoLoop(index,loop_region)=>
+                self.channel_system.set_track_loop(index,loop_region),
+
+            // Отправляет положение в треке
+            // Ошибка отправки означает, что ответ больше не нужен
+            AudioEngineCommand::GetMonoPosition(index,sender)=>{
+                let _=sender.send(self.channel_system.track_position(index));
             }
-        }
 
-        // Вывод звука
-        match result{
-            Ok(data)=>{
-                // let stream_lock=main_stream.lock().unwrap();
-                match data{
-                    StreamData::Output{buffer:UnknownTypeOutputBuffer::I16(buffer)}
-                    =>output(
-                        &mut channel_system,
-                        settings.format.channels,
-                        settings.general_volume,
-                        buffer
-                    ),
+            // Плавно изменяет громкость трека
+            // Если нет такого трека, ничего не происходит
+            AudioEngineCommand::FadeMono(index,level,seconds)=>
+                self.channel_system.fade_track(index,level,seconds,false),
+
+            // Плавно убирает и останавливает трек
+            // Если нет такого трека, ничего не происходит
+            AudioEngineCommand::FadeOutMono(index,seconds)=>
+                self.channel_system.fade_track(index,0f32,seconds,true),
+
+            // Плавно переходит от одного трека к другому
+            // Если нет таких треков, ничего не происходит
+            AudioEngineCommand::CrossfadeMonos(from,to,seconds)=>
+                self.channel_system.crossfade_tracks(from,to,seconds),
+
+            // Устанавливает размещение трека
+            // Если нет такого трека, ничего не происходит
+            AudioEngineCommand::SetMonoPlacement(index,placement)=>
+                self.channel_system.set_track_placement(index,placement),
+
+            // Перемещает размещённый на плоскости трек
+            // Если нет такого трека, ничего не происходит
+            AudioEngineCommand::SetMonoPosition(index,position)=>
+                self.channel_system.set_track_position(index,position),
+
+            AudioEngineCommand::SetMonosPositions(sets)=>
+                for (index,position) in sets{
+                    self.channel_system.set_track_position(index,position)
+                }
 
-                    StreamData::Output{buffer:UnknownTypeOutputBuffer::U16(buffer)}
-                    =>output(
-                        &mut channel_system,
-                        settings.format.channels,
-                        settings.general_volume,
-                        buffer
-                    ),
+            // Устанавливает слушателя
+            AudioEngineCommand::SetListener(listener)=>
+                self.channel_system.set_listener(listener),
 
-                    StreamData::Output{buffer:UnknownTypeOutputBuffer::F32(buffer)}
-                    =>output(
-                        &mut channel_system,
-                        settings.format.channels,
-                        settings.general_volume,
-                        buffer
-                    ),
+            // Устанавливает общую громкость
+            AudioEngineCommand::SetGeneralVolume(v)=>
+                self.general_volume=v,
 
-                    _=>{}
-                }
-            }
+            // Устанавливает обработку общей шины
+            AudioEngineCommand::SetMasterBus(settings)=>
+                self.master_bus.set_settings(settings),
 
-            Err(error)=>{
-                match error{
-                    // Выбор нового устройства, если прежнее не доступно
-                    StreamError::DeviceNotAvailable=>{
-                        let mut stream_lock=main_stream.lock().unwrap();
+        // ШИНЫ \\
+            // Добавляет шину
+            AudioEngineCommand::AddBus(parent)=>
+                self.channel_system.add_bus(parent),
 
-                        let new_device=host.default_output_device().expect("No available device");
+            // Устанавливают параметры шины
+            // Если нет такой шины, ничего не происходит
+            AudioEngineCommand::SetBusVolume(bus,volume)=>
+                self.channel_system.set_bus_volume(bus,volume),
 
-                        settings.format=new_device.default_output_format().expect("No available device");
+            AudioEngineCommand::SetBusMute(bus,mute)=>
+                self.channel_system.set_bus_mute(bus,mute),
 
-                        // Установка новой частоты дискретизации
-                        channel_system.set_system_sample_rate(settings.format.sample_rate.0);
+            AudioEngineCommand::SetBusSolo(bus,solo)=>
+                self.channel_system.set_bus_solo(bus,solo),
 
-                        channel_system.set_system_channels(settings.format.channels);
+            AudioEngineCommand::FadeBus(bus,level,seconds)=>
+                self.channel_system.fade_bus(bus,level,seconds),
 
-                        let new_stream=event_loop.build_output_stream(&new_device,&settings.format).expect("Build a new stream");
+            // Добавляет эффект канала шины
+            // Если нет такой шины или канала, ничего не происходит
+            AudioEngineCommand::AddBusEffect(bus,channel,effect)=>
+                self.channel_system.add_bus_effect(bus,channel,effect),
 
-                        if playing_flag.lock().unwrap().clone(){
-                            event_loop.play_stream(new_stream.clone()).unwrap();
-                        }
-                        else{
-                            event_loop.pause_stream(new_stream.clone()).unwrap();
-                        }
+            AudioEngineCommand::ClearBusEffects(bus)=>
+                self.channel_system.clear_bus_effects(bus),
 
-                        *stream_lock=Some(new_stream.clone());
-                    }
-                    // Паникует, если какая-то другая ошибка
-                    // (пока не знаю, как нормально обработать)
-                    StreamError::BackendSpecific{err}=>{
-                        panic!("{}",err)
-                    }
-                }
-            }
-        }
-    })
-}
+        // ЭФФЕКТЫ \\
+            // Добавляет эффект трека
+            // Если нет такого трека, ничего не происходит
+            AudioEngineCommand::AddMonoEffect(index,effect)=>
+                self.channel_system.add_track_effect(index,effect),
 
-/// Вывод звука и постобработка (`feature="audio_post_processing"`).
-fn output<
-    S:SampleTransform,
-    #[cfg(feature="audio_post_processing")]P:FnMut(&mut Vec<f32>)
->(
-    channel_system:&mut ChannelSystem,
-    channels:u16,
-    volume:f32,
-    mut buffer:OutputBuffer<S>,
-    #[cfg(feature="audio_post_processing")]post_processing:P,
-){
-    let mut c=0usize;
+            AudioEngineCommand::ClearMonoEffects(index)=>
+                self.channel_system.clear_track_effects(index),
 
-    let mut frame=channel_system.next_frame();
+            // Добавляет эффект канала вывода
+            // Если нет такого канала, ничего не происходит
+            AudioEngineCommand::AddChannelEffect(channel,effect)=>
+                self.channel_system.add_channel_effect(channel,effect),
 
-    #[cfg(feature="audio_post_processing")]
-    post_processing(frame);
+            AudioEngineCommand::ClearChannelEffects(channel)=>
+                self.channel_system.clear_channel_effects(channel),
 
-    for b in buffer.iter_mut(){
-        // Обновление кадра
-        if c==channels as usize{
-            frame=channel_system.next_frame();
-            #[cfg(feature="audio_post_processing")]
-            post_processing(frame);
-            c=0;
-        }
+            // Обрабатывается владельцем
+            AudioEngineCommand::Close=>{}
 
-        let sample=frame[c];
-        *b=SampleTransform::from(sample,volume);
+            // Устройства вывода нет у `OfflineRenderer`,
+            // в аудио потоке обрабатывается владельцем
+            AudioEngineCommand::SwitchDevice(..)=>{}
+        }
+    }
 
-        c+=1;
+    /// Добавляет трек из хранилища в плейлист.
+    /// 
+    /// Если нет такого трека, ничего не происходит.
+    fn play_track(&mut self,set:TrackSet){
+        // Получение номера трека в хранилище
+        if let Some(track)=self.track_storage.get(set.index){
+            let index=set.index;
+            if !self.channel_system.add_track(track,set){
+                self.events.send(AudioEvent::PlaylistOverflow(Some(index)))
+            }
+        }
     }
-}
\ No newline at end of file
+}
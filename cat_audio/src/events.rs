@@ -0,0 +1,117 @@
+use std::sync::mpsc::{
+    Receiver,
+    SyncSender,
+    sync_channel,
+};
+
+/// Уведомления аудио потока.
+///
+/// Получаются через `Audio::events` или `Audio::poll_event`,
+/// например, при каждом обновлении игрового цикла.
+///
+/// Notifications from the audio thread.
+///
+/// Received with `Audio::events` or `Audio::poll_event`,
+/// for example, on every update of the game loop.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum AudioEvent{
+    /// Трек закончился (проигран полностью, остановлен или убран плавно)
+    /// и убран из плейлиста.
+    ///
+    /// Номер трека в хранилище, `None` для потоковых треков и источников звука.
+    ///
+    /// Треки, убранные из плейлиста напрямую
+    /// (`Audio::stop_track`, `Audio::clear_playlist`), не уведомляют.
+    ///
+    /// A track is finished (entirely played, stopped or faded out)
+    /// and removed from the playlist.
+    ///
+    /// The storage index of the track, `None` for streaming tracks and sound sources.
+    ///
+    /// Tracks removed from the playlist directly
+    /// (`Audio::stop_track`, `Audio::clear_playlist`) don't notify.
+    TrackFinished(Option<usize>),
+
+    /// Трек вернулся к началу повторяемой части.
+    ///
+    /// Номер трека в хранилище.
+    ///
+    /// A track has returned to the beginning of the repeated part.
+    ///
+    /// The storage index of the track.
+    LoopCompleted(usize),
+
+    /// Трек не добавлен - плейлист переполнен.
+    ///
+    /// Номер трека в хранилище, `None` для каналов потоковых треков и источников звука.
+    ///
+    /// A track is not added - the playlist is overflown.
+    ///
+    /// The storage index of the track, `None` for channels of streaming tracks and sound sources.
+    PlaylistOverflow(Option<usize>),
+
+    /// Трек не добавлен - нет такой ячейки хранилища.
+    ///
+    /// `Audio::add_track` сообщает о переполнении результатом,
+    /// так что это возможно только при прямой отправке команд.
+    ///
+    /// A track is not added - there is no such storage slot.
+    ///
+    /// `Audio::add_track` reports overflow by its result,
+    /// so this is possible only when sending commands directly.
+    StorageOverflow(usize),
+
+    /// Устройство вывода стало недоступно.
+    ///
+    /// Аудио поток ожидает устройство по умолчанию,
+    /// выполняя команды, пока оно не появится.
+    ///
+    /// The output device has become unavailable.
+    ///
+    /// The audio thread waits for the default device
+    /// executing commands until it appears.
+    DeviceLost,
+
+    /// Не удалось переключиться на устройство (`Audio::switch_device`):
+    /// оно не найдено или не поддерживает формат.
+    ///
+    /// Вывод остаётся на прежнем устройстве.
+    ///
+    /// Failed to switch to a device (`Audio::switch_device`):
+    /// it's not found or doesn't support the format.
+    ///
+    /// The output stays on the previous device.
+    DeviceUnavailable,
+
+    /// Вывод переключён на новое устройство
+    /// с данными частотой дискретизации и количеством каналов.
+    ///
+    /// The output is switched to a new device
+    /// with the given sample rate and channel count.
+    FormatChanged{
+        sample_rate:u32,
+        channels:u16,
+    },
+}
+
+/// Отправитель уведомлений.
+///
+/// Аудио поток не должен блокироваться,
+/// поэтому при переполненной очереди уведомления теряются.
+#[derive(Clone)]
+pub (crate) struct EventSender{
+    sender:SyncSender<AudioEvent>,
+}
+
+impl EventSender{
+    pub fn send(&self,event:AudioEvent){
+        // Очередь переполнена или уведомления больше не нужны
+        let _=self.sender.try_send(event);
+    }
+}
+
+/// Создаёт очередь уведомлений данной вместимости.
+pub (crate) fn event_channel(capacity:usize)->(EventSender,Receiver<AudioEvent>){
+    let (sender,receiver)=sync_channel(capacity);
+    (EventSender{sender},receiver)
+}
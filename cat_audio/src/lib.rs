@@ -6,8 +6,22 @@
 //! Также в нём есть хранилище аудио треков, которые можно запустить.
 //! 
 //! Поддерживает только вывод.
-//! Пока что позволяет декодировать только треки формата `mp3`.
+//! Декодирует треки форматов `mp3`, `wav`, `flac` и `ogg` (Vorbis).
 //! Все треки переводятся в 24-битный формат.
+//! Длинные треки можно проигрывать потоково (`StreamingTrack`),
+//! не декодируя их целиком.
+//! Без устройства вывода треки можно свести в файл (`Audio::offline`).
+//! К трекам и каналам вывода подключаются цепочки эффектов (`AudioEffect`).
+//! Общая шина защищена от перегрузки (`MasterBusSettings`).
+//! Треки можно панорамировать и размещать на плоскости (`TrackPlacement`),
+//! плавно проявлять, убирать и сменять (`Audio::crossfade_tracks`),
+//! перематывать и повторять их части (`LoopRegion`).
+//! Аудио поток уведомляет о завершении треков и смене устройства (`AudioEvent`).
+//! Треки группируются в шины со своими громкостью, выключением и соло (`Audio::add_bus`).
+//! Скорость и высота звука треков изменяются во время проигрывания (`Audio::set_track_rate`).
+//! Звуки можно синтезировать без файлов (`SoundSource`, `Oscillator`, `Sfxr`).
+//! Устройство вывода можно сменить во время работы (`Audio::switch_device`),
+//! а при его потере вывод переходит на устройство по умолчанию.
 //! 
 //! Поток закрывается с паникой, так что не паникуте!
 //! 
@@ -19,8 +33,22 @@
 //! Also it has a storage of audio tracks that could be played.
 //! 
 //! Supports only output.
-//! For now only 'mp3' format decoding is supported.
+//! Decodes `mp3`, `wav`, `flac` and `ogg` (Vorbis) tracks.
 //! All tracks are converted to the 24-bit format.
+//! Long tracks can be streamed (`StreamingTrack`)
+//! without decoding them entirely.
+//! Without an output device tracks can be mixed to a file (`Audio::offline`).
+//! Effect chains (`AudioEffect`) are attached to tracks and output channels.
+//! The master bus is protected from clipping (`MasterBusSettings`).
+//! Tracks can be panned and placed on the plane (`TrackPlacement`),
+//! faded in, faded out and crossfaded (`Audio::crossfade_tracks`),
+//! seeked and partially repeated (`LoopRegion`).
+//! The audio thread notifies about finished tracks and device changes (`AudioEvent`).
+//! Tracks are grouped into buses with their own volume, mute and solo (`Audio::add_bus`).
+//! The rate and pitch of tracks are changed during playback (`Audio::set_track_rate`).
+//! Sounds can be synthesized without files (`SoundSource`, `Oscillator`, `Sfxr`).
+//! The output device can be switched at runtime (`Audio::switch_device`),
+//! and when it's lost the output moves to the default device.
 //! 
 //! The thread closes with panic, so don't panic!
 //! 
@@ -55,13 +83,55 @@ mod tracks;
 pub use tracks::*;
 
 mod sample;
-use sample::SampleTransform;
+
+mod resampler;
+pub use resampler::ResamplerQuality;
+
+mod effects;
+pub use effects::*;
+
+mod synth;
+pub use synth::*;
+
+mod spatial;
+pub use spatial::{
+    PanLaw,
+    Attenuation,
+    Listener,
+    SpatialSource,
+    TrackPlacement,
+};
+
+mod bus;
+
+mod devices;
+pub use devices::{
+    OutputDevice,
+    output_devices,
+};
+
+mod events;
+pub use events::AudioEvent;
+use events::{
+    EventSender,
+    event_channel,
+};
+
+mod master_bus;
+pub use master_bus::{
+    MasterBusSettings,
+    CompressorSettings,
+    LimiterSettings,
+};
 
 #[cfg(not(feature="raw"))]
 mod wrapper;
 #[cfg(not(feature="raw"))]
 pub use wrapper::AudioWrapper;
 
+mod offline;
+pub use offline::OfflineRenderer;
+
 mod engine_commands;
 
 #[cfg(not(feature="extended"))]
@@ -71,6 +141,9 @@ pub use engine_commands::AudioEngineCommand;
 
 pub use engine_commands::AudioCommandResult;
 
+#[cfg(all(test,not(feature="raw")))]
+mod tests;
+
 use cpal::{
     Host,
     Device,
@@ -90,7 +163,7 @@ use std::{
     sync::{
         Arc,
         Mutex,
-        mpsc::{Sender,channel},
+        mpsc::{Sender,Receiver,TryIter,channel},
     },
 };
 
@@ -133,6 +206,55 @@ pub struct AudioSettings{
     /// 
     /// The default is 8.
     pub track_playlist_capacity:usize,
+
+    /// Качество перевода частоты треков в частоту системы.
+    /// 
+    /// По умолчанию `ResamplerQuality::Linear`.
+    /// 
+    /// The quality of converting the sample rate of tracks to the system one.
+    /// 
+    /// The default is `ResamplerQuality::Linear`.
+    pub resampler_quality:ResamplerQuality,
+
+    /// Обработка общей шины (защита от перегрузки).
+    /// 
+    /// По умолчанию `MasterBusSettings::new()` - только мягкое ограничение.
+    /// 
+    /// Master bus processing (clipping protection).
+    /// 
+    /// The default is `MasterBusSettings::new()` - only soft clipping.
+    pub master_bus:MasterBusSettings,
+
+    /// Закон панорамирования треков.
+    /// 
+    /// По умолчанию `PanLaw::ConstantPower`.
+    /// 
+    /// The pan law of tracks.
+    /// 
+    /// The default is `PanLaw::ConstantPower`.
+    pub pan_law:PanLaw,
+
+    /// Слушатель для треков, размещённых на плоскости.
+    /// 
+    /// По умолчанию в начале координат с `pan_width` равной 1.
+    /// 
+    /// The listener for tracks placed on the plane.
+    /// 
+    /// The default is at the origin with `pan_width` equal to 1.
+    pub listener:Listener,
+
+    /// Вместимость очереди уведомлений аудио потока.
+    /// 
+    /// Уведомления, не поместившиеся в очередь, теряются.
+    /// 
+    /// По умолчанию 64.
+    /// 
+    /// The capacity of the audio thread notification queue.
+    /// 
+    /// Notifications that don't fit into the queue are lost.
+    /// 
+    /// The default is 64.
+    pub event_queue_capacity:usize,
 }
 
 impl AudioSettings{
@@ -141,18 +263,22 @@ impl AudioSettings{
             general_volume:0.5f32,
             track_storage_capacity:8,
             track_playlist_capacity:8,
+            resampler_quality:ResamplerQuality::Linear,
+            master_bus:MasterBusSettings::new(),
+            pan_law:PanLaw::ConstantPower,
+            listener:Listener::default(),
+            event_queue_capacity:64,
         }
     }
 }
 
 /// Внутренние настройки системы.
 pub (crate) struct AudioSystemSettings{
-    pub general_volume:f32,
     pub output_channels:u16,
     pub format:Format,
 
-    pub track_storage_capacity:usize,
-    pub track_playlist:usize,
+    /// Настройки сведения.
+    pub mixer:AudioSettings,
 }
 
 
@@ -173,6 +299,7 @@ pub (crate) struct AudioSystemSettings{
 /// 
 /// Only output is available now.
 pub struct Audio{
+    host:Arc<Host>,
     playing_flag:Arc<Mutex<bool>>,
     stream:Arc<Mutex<Option<StreamId>>>,
 
@@ -181,11 +308,18 @@ pub struct Audio{
     command:Sender<AudioEngineCommand>,
     thread:Option<JoinHandle<()>>,
 
+    /// Уведомления аудио потока.
+    events:Receiver<AudioEvent>,
+
     // Флаги занятости слотов
     #[cfg(not(feature="raw"))]
     storage_slots:Vec<bool>,
     #[cfg(not(feature="raw"))]
     free_storage_slots:Vec<usize>,
+
+    // Имена шин по номерам
+    #[cfg(not(feature="raw"))]
+    bus_names:Vec<String>,
 }
 
 impl Audio{
@@ -216,6 +350,8 @@ impl Audio{
         let el=event_loop.clone();
         // Канал для передачи команд от управляющего потока выполняющему
         let (sender,receiver)=channel::<AudioEngineCommand>();
+        // Канал для уведомлений от выполняющего потока управляющему
+        let (event_sender,events)=event_channel(settings.event_queue_capacity);
 
         let owner_host=Arc::new(host);
         let host=owner_host.clone();
@@ -240,12 +376,10 @@ impl Audio{
             event_loop.play_stream(main_stream.clone()).unwrap();
 
             let system_settings=AudioSystemSettings{
-                general_volume:settings.general_volume,
                 output_channels:format.channels,
                 format,
 
-                track_storage_capacity:settings.track_storage_capacity,
-                track_playlist:settings.track_playlist_capacity,
+                mixer:settings,
             };
 
             // Забирает контроль над потоком и начинает обработку аудио потоков
@@ -257,6 +391,7 @@ impl Audio{
                 stream,
                 event_loop,
                 receiver,
+                event_sender,
             )
         });
 
@@ -265,7 +400,7 @@ impl Audio{
             Err(e)=>return Err(e),
         };
 
-        Ok(Self::init(playing_flag1,s,el,sender,thread,
+        Ok(Self::init(owner_host,playing_flag1,s,el,sender,events,Some(thread),
             #[cfg(not(feature="raw"))]track_storage_capacity))
     }
 
@@ -289,6 +424,8 @@ impl Audio{
         let el=event_loop.clone();
         // Канал для передачи команд от управляющего потока выполняющему
         let (sender,receiver)=channel::<AudioEngineCommand>();
+        // Канал для уведомлений от выполняющего потока управляющему
+        let (event_sender,events)=event_channel(settings.event_queue_capacity);
 
         let owner_host=Arc::new(host);
         let host=owner_host.clone();
@@ -312,12 +449,10 @@ impl Audio{
             event_loop.play_stream(main_stream.clone()).expect("No available device");
 
             let system_settings=AudioSystemSettings{
-                general_volume:settings.general_volume,
                 output_channels:format.channels,
                 format,
 
-                track_storage_capacity:settings.track_storage_capacity,
-                track_playlist:settings.track_playlist_capacity,
+                mixer:settings,
             };
 
             // Забирает контроль над потоком и начинает обработку аудио потоков
@@ -329,6 +464,7 @@ impl Audio{
                 stream,
                 event_loop,
                 receiver,
+                event_sender,
             )
         });
 
@@ -337,17 +473,53 @@ impl Audio{
             Err(e)=>return Err(e),
         };
 
-        Ok(Self::init(playing_flag1,s,el,sender,thread,
+        Ok(Self::init(owner_host,playing_flag1,s,el,sender,events,Some(thread),
             #[cfg(not(feature="raw"))]track_storage_capacity))
     }
 
+    /// Строит аудио движок без аудио потока и устройства вывода.
+    /// 
+    /// Команды выполняются и треки сводятся возвращаемым `OfflineRenderer`
+    /// с заданными частотой дискретизации и количеством каналов.
+    /// 
+    /// Creates an audio engine without an audio thread and an output device.
+    /// 
+    /// Commands are executed and tracks are mixed by the returned `OfflineRenderer`
+    /// with the given sample rate and channel count.
+    pub fn offline(settings:AudioSettings,sample_rate:u32,channels:u16)->(Audio,OfflineRenderer){
+        // Цикл событий без потоков не требует устройства
+        let host=cpal::default_host();
+        let event_loop=Arc::new(host.event_loop());
+
+        let (sender,receiver)=channel::<AudioEngineCommand>();
+        let (event_sender,events)=event_channel(settings.event_queue_capacity);
+
+        let renderer=OfflineRenderer::new(receiver,event_sender,&settings,sample_rate,channels);
+
+        let audio=Self::init(
+            Arc::new(host),
+            Arc::new(Mutex::new(true)),
+            Arc::new(Mutex::new(None)),
+            event_loop,
+            sender,
+            events,
+            None,
+            #[cfg(not(feature="raw"))]settings.track_storage_capacity
+        );
+
+        (audio,renderer)
+    }
+
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     fn init(
+        host:Arc<Host>,
         playing_flag:Arc<Mutex<bool>>,
         stream:Arc<Mutex<Option<StreamId>>>,
         event_loop:Arc<EventLoop>,
         sender:Sender<AudioEngineCommand>,
-        thread:JoinHandle<()>,
+        events:Receiver<AudioEvent>,
+        thread:Option<JoinHandle<()>>,
         #[cfg(not(feature="raw"))]track_storage_capacity:usize,
     )->Audio{
         #[cfg(not(feature="raw"))]
@@ -361,17 +533,21 @@ impl Audio{
         }
 
         Self{
+            host,
             playing_flag,
             stream,
 
             event_loop,
             command:sender,
-            thread:Some(thread),
+            thread,
+            events,
 
             #[cfg(not(feature="raw"))]
             free_storage_slots,
             #[cfg(not(feature="raw"))]
-            storage_slots
+            storage_slots,
+            #[cfg(not(feature="raw"))]
+            bus_names:Vec::new(),
         }
     }
 
@@ -384,6 +560,65 @@ impl Audio{
     }
 }
 
+/// Уведомления аудио потока.
+/// 
+/// Notifications from the audio thread.
+impl Audio{
+    /// Возвращает следующее уведомление, если оно есть.
+    /// 
+    /// Returns the next notification if there is any.
+    pub fn poll_event(&self)->Option<AudioEvent>{
+        self.events.try_recv().ok()
+    }
+
+    /// Возвращает итератор по пришедшим уведомлениям.
+    /// Итератор не ждёт новых уведомлений.
+    /// 
+    /// Returns an iterator over the received notifications.
+    /// The iterator doesn't wait for new notifications.
+    pub fn events(&self)->TryIter<'_,AudioEvent>{
+        self.events.try_iter()
+    }
+}
+
+/// Устройства вывода.
+/// 
+/// Output devices.
+impl Audio{
+    /// Возвращает доступные устройства вывода хоста движка.
+    /// 
+    /// Returns available output devices of the engine's host.
+    pub fn output_devices(&self)->Vec<OutputDevice>{
+        output_devices(&self.host)
+    }
+
+    /// Переключает вывод на устройство с данным именем
+    /// (`None` - по умолчанию) и данным форматом (`None` - формат устройства по умолчанию).
+    /// 
+    /// Хранилище, плейлист и шины сохраняются,
+    /// треки переводятся в новые частоту и количество каналов.
+    /// Об успехе сообщает `AudioEvent::FormatChanged`,
+    /// о неудаче - `AudioEvent::DeviceUnavailable`.
+    /// 
+    /// Команда выполняется, пока поток вывода проигрывается.
+    /// 
+    /// Switches the output to the device with the given name
+    /// (`None` - the default one) and the given format (`None` - the device's default one).
+    /// 
+    /// The storage, the playlist and the buses are kept,
+    /// tracks are converted to the new sample rate and channel count.
+    /// Success is reported by `AudioEvent::FormatChanged`,
+    /// failure - by `AudioEvent::DeviceUnavailable`.
+    /// 
+    /// The command is executed while the output stream is playing.
+    pub fn switch_device(&self,device:Option<&str>,format:Option<Format>)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::SwitchDevice(device.map(String::from),format)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+}
+
 /// feature="extended"
 #[cfg(feature="extended")]
 impl Audio{
@@ -597,6 +832,94 @@ impl Audio{
         result
     }
 
+    /// Проигрывает источник звука на данных каналах.
+    /// 
+    /// Источник занимает место в плейлисте
+    /// и управляется как обычный трек.
+    /// 
+    /// Plays a sound source on the given channels.
+    /// 
+    /// The source takes a place in the playlist
+    /// and is operated as a usual track.
+    pub fn play_source<S:SoundSource+'static>(&self,source:S,channels:Vec<usize>,volume:f32)->AudioCommandResult{
+        let stream_lock=match self.stream.lock(){
+            LockResult::Ok(lock)=>lock,
+            LockResult::Err(_)=>return AudioCommandResult::ThreadClosed
+        };
+
+        // Отправка команды
+        let result=match self.command.send(
+            AudioEngineCommand::PlaySourceOnChannels(Box::new(source),channels,volume)
+        ){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>return AudioCommandResult::ThreadClosed
+        };
+
+        if let Some(stream)=stream_lock.as_ref(){
+            self.event_loop.play_stream(stream.clone()).unwrap();
+        }
+
+        *self.playing_flag.lock().unwrap()=true;
+
+        result
+    }
+
+    /// Запускает поток декодирования трека и проигрывает трек.
+    /// 
+    /// Каждый канал трека занимает отдельное место в плейлисте
+    /// (в порядке каналов трека) и управляется как обычный трек.
+    /// Каналы трека без каналов распределения не выводятся.
+    /// Если в плейлисте нет места для всех каналов,
+    /// трек не проигрывается (`AudioEvent::PlaylistOverflow`).
+    /// Если поток декодирования не удалось создать,
+    /// возвращает `AudioCommandResult::ThreadClosed`.
+    /// 
+    /// Starts the decoding thread of a track and plays the track.
+    /// 
+    /// Each channel of the track takes a separate place in the playlist
+    /// (in the order of the track channels) and is operated as a usual track.
+    /// Track channels without output channels are not output.
+    /// If the playlist has no room for all the channels,
+    /// the track isn't played (`AudioEvent::PlaylistOverflow`).
+    /// If the decoding thread can't be created,
+    /// returns `AudioCommandResult::ThreadClosed`.
+    pub fn play_stream(&self,set:StreamSet)->AudioCommandResult{
+        let StreamSet{track,channels,repeats,volume}=set;
+
+        let receivers=match track.start(repeats){
+            Ok(receivers)=>receivers,
+            Err(_)=>return AudioCommandResult::ThreadClosed
+        };
+
+        // Все каналы трека должны читаться,
+        // иначе поток декодирования остановится
+        let mut channels=channels.into_iter();
+        let streams=receivers.into_iter()
+            .map(|receiver|(receiver,channels.next().unwrap_or_default()))
+            .collect();
+
+        let stream_lock=match self.stream.lock(){
+            LockResult::Ok(lock)=>lock,
+            LockResult::Err(_)=>return AudioCommandResult::ThreadClosed
+        };
+
+        // Отправка команды
+        let result=match self.command.send(
+            AudioEngineCommand::PlayStreamOnChannels(streams,volume)
+        ){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>return AudioCommandResult::ThreadClosed
+        };
+
+        if let Some(stream)=stream_lock.as_ref(){
+            self.event_loop.play_stream(stream.clone()).unwrap();
+        }
+
+        *self.playing_flag.lock().unwrap()=true;
+
+        result
+    }
+
     /// Останаливает трек из плейлиста.
     /// 
     /// Stops a track from the playlist.
@@ -767,6 +1090,31 @@ impl Audio{
         }
     }
 
+    /// Устанавливает скорость проигрывания играющего трека
+    /// (изменяет и высоту звука), 1 - исходная.
+    /// 
+    /// Скорость ограничена промежутком [1/16; 16].
+    /// 
+    /// Sets the playback rate of a playing track
+    /// (changes the pitch too), 1 - the original one.
+    /// 
+    /// The rate is limited to [1/16; 16].
+    pub fn set_track_rate(&self,index:usize,rate:f32)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::SetMonoRate(index,rate)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Устанавливает высоту звука играющего трека в полутонах
+    /// относительно исходной, изменяя скорость проигрывания.
+    /// 
+    /// Sets the pitch of a playing track in semitones
+    /// relative to the original one by changing the playback rate.
+    pub fn set_track_pitch(&self,index:usize,semitones:f32)->AudioCommandResult{
+        self.set_track_rate(index,semitones_to_rate(semitones))
+    }
+
     /// Устанавливает общую громкость.
     /// 
     /// Sets the general volume.
@@ -776,6 +1124,329 @@ impl Audio{
             Err(_)=>AudioCommandResult::ThreadClosed
         }
     }
+
+    /// Переходит к данному значению играющего трека.
+    /// 
+    /// Для потоковых треков ничего не происходит.
+    /// 
+    /// Seeks a playing track to the given sample.
+    /// 
+    /// Nothing happens for streaming tracks.
+    pub fn seek_track(&self,index:usize,frame:usize)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::SeekMono(index,frame)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Устанавливает повторяемую часть играющего трека,
+    /// `None` - весь трек.
+    /// 
+    /// Sets the repeated part of a playing track,
+    /// `None` - the whole track.
+    pub fn set_track_loop(&self,index:usize,loop_region:Option<LoopRegion>)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::SetMonoLoop(index,loop_region)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Запрашивает положение в играющем треке.
+    /// 
+    /// Ответ отправляется аудио потоком при выполнении команды,
+    /// `None` - нет такого трека.
+    /// 
+    /// Requests the position in a playing track.
+    /// 
+    /// The answer is sent by the audio thread when the command is executed,
+    /// `None` - no such track.
+    pub fn track_position(&self,index:usize,sender:Sender<Option<TrackPosition>>)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::GetMonoPosition(index,sender)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Плавно изменяет уровень громкости играющего трека
+    /// за данное время в секундах.
+    /// 
+    /// Уровень умножается на громкость трека, изначально равен 1.
+    /// 
+    /// Smoothly changes the volume level of a playing track
+    /// during the given time in seconds.
+    /// 
+    /// The level is multiplied by the track volume, initially it's 1.
+    pub fn fade_track(&self,index:usize,level:f32,seconds:f32)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::FadeMono(index,level,seconds)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Плавно убирает играющий трек за данное время в секундах,
+    /// после чего останавливает его.
+    /// 
+    /// Fades out a playing track during the given time in seconds
+    /// and then stops it.
+    pub fn fade_out_track(&self,index:usize,seconds:f32)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::FadeOutMono(index,seconds)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Плавно убирает и останавливает играющий трек `from`
+    /// и проявляет играющий трек `to` за данное время в секундах.
+    /// 
+    /// Fades out and stops the playing track `from`
+    /// and fades in the playing track `to` during the given time in seconds.
+    pub fn crossfade_tracks(&self,from:usize,to:usize,seconds:f32)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::CrossfadeMonos(from,to,seconds)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Устанавливает размещение играющего трека.
+    /// 
+    /// Громкости каналов плавно изменяются в течение блока сведения.
+    /// 
+    /// Sets the placement of a playing track.
+    /// 
+    /// Channel volumes change smoothly during a mixing block.
+    pub fn set_track_placement(&self,index:usize,placement:TrackPlacement)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::SetMonoPlacement(index,placement)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Устанавливает панораму играющего трека.
+    /// 
+    /// Sets the panning of a playing track.
+    pub fn set_track_pan(&self,index:usize,pan:f32)->AudioCommandResult{
+        self.set_track_placement(index,TrackPlacement::Pan(pan))
+    }
+
+    /// Перемещает играющий трек, размещённый на плоскости.
+    /// 
+    /// Moves a playing track placed on the plane.
+    pub fn set_track_position(&self,index:usize,position:[f32;2])->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::SetMonoPosition(index,position)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Перемещает играющие треки, размещённые на плоскости.
+    /// 
+    /// Moves playing tracks placed on the plane.
+    pub fn set_tracks_positions(&self,sets:Vec<(usize,[f32;2])>)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::SetMonosPositions(sets)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Устанавливает слушателя для треков, размещённых на плоскости.
+    /// 
+    /// Sets the listener for tracks placed on the plane.
+    pub fn set_listener(&self,listener:Listener)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::SetListener(listener)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Устанавливает обработку общей шины.
+    /// 
+    /// Состояние компрессора и ограничителя сбрасывается.
+    /// 
+    /// Sets the master bus processing.
+    /// 
+    /// The state of the compressor and the limiter is reset.
+    pub fn set_master_bus(&self,settings:MasterBusSettings)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::SetMasterBus(settings)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+}
+
+/// Эффекты.
+/// 
+/// Effects.
+#[cfg(not(feature="raw"))]
+impl Audio{
+    /// Добавляет эффект в конец цепочки играющего трека.
+    /// 
+    /// Эффекты трека удаляются вместе с ним,
+    /// так что хвосты (эхо, реверберация) обрываются, когда трек заканчивается.
+    /// 
+    /// Adds an effect to the end of the chain of a playing track.
+    /// 
+    /// Effects of a track are removed with it,
+    /// so tails (echo, reverb) are cut off when the track ends.
+    pub fn add_track_effect<E:AudioEffect+'static>(&self,index:usize,effect:E)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::AddMonoEffect(index,Box::new(effect))){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Удаляет все эффекты играющего трека.
+    /// 
+    /// Removes all the effects of a playing track.
+    pub fn clear_track_effects(&self,index:usize)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::ClearMonoEffects(index)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Добавляет эффект в конец цепочки канала вывода.
+    /// 
+    /// Эффекты канала обрабатывают сведённые треки до общей громкости.
+    /// 
+    /// Adds an effect to the end of the chain of an output channel.
+    /// 
+    /// Channel effects process the mixed tracks before the general volume.
+    pub fn add_channel_effect<E:AudioEffect+'static>(&self,channel:usize,effect:E)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::AddChannelEffect(channel,Box::new(effect))){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Удаляет все эффекты канала вывода.
+    /// 
+    /// Removes all the effects of an output channel.
+    pub fn clear_channel_effects(&self,channel:usize)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::ClearChannelEffects(channel)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+}
+
+/// Шины.
+/// 
+/// Треки сводятся в шину, заданную при запуске (`TrackSet::bus`),
+/// шины - в родительские шины или в общую.
+/// 
+/// Buses.
+/// 
+/// Tracks are mixed into the bus given on playing (`TrackSet::bus`),
+/// buses - into parent buses or into the master one.
+#[cfg(not(feature="raw"))]
+impl Audio{
+    /// Добавляет именованную шину, сводимую в родительскую шину,
+    /// `None` - в общую шину.
+    /// 
+    /// Возвращает номер шины.
+    /// Если шина с таким именем уже есть, возвращает её номер.
+    /// 
+    /// Adds a named bus mixed into the parent bus,
+    /// `None` - into the master bus.
+    /// 
+    /// Returns the bus index.
+    /// If there is already a bus with this name, returns its index.
+    pub fn add_bus(&mut self,name:&str,parent:Option<usize>)->AudioCommandResult{
+        if let Some(bus)=self.bus(name){
+            return AudioCommandResult::Index(bus)
+        }
+
+        match self.command.send(AudioEngineCommand::AddBus(parent)){
+            Ok(())=>{
+                self.bus_names.push(name.to_string());
+                AudioCommandResult::Index(self.bus_names.len()-1)
+            }
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Возвращает номер шины по имени.
+    /// 
+    /// Returns the index of a bus by its name.
+    pub fn bus(&self,name:&str)->Option<usize>{
+        self.bus_names.iter().position(|bus_name|bus_name==name)
+    }
+
+    /// Устанавливает громкость шины.
+    /// 
+    /// Sets a volume of a bus.
+    pub fn set_bus_volume(&self,bus:usize,volume:f32)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::SetBusVolume(bus,volume)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Выключает (`true`) или включает шину.
+    /// 
+    /// Mutes (`true`) or unmutes a bus.
+    pub fn set_bus_mute(&self,bus:usize,mute:bool)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::SetBusMute(bus,mute)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Включает (`true`) или выключает соло шины.
+    /// 
+    /// Пока у какой-то шины включено соло, слышны только шины с соло,
+    /// их дочерние и родительские шины. Треки без шины не слышны.
+    /// 
+    /// Enables (`true`) or disables solo of a bus.
+    /// 
+    /// While some bus is soloed, only soloed buses,
+    /// their child and parent buses are heard. Tracks without a bus are silent.
+    pub fn set_bus_solo(&self,bus:usize,solo:bool)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::SetBusSolo(bus,solo)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Плавно изменяет уровень громкости шины
+    /// за данное время в секундах.
+    /// 
+    /// Уровень умножается на громкость шины.
+    /// 
+    /// Smoothly changes the volume level of a bus
+    /// during the given time in seconds.
+    /// 
+    /// The level is multiplied by the bus volume.
+    pub fn fade_bus(&self,bus:usize,level:f32,seconds:f32)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::FadeBus(bus,level,seconds)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Добавляет эффект в конец цепочки канала шины.
+    /// 
+    /// Эффекты шины обрабатывают её сведённые треки до громкости шины.
+    /// 
+    /// Adds an effect to the end of the chain of a bus channel.
+    /// 
+    /// Bus effects process its mixed tracks before the bus volume.
+    pub fn add_bus_effect<E:AudioEffect+'static>(&self,bus:usize,channel:usize,effect:E)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::AddBusEffect(bus,channel,Box::new(effect))){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Удаляет все эффекты шины.
+    /// 
+    /// Removes all the effects of a bus.
+    pub fn clear_bus_effects(&self,bus:usize)->AudioCommandResult{
+        match self.command.send(AudioEngineCommand::ClearBusEffects(bus)){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
 }
 
 /// Упраление через хранилище треков.
@@ -867,6 +1538,34 @@ impl Audio{
         }
     }
 
+    /// Плавно изменяет уровень громкости треков из плейлиста,
+    /// привязанных к трекам из хранилища.
+    /// 
+    /// Smoothly changes the volume level of tracks from the playlist
+    /// attached to tracks from the storage.
+    pub fn fade_tracks_storage(&self,indices:Vec<usize>,level:f32,seconds:f32)->AudioCommandResult{
+        match self.command.send(
+            AudioEngineCommand::FadeMonosFromStorage(indices,level,seconds)
+        ){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
+    /// Плавно убирает и останавливает треки из плейлиста,
+    /// привязанные к трекам из хранилища.
+    /// 
+    /// Fades out and stops tracks from the playlist
+    /// attached to tracks from the storage.
+    pub fn fade_out_tracks_storage(&self,indices:Vec<usize>,seconds:f32)->AudioCommandResult{
+        match self.command.send(
+            AudioEngineCommand::FadeOutMonosFromStorage(indices,seconds)
+        ){
+            Ok(())=>AudioCommandResult::Sent,
+            Err(_)=>AudioCommandResult::ThreadClosed
+        }
+    }
+
     /// Устанавливает громкость треков из плейлиста,
     /// привязанных к треку из хранилища.
     /// 
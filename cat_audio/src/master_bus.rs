@@ -0,0 +1,400 @@
+use std::collections::VecDeque;
+
+/// Значения ниже этой амплитуды мягкий ограничитель не изменяет.
+const soft_clip_knee:f32=0.8f32;
+
+/// Настройки компрессора общей шины.
+///
+/// Settings of the master bus compressor.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct CompressorSettings{
+    /// Амплитуда, выше которой сигнал сжимается.
+    ///
+    /// The amplitude above which the signal is compressed.
+    pub threshold:f32,
+
+    /// Степень сжатия (от 1).
+    ///
+    /// The compression ratio (from 1).
+    pub ratio:f32,
+
+    /// Время атаки в секундах.
+    ///
+    /// The attack time in seconds.
+    pub attack:f32,
+
+    /// Время восстановления в секундах.
+    ///
+    /// The release time in seconds.
+    pub release:f32,
+
+    /// Усиление после сжатия.
+    ///
+    /// The gain after compression.
+    pub makeup:f32,
+}
+
+impl Default for CompressorSettings{
+    fn default()->CompressorSettings{
+        CompressorSettings::new()
+    }
+}
+
+impl CompressorSettings{
+    pub fn new()->CompressorSettings{
+        Self{
+            threshold:0.5f32,
+            ratio:4f32,
+            attack:0.01f32,
+            release:0.1f32,
+            makeup:1f32,
+        }
+    }
+}
+
+/// Настройки ограничителя общей шины.
+///
+/// Ограничитель видит сигнал на `look_ahead` секунд вперёд
+/// и плавно понижает громкость до пика,
+/// поэтому вывод задерживается на это время.
+///
+/// Settings of the master bus limiter.
+///
+/// The limiter sees the signal `look_ahead` seconds ahead
+/// and smoothly lowers the gain before a peak,
+/// so the output is delayed by this time.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct LimiterSettings{
+    /// Наибольшая амплитуда вывода.
+    ///
+    /// The greatest output amplitude.
+    pub ceiling:f32,
+
+    /// Время предпросмотра в секундах.
+    ///
+    /// The look-ahead time in seconds.
+    pub look_ahead:f32,
+
+    /// Время восстановления в секундах.
+    ///
+    /// The release time in seconds.
+    pub release:f32,
+}
+
+impl Default for LimiterSettings{
+    fn default()->LimiterSettings{
+        LimiterSettings::new()
+    }
+}
+
+impl LimiterSettings{
+    pub fn new()->LimiterSettings{
+        Self{
+            ceiling:0.95f32,
+            look_ahead:0.005f32,
+            release:0.05f32,
+        }
+    }
+}
+
+/// Обработка общей шины - после общей громкости и перед выводом.
+///
+/// Порядок: компрессор, ограничитель, мягкое ограничение.
+/// После обработки значения всегда обрезаются до [-1; 1].
+///
+/// Master bus processing - after the general volume and before the output.
+///
+/// The order: compressor, limiter, soft clipping.
+/// After processing samples are always clamped to [-1; 1].
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct MasterBusSettings{
+    /// Компрессор, `None` - выключен.
+    ///
+    /// По умолчанию выключен.
+    ///
+    /// The compressor, `None` - disabled.
+    ///
+    /// The default is disabled.
+    pub compressor:Option<CompressorSettings>,
+
+    /// Ограничитель, `None` - выключен.
+    ///
+    /// По умолчанию выключен.
+    ///
+    /// The limiter, `None` - disabled.
+    ///
+    /// The default is disabled.
+    pub limiter:Option<LimiterSettings>,
+
+    /// Мягкое ограничение амплитуд выше 0.8 вместо обрезки.
+    ///
+    /// По умолчанию включено.
+    ///
+    /// Soft clipping of amplitudes above 0.8 instead of hard clipping.
+    ///
+    /// The default is enabled.
+    pub soft_clip:bool,
+}
+
+impl Default for MasterBusSettings{
+    fn default()->MasterBusSettings{
+        MasterBusSettings::new()
+    }
+}
+
+impl MasterBusSettings{
+    pub fn new()->MasterBusSettings{
+        Self{
+            compressor:None,
+            limiter:None,
+            soft_clip:true,
+        }
+    }
+
+    /// Без обработки - только обрезка до [-1; 1].
+    ///
+    /// No processing - only clamping to [-1; 1].
+    pub fn bypass()->MasterBusSettings{
+        Self{
+            compressor:None,
+            limiter:None,
+            soft_clip:false,
+        }
+    }
+}
+
+/// Состояние компрессора.
+struct Compressor{
+    settings:CompressorSettings,
+    attack:f32,
+    release:f32,
+    /// Огибающая пиков кадров.
+    envelope:f32,
+}
+
+impl Compressor{
+    fn new(settings:CompressorSettings,sample_rate:u32)->Compressor{
+        Self{
+            settings,
+            attack:smoothing(settings.attack,sample_rate),
+            release:smoothing(settings.release,sample_rate),
+            envelope:0f32,
+        }
+    }
+
+    fn process(&mut self,frame:&mut [f32]){
+        let peak=frame_peak(frame);
+
+        let coefficient=if peak>self.envelope{self.attack}else{self.release};
+        self.envelope+=(peak-self.envelope)*coefficient;
+
+        let threshold=self.settings.threshold.max(f32::EPSILON);
+
+        let mut gain=self.settings.makeup;
+        if self.envelope>threshold{
+            let ratio=self.settings.ratio.max(1f32);
+            let compressed=threshold*(self.envelope/threshold).powf(1f32/ratio);
+            gain*=compressed/self.envelope;
+        }
+
+        for sample in frame{
+            *sample*=gain
+        }
+    }
+}
+
+/// Состояние ограничителя.
+///
+/// Необходимое усиление каждого кадра удерживается на время предпросмотра
+/// (минимум в окне из `look_ahead+1` кадров), плавно восстанавливается
+/// и усредняется по `look_ahead` кадрам,
+/// так что к выходу пика из задержки усиление уже понижено.
+struct Limiter{
+    ceiling:f32,
+    release:f32,
+    /// Длина предпросмотра в кадрах.
+    look_ahead:usize,
+
+    /// Задержанные кадры (каналы чередуются).
+    delay:Vec<f32>,
+    /// Номер текущего кадра в задержке.
+    position:usize,
+
+    /// Номер текущего кадра с начала обработки.
+    frame:u64,
+    /// Кандидаты на минимум в окне (номер кадра, усиление).
+    hold:VecDeque<(u64,f32)>,
+    /// Усиление после восстановления.
+    released:f32,
+
+    /// Окно усреднения усилений.
+    smooth:Vec<f32>,
+    smooth_sum:f64,
+}
+
+impl Limiter{
+    fn new(settings:LimiterSettings,sample_rate:u32,channels:usize)->Limiter{
+        let look_ahead=((settings.look_ahead.max(0f32)*sample_rate as f32).round() as usize).max(1);
+
+        Self{
+            ceiling:settings.ceiling.clamp(f32::EPSILON,1f32),
+            release:smoothing(settings.release,sample_rate),
+            look_ahead,
+
+            delay:vec![0f32;look_ahead*channels],
+            position:0,
+
+            frame:0,
+            hold:VecDeque::with_capacity(look_ahead+2),
+            released:1f32,
+
+            smooth:vec![1f32;look_ahead],
+            smooth_sum:look_ahead as f64,
+        }
+    }
+
+    fn process(&mut self,frame:&mut [f32]){
+        let channels=frame.len();
+
+        // Необходимое усиление кадра
+        let peak=frame_peak(frame);
+        let target=if peak>self.ceiling{self.ceiling/peak}else{1f32};
+
+        // Минимум в окне предпросмотра
+        while let Some(&(_,gain))=self.hold.back(){
+            if gain>=target{
+                self.hold.pop_back();
+            }
+            else{
+                break
+            }
+        }
+        self.hold.push_back((self.frame,target));
+        while let Some(&(frame,_))=self.hold.front(){
+            if frame+(self.look_ahead as u64)<self.frame{
+                self.hold.pop_front();
+            }
+            else{
+                break
+            }
+        }
+        let held=self.hold.front().map(|&(_,gain)|gain).unwrap_or(1f32);
+
+        // Мгновенное понижение и плавное восстановление
+        self.released=held.min(self.released+(1f32-self.released)*self.release);
+
+        // Усреднение
+        let slot=(self.frame%self.look_ahead as u64) as usize;
+        self.smooth_sum+=self.released as f64-self.smooth[slot] as f64;
+        self.smooth[slot]=self.released;
+        let gain=(self.smooth_sum/self.look_ahead as f64) as f32;
+
+        // Задержка
+        let delayed=&mut self.delay[self.position*channels..(self.position+1)*channels];
+        for (sample,delayed) in frame.iter_mut().zip(delayed.iter_mut()){
+            *sample=std::mem::replace(delayed,*sample)*gain;
+        }
+
+        self.position=(self.position+1)%self.look_ahead;
+        self.frame+=1;
+    }
+}
+
+/// Обработчик общей шины.
+pub (crate) struct MasterBus{
+    settings:MasterBusSettings,
+    sample_rate:u32,
+    channels:usize,
+
+    compressor:Option<Compressor>,
+    limiter:Option<Limiter>,
+}
+
+impl MasterBus{
+    pub fn new(settings:MasterBusSettings,sample_rate:u32,channels:u16)->MasterBus{
+        let mut master=Self{
+            settings,
+            sample_rate,
+            channels:channels as usize,
+
+            compressor:None,
+            limiter:None,
+        };
+        master.reset();
+        master
+    }
+
+    /// Устанавливает новые настройки и сбрасывает состояние.
+    pub fn set_settings(&mut self,settings:MasterBusSettings){
+        self.settings=settings;
+        self.reset()
+    }
+
+    /// Устанавливает новые частоту дискретизации и количество каналов
+    /// и сбрасывает состояние.
+    pub fn set_format(&mut self,sample_rate:u32,channels:u16){
+        self.sample_rate=sample_rate;
+        self.channels=channels as usize;
+        self.reset()
+    }
+
+    fn reset(&mut self){
+        self.compressor=self.settings.compressor.map(|settings|Compressor::new(settings,self.sample_rate));
+        self.limiter=self.settings.limiter.map(|settings|Limiter::new(settings,self.sample_rate,self.channels));
+    }
+
+    /// Обрабатывает блок кадров (каналы чередуются).
+    pub fn process(&mut self,block:&mut [f32]){
+        if self.channels==0{
+            return
+        }
+
+        for frame in block.chunks_exact_mut(self.channels){
+            if let Some(compressor)=&mut self.compressor{
+                compressor.process(frame)
+            }
+
+            if let Some(limiter)=&mut self.limiter{
+                limiter.process(frame)
+            }
+
+            for sample in frame{
+                if self.settings.soft_clip{
+                    *sample=soft_clip(*sample)
+                }
+                *sample=sample.clamp(-1f32,1f32)
+            }
+        }
+    }
+}
+
+/// Не изменяет амплитуды ниже `soft_clip_knee`,
+/// остальные плавно сжимает к единице.
+fn soft_clip(sample:f32)->f32{
+    let amplitude=sample.abs();
+
+    if amplitude<=soft_clip_knee{
+        sample
+    }
+    else{
+        let range=1f32-soft_clip_knee;
+        let clipped=soft_clip_knee+range*((amplitude-soft_clip_knee)/range).tanh();
+        clipped.copysign(sample)
+    }
+}
+
+fn frame_peak(frame:&[f32])->f32{
+    frame.iter().fold(0f32,|peak,&sample|peak.max(sample.abs()))
+}
+
+/// Коэффициент экспоненциального сглаживания за заданное время.
+fn smoothing(time:f32,sample_rate:u32)->f32{
+    let frames=time*sample_rate as f32;
+
+    if frames<=1f32{
+        1f32
+    }
+    else{
+        1f32-(-1f32/frames).exp()
+    }
+}
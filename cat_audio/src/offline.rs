@@ -0,0 +1,143 @@
+use super::{
+    AudioEngineCommand,
+    AudioSettings,
+    EventSender,
+    engine_core::Mixer,
+    mix_block_frames,
+};
+
+use std::{
+    io::{
+        Seek,
+        Write,
+    },
+    path::Path,
+    sync::mpsc::Receiver,
+};
+
+/// Сведение звука без устройства вывода.
+///
+/// Выполняет те же команды и сводит треки так же, как аудио поток,
+/// но только по запросу и с заданными частотой и количеством каналов.
+/// Создаётся вместе с управляющим `Audio` функцией `Audio::offline`.
+///
+/// Offline rendering without an output device.
+///
+/// Executes the same commands and mixes tracks the same way as the audio thread,
+/// but only on request and with the given sample rate and channel count.
+/// Created with the controlling `Audio` by `Audio::offline`.
+///
+/// ```
+/// # use cat_audio::{Audio,AudioSettings,AudioCommandResult,MonoTrack,TrackSet};
+/// let (mut audio,mut renderer)=Audio::offline(AudioSettings::new(),44100,2);
+///
+/// if let AudioCommandResult::Index(index)=audio.add_track(MonoTrack::new(vec![0.5f32;44100],44100)){
+///     audio.play_track(TrackSet::once(index,vec![0,1]));
+/// }
+///
+/// let mix=renderer.render_seconds(2f32);
+/// assert_eq!(mix.len(),2*44100*2);
+/// ```
+pub struct OfflineRenderer{
+    mixer:Mixer,
+    receiver:Receiver<AudioEngineCommand>,
+    sample_rate:u32,
+    channels:u16,
+}
+
+impl OfflineRenderer{
+    pub (crate) fn new(
+        receiver:Receiver<AudioEngineCommand>,
+        events:EventSender,
+        settings:&AudioSettings,
+        sample_rate:u32,
+        channels:u16,
+    )->OfflineRenderer{
+        Self{
+            mixer:Mixer::new(settings,sample_rate,channels,events),
+            receiver,
+            sample_rate,
+            channels,
+        }
+    }
+
+    pub fn sample_rate(&self)->u32{
+        self.sample_rate
+    }
+
+    pub fn channels(&self)->u16{
+        self.channels
+    }
+
+    /// Выполняет все отправленные команды.
+    ///
+    /// Executes all the sent commands.
+    pub fn execute_commands(&mut self){
+        while let Ok(command)=self.receiver.try_recv(){
+            self.mixer.execute(command)
+        }
+    }
+
+    /// Выполняет отправленные команды и сводит `frames` кадров
+    /// с общей громкостью и обработкой общей шины,
+    /// добавляя их в буфер (каналы чередуются).
+    ///
+    /// Executes the sent commands and mixes `frames` frames
+    /// with the general volume and the master bus processing
+    /// appending them to the buffer (channels are interleaved).
+    pub fn render(&mut self,frames:usize,buffer:&mut Vec<f32>){
+        self.execute_commands();
+
+        buffer.reserve(frames*self.channels as usize);
+
+        let mut left=frames;
+        while left!=0{
+            let block_frames=left.min(mix_block_frames);
+            buffer.extend_from_slice(self.mixer.next_output_block(block_frames));
+            left-=block_frames;
+        }
+    }
+
+    /// Сводит заданное количество секунд.
+    ///
+    /// Mixes the given amount of seconds.
+    pub fn render_seconds(&mut self,seconds:f32)->Vec<f32>{
+        let mut buffer=Vec::new();
+        self.render(self.frames(seconds),&mut buffer);
+        buffer
+    }
+
+    /// Сводит заданное количество секунд и записывает их
+    /// в формате `wav` (32-битный вещественный PCM).
+    ///
+    /// Mixes the given amount of seconds and writes them
+    /// in the `wav` format (32-bit float PCM).
+    pub fn render_to_wav<W:Write+Seek>(&mut self,seconds:f32,writer:W)->hound::Result<()>{
+        let spec=hound::WavSpec{
+            channels:self.channels,
+            sample_rate:self.sample_rate,
+            bits_per_sample:32,
+            sample_format:hound::SampleFormat::Float,
+        };
+
+        let mut writer=hound::WavWriter::new(writer,spec)?;
+
+        for sample in self.render_seconds(seconds){
+            writer.write_sample(sample)?;
+        }
+
+        writer.finalize()
+    }
+
+    /// Сводит заданное количество секунд и записывает их в файл `wav`.
+    ///
+    /// Mixes the given amount of seconds and writes them to a `wav` file.
+    pub fn render_to_file<P:AsRef<Path>>(&mut self,seconds:f32,path:P)->hound::Result<()>{
+        let file=std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.render_to_wav(seconds,file)
+    }
+
+    fn frames(&self,seconds:f32)->usize{
+        (seconds.max(0f32)*self.sample_rate as f32).round() as usize
+    }
+}
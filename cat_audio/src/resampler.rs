@@ -0,0 +1,123 @@
+use std::{
+    f32::consts::PI,
+    sync::OnceLock,
+};
+
+/// Половина ширины ядра `ResamplerQuality::Sinc` в значениях трека.
+const sinc_half_width:usize=8;
+
+/// Количество значений таблицы ядра на единицу.
+const sinc_table_resolution:usize=256;
+
+/// Наибольшее окно значений трека.
+pub (crate) const max_resampler_window:usize=sinc_half_width*2;
+
+/// Качество перевода частоты дискретизации трека в частоту системы.
+///
+/// The quality of converting the sample rate of a track to the system one.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub enum ResamplerQuality{
+    /// Линейная интерполяция между соседними значениями.
+    /// Самый быстрый, но при повышении частоты заметны искажения.
+    ///
+    /// Linear interpolation between neighbouring samples.
+    /// The fastest, but upsampling artifacts are audible.
+    #[default]
+    Linear,
+
+    /// Кубическая интерполяция Эрмита по четырём значениям.
+    ///
+    /// Cubic Hermite interpolation over four samples.
+    Cubic,
+
+    /// Интерполяция синком с окном Ланцоша по 16 значениям.
+    /// При понижении частоты срезает частоты выше новой частоты Найквиста.
+    /// Самый точный и самый дорогой.
+    ///
+    /// Lanczos windowed sinc interpolation over 16 samples.
+    /// Cuts frequencies above the new Nyquist frequency when downsampling.
+    /// The most accurate and the most expensive.
+    Sinc,
+}
+
+impl ResamplerQuality{
+    /// Количество значений трека, необходимых для интерполяции.
+    /// Интерполируется середина окна.
+    pub (crate) fn window(self)->usize{
+        match self{
+            ResamplerQuality::Linear=>2,
+            ResamplerQuality::Cubic=>4,
+            ResamplerQuality::Sinc=>max_resampler_window,
+        }
+    }
+
+    /// Значение между `window[len/2-1]` и `window[len/2]`
+    /// в позиции `t` ([0; 1)).
+    ///
+    /// `cutoff` - отношение частоты вывода к частоте трека, если оно меньше единицы.
+    pub (crate) fn interpolate(self,window:&[f32],t:f32,cutoff:f32)->f32{
+        match self{
+            ResamplerQuality::Linear=>window[0]+(window[1]-window[0])*t,
+
+            ResamplerQuality::Cubic=>{
+                let [y0,y1,y2,y3]=[window[0],window[1],window[2],window[3]];
+
+                // Сплайн Катмулла-Рома
+                let c1=0.5f32*(y2-y0);
+                let c2=y0-2.5f32*y1+2f32*y2-0.5f32*y3;
+                let c3=0.5f32*(y3-y0)+1.5f32*(y1-y2);
+
+                ((c3*t+c2)*t+c1)*t+y1
+            }
+
+            ResamplerQuality::Sinc=>{
+                let mut sum=0f32;
+                let mut weights=0f32;
+
+                // `window[i]` находится в позиции `i-(half_width-1)`
+                for (i,&sample) in window.iter().enumerate(){
+                    let x=(t+(sinc_half_width-1) as f32-i as f32)*cutoff;
+                    let weight=lanczos(x);
+                    sum+=sample*weight;
+                    weights+=weight;
+                }
+
+                // Сохранение громкости при усечённом ядре
+                if weights.abs()>f32::EPSILON{
+                    sum/weights
+                }
+                else{
+                    0f32
+                }
+            }
+        }
+    }
+}
+
+/// Ядро Ланцоша по таблице с линейной интерполяцией.
+fn lanczos(x:f32)->f32{
+    static lanczos_table:OnceLock<Vec<f32>>=OnceLock::new();
+
+    let table=lanczos_table.get_or_init(||{
+        (0..=sinc_half_width*sinc_table_resolution).map(|n|{
+            let x=n as f32/sinc_table_resolution as f32;
+            if n==0{
+                1f32
+            }
+            else{
+                let a=sinc_half_width as f32;
+                a*(PI*x).sin()*(PI*x/a).sin()/(PI*PI*x*x)
+            }
+        }).collect()
+    });
+
+    let position=x.abs()*sinc_table_resolution as f32;
+    let index=position as usize;
+
+    if index+1>=table.len(){
+        return 0f32
+    }
+
+    let fraction=position-index as f32;
+    table[index]+(table[index+1]-table[index])*fraction
+}
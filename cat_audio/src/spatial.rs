@@ -0,0 +1,267 @@
+use std::f32::consts::FRAC_PI_2;
+
+/// Закон панорамирования - распределение громкости
+/// между двумя соседними каналами.
+///
+/// The pan law - distribution of the volume
+/// between two neighbouring channels.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub enum PanLaw{
+    /// Громкости линейны, в центре каждый канал тише на 6 дБ.
+    ///
+    /// Linear volumes, each channel is 6 dB lower at the center.
+    Linear,
+
+    /// Постоянная мощность, в центре каждый канал тише на 3 дБ.
+    ///
+    /// Constant power, each channel is 3 dB lower at the center.
+    #[default]
+    ConstantPower,
+
+    /// Среднее между линейным законом и постоянной мощностью,
+    /// в центре каждый канал тише на 4.5 дБ.
+    ///
+    /// The mean of the linear law and constant power,
+    /// each channel is 4.5 dB lower at the center.
+    Compromise,
+}
+
+impl PanLaw{
+    /// Громкости двух каналов в позиции `t` между ними ([0; 1]).
+    ///
+    /// Volumes of two channels at the position `t` between them ([0; 1]).
+    pub fn gains(self,t:f32)->(f32,f32){
+        let t=t.clamp(0f32,1f32);
+
+        match self{
+            PanLaw::Linear=>(1f32-t,t),
+
+            PanLaw::ConstantPower=>((t*FRAC_PI_2).cos(),(t*FRAC_PI_2).sin()),
+
+            PanLaw::Compromise=>(
+                ((1f32-t)*(t*FRAC_PI_2).cos()).sqrt(),
+                (t*(t*FRAC_PI_2).sin()).sqrt()
+            ),
+        }
+    }
+}
+
+/// Затухание звука с расстоянием до слушателя.
+///
+/// Sound attenuation with the distance to the listener.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum Attenuation{
+    /// Без затухания.
+    ///
+    /// No attenuation.
+    None,
+
+    /// Полная громкость до `min_distance`,
+    /// линейно затухает до нуля к `max_distance`.
+    ///
+    /// Full volume up to `min_distance`,
+    /// linearly fades to zero at `max_distance`.
+    Linear{
+        min_distance:f32,
+        max_distance:f32,
+    },
+
+    /// Полная громкость до `min_distance`, дальше
+    /// `min_distance/(min_distance+rolloff*(distance-min_distance))`.
+    ///
+    /// Full volume up to `min_distance`, further
+    /// `min_distance/(min_distance+rolloff*(distance-min_distance))`.
+    Inverse{
+        min_distance:f32,
+        rolloff:f32,
+    },
+
+    /// Полная громкость до `min_distance`, дальше
+    /// `(distance/min_distance)^(-rolloff)`.
+    ///
+    /// Full volume up to `min_distance`, further
+    /// `(distance/min_distance)^(-rolloff)`.
+    Exponential{
+        min_distance:f32,
+        rolloff:f32,
+    },
+}
+
+impl Attenuation{
+    /// Громкость на данном расстоянии.
+    ///
+    /// The volume at the given distance.
+    pub fn gain(self,distance:f32)->f32{
+        match self{
+            Attenuation::None=>1f32,
+
+            Attenuation::Linear{min_distance,max_distance}=>{
+                if distance<=min_distance{
+                    1f32
+                }
+                else if distance>=max_distance{
+                    0f32
+                }
+                else{
+                    (max_distance-distance)/(max_distance-min_distance)
+                }
+            }
+
+            Attenuation::Inverse{min_distance,rolloff}=>{
+                if distance<=min_distance{
+                    1f32
+                }
+                else{
+                    min_distance/(min_distance+rolloff.max(0f32)*(distance-min_distance))
+                }
+            }
+
+            Attenuation::Exponential{min_distance,rolloff}=>{
+                if distance<=min_distance{
+                    1f32
+                }
+                else{
+                    (distance/min_distance).powf(-rolloff.max(0f32))
+                }
+            }
+        }
+    }
+}
+
+/// Слушатель - точка, относительно которой размещаются источники.
+///
+/// The listener - the point relative to which sources are placed.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Listener{
+    /// Положение слушателя.
+    ///
+    /// The listener position.
+    pub position:[f32;2],
+
+    /// Расстояние по горизонтали,
+    /// на котором источник полностью в крайнем канале.
+    ///
+    /// The horizontal distance
+    /// at which a source is entirely in the outermost channel.
+    pub pan_width:f32,
+}
+
+impl Listener{
+    pub fn new(position:[f32;2],pan_width:f32)->Listener{
+        Self{
+            position,
+            pan_width,
+        }
+    }
+}
+
+impl Default for Listener{
+    fn default()->Listener{
+        Listener::new([0f32;2],1f32)
+    }
+}
+
+/// Источник звука на плоскости.
+///
+/// A sound source on the plane.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct SpatialSource{
+    pub position:[f32;2],
+    pub attenuation:Attenuation,
+}
+
+impl SpatialSource{
+    pub fn new(position:[f32;2],attenuation:Attenuation)->SpatialSource{
+        Self{
+            position,
+            attenuation,
+        }
+    }
+}
+
+/// Размещение трека между его каналами.
+///
+/// Каналы трека (`TrackSet::channels`) считаются
+/// расположенными слева направо.
+///
+/// Placement of a track between its channels.
+///
+/// Channels of the track (`TrackSet::channels`) are considered
+/// to be located from left to right.
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub enum TrackPlacement{
+    /// Полная громкость во всех каналах.
+    ///
+    /// Full volume in all the channels.
+    #[default]
+    Fixed,
+
+    /// Панорама: -1 - первый канал, 0 - середина, 1 - последний канал.
+    ///
+    /// Panning: -1 - the first channel, 0 - the middle, 1 - the last channel.
+    Pan(f32),
+
+    /// Положение на плоскости: панорама по горизонтальному смещению
+    /// от слушателя и затухание по расстоянию до него.
+    ///
+    /// Position on the plane: panning by the horizontal offset
+    /// from the listener and attenuation by the distance to it.
+    Spatial(SpatialSource),
+}
+
+impl TrackPlacement{
+    /// Вычисляет громкости каналов трека.
+    pub (crate) fn gains(&self,listener:&Listener,pan_law:PanLaw,gains:&mut [f32]){
+        match *self{
+            TrackPlacement::Fixed=>{
+                for gain in gains{
+                    *gain=1f32
+                }
+            }
+
+            TrackPlacement::Pan(pan)=>pan_gains(pan,pan_law,1f32,gains),
+
+            TrackPlacement::Spatial(source)=>{
+                let dx=source.position[0]-listener.position[0];
+                let dy=source.position[1]-listener.position[1];
+
+                let pan=if listener.pan_width>0f32{
+                    dx/listener.pan_width
+                }
+                // Без ширины панорамы - крайние каналы,
+                // прямо перед или за слушателем - середина
+                else if dx==0f32{
+                    0f32
+                }
+                else{
+                    dx.signum()
+                };
+
+                let volume=source.attenuation.gain((dx*dx+dy*dy).sqrt());
+
+                pan_gains(pan,pan_law,volume,gains)
+            }
+        }
+    }
+}
+
+/// Распределяет громкость между двумя ближайшими к позиции каналами.
+fn pan_gains(pan:f32,pan_law:PanLaw,volume:f32,gains:&mut [f32]){
+    match gains.len(){
+        0=>{}
+
+        1=>gains[0]=volume,
+
+        len=>{
+            let position=(pan.clamp(-1f32,1f32)+1f32)*0.5f32*(len-1) as f32;
+            let left=(position as usize).min(len-2);
+            let (left_gain,right_gain)=pan_law.gains(position-left as f32);
+
+            for gain in gains.iter_mut(){
+                *gain=0f32
+            }
+            gains[left]=left_gain*volume;
+            gains[left+1]=right_gain*volume;
+        }
+    }
+}
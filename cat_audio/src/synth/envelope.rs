@@ -0,0 +1,131 @@
+use super::SoundSource;
+
+/// Огибающая громкости ADSR.
+///
+/// Громкость линейно растёт до 1 за `attack`, спадает до `sustain` за `decay`,
+/// держится до отпускания и спадает до нуля за `release` (время в секундах).
+///
+/// An ADSR volume envelope.
+///
+/// The volume linearly rises to 1 during `attack`, falls to `sustain` during `decay`,
+/// holds until the release and falls to zero during `release` (times are in seconds).
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Adsr{
+    pub attack:f32,
+    pub decay:f32,
+    /// Уровень удержания, [0; 1].
+    ///
+    /// The sustain level, [0; 1].
+    pub sustain:f32,
+    pub release:f32,
+}
+
+impl Adsr{
+    pub fn new(attack:f32,decay:f32,sustain:f32,release:f32)->Adsr{
+        Self{
+            attack,
+            decay,
+            sustain,
+            release,
+        }
+    }
+
+    /// Уровень огибающей через `time` секунд после начала
+    /// при отпускании через `duration` секунд.
+    ///
+    /// `None` - огибающая закончилась.
+    ///
+    /// The envelope level `time` seconds after the start
+    /// when released `duration` seconds after the start.
+    ///
+    /// `None` - the envelope is finished.
+    pub fn level(&self,time:f32,duration:f32)->Option<f32>{
+        if time<duration{
+            Some(self.held_level(time))
+        }
+        else{
+            let released=time-duration;
+            if released>=self.release{
+                return None
+            }
+
+            let start=self.held_level(duration);
+            Some(start*(1f32-released/self.release))
+        }
+    }
+
+    /// Уровень до отпускания.
+    fn held_level(&self,time:f32)->f32{
+        if time<self.attack{
+            time/self.attack
+        }
+        else if time<self.attack+self.decay{
+            let t=(time-self.attack)/self.decay;
+            1f32+(self.sustain-1f32)*t
+        }
+        else{
+            self.sustain
+        }
+    }
+}
+
+/// Источник с огибающей громкости.
+///
+/// Заканчивается вместе с огибающей или источником.
+///
+/// A source with a volume envelope.
+///
+/// Finishes with the envelope or the source.
+pub struct Envelope<S:SoundSource>{
+    source:S,
+    adsr:Adsr,
+    /// Время до отпускания в секундах.
+    duration:f32,
+
+    sample_rate:u32,
+    /// Номер текущего значения.
+    frame:u64,
+}
+
+impl<S:SoundSource> Envelope<S>{
+    /// Источник, отпускаемый через `duration` секунд.
+    ///
+    /// A source released after `duration` seconds.
+    pub fn new(source:S,adsr:Adsr,duration:f32)->Envelope<S>{
+        Self{
+            source,
+            adsr,
+            duration,
+
+            sample_rate:0,
+            frame:0,
+        }
+    }
+
+    pub fn source(&mut self)->&mut S{
+        &mut self.source
+    }
+}
+
+impl<S:SoundSource> SoundSource for Envelope<S>{
+    fn set_sample_rate(&mut self,sample_rate:u32){
+        // Время сохраняется
+        if self.sample_rate!=0{
+            self.frame=self.frame*sample_rate as u64/self.sample_rate as u64;
+        }
+        self.sample_rate=sample_rate;
+        self.source.set_sample_rate(sample_rate)
+    }
+
+    fn next_sample(&mut self)->Option<f32>{
+        if self.sample_rate==0{
+            return Some(0f32)
+        }
+
+        let time=self.frame as f32/self.sample_rate as f32;
+        let level=self.adsr.level(time,self.duration)?;
+        self.frame+=1;
+
+        self.source.next_sample().map(|sample|sample*level)
+    }
+}
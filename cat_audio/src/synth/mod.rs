@@ -0,0 +1,106 @@
+//! Синтез звука.
+//!
+//! Источники генерируют значения в аудио потоке
+//! и проигрываются наравне с треками (`Audio::play_source`).
+//!
+//! #
+//!
+//! Sound synthesis.
+//!
+//! Sources generate samples in the audio thread
+//! and are played alongside tracks (`Audio::play_source`).
+
+mod oscillator;
+pub use oscillator::{
+    Oscillator,
+    Waveform,
+};
+
+mod envelope;
+pub use envelope::{
+    Adsr,
+    Envelope,
+};
+
+mod sfxr;
+pub use sfxr::{
+    Sfxr,
+    SfxrParams,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Источник звука, генерирующий значения одного канала.
+///
+/// Источники выполняются в аудио потоке,
+/// так что `next_sample` не должна блокироваться.
+///
+/// A sound source generating samples of a single channel.
+///
+/// Sources run in the audio thread,
+/// so `next_sample` shouldn't block.
+pub trait SoundSource:Send{
+    /// Устанавливает частоту дискретизации генерируемых значений.
+    ///
+    /// Вызывается перед первым значением и при смене частоты системы.
+    ///
+    /// Sets the sample rate of the generated samples.
+    ///
+    /// Called before the first sample and when the system sample rate changes.
+    fn set_sample_rate(&mut self,sample_rate:u32);
+
+    /// Возвращает следующее значение, `None` - звук закончился.
+    ///
+    /// Returns the next sample, `None` - the sound is finished.
+    fn next_sample(&mut self)->Option<f32>;
+}
+
+impl<S:SoundSource+?Sized> SoundSource for Box<S>{
+    fn set_sample_rate(&mut self,sample_rate:u32){
+        self.as_mut().set_sample_rate(sample_rate)
+    }
+
+    fn next_sample(&mut self)->Option<f32>{
+        self.as_mut().next_sample()
+    }
+}
+
+/// Генератор псевдослучайных чисел (xorshift).
+#[derive(Clone)]
+pub (crate) struct Random{
+    state:u32,
+}
+
+impl Random{
+    pub fn new(seed:u32)->Random{
+        Self{
+            // Нулевое состояние не изменяется
+            state:seed.wrapping_mul(0x9E37_79B9)|1,
+        }
+    }
+
+    pub fn next_u32(&mut self)->u32{
+        let mut x=self.state;
+        x^=x<<13;
+        x^=x>>17;
+        x^=x<<5;
+        self.state=x;
+        x
+    }
+
+    /// Случайное число из [0; 1].
+    pub fn unit(&mut self)->f32{
+        (self.next_u32() as f64/u32::MAX as f64) as f32
+    }
+
+    /// Случайное число из [-1; 1].
+    pub fn signed(&mut self)->f32{
+        self.unit()*2f32-1f32
+    }
+
+    /// Случайное число из [min; max].
+    pub fn range(&mut self,min:f32,max:f32)->f32{
+        min+(max-min)*self.unit()
+    }
+}
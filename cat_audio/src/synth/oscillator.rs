@@ -0,0 +1,173 @@
+use super::{
+    SoundSource,
+    Random,
+};
+
+use std::f32::consts::PI;
+
+/// Форма волны генератора.
+///
+/// The waveform of an oscillator.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum Waveform{
+    Sine,
+
+    /// Прямоугольная волна с долей периода в верхнем положении (0; 1).
+    ///
+    /// A square wave with the part of the period in the high state (0; 1).
+    Square{
+        duty:f32,
+    },
+
+    /// Пила, растущая от -1 до 1.
+    ///
+    /// A sawtooth rising from -1 to 1.
+    Saw,
+
+    Triangle,
+
+    /// Шум, меняющий значение 32 раза за период -
+    /// высота шума зависит от частоты.
+    ///
+    /// Noise changing its value 32 times per period -
+    /// the noise pitch depends on the frequency.
+    Noise,
+}
+
+impl Waveform{
+    /// Прямоугольная волна с равными половинами.
+    ///
+    /// A square wave with equal halves.
+    pub fn square()->Waveform{
+        Waveform::Square{duty:0.5f32}
+    }
+}
+
+/// Состояние формы волны - фаза и шум.
+#[derive(Clone)]
+pub (crate) struct WaveState{
+    /// Фаза в периодах, [0; 1).
+    pub phase:f32,
+    noise:f32,
+    noise_step:u32,
+    random:Random,
+}
+
+impl WaveState{
+    pub fn new(seed:u32)->WaveState{
+        let mut random=Random::new(seed);
+
+        Self{
+            phase:0f32,
+            noise:random.signed(),
+            noise_step:0,
+            random,
+        }
+    }
+
+    /// Значение волны в текущей фазе
+    /// с продвижением фазы на `step` периодов.
+    pub fn next(&mut self,waveform:Waveform,step:f32)->f32{
+        let phase=self.phase;
+
+        let sample=match waveform{
+            Waveform::Sine=>(2f32*PI*phase).sin(),
+
+            Waveform::Square{duty}=>if phase<duty{1f32}else{-1f32},
+
+            Waveform::Saw=>2f32*phase-1f32,
+
+            Waveform::Triangle=>{
+                if phase<0.5f32{
+                    4f32*phase-1f32
+                }
+                else{
+                    3f32-4f32*phase
+                }
+            }
+
+            Waveform::Noise=>{
+                let noise_step=(phase*32f32) as u32;
+                if noise_step!=self.noise_step{
+                    self.noise_step=noise_step;
+                    self.noise=self.random.signed();
+                }
+                self.noise
+            }
+        };
+
+        // Отрицательная частота ведёт фазу назад
+        self.phase=(self.phase+step).rem_euclid(1f32);
+        // Малая отрицательная фаза округляется до 1
+        if self.phase>=1f32{
+            self.phase=0f32
+        }
+
+        sample
+    }
+}
+
+/// Генератор периодической волны или шума.
+///
+/// Звучит бесконечно, длительность задаётся огибающей (`Envelope`)
+/// или остановкой трека.
+///
+/// An oscillator of a periodic wave or noise.
+///
+/// Sounds forever, the duration is set by an envelope (`Envelope`)
+/// or by stopping the track.
+pub struct Oscillator{
+    waveform:Waveform,
+    frequency:f32,
+    amplitude:f32,
+
+    sample_rate:u32,
+    state:WaveState,
+}
+
+impl Oscillator{
+    /// Генератор с амплитудой 1.
+    ///
+    /// An oscillator with the amplitude 1.
+    pub fn new(waveform:Waveform,frequency:f32)->Oscillator{
+        Self{
+            waveform,
+            frequency,
+            amplitude:1f32,
+
+            sample_rate:0,
+            state:WaveState::new(frequency.to_bits()),
+        }
+    }
+
+    /// Устанавливает амплитуду.
+    ///
+    /// Sets the amplitude.
+    pub fn with_amplitude(mut self,amplitude:f32)->Oscillator{
+        self.amplitude=amplitude;
+        self
+    }
+
+    pub fn set_frequency(&mut self,frequency:f32){
+        self.frequency=frequency
+    }
+
+    pub fn set_waveform(&mut self,waveform:Waveform){
+        self.waveform=waveform
+    }
+}
+
+impl SoundSource for Oscillator{
+    fn set_sample_rate(&mut self,sample_rate:u32){
+        self.sample_rate=sample_rate
+    }
+
+    fn next_sample(&mut self)->Option<f32>{
+        if self.sample_rate==0{
+            return Some(0f32)
+        }
+
+        let step=self.frequency/self.sample_rate as f32;
+        Some(self.state.next(self.waveform,step)*self.amplitude)
+    }
+}
@@ -0,0 +1,434 @@
+use super::{
+    SoundSource,
+    Random,
+    Waveform,
+    oscillator::WaveState,
+};
+
+use std::f32::consts::PI;
+
+/// Наибольшая частота генератора.
+const max_frequency:f32=20000f32;
+
+/// Параметры звукового эффекта в духе sfxr.
+///
+/// Время в секундах, частоты в герцах.
+///
+/// Готовые наборы (`pickup`, `laser`, `explosion` и другие)
+/// случайно изменяются по зерну, так что разные зёрна дают похожие,
+/// но разные звуки.
+///
+/// Parameters of an sfxr-style sound effect.
+///
+/// Times are in seconds, frequencies are in hertz.
+///
+/// The presets (`pickup`, `laser`, `explosion` and others)
+/// are randomly varied by a seed, so different seeds give similar
+/// but different sounds.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct SfxrParams{
+    pub waveform:Waveform,
+
+    /// Начальная частота.
+    ///
+    /// The start frequency.
+    pub frequency:f32,
+
+    /// Частота, ниже которой звук обрывается, 0 - без ограничения.
+    ///
+    /// The frequency below which the sound is cut off, 0 - no limit.
+    pub min_frequency:f32,
+
+    /// Скольжение частоты в октавах в секунду.
+    ///
+    /// The frequency slide in octaves per second.
+    pub frequency_slide:f32,
+
+    /// Изменение скольжения частоты в октавах в секунду за секунду.
+    ///
+    /// The change of the frequency slide in octaves per second per second.
+    pub frequency_delta_slide:f32,
+
+    /// Глубина вибрато - доля частоты.
+    ///
+    /// The vibrato depth - a part of the frequency.
+    pub vibrato_depth:f32,
+
+    /// Частота вибрато.
+    ///
+    /// The vibrato frequency.
+    pub vibrato_speed:f32,
+
+    /// Множитель частоты, применяемый через `arpeggio_time`, 1 - без изменения.
+    ///
+    /// The frequency multiplier applied after `arpeggio_time`, 1 - no change.
+    pub arpeggio:f32,
+    pub arpeggio_time:f32,
+
+    /// Изменение скважности прямоугольной волны в секунду.
+    ///
+    /// The change of the square wave duty per second.
+    pub duty_sweep:f32,
+
+    /// Время нарастания громкости.
+    ///
+    /// The attack time.
+    pub attack:f32,
+
+    /// Время удержания громкости.
+    ///
+    /// The sustain time.
+    pub sustain:f32,
+
+    /// Дополнительная громкость в начале удержания, спадающая к его концу.
+    ///
+    /// Additional volume at the start of the sustain falling by its end.
+    pub punch:f32,
+
+    /// Время спада громкости.
+    ///
+    /// The decay time.
+    pub decay:f32,
+
+    /// Срез фильтра низких частот, 0 - без фильтра.
+    ///
+    /// The low-pass filter cutoff, 0 - no filter.
+    pub low_pass:f32,
+
+    /// Период возвращения частоты, скольжения и скважности к начальным, 0 - без повторений.
+    ///
+    /// The period of resetting the frequency, slide and duty to the start ones, 0 - no repeats.
+    pub repeat_time:f32,
+
+    pub volume:f32,
+}
+
+impl Default for SfxrParams{
+    fn default()->SfxrParams{
+        SfxrParams::new()
+    }
+}
+
+impl SfxrParams{
+    /// Прямоугольная волна 440 Гц на 0.3 секунды без изменений.
+    ///
+    /// A 440 Hz square wave for 0.3 seconds without changes.
+    pub fn new()->SfxrParams{
+        Self{
+            waveform:Waveform::square(),
+            frequency:440f32,
+            min_frequency:0f32,
+            frequency_slide:0f32,
+            frequency_delta_slide:0f32,
+            vibrato_depth:0f32,
+            vibrato_speed:0f32,
+            arpeggio:1f32,
+            arpeggio_time:0f32,
+            duty_sweep:0f32,
+            attack:0f32,
+            sustain:0.3f32,
+            punch:0f32,
+            decay:0.1f32,
+            low_pass:0f32,
+            repeat_time:0f32,
+            volume:0.5f32,
+        }
+    }
+
+    /// Длительность звука.
+    ///
+    /// The duration of the sound.
+    pub fn duration(&self)->f32{
+        self.attack.max(0f32)+self.sustain.max(0f32)+self.decay.max(0f32)
+    }
+
+    /// Подбор монеты.
+    ///
+    /// A coin pickup.
+    pub fn pickup(seed:u32)->SfxrParams{
+        let mut random=Random::new(seed);
+
+        let mut params=SfxrParams::new();
+        params.frequency=random.range(600f32,1800f32);
+        params.sustain=random.range(0.02f32,0.1f32);
+        params.punch=random.range(0.3f32,0.6f32);
+        params.decay=random.range(0.1f32,0.4f32);
+        if random.unit()<0.5f32{
+            params.arpeggio=random.range(1.2f32,1.6f32);
+            params.arpeggio_time=random.range(0.03f32,0.1f32);
+        }
+        params
+    }
+
+    /// Выстрел лазера.
+    ///
+    /// A laser shot.
+    pub fn laser(seed:u32)->SfxrParams{
+        let mut random=Random::new(seed);
+
+        let mut params=SfxrParams::new();
+        params.waveform=match random.next_u32()%3{
+            0=>Waveform::Square{duty:random.range(0.2f32,0.5f32)},
+            1=>Waveform::Saw,
+            _=>Waveform::Sine,
+        };
+        params.frequency=random.range(800f32,3000f32);
+        params.min_frequency=params.frequency*random.range(0.05f32,0.3f32);
+        params.frequency_slide=random.range(-20f32,-6f32);
+        params.duty_sweep=random.range(-0.5f32,0.5f32);
+        params.sustain=random.range(0.05f32,0.2f32);
+        params.decay=random.range(0.05f32,0.3f32);
+        if random.unit()<0.5f32{
+            params.punch=random.range(0f32,0.3f32);
+        }
+        params
+    }
+
+    /// Взрыв.
+    ///
+    /// An explosion.
+    pub fn explosion(seed:u32)->SfxrParams{
+        let mut random=Random::new(seed);
+
+        let mut params=SfxrParams::new();
+        params.waveform=Waveform::Noise;
+        params.frequency=random.range(60f32,600f32);
+        params.frequency_slide=random.range(-3f32,1f32);
+        if random.unit()<0.3f32{
+            params.vibrato_depth=random.range(0.1f32,0.5f32);
+            params.vibrato_speed=random.range(5f32,20f32);
+        }
+        params.sustain=random.range(0.1f32,0.4f32);
+        params.punch=random.range(0.2f32,0.8f32);
+        params.decay=random.range(0.3f32,0.8f32);
+        params
+    }
+
+    /// Усиление.
+    ///
+    /// A power-up.
+    pub fn power_up(seed:u32)->SfxrParams{
+        let mut random=Random::new(seed);
+
+        let mut params=SfxrParams::new();
+        params.waveform=if random.unit()<0.5f32{Waveform::Saw}else{Waveform::square()};
+        params.frequency=random.range(200f32,800f32);
+        params.frequency_slide=random.range(2f32,8f32);
+        if random.unit()<0.5f32{
+            params.repeat_time=random.range(0.05f32,0.2f32);
+        }
+        else if random.unit()<0.5f32{
+            params.vibrato_depth=random.range(0.05f32,0.2f32);
+            params.vibrato_speed=random.range(10f32,30f32);
+        }
+        params.sustain=random.range(0.1f32,0.3f32);
+        params.decay=random.range(0.1f32,0.4f32);
+        params
+    }
+
+    /// Удар, получение урона.
+    ///
+    /// A hit, taking damage.
+    pub fn hit(seed:u32)->SfxrParams{
+        let mut random=Random::new(seed);
+
+        let mut params=SfxrParams::new();
+        params.waveform=match random.next_u32()%3{
+            0=>Waveform::Saw,
+            1=>Waveform::square(),
+            _=>Waveform::Noise,
+        };
+        params.frequency=random.range(200f32,1200f32);
+        params.frequency_slide=random.range(-12f32,-4f32);
+        params.sustain=random.range(0f32,0.05f32);
+        params.decay=random.range(0.05f32,0.2f32);
+        if random.unit()<0.5f32{
+            params.low_pass=random.range(1000f32,6000f32);
+        }
+        params
+    }
+
+    /// Прыжок.
+    ///
+    /// A jump.
+    pub fn jump(seed:u32)->SfxrParams{
+        let mut random=Random::new(seed);
+
+        let mut params=SfxrParams::new();
+        params.waveform=Waveform::Square{duty:random.range(0.2f32,0.5f32)};
+        params.frequency=random.range(250f32,800f32);
+        params.frequency_slide=random.range(3f32,8f32);
+        params.sustain=random.range(0.05f32,0.2f32);
+        params.decay=random.range(0.05f32,0.2f32);
+        if random.unit()<0.5f32{
+            params.low_pass=random.range(2000f32,8000f32);
+        }
+        params
+    }
+
+    /// Выбор пункта меню.
+    ///
+    /// A menu selection blip.
+    pub fn blip(seed:u32)->SfxrParams{
+        let mut random=Random::new(seed);
+
+        let mut params=SfxrParams::new();
+        params.waveform=if random.unit()<0.5f32{Waveform::Saw}else{Waveform::square()};
+        params.frequency=random.range(400f32,1500f32);
+        params.sustain=random.range(0.02f32,0.08f32);
+        params.decay=random.range(0.01f32,0.05f32);
+        params
+    }
+}
+
+/// Генератор звукового эффекта по параметрам `SfxrParams`.
+///
+/// Заканчивается после спада громкости
+/// или при падении частоты ниже `min_frequency`.
+///
+/// A sound effect generator by `SfxrParams`.
+///
+/// Finishes after the decay
+/// or when the frequency falls below `min_frequency`.
+pub struct Sfxr{
+    params:SfxrParams,
+    sample_rate:u32,
+
+    /// Время с начала звука.
+    time:f64,
+    /// Время с последнего повторения.
+    repeat_time:f64,
+
+    frequency:f32,
+    slide:f32,
+    duty:f32,
+    arpeggio_done:bool,
+
+    wave:WaveState,
+    /// Состояние фильтра низких частот.
+    low_pass:f32,
+}
+
+impl Sfxr{
+    pub fn new(params:SfxrParams)->Sfxr{
+        let mut sfxr=Self{
+            params,
+            sample_rate:0,
+
+            time:0f64,
+            repeat_time:0f64,
+
+            frequency:0f32,
+            slide:0f32,
+            duty:0f32,
+            arpeggio_done:false,
+
+            wave:WaveState::new(params.frequency.to_bits()),
+            low_pass:0f32,
+        };
+        sfxr.reset_frequency();
+        sfxr
+    }
+
+    pub fn params(&self)->&SfxrParams{
+        &self.params
+    }
+
+    /// Возвращение частоты, скольжения и скважности к начальным.
+    fn reset_frequency(&mut self){
+        self.frequency=self.params.frequency;
+        self.slide=self.params.frequency_slide;
+        self.duty=match self.params.waveform{
+            Waveform::Square{duty}=>duty,
+            _=>0.5f32,
+        };
+        self.arpeggio_done=false;
+        self.repeat_time=0f64;
+    }
+
+    /// Уровень огибающей, `None` - звук закончился.
+    fn envelope(&self,time:f32)->Option<f32>{
+        let SfxrParams{attack,sustain,punch,decay,..}=self.params;
+        let (attack,sustain,decay)=(attack.max(0f32),sustain.max(0f32),decay.max(0f32));
+
+        if time<attack{
+            Some(time/attack)
+        }
+        else if time<attack+sustain{
+            Some(1f32+punch*(1f32-(time-attack)/sustain))
+        }
+        else if time<attack+sustain+decay{
+            Some(1f32-(time-attack-sustain)/decay)
+        }
+        else{
+            None
+        }
+    }
+}
+
+impl SoundSource for Sfxr{
+    fn set_sample_rate(&mut self,sample_rate:u32){
+        self.sample_rate=sample_rate
+    }
+
+    fn next_sample(&mut self)->Option<f32>{
+        if self.sample_rate==0{
+            return Some(0f32)
+        }
+
+        let sample_rate=self.sample_rate as f32;
+        let time=self.time as f32;
+
+        let level=self.envelope(time)?;
+
+        // Повторение
+        if self.params.repeat_time>0f32 && self.repeat_time>=self.params.repeat_time as f64{
+            self.reset_frequency();
+        }
+
+        // Скольжение частоты
+        self.slide+=self.params.frequency_delta_slide/sample_rate;
+        self.frequency*=(self.slide/sample_rate).exp2();
+
+        // Арпеджио
+        if !self.arpeggio_done && self.repeat_time>=self.params.arpeggio_time as f64{
+            self.arpeggio_done=true;
+            self.frequency*=self.params.arpeggio;
+        }
+
+        if self.frequency<self.params.min_frequency{
+            return None
+        }
+
+        // Вибрато
+        let mut frequency=self.frequency;
+        if self.params.vibrato_depth!=0f32{
+            frequency*=1f32+self.params.vibrato_depth*(2f32*PI*self.params.vibrato_speed*time).sin();
+        }
+        let frequency=frequency.clamp(0f32,max_frequency.min(sample_rate*0.5f32));
+
+        // Скважность
+        let waveform=match self.params.waveform{
+            Waveform::Square{..}=>{
+                self.duty=(self.duty+self.params.duty_sweep/sample_rate).clamp(0.02f32,0.98f32);
+                Waveform::Square{duty:self.duty}
+            }
+            waveform=>waveform,
+        };
+
+        let mut sample=self.wave.next(waveform,frequency/sample_rate);
+
+        // Фильтр низких частот
+        if self.params.low_pass>0f32{
+            let coefficient=1f32-(-2f32*PI*self.params.low_pass/sample_rate).exp();
+            self.low_pass+=(sample-self.low_pass)*coefficient;
+            sample=self.low_pass;
+        }
+
+        let step=1f64/self.sample_rate as f64;
+        self.time+=step;
+        self.repeat_time+=step;
+
+        Some(sample*level*self.params.volume)
+    }
+}
@@ -0,0 +1,90 @@
+use super::{
+    SoundSource,
+    Oscillator,
+    Waveform,
+    Adsr,
+    Envelope,
+    Sfxr,
+    SfxrParams,
+};
+
+const sample_rate:u32=48000;
+
+fn render<S:SoundSource>(source:&mut S,len:usize)->Vec<f32>{
+    source.set_sample_rate(sample_rate);
+    (0..len).map_while(|_|source.next_sample()).collect()
+}
+
+#[test]
+fn oscillators(){
+    // Секунда синусоиды 440 Гц - 440 переходов через ноль снизу вверх
+    let sine=render(&mut Oscillator::new(Waveform::Sine,440f32),sample_rate as usize);
+    let crossings=sine.windows(2).filter(|w|w[0]<0f32 && w[1]>=0f32).count();
+    assert!((crossings as i32-440).abs()<=1,"{}",crossings);
+
+    for waveform in [Waveform::Sine,Waveform::square(),Waveform::Saw,Waveform::Triangle,Waveform::Noise].iter(){
+        let samples=render(&mut Oscillator::new(*waveform,220f32).with_amplitude(0.5f32),4800);
+        assert_eq!(samples.len(),4800);
+        assert!(samples.iter().all(|s|s.abs()<=0.5f32),"{:?}",waveform);
+    }
+
+    // Отрицательная частота - волна в обратную сторону
+    for waveform in [Waveform::Sine,Waveform::square(),Waveform::Saw,Waveform::Triangle,Waveform::Noise].iter(){
+        let samples=render(&mut Oscillator::new(*waveform,-220f32),4800);
+        assert!(samples.iter().all(|s|s.abs()<=1f32),"{:?}",waveform);
+    }
+    let noise=render(&mut Oscillator::new(Waveform::Noise,-220f32),4800);
+    assert!(noise.windows(2).filter(|w|w[0]!=w[1]).count()>100);
+
+    // Доля верхнего положения прямоугольной волны
+    let square=render(&mut Oscillator::new(Waveform::Square{duty:0.25f32},100f32),sample_rate as usize);
+    let high=square.iter().filter(|&&s|s>0f32).count() as f32/square.len() as f32;
+    assert!((high-0.25f32).abs()<0.01f32,"{}",high);
+}
+
+#[test]
+fn envelopes(){
+    let adsr=Adsr::new(0.1f32,0.1f32,0.5f32,0.2f32);
+    assert_eq!(adsr.level(0f32,1f32),Some(0f32));
+    assert_eq!(adsr.level(0.1f32,1f32),Some(1f32));
+    assert_eq!(adsr.level(0.5f32,1f32),Some(0.5f32));
+    assert!((adsr.level(1.1f32,1f32).unwrap()-0.25f32).abs()<1e-5);
+    assert_eq!(adsr.level(1.2f32,1f32),None);
+
+    // Постоянный источник показывает огибающую
+    let mut envelope=Envelope::new(Oscillator::new(Waveform::square(),0f32),adsr,1f32);
+    let samples=render(&mut envelope,sample_rate as usize*2);
+    assert_eq!(samples.len(),(sample_rate as f32*1.2f32) as usize);
+    assert!((samples[sample_rate as usize/10]-1f32).abs()<1e-3);
+    assert!((samples[sample_rate as usize/2]-0.5f32).abs()<1e-3);
+    assert!(samples.last().unwrap().abs()<1e-3);
+}
+
+#[test]
+fn sfxr_presets(){
+    let presets:[fn(u32)->SfxrParams;7]=[
+        SfxrParams::pickup,
+        SfxrParams::laser,
+        SfxrParams::explosion,
+        SfxrParams::power_up,
+        SfxrParams::hit,
+        SfxrParams::jump,
+        SfxrParams::blip,
+    ];
+
+    for preset in presets.iter(){
+        // Одинаковое зерно - одинаковый звук
+        assert_eq!(preset(5),preset(5));
+
+        let params=preset(5);
+        let max_len=(params.duration()*sample_rate as f32) as usize+1;
+        let samples=render(&mut Sfxr::new(params),max_len*2);
+
+        assert!(!samples.is_empty());
+        assert!(samples.len()<=max_len,"{} > {}",samples.len(),max_len);
+        assert!(samples.iter().all(|s|s.is_finite() && s.abs()<=1f32));
+        assert!(samples.iter().any(|s|s.abs()>1e-3));
+    }
+
+    assert_ne!(SfxrParams::laser(1),SfxrParams::laser(2));
+}
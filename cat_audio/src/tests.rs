@@ -0,0 +1,799 @@
+use super::{
+    Audio,
+    AudioEffect,
+    AudioSettings,
+    AudioCommandResult,
+    AudioEvent,
+    Attenuation,
+    Listener,
+    LoopRegion,
+    CompressorSettings,
+    Delay,
+    LimiterSettings,
+    MasterBusSettings,
+    MonoTrack,
+    Oscillator,
+    Waveform,
+    Adsr,
+    Envelope,
+    ResamplerQuality,
+    SpatialSource,
+    TrackPlacement,
+    TrackSet,
+    StreamSet,
+    StreamingTrack,
+    OfflineRenderer,
+    AudioEngineCommand,
+    engine_core::{
+        Mixer,
+        Output,
+        OutputDevices,
+    },
+    events::{
+        EventSender,
+        event_channel,
+    },
+};
+
+use cpal::{
+    Format,
+    SampleFormat,
+    SampleRate,
+};
+
+use std::io::Cursor;
+use std::sync::mpsc::{
+    channel,
+    Receiver,
+};
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::thread::sleep;
+use std::time::Duration;
+
+/// Настройки с единичной общей громкостью, чтобы сведение совпадало с треками.
+fn unit_settings()->AudioSettings{
+    let mut settings=AudioSettings::new();
+    settings.general_volume=1f32;
+    settings
+}
+
+/// Движок без устройства вывода с частотой 48000 и единичной общей громкостью.
+fn offline_audio(channels:u16)->(Audio,OfflineRenderer){
+    Audio::offline(unit_settings(),48000,channels)
+}
+
+/// Секунда постоянного сигнала с частотой 48000.
+fn constant_track(value:f32)->MonoTrack{
+    MonoTrack::new(vec![value;48000],48000)
+}
+
+#[test]
+fn offline_mixing(){
+    let (mut audio,mut renderer)=Audio::offline(AudioSettings::new(),48000,2);
+    assert_eq!(renderer.channels(),2);
+
+    // Полсекунды при частоте в два раза меньше системной
+    let track=MonoTrack::new(vec![0.5f32;12000],24000);
+    assert_eq!(audio.add_track(track),AudioCommandResult::Index(7));
+    audio.set_general_volume(1f32).unwrap();
+
+    let mut set=TrackSet::once(7,vec![0]);
+    set.volume=0.5f32;
+    audio.play_track(set).unwrap();
+
+    let mix=renderer.render_seconds(1f32);
+    assert_eq!(mix.len(),96000);
+
+    let left:Vec<f32>=mix.iter().step_by(2).cloned().collect();
+    assert!(mix.iter().skip(1).step_by(2).all(|&sample|sample==0f32));
+
+    // Трек переведён в частоту системы целиком,
+    // между последним значением и тишиной - интерполяция
+    let played=left.iter().take_while(|&&sample|sample!=0f32).count();
+    assert_eq!(played,24000);
+    assert!(left[..played-1].iter().all(|&sample|(sample-0.25f32).abs()<1e-6));
+    assert!((left[played-1]-0.125f32).abs()<1e-6);
+    assert!(left[played..].iter().all(|&sample|sample==0f32));
+
+    // Общая громкость применяется к выводу
+    audio.set_general_volume(0.5f32).unwrap();
+    audio.play_track(TrackSet::once(7,vec![0,1])).unwrap();
+
+    let mut file=Cursor::new(Vec::new());
+    renderer.render_to_wav(0.1f32,&mut file).unwrap();
+    file.set_position(0);
+
+    let mut reader=hound::WavReader::new(file).unwrap();
+    assert_eq!(reader.spec().channels,2);
+    assert_eq!(reader.spec().sample_rate,48000);
+    assert_eq!(reader.len(),9600);
+    let samples:Vec<f32>=reader.samples::<f32>().map(Result::unwrap).collect();
+    assert!(samples.iter().all(|&sample|(sample-0.25f32).abs()<1e-6));
+}
+
+/// Среднеквадратичное отклонение синусоиды, переведённой из 22050 в 48000.
+fn resampling_error(quality:ResamplerQuality)->f32{
+    let mut settings=unit_settings();
+    settings.resampler_quality=quality;
+    let (mut audio,mut renderer)=Audio::offline(settings,48000,1);
+
+    let frequency=5000f32;
+    let phase=|time:f32|(2f32*std::f32::consts::PI*frequency*time).sin()*0.5f32;
+
+    let data=(0..22050).map(|n|phase(n as f32/22050f32)).collect();
+    audio.add_track(MonoTrack::new(data,22050));
+    audio.play_track(TrackSet::once(7,vec![0])).unwrap();
+
+    let mix=renderer.render_seconds(0.5f32);
+
+    // Без краёв, где окно захватывает тишину
+    let range=100..mix.len()-100;
+    let error:f32=mix[range.clone()].iter()
+        .enumerate()
+        .map(|(k,&sample)|sample-phase((k+range.start) as f32/48000f32))
+        .map(|error|error*error)
+        .sum();
+
+    (error/range.len() as f32).sqrt()
+}
+
+#[test]
+fn resampler_quality(){
+    let linear=resampling_error(ResamplerQuality::Linear);
+    let cubic=resampling_error(ResamplerQuality::Cubic);
+    let sinc=resampling_error(ResamplerQuality::Sinc);
+
+    assert!(cubic<linear*0.5f32,"{} {}",cubic,linear);
+    assert!(sinc<cubic*0.1f32,"{} {}",sinc,cubic);
+}
+
+#[test]
+fn resampled_track_end(){
+    let mut settings=unit_settings();
+    settings.resampler_quality=ResamplerQuality::Sinc;
+    let (mut audio,mut renderer)=Audio::offline(settings,48000,1);
+
+    // Десятая доля секунды при частоте в два раза меньше системной
+    audio.add_track(MonoTrack::new(vec![0.5f32;2400],24000));
+    audio.play_track(TrackSet::once(7,vec![0])).unwrap();
+
+    let mix=renderer.render_seconds(0.2f32);
+
+    // Значения, прочитанные окном наперёд, доигрываются:
+    // последнее значение трека попадает в 4798-й кадр
+    assert!((mix[4798]-0.5f32).abs()<1e-5,"{}",mix[4798]);
+    assert!(mix[4800..].iter().all(|&sample|sample.abs()<0.1f32));
+    assert!(mix[4900..].iter().all(|&sample|sample==0f32));
+    assert_eq!(audio.poll_event(),Some(AudioEvent::TrackFinished(Some(7))));
+}
+
+struct Gain(f32);
+
+impl AudioEffect for Gain{
+    fn set_sample_rate(&mut self,_sample_rate:u32){}
+
+    fn process(&mut self,samples:&mut [f32]){
+        for sample in samples{
+            *sample*=self.0
+        }
+    }
+}
+
+#[test]
+fn effect_chains(){
+    let (mut audio,mut renderer)=offline_audio(2);
+
+    audio.add_track(constant_track(0.5f32));
+
+    let mut set=TrackSet::once(7,vec![0,1]);
+    set.effects.push(Box::new(Gain(0.5f32)));
+    audio.play_track(set).unwrap();
+
+    // Эхо без исходного звука - задержка на 480 кадров
+    audio.add_channel_effect(1,Delay::new(0.01f32,0f32,1f32)).unwrap();
+
+    let mix=renderer.render_seconds(0.1f32);
+    let (left,right):(Vec<f32>,Vec<f32>)=mix.chunks_exact(2).map(|frame|(frame[0],frame[1])).unzip();
+
+    assert!(left.iter().all(|&sample|sample==0.25f32));
+    assert!(right[..480].iter().all(|&sample|sample==0f32));
+    assert!(right[480..].iter().all(|&sample|sample==0.25f32));
+
+    // Эффекты играющего трека
+    audio.add_track_effect(0,Gain(2f32)).unwrap();
+    audio.clear_channel_effects(1).unwrap();
+
+    let mix=renderer.render_seconds(0.1f32);
+    assert!(mix.iter().all(|&sample|sample==0.5f32));
+
+    audio.clear_track_effects(0).unwrap();
+
+    let mix=renderer.render_seconds(0.1f32);
+    assert!(mix.iter().all(|&sample|sample==0.5f32));
+}
+
+/// Сводит полсекунды постоянного сигнала с амплитудой 2.
+fn overloaded_mix(master_bus:MasterBusSettings)->Vec<f32>{
+    let mut settings=unit_settings();
+    settings.master_bus=master_bus;
+    let (mut audio,mut renderer)=Audio::offline(settings,48000,1);
+
+    audio.add_track(constant_track(1f32));
+    let mut set=TrackSet::once(7,vec![0]);
+    set.volume=2f32;
+    audio.play_track(set).unwrap();
+
+    renderer.render_seconds(0.5f32)
+}
+
+#[test]
+fn master_bus(){
+    // Обрезка
+    let mix=overloaded_mix(MasterBusSettings::bypass());
+    assert!(mix.iter().all(|&sample|sample==1f32));
+
+    // Мягкое ограничение
+    let mix=overloaded_mix(MasterBusSettings::new());
+    assert!(mix.iter().all(|&sample|sample>0.95f32 && sample<1f32));
+
+    // Ограничитель с задержкой на 240 кадров
+    let mut settings=MasterBusSettings::bypass();
+    settings.limiter=Some(LimiterSettings::new());
+    let mix=overloaded_mix(settings);
+    assert!(mix[..240].iter().all(|&sample|sample==0f32));
+    assert!(mix[240..].iter().all(|&sample|(sample-0.95f32).abs()<1e-4));
+
+    // Компрессор: 0.5*(2/0.5)^(1/4)
+    let mut settings=MasterBusSettings::bypass();
+    settings.compressor=Some(CompressorSettings::new());
+    let mix=overloaded_mix(settings);
+    assert!((mix[mix.len()-1]-0.5f32*2f32.sqrt()).abs()<1e-3,"{}",mix[mix.len()-1]);
+}
+
+#[test]
+fn track_placement(){
+    let (mut audio,mut renderer)=offline_audio(2);
+
+    audio.add_track(constant_track(0.5f32));
+
+    let frames=|mix:Vec<f32>|->Vec<(f32,f32)>{
+        mix.chunks_exact(2).map(|frame|(frame[0],frame[1])).collect()
+    };
+    let close=|a:f32,b:f32|(a-b).abs()<1e-5;
+
+    // Панорама влево
+    let mut set=TrackSet::once(7,vec![0,1]);
+    set.placement=TrackPlacement::Pan(-1f32);
+    audio.play_track(set).unwrap();
+
+    let mix=frames(renderer.render_seconds(0.02f32));
+    assert!(mix.iter().all(|&(left,right)|close(left,0.5f32) && right==0f32));
+
+    // Центр - постоянная мощность, громкости изменяются плавно
+    audio.set_track_pan(0,0f32).unwrap();
+
+    let mix=frames(renderer.render_seconds(0.02f32));
+    let center=0.5f32*std::f32::consts::FRAC_1_SQRT_2;
+    assert!(mix[..511].iter().all(|&(left,right)|left>center && right<center));
+    assert!(mix.windows(2).all(|pair|pair[1].0<=pair[0].0 && pair[1].1>=pair[0].1));
+    assert!(mix[511..].iter().all(|&(left,right)|close(left,center) && close(right,center)));
+
+    // Источник справа на расстоянии 2
+    let source=SpatialSource::new([2f32,0f32],Attenuation::Inverse{min_distance:1f32,rolloff:1f32});
+    audio.set_track_placement(0,TrackPlacement::Spatial(source)).unwrap();
+    renderer.render_seconds(0.02f32);
+
+    let mix=frames(renderer.render_seconds(0.02f32));
+    assert!(mix.iter().all(|&(left,right)|close(left,0f32) && close(right,0.25f32)));
+
+    // Слушатель рядом с источником
+    audio.set_listener(Listener::new([2f32,0.5f32],1f32)).unwrap();
+    renderer.render_seconds(0.02f32);
+
+    let mix=frames(renderer.render_seconds(0.02f32));
+    assert!(mix.iter().all(|&(left,right)|close(left,center) && close(right,center)));
+
+    // Источник слева от слушателя
+    audio.set_track_position(0,[1f32,0.5f32]).unwrap();
+    renderer.render_seconds(0.02f32);
+
+    let mix=frames(renderer.render_seconds(0.02f32));
+    assert!(mix.iter().all(|&(left,right)|close(left,0.5f32) && close(right,0f32)));
+
+    // Без ширины панорамы источник прямо перед слушателем - в центре
+    audio.set_listener(Listener::new([1f32,0f32],0f32)).unwrap();
+    renderer.render_seconds(0.02f32);
+
+    let mix=frames(renderer.render_seconds(0.02f32));
+    assert!(mix.iter().all(|&(left,right)|close(left,center) && close(right,center)));
+}
+
+#[test]
+fn fades(){
+    let (mut audio,mut renderer)=offline_audio(2);
+
+    audio.add_track(constant_track(0.5f32));
+    audio.add_track(constant_track(0.5f32));
+
+    let close=|a:f32,b:f32|(a-b).abs()<1e-4;
+
+    // Появление за 480 кадров
+    let mut set=TrackSet::once(7,vec![0]);
+    set.fade_in=0.01f32;
+    audio.play_track(set).unwrap();
+
+    let mix=renderer.render_seconds(0.02f32);
+    let left:Vec<f32>=mix.iter().step_by(2).cloned().collect();
+    assert_eq!(left[0],0f32);
+    assert!(left[..480].windows(2).all(|pair|pair[1]>pair[0]));
+    assert!(close(left[240],0.25f32));
+    assert!(left[480..].iter().all(|&sample|close(sample,0.5f32)));
+
+    // Мгновенное изменение уровня
+    audio.fade_track(0,0.5f32,0f32).unwrap();
+    let mix=renderer.render_seconds(0.01f32);
+    assert!(mix.iter().step_by(2).all(|&sample|close(sample,0.25f32)));
+
+    // Смена треков: первый убирается и останавливается, второй проявляется
+    audio.fade_track(0,1f32,0f32).unwrap();
+    let mut set=TrackSet::once(6,vec![1]);
+    set.fade_in=1f32;
+    audio.play_track(set).unwrap();
+    audio.crossfade_tracks(0,1,0.01f32).unwrap();
+
+    let mix=renderer.render_seconds(0.02f32);
+    let (left,right):(Vec<f32>,Vec<f32>)=mix.chunks_exact(2).map(|frame|(frame[0],frame[1])).unzip();
+    assert!(close(left[0],0.5f32) && right[0]==0f32);
+    assert!(close(left[240],0.25f32) && close(right[240],0.25f32));
+    assert!(left[480..].iter().all(|&sample|sample==0f32));
+    assert!(right[480..].iter().all(|&sample|close(sample,0.5f32)));
+
+    // Остался только второй трек
+    audio.fade_out_track(0,0.01f32).unwrap();
+    let mix=renderer.render_seconds(0.02f32);
+    assert!(close(mix[1],0.5f32));
+    assert!(mix[960..].iter().all(|&sample|sample==0f32));
+}
+
+#[test]
+fn positions_and_loops(){
+    let (mut audio,mut renderer)=offline_audio(1);
+
+    // Значение трека - его номер
+    let data:Vec<f32>=(0..1000).map(|frame|frame as f32/2000f32).collect();
+    audio.add_track(MonoTrack::new(data,48000));
+
+    let close=|sample:f32,frame:usize|(sample-frame as f32/2000f32).abs()<1e-6;
+    let mut render=|frames|{
+        let mut mix=Vec::new();
+        renderer.render(frames,&mut mix);
+        mix
+    };
+
+    // Вступление, три повторения части и окончание
+    let mut set=TrackSet::once(7,vec![0]);
+    set.repeats=3;
+    set.loop_region=Some(LoopRegion::new(200,400));
+    audio.play_track(set).unwrap();
+
+    let mix=render(1500);
+    let expected=(0..400).chain(200..400).chain(200..400).chain(400..1000);
+    for (&sample,frame) in mix.iter().zip(expected){
+        assert!(close(sample,frame));
+    }
+    assert!(mix[1600-200..].iter().all(|&sample|sample==0f32));
+
+    // Переход и положение
+    let mut set=TrackSet::once(7,vec![0]);
+    set.repeats=0;
+    audio.play_track(set).unwrap();
+    render(100);
+
+    let (sender,receiver)=channel();
+    audio.seek_track(0,900).unwrap();
+    audio.track_position(0,sender.clone()).unwrap();
+    audio.track_position(1,sender.clone()).unwrap();
+    let mix=render(200);
+
+    let position=receiver.recv().unwrap().unwrap();
+    assert_eq!(position.frame,900);
+    assert_eq!(position.length,Some(1000));
+    assert_eq!(position.duration(),Some(1000f32/48000f32));
+    assert_eq!(receiver.recv().unwrap(),None);
+
+    // Бесконечный трек повторяется с начала
+    assert!(close(mix[0],900));
+    assert!(close(mix[99],999));
+    assert!(close(mix[100],0));
+
+    // Повторение части играющего трека
+    audio.set_track_loop(0,Some(LoopRegion::new(150,250))).unwrap();
+    let mix=render(200);
+    assert!(close(mix[0],100));
+    assert!(close(mix[149],249));
+    assert!(close(mix[150],150));
+
+    audio.track_position(0,sender).unwrap();
+    render(1);
+    assert_eq!(receiver.recv().unwrap().unwrap().frame,200);
+}
+
+#[test]
+fn events(){
+    let mut settings=AudioSettings::new();
+    settings.track_playlist_capacity=1;
+    let (mut audio,mut renderer)=Audio::offline(settings,48000,1);
+
+    audio.add_track(MonoTrack::new(vec![0.5f32;100],48000));
+
+    // Три повторения - два возвращения к началу
+    let mut set=TrackSet::once(7,vec![0]);
+    set.repeats=3;
+    audio.play_track(set).unwrap();
+    // Плейлист переполнен
+    audio.play_track(TrackSet::once(7,vec![0])).unwrap();
+
+    renderer.render_seconds(0.001f32);
+    assert_eq!(audio.poll_event(),Some(AudioEvent::PlaylistOverflow(Some(7))));
+    assert_eq!(audio.poll_event(),None);
+
+    renderer.render_seconds(0.01f32);
+    let events:Vec<AudioEvent>=audio.events().collect();
+    assert_eq!(events,vec![
+        AudioEvent::LoopCompleted(7),
+        AudioEvent::LoopCompleted(7),
+        AudioEvent::TrackFinished(Some(7)),
+    ]);
+
+    // Убранные напрямую треки не уведомляют
+    audio.play_track(TrackSet::once(7,vec![0])).unwrap();
+    renderer.render_seconds(0.001f32);
+    audio.stop_track(0).unwrap();
+    renderer.render_seconds(0.01f32);
+    assert_eq!(audio.poll_event(),None);
+}
+
+#[test]
+fn buses(){
+    let (mut audio,mut renderer)=offline_audio(1);
+
+    let music=audio.add_bus("music",None);
+    let sfx=audio.add_bus("sfx",None);
+    let ui=audio.add_bus("ui",Some(1));
+    assert_eq!((music,sfx,ui),(AudioCommandResult::Index(0),AudioCommandResult::Index(1),AudioCommandResult::Index(2)));
+    assert_eq!(audio.add_bus("music",None),AudioCommandResult::Index(0));
+    assert_eq!(audio.bus("ui"),Some(2));
+    assert_eq!(audio.bus("voice"),None);
+
+    audio.add_track(constant_track(0.2f32));
+    audio.add_track(constant_track(0.1f32));
+
+    let play=|audio:&Audio,index:usize,bus:Option<usize>|{
+        let mut set=TrackSet::once(index,vec![0]);
+        set.repeats=0;
+        set.bus=bus;
+        audio.play_track(set).unwrap();
+    };
+    play(&audio,7,Some(0));
+    play(&audio,7,Some(2));
+    play(&audio,6,None);
+
+    // Последний кадр после установки громкостей
+    let mut last=||*renderer.render_seconds(0.02f32).last().unwrap();
+    let close=|a:f32,b:f32|(a-b).abs()<1e-5;
+
+    assert!(close(last(),0.5f32));
+
+    audio.set_bus_volume(0,0.5f32).unwrap();
+    assert!(close(last(),0.4f32));
+
+    // Дочерняя шина сводится через родительскую
+    audio.set_bus_volume(1,0.5f32).unwrap();
+    assert!(close(last(),0.3f32));
+
+    audio.set_bus_mute(0,true).unwrap();
+    assert!(close(last(),0.2f32));
+
+    // Соло дочерней шины - слышна только она
+    audio.set_bus_solo(2,true).unwrap();
+    assert!(close(last(),0.1f32));
+
+    audio.set_bus_solo(2,false).unwrap();
+    audio.set_bus_mute(0,false).unwrap();
+    assert!(close(last(),0.3f32));
+
+    audio.fade_bus(1,0f32,0.01f32).unwrap();
+    assert!(close(last(),0.2f32));
+}
+
+#[test]
+fn playback_rate(){
+    let (mut audio,mut renderer)=offline_audio(1);
+
+    // Значение трека - его номер
+    let data:Vec<f32>=(0..4000).map(|frame|frame as f32/8000f32).collect();
+    audio.add_track(MonoTrack::new(data,48000));
+
+    let close=|sample:f32,frame:f32|(sample-frame/8000f32).abs()<1e-5;
+
+    // В два раза быстрее - каждое второе значение
+    let mut set=TrackSet::once(7,vec![0]);
+    set.rate=2f32;
+    audio.play_track(set).unwrap();
+
+    let mix=renderer.render_seconds(0.01f32);
+    assert!(mix.iter().enumerate().all(|(n,&sample)|close(sample,2f32*n as f32)));
+
+    // В два раза медленнее, без скачка
+    audio.set_track_rate(0,0.5f32).unwrap();
+    let mix=renderer.render_seconds(0.01f32);
+    assert!(mix.iter().enumerate().all(|(n,&sample)|close(sample,960f32+0.5f32*n as f32)));
+
+    // Октава вниз
+    audio.set_track_pitch(0,-12f32).unwrap();
+    let mix=renderer.render_seconds(0.01f32);
+    assert!(close(mix[1]-mix[0],0.5f32));
+    audio.stop_track(0).unwrap();
+
+    // Случайные отклонения в пределах октавы
+    let mut rates=Vec::new();
+    for _ in 0..8{
+        let mut set=TrackSet::once(7,vec![0]);
+        set.pitch_variation=12f32;
+        audio.play_track(set).unwrap();
+
+        let mix=renderer.render_seconds(0.001f32);
+        let rate=(mix[10]-mix[0])*8000f32/10f32;
+        assert!((0.5f32-1e-3..=2f32+1e-3).contains(&rate));
+        rates.push(rate);
+
+        audio.stop_track(0).unwrap();
+    }
+    assert!(rates.iter().any(|&rate|(rate-rates[0]).abs()>1e-3));
+}
+
+#[test]
+fn sound_sources(){
+    let (audio,mut renderer)=offline_audio(2);
+
+    // Десятая доля секунды без атаки и спада
+    let tone=Envelope::new(
+        Oscillator::new(Waveform::square(),100f32),
+        Adsr::new(0f32,0f32,1f32,0.001f32),
+        0.1f32
+    );
+    assert_eq!(audio.play_source(tone,vec![1],0.5f32),AudioCommandResult::Sent);
+
+    let mix=renderer.render_seconds(0.2f32);
+    assert!(mix.chunks(2).all(|frame|frame[0]==0f32));
+    assert!((mix[3]-0.5f32).abs()<1e-3);
+    assert!(mix[2*4900..].iter().all(|&s|s==0f32));
+
+    assert_eq!(audio.poll_event(),Some(AudioEvent::TrackFinished(None)));
+}
+
+#[test]
+fn device_switching(){
+    let (mut audio,mut renderer)=offline_audio(1);
+
+    audio.add_track(constant_track(0.5f32));
+    audio.play_track(TrackSet::once(7,vec![0])).unwrap();
+
+    // Без устройства вывода переключение игнорируется,
+    // хранилище и плейлист сохраняются
+    assert_eq!(audio.switch_device(Some("missing device"),None),AudioCommandResult::Sent);
+    assert_eq!(audio.switch_device(None,None),AudioCommandResult::Sent);
+
+    let mix=renderer.render_seconds(0.05f32);
+    assert!(mix[..2000].iter().all(|&s|s!=0f32));
+    assert_eq!(audio.poll_event(),None);
+
+    // Перечисление устройств не требует устройства вывода
+    let devices=audio.output_devices();
+    assert!(devices.iter().filter(|device|device.default).count()<=1);
+}
+
+/// Стерео трек постоянного сигнала 0.5 с частотой 48000.
+fn stereo_stream(frames:usize)->StreamingTrack{
+    let spec=hound::WavSpec{
+        channels:2,
+        sample_rate:48000,
+        bits_per_sample:16,
+        sample_format:hound::SampleFormat::Int,
+    };
+
+    let mut file=Cursor::new(Vec::new());
+    let mut writer=hound::WavWriter::new(&mut file,spec).unwrap();
+    for _ in 0..2*frames{
+        writer.write_sample(16384i16).unwrap();
+    }
+    writer.finalize().unwrap();
+    file.set_position(0);
+
+    StreamingTrack::from_reader(file).unwrap()
+}
+
+/// Сводит по 0.05 секунды, пока не придут `count` уведомлений.
+/// Возвращает уведомления и сведённые значения.
+fn render_until_events(audio:&Audio,renderer:&mut OfflineRenderer,count:usize)->(Vec<AudioEvent>,Vec<f32>){
+    let mut events=Vec::new();
+    let mut mix=Vec::new();
+
+    for _ in 0..1000{
+        mix.extend(renderer.render_seconds(0.05f32));
+        events.extend(audio.events());
+
+        if events.len()>=count{
+            return (events,mix)
+        }
+
+        // Время потоку декодирования
+        sleep(Duration::from_millis(1));
+    }
+
+    panic!("The stream is stuck: {:?}",events)
+}
+
+#[test]
+fn streams(){
+    let (audio,mut renderer)=offline_audio(2);
+
+    // Каждый канал трека завершается отдельно
+    assert_eq!(audio.play_stream(StreamSet::once(stereo_stream(4800))),AudioCommandResult::Sent);
+
+    let (events,mix)=render_until_events(&audio,&mut renderer,2);
+    assert_eq!(events,vec![AudioEvent::TrackFinished(None),AudioEvent::TrackFinished(None)]);
+    assert!(mix.contains(&0.5f32));
+
+    // Трек длиннее буферов каналов:
+    // остановленный канал не задерживает второй
+    audio.play_stream(StreamSet::once(stereo_stream(96000))).unwrap();
+    renderer.render_seconds(0.05f32);
+    audio.stop_track(0).unwrap();
+
+    let (events,mix)=render_until_events(&audio,&mut renderer,1);
+    assert_eq!(events,vec![AudioEvent::TrackFinished(None)]);
+    assert!(mix.chunks(2).all(|frame|frame[0]==0f32));
+    assert!(mix.chunks(2).filter(|frame|frame[1]==0.5f32).count()>=90000);
+}
+
+/// Подключённые устройства и их форматы по умолчанию.
+type DeviceList=Rc<RefCell<Vec<(&'static str,Format)>>>;
+
+/// Список устройств без потоков.
+struct FakeDevices{
+    connected:DeviceList,
+    /// Имя устройства по умолчанию.
+    default:&'static str,
+    /// Имя текущего устройства.
+    current:&'static str,
+}
+
+impl OutputDevices for FakeDevices{
+    fn open(&mut self,device:Option<&str>,format:Option<Format>)->Option<Format>{
+        let connected=self.connected.borrow();
+
+        let device=device.unwrap_or(self.default);
+        let (name,default_format)=connected.iter().find(|(name,_)|*name==device)?.clone();
+
+        self.current=name;
+
+        Some(format.unwrap_or(default_format))
+    }
+}
+
+fn format(sample_rate:u32,channels:u16)->Format{
+    Format{
+        channels,
+        sample_rate:SampleRate(sample_rate),
+        data_type:SampleFormat::F32,
+    }
+}
+
+/// Вывод на устройстве по умолчанию "speakers", которое уже отключено,
+/// и очередь уведомлений.
+fn lost_output(connected:&DeviceList)->(Output<FakeDevices>,Mixer,EventSender,Receiver<AudioEvent>){
+    let (events,event_receiver)=event_channel(16);
+    let mixer=Mixer::new(&unit_settings(),48000,2,events.clone());
+
+    let output=Output{
+        devices:FakeDevices{
+            connected:connected.clone(),
+            default:"speakers",
+            current:"speakers",
+        },
+        format:format(48000,2),
+    };
+
+    (output,mixer,events,event_receiver)
+}
+
+#[test]
+fn device_lost(){
+    let connected=DeviceList::default();
+    let (mut output,mut mixer,events,event_receiver)=lost_output(&connected);
+    let (_commands,receiver)=channel();
+
+    // Устройство по умолчанию подключается снова после второй попытки
+    let mut attempts=0;
+    output.recover(&receiver,&mut mixer,&events,||{
+        attempts+=1;
+        if attempts==2{
+            connected.borrow_mut().push(("speakers",format(44100,1)));
+        }
+    });
+
+    assert_eq!(attempts,2);
+    assert_eq!(output.devices.current,"speakers");
+    assert_eq!(output.format,format(44100,1));
+
+    let events:Vec<AudioEvent>=event_receiver.try_iter().collect();
+    assert_eq!(events,vec![
+        AudioEvent::DeviceLost,
+        AudioEvent::FormatChanged{sample_rate:44100,channels:1},
+    ]);
+}
+
+#[test]
+fn switching_to_named_device(){
+    // Устройство по умолчанию не подключено
+    let connected=DeviceList::default();
+    let (mut output,mut mixer,events,event_receiver)=lost_output(&connected);
+    let (commands,receiver)=channel();
+
+    // Переключение во время ожидания завершает восстановление
+    connected.borrow_mut().push(("hdmi",format(48000,2)));
+    commands.send(AudioEngineCommand::SwitchDevice(Some("hdmi".to_string()),Some(format(96000,2)))).unwrap();
+
+    output.recover(&receiver,&mut mixer,&events,||panic!("Switching is waiting"));
+
+    assert_eq!(output.devices.current,"hdmi");
+    assert_eq!(output.format,format(96000,2));
+
+    // Переключение при работающем выводе
+    connected.borrow_mut().push(("usb",format(44100,2)));
+    output.switch_device(Some("usb"),None,&mut mixer,&events);
+
+    assert_eq!(output.devices.current,"usb");
+
+    let events:Vec<AudioEvent>=event_receiver.try_iter().collect();
+    assert_eq!(events,vec![
+        AudioEvent::DeviceLost,
+        AudioEvent::FormatChanged{sample_rate:96000,channels:2},
+        AudioEvent::FormatChanged{sample_rate:44100,channels:2},
+    ]);
+}
+
+#[test]
+fn unavailable_device(){
+    let connected=DeviceList::default();
+    let (mut output,mut mixer,events,event_receiver)=lost_output(&connected);
+    let (commands,receiver)=channel();
+
+    // Отсутствующее устройство не прерывает ожидание
+    commands.send(AudioEngineCommand::SwitchDevice(Some("missing".to_string()),None)).unwrap();
+
+    let mut attempts=0;
+    output.recover(&receiver,&mut mixer,&events,||{
+        attempts+=1;
+        connected.borrow_mut().push(("speakers",format(48000,2)));
+    });
+
+    assert_eq!(attempts,1);
+    assert_eq!(output.devices.current,"speakers");
+
+    // При работающем выводе устройство и формат сохраняются
+    output.switch_device(Some("missing"),Some(format(96000,1)),&mut mixer,&events);
+
+    assert_eq!(output.devices.current,"speakers");
+    assert_eq!(output.format,format(48000,2));
+
+    let events:Vec<AudioEvent>=event_receiver.try_iter().collect();
+    assert_eq!(events,vec![
+        AudioEvent::DeviceLost,
+        AudioEvent::DeviceUnavailable,
+        AudioEvent::FormatChanged{sample_rate:48000,channels:2},
+        AudioEvent::DeviceUnavailable,
+    ]);
+}
@@ -1,12 +1,26 @@
 use super::{
-    SampleTransform,
     MonoTrack,
+    LoopRegion,
+    TrackPosition,
+    StreamReceiver,
+    StreamSample,
+    SoundSource,
+    ResamplerQuality,
+    resampler::max_resampler_window,
+    effects::{
+        AudioEffect,
+        EffectChain,
+        process_chain,
+        set_chain_sample_rate,
+    },
 };
 
-use cat_engine_basement::support::{
-    math::gcd,
-    SyncRawPtr,
-};
+use cat_engine_basement::support::SyncRawPtr;
+
+/// Наименьшая скорость проигрывания.
+pub (crate) const min_playback_rate:f32=1f32/16f32;
+/// Наибольшая скорость проигрывания.
+pub (crate) const max_playback_rate:f32=16f32;
 
 enum PlayType{
     /// Перестаёт играть
@@ -40,62 +54,104 @@ enum PlayType{
 /// Имеет возможность повторять трек и конвертировать его частоту.
 pub struct TrackIter{
     data:SyncRawPtr<Vec<f32>>,
+    /// Канал потокового трека - используется вместо `data`.
+    stream:Option<StreamReceiver>,
+    /// Источник звука - используется вместо `data`.
+    source:Option<Box<dyn SoundSource>>,
     /// Частота дискретизации.
     track_sample_rate:u32,
     /// Длина трека
     track_len:usize,
     /// Индекс текущего значения.
     track_current_frame:usize,
+    /// Начало повторяемой части трека.
+    loop_start:usize,
+    /// Конец повторяемой части трека (не включается).
+    loop_end:usize,
     /// Режим повторений.
     play_type:PlayType,
     /// Повторений осталось.
     repeats:u32,
+    /// Возвращений к началу повторяемой части с последней проверки.
+    loops:u32,
     /// Громкость трека.
     volume:f32,
+    /// Цепочка эффектов трека.
+    effects:EffectChain,
+
+    /// Уровень плавного изменения громкости (множитель громкости).
+    fade:f32,
+    /// Уровень в конце плавного изменения.
+    fade_target:f32,
+    /// Изменение уровня за значение.
+    fade_step:f32,
+    /// Значений плавного изменения осталось.
+    fade_left:u32,
+    /// Остановить трек в конце плавного изменения.
+    fade_stop:bool,
 
     // Поля конвертера (не использовал Option для оптимизации и упрощения кода)
 
     /// Флаг включения конвертера
+    /// (частоты трека и системы различаются или скорость не равна единице).
     converter_enabled:bool,
-    /// Сокращённая частота трека - используется для подсчёта шагов.
-    /// We convert chunks of `from` samples into chunks of `to` samples.
-    from:u32,
-    /// Сокращённая частота системы - используется для подсчёта шагов.
-    /// We convert chunks of `from` samples into chunks of `to` samples.
-    to:u32,
-    /// Текущие значение - нужно для расчёта значения волны между текущим и следующим (взято из трека)
-    current_frame:f32,
-    /// Position of `current_sample` modulo `from`.
-    current_frame_pos_in_chunk:u32,
-    /// Слудующее значение - читай `current_frame` (взято из трека)
-    /// The samples right after `current_sample` (one per channel), extracted from `input`.
-    next_frame:f32,
-    /// The position of the next sample that the iterator should return, modulo `to`.
-    /// This counter is incremented (modulo `to`) every time the iterator is called.
-    next_output_frame_pos_in_chunk:u32,
+    /// Качество конвертера.
+    quality:ResamplerQuality,
+    /// Скорость проигрывания (изменяет и высоту звука).
+    rate:f32,
+    /// Выходящая (системная) частота дискретизации.
+    system_sample_rate:u32,
+    /// Отношение частот при понижении частоты (срез для синка), иначе 1.
+    cutoff:f32,
+    /// Шаг по значениям трека за одно выходящее значение.
+    step:f64,
+    /// Положение выходящего значения между серединными значениями окна, [0; 1).
+    phase:f64,
+    /// Окно значений трека для интерполяции (используется `quality.window()` значений).
+    /// Интерполируется значение между серединными значениями окна -
+    /// текущим и следующим (взяты из трека).
+    window:[f32;max_resampler_window],
+    /// Значений окна осталось дополнить тишиной после конца трека,
+    /// пока значения трека в окне не будут проиграны.
+    tail_frames:usize,
 }
 
 impl TrackIter{
     pub fn empty()->TrackIter{
         Self{
             data:SyncRawPtr::zero(),
+            stream:None,
+            source:None,
             track_sample_rate:0u32,
 
             track_len:0usize,
 
             track_current_frame:0usize,
+            loop_start:0usize,
+            loop_end:0usize,
             play_type:PlayType::None, // Определяется позже
             repeats:0u32, // Определяется позже
+            loops:0u32,
 
             volume:0f32,
 
+            effects:Vec::new(),
+
+            fade:1f32,
+            fade_target:1f32,
+            fade_step:0f32,
+            fade_left:0u32,
+            fade_stop:false,
+
             converter_enabled:false, // Определяется позже
-            from:0u32,
-            to:0u32,
-            current_frame:0f32,
-            current_frame_pos_in_chunk:0u32,
-            next_frame:0f32,
-            next_output_frame_pos_in_chunk:0u32,
+            quality:ResamplerQuality::Linear, // Определяется позже
+            rate:1f32,
+            system_sample_rate:0u32,
+            cutoff:1f32,
+            step:1f64,
+            phase:0f64,
+            window:[0f32;max_resampler_window],
+            tail_frames:0usize,
         }
     }
 
@@ -132,68 +188,301 @@ impl TrackIter{
     /// Останавливает трек
     /// без возвожности возобновления.
     pub fn stop(&mut self){
-        self.play_type=PlayType::None
+        self.play_type=PlayType::None;
+        self.tail_frames=0
+    }
+
+    /// Освобождает канал потокового трека,
+    /// чтобы поток декодирования мог завершиться,
+    /// и удаляет источник звука и эффекты трека.
+    pub fn release(&mut self){
+        self.stream=None;
+        self.source=None;
+        self.effects.clear()
     }
 }
 
 /// Установка параметров.
 impl TrackIter{
-        /// Установка новой итерации трека.
-        pub fn set_track(&mut self,track:&MonoTrack,system_sample_rate:u32,repeats:u32,volume:f32){
-            self.data=SyncRawPtr::new(&track.data);
-            self.track_sample_rate=track.sample_rate;
-            self.volume=volume;
-            self.track_current_frame=0;
-            self.track_len=track.len();
-
-            match repeats{
-                0=>self.play_type=PlayType::Forever,
-                1=>self.play_type=PlayType::Once,
-                _=>{
-                    self.play_type=PlayType::Repeat;
-                    self.repeats=repeats
-                }
-            }
-    
-            // Проверка частоты и настройка конвертера
-            if system_sample_rate!=self.track_sample_rate{
-                self.current_frame_pos_in_chunk=0;
-                self.next_output_frame_pos_in_chunk=0;
-    
-                self.converter_enabled=true;
-                // Вычисление максимального общего делителя
-                let gcd=gcd(self.track_sample_rate,system_sample_rate);
-    
-                self.from=self.track_sample_rate/gcd;
-                self.to=system_sample_rate/gcd;
-    
-                self.current_frame=self.next_track_sample();
-                self.next_frame=self.next_track_sample();
-            }
-            else{
-                self.converter_enabled=false;
+    /// Установка новой итерации трека.
+    pub fn set_track(
+        &mut self,
+        track:&MonoTrack,
+        system_sample_rate:u32,
+        repeats:u32,
+        volume:f32,
+        quality:ResamplerQuality,
+        loop_region:Option<LoopRegion>
+    ){
+        self.data=SyncRawPtr::new(&track.data);
+        self.stream=None;
+        self.source=None;
+        self.track_sample_rate=track.sample_rate;
+        self.volume=volume;
+        self.set_fade(1f32);
+        self.track_current_frame=0;
+        self.loops=0;
+        self.track_len=track.len();
+        self.set_loop(loop_region);
+
+        match repeats{
+            0=>self.play_type=PlayType::Forever,
+            1=>self.play_type=PlayType::Once,
+            _=>{
+                self.play_type=PlayType::Repeat;
+                self.repeats=repeats
             }
         }
 
-    /// Устанавливает частоту вывода (системную частоту).
-    pub fn set_system_sample_rate(&mut self,sample_rate:u32){
-        if sample_rate==self.track_sample_rate{
+        self.quality=quality;
+        self.rate=1f32;
+        self.set_converter(system_sample_rate)
+    }
+
+    /// Установка канала потокового трека.
+    /// 
+    /// Повторения выполняет поток декодирования,
+    /// итератор играет, пока канал не закончится.
+    pub fn set_stream(
+        &mut self,
+        stream:StreamReceiver,
+        system_sample_rate:u32,
+        volume:f32,
+        quality:ResamplerQuality
+    ){
+        self.data=SyncRawPtr::zero();
+        self.track_sample_rate=stream.sample_rate();
+        self.stream=Some(stream);
+        self.source=None;
+        self.volume=volume;
+        self.set_fade(1f32);
+        self.track_current_frame=0;
+        self.loops=0;
+        self.track_len=0;
+        self.loop_start=0;
+        self.loop_end=0;
+        self.play_type=PlayType::Forever;
+
+        self.quality=quality;
+        self.rate=1f32;
+        self.set_converter(system_sample_rate)
+    }
+
+    /// Установка источника звука.
+    /// 
+    /// Источник генерирует значения с частотой системы
+    /// и играет, пока не закончится.
+    pub fn set_source(
+        &mut self,
+        mut source:Box<dyn SoundSource>,
+        system_sample_rate:u32,
+        volume:f32,
+        quality:ResamplerQuality
+    ){
+        source.set_sample_rate(system_sample_rate);
+
+        self.data=SyncRawPtr::zero();
+        self.stream=None;
+        self.source=Some(source);
+        self.track_sample_rate=system_sample_rate;
+        self.volume=volume;
+        self.set_fade(1f32);
+        self.track_current_frame=0;
+        self.loops=0;
+        self.track_len=0;
+        self.loop_start=0;
+        self.loop_end=0;
+        self.play_type=PlayType::Forever;
+
+        self.quality=quality;
+        self.rate=1f32;
+        self.set_converter(system_sample_rate)
+    }
+
+    /// Проверка частоты и скорости и настройка конвертера.
+    fn set_converter(&mut self,system_sample_rate:u32){
+        self.system_sample_rate=system_sample_rate;
+
+        if system_sample_rate!=self.track_sample_rate || self.rate!=1f32{
+            self.converter_enabled=true;
+            self.set_step();
+            self.fill_window();
+        }
+        else{
             self.converter_enabled=false;
         }
+    }
+
+    /// Вычисление шага конвертера.
+    fn set_step(&mut self){
+        self.step=self.track_sample_rate as f64*self.rate as f64/self.system_sample_rate as f64;
+
+        self.cutoff=if self.step>1f64{
+            (1f64/self.step) as f32
+        }
         else{
+            1f32
+        };
+    }
+
+    /// Устанавливает скорость проигрывания.
+    /// 
+    /// Включённый конвертер не выключается при возвращении скорости к единице,
+    /// так как он читает значения трека наперёд.
+    pub fn set_rate(&mut self,rate:f32){
+        let rate=if rate.is_nan(){1f32}else{rate.clamp(min_playback_rate,max_playback_rate)};
+
+        self.rate=rate;
+
+        if self.converter_enabled{
+            // Положение между значениями сохраняется
+            self.set_step()
+        }
+        else if rate!=1f32{
             self.converter_enabled=true;
-            // Вычисление максимального общего делителя
-            let gcd=gcd(self.track_sample_rate,sample_rate);
+            self.set_step();
+            self.fill_window();
+        }
+    }
+
+    /// Заполняет окно конвертера, начиная с текущего значения трека.
+    fn fill_window(&mut self){
+        self.phase=0f64;
+        self.tail_frames=0;
+
+        // Значения до текущего - из трека, до начала трека - тишина
+        let window=self.quality.window();
+        let history=window/2-1;
+        for c in 0..history{
+            self.window[c]=match (self.track_current_frame+c).checked_sub(history){
+                Some(frame) if self.stream.is_none() && frame<self.track_len=>
+                    self.data.as_ref()[frame]*self.volume,
+                _=>0f32,
+            };
+        }
+        // Текущее значение и следующие
+        for c in history..window{
+            let sample=self.next_window_sample();
+            self.window[c]=sample;
+        }
+    }
+
+    /// Устанавливает повторяемую часть трека,
+    /// `None` - весь трек.
+    pub fn set_loop(&mut self,loop_region:Option<LoopRegion>){
+        let (start,end)=match loop_region{
+            Some(LoopRegion{start,end})=>{
+                let end=end.min(self.track_len);
+                (start.min(end),end)
+            }
+            None=>(0,self.track_len),
+        };
+
+        if start<end{
+            self.loop_start=start;
+            self.loop_end=end;
+        }
+        else{
+            self.loop_start=0;
+            self.loop_end=self.track_len;
+        }
+    }
+
+    /// Переходит к данному значению трека.
+    /// 
+    /// Для потоковых треков и источников звука ничего не происходит.
+    pub fn seek(&mut self,frame:usize){
+        if self.stream.is_some() || self.source.is_some(){
+            return
+        }
+
+        self.track_current_frame=frame.min(self.track_len);
+
+        if self.converter_enabled{
+            self.fill_window()
+        }
+    }
+
+    /// Текущее положение в треке.
+    pub fn position(&self)->TrackPosition{
+        // Конвертер считывает значения наперёд
+        let frame=if self.converter_enabled{
+            self.track_current_frame.saturating_sub(self.quality.window()/2+1)
+        }
+        else{
+            self.track_current_frame
+        };
+
+        TrackPosition{
+            frame,
+            length:if self.stream.is_some() || self.source.is_some(){None}else{Some(self.track_len)},
+            sample_rate:self.track_sample_rate,
+        }
+    }
 
-            self.from=self.track_sample_rate/gcd;
-            self.to=sample_rate/gcd;
+    /// Устанавливает частоту вывода (системную частоту).
+    pub fn set_system_sample_rate(&mut self,sample_rate:u32){
+        // Источник генерирует значения с новой частотой
+        if let Some(source)=&mut self.source{
+            source.set_sample_rate(sample_rate);
+            self.track_sample_rate=sample_rate;
         }
+        self.set_converter(sample_rate);
+        set_chain_sample_rate(&mut self.effects,sample_rate)
     }
 
     /// Устанавливает громкость трека.
     pub fn set_volume(&mut self,volume:f32){
         self.volume=volume
     }
+
+    /// Устанавливает уровень громкости, отменяя плавное изменение.
+    pub fn set_fade(&mut self,level:f32){
+        self.fade=level;
+        self.fade_left=0;
+        self.fade_stop=false;
+    }
+
+    /// Плавно изменяет уровень громкости за `frames` значений итератора
+    /// и останавливает трек, если `stop`.
+    /// 
+    /// На паузе уровень не изменяется.
+    pub fn fade(&mut self,level:f32,frames:u32,stop:bool){
+        if frames==0{
+            self.set_fade(level);
+            if stop{
+                self.stop()
+            }
+            return
+        }
+
+        self.fade_target=level;
+        self.fade_step=(level-self.fade)/frames as f32;
+        self.fade_left=frames;
+        self.fade_stop=stop;
+    }
+
+    /// Добавляет эффект в конец цепочки трека.
+    pub fn add_effect(&mut self,mut effect:Box<dyn AudioEffect>,system_sample_rate:u32){
+        effect.set_sample_rate(system_sample_rate);
+        self.effects.push(effect)
+    }
+
+    /// Удаляет все эффекты трека.
+    pub fn clear_effects(&mut self){
+        self.effects.clear()
+    }
+
+    /// Возвращает количество возвращений к началу повторяемой части
+    /// с последней проверки.
+    pub fn take_loops(&mut self)->u32{
+        std::mem::replace(&mut self.loops,0)
+    }
+
+    /// Обрабатывает блок значений трека цепочкой эффектов.
+    pub fn process_effects(&mut self,samples:&mut [f32]){
+        process_chain(&mut self.effects,samples)
+    }
 }
 
 
@@ -201,26 +490,79 @@ impl TrackIter{
 impl TrackIter{
     /// Следующее значение трека.
     pub fn next_track_sample(&mut self)->f32{
+        if let Some(source)=&mut self.source{
+            return match self.play_type{
+                PlayType::Forever=>match source.next_sample(){
+                    Some(sample)=>{
+                        self.track_current_frame+=1;
+                        sample*self.volume
+                    }
+                    None=>{
+                        self.play_type=PlayType::None;
+                        0f32
+                    }
+                }
+
+                // Паузы и остановка
+                _=>0f32,
+            }
+        }
+
+        if let Some(stream)=&self.stream{
+            return match self.play_type{
+                PlayType::Forever=>match stream.next_sample(){
+                    StreamSample::Sample(sample)=>{
+                        self.track_current_frame+=1;
+                        sample*self.volume
+                    }
+                    // Декодер не успевает - тишина
+                    StreamSample::Empty=>0f32,
+                    StreamSample::End=>{
+                        self.play_type=PlayType::None;
+                        0f32
+                    }
+                }
+
+                // Паузы и остановка
+                _=>0f32,
+            }
+        }
+
         match self.play_type{
             PlayType::Once=>{
-                if self.track_current_frame==self.track_len-1{
+                if self.track_current_frame+1>=self.track_len{
                     self.play_type=PlayType::None
                 }
             }
 
             PlayType::Repeat=>{
-                if self.track_current_frame==self.track_len{
+                let at_end=self.track_current_frame>=self.track_len;
+                if self.track_current_frame==self.loop_end || at_end{
                     self.repeats-=1;
-                    if self.repeats==0{
-                        self.play_type=PlayType::None
+                    if self.repeats!=0{
+                        self.track_current_frame=self.loop_start;
+                        self.loops+=1
+                    }
+                    else if at_end{
+                        // Последнее значение уже возвращено
+                        self.play_type=PlayType::None;
+                        self.track_current_frame=self.loop_start;
+                        return 0f32
+                    }
+                    else{
+                        // Проигрывание оставшейся части трека
+                        self.play_type=PlayType::Once;
+                        if self.track_current_frame+1>=self.track_len{
+                            self.play_type=PlayType::None
+                        }
                     }
-                    self.track_current_frame=0;
                 }
             }
 
             PlayType::Forever=>{
-                if self.track_current_frame==self.track_len{
-                    self.track_current_frame=0
+                if self.track_current_frame==self.loop_end || self.track_current_frame>=self.track_len{
+                    self.track_current_frame=self.loop_start;
+                    self.loops+=1
                 }
             }
 
@@ -228,6 +570,11 @@ impl TrackIter{
             _=>return 0f32,
         }
 
+        // Пустой трек или переход за конец трека
+        if self.track_current_frame>=self.track_len{
+            return 0f32
+        }
+
         let sample=self.data.as_ref()[self.track_current_frame];
 
         self.track_current_frame+=1;
@@ -235,71 +582,86 @@ impl TrackIter{
         sample*self.volume
     }
 
+    /// Следующее значение трека для окна конвертера.
+    /// 
+    /// Окно читает трек наперёд, поэтому после конца трека
+    /// оно дополняется тишиной, пока последнее значение трека
+    /// не пройдёт середину окна.
+    fn next_window_sample(&mut self)->f32{
+        let ended=matches!(self.play_type,PlayType::None);
+
+        let sample=self.next_track_sample();
+
+        if ended{
+            self.tail_frames=self.tail_frames.saturating_sub(1)
+        }
+        else if let PlayType::None=self.play_type{
+            self.tail_frames=self.quality.window()/2+1
+        }
+
+        sample
+    }
+
     fn next_input_frame(&mut self){
-        self.current_frame_pos_in_chunk+=1;
+        let window=self.quality.window();
 
-        std::mem::swap(&mut self.current_frame,&mut self.next_frame);
+        self.window.copy_within(1..window,0);
 
-        self.next_frame=self.next_track_sample();
+        let sample=self.next_window_sample();
+        self.window[window-1]=sample;
     }
 
     /// Перевод в нужную частоту.
     pub fn next_converter_sample(&mut self)->f32{
-        // The frame we are going to return from this function will be a linear interpolation
-        // between `self.current_frame` and `self.next_frame`.
-
-        if self.next_output_frame_pos_in_chunk>=self.to{
-            // If we jump to the next frame, we reset the whole state.
-            self.next_output_frame_pos_in_chunk=0;
-
+        // Интерполяция между серединными значениями окна
+        let window=&self.window[..self.quality.window()];
+        let sample=self.quality.interpolate(window,self.phase as f32,self.cutoff);
+
+        // Переход к следующему положению
+        self.phase+=self.step;
+        while self.phase>=1f64{
+            self.phase-=1f64;
             self.next_input_frame();
-            while self.current_frame_pos_in_chunk<self.from{
-                self.next_input_frame();
-            }
-            self.current_frame_pos_in_chunk=0;
-        }
-        else{
-            // Finding the position of the first sample of the linear interpolation.
-            let req_left_sample=(
-                self.from*self.next_output_frame_pos_in_chunk/self.to
-            )%self.from;
-
-            // Advancing `self.current_frame`, `self.next_frame` and
-            // `self.current_frame_pos_in_chunk` until the latter variable
-            // matches `req_left_sample`.
-            while self.current_frame_pos_in_chunk<req_left_sample{
-                self.next_input_frame();
-                debug_assert!(self.current_frame_pos_in_chunk<self.from);
-            }
         }
 
-        // Merging `self.current_frame` and `self.next_frame` into `self.output_buffer`.
-        // Note that `self.output_buffer` can be truncated if there is not enough data in
-        // `self.next_frame`.
-        //let mut result = None;
-        let numerator=(self.from*self.next_output_frame_pos_in_chunk)%self.to;
-
-        let sample=SampleTransform::lerp(self.current_frame,self.next_frame,numerator,self.to);
-
-        // Incrementing the counter for the next iteration.
-        self.next_output_frame_pos_in_chunk+=1;
-
         sample
     }
 
     /// Возвращает следующее значение итератора или 
     /// уведомляет о его завершении.
     pub fn next(&mut self)->Option<f32>{
-        if let PlayType::None=self.play_type{
-            None
+        let playing=match self.play_type{
+            // Окно конвертера доигрывается после конца трека
+            PlayType::None if self.converter_enabled && self.tail_frames!=0=>true,
+            PlayType::None=>return None,
+            PlayType::Once | PlayType::Repeat | PlayType::Forever=>true,
+            _=>false,
+        };
+
+        let sample=if self.converter_enabled{
+            self.next_converter_sample()
         }
         else{
-            Some(if self.converter_enabled{
-                self.next_converter_sample()
+            self.next_track_sample()
+        };
+
+        let fade=self.fade;
+
+        // Плавное изменение громкости
+        if self.fade_left!=0 && playing{
+            self.fade_left-=1;
+
+            if self.fade_left==0{
+                self.fade=self.fade_target;
+                if self.fade_stop{
+                    self.stop()
+                }
             }
             else{
-                self.next_track_sample()
-            })
+                self.fade+=self.fade_step;
+            }
         }
+
+        Some(sample*fade)
     }
 }
\ No newline at end of file
@@ -0,0 +1,389 @@
+use crate::sample::SampleTransform;
+
+use super::{
+    Track,
+    TrackResult,
+};
+
+use minimp3::Decoder;
+
+use std::{
+    io::{
+        self,
+        Read,
+        Seek,
+        SeekFrom,
+    },
+    path::Path,
+};
+
+/// Форматы аудио файлов.
+///
+/// Audio file formats.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum AudioFormat{
+    Mp3,
+    /// Целочисленный (8, 16, 24, 32 бита) или вещественный PCM.
+    ///
+    /// Integer (8, 16, 24, 32 bits) or float PCM.
+    Wav,
+    Flac,
+    /// Ogg Vorbis.
+    Vorbis,
+}
+
+impl AudioFormat{
+    /// Определяет формат по сигнатуре файла.
+    ///
+    /// Detects a format by the file signature.
+    pub fn detect(header:&[u8])->Option<AudioFormat>{
+        if header.len()>=12 && &header[0..4]==b"RIFF" && &header[8..12]==b"WAVE"{
+            Some(AudioFormat::Wav)
+        }
+        else if header.starts_with(b"fLaC"){
+            Some(AudioFormat::Flac)
+        }
+        else if header.starts_with(b"OggS"){
+            Some(AudioFormat::Vorbis)
+        }
+        // ID3 tags or a frame sync
+        else if header.starts_with(b"ID3") || (header.len()>=2 && header[0]==0xFF && header[1]&0xE0==0xE0){
+            Some(AudioFormat::Mp3)
+        }
+        else{
+            None
+        }
+    }
+
+    /// Определяет формат по расширению файла.
+    ///
+    /// Detects a format by the file extension.
+    pub fn from_extension<P:AsRef<Path>>(path:P)->Option<AudioFormat>{
+        let extension=path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+
+        match extension.as_str(){
+            "mp3"=>Some(AudioFormat::Mp3),
+            "wav"|"wave"=>Some(AudioFormat::Wav),
+            "flac"=>Some(AudioFormat::Flac),
+            "ogg"|"oga"=>Some(AudioFormat::Vorbis),
+            _=>None,
+        }
+    }
+
+    /// Определяет формат по началу потока
+    /// и возвращается к исходной позиции.
+    ///
+    /// Detects a format by the beginning of a stream
+    /// and returns to the initial position.
+    pub fn detect_stream<R:Read+Seek>(reader:&mut R)->io::Result<Option<AudioFormat>>{
+        let start=reader.stream_position()?;
+
+        let mut header=[0u8;12];
+        let mut len=0;
+        while len<header.len(){
+            match reader.read(&mut header[len..]){
+                Ok(0)=>break,
+                Ok(read)=>len+=read,
+                Err(e) if e.kind()==io::ErrorKind::Interrupted=>{}
+                Err(e)=>return Err(e),
+            }
+        }
+
+        reader.seek(SeekFrom::Start(start))?;
+
+        Ok(AudioFormat::detect(&header[..len]))
+    }
+}
+
+/// Количество кадров, декодируемых за раз из `wav`.
+///
+/// The amount of frames decoded from `wav` at a time.
+const wav_chunk_frames:usize=1024;
+
+/// Декодирует поток заданного формата.
+///
+/// Decodes a stream of the given format.
+pub (crate) fn decode<R:Read+Seek>(reader:R,format:AudioFormat)->TrackResult<Track<f32>>{
+    let mut decoder=match StreamDecoder::new(reader,format){
+        TrackResult::Ok(decoder)=>decoder,
+        TrackResult::FileError(e)=>return TrackResult::FileError(e),
+        TrackResult::NoData=>return TrackResult::NoData,
+        TrackResult::UnsupportedFormat=>return TrackResult::UnsupportedFormat,
+    };
+
+    let mut data=Vec::new();
+
+    loop{
+        let len=data.len();
+        decoder.next_chunk(&mut data);
+        if data.len()==len{
+            break
+        }
+    }
+
+    track(data,decoder.channels(),decoder.sample_rate())
+}
+
+fn track(data:Vec<f32>,channels:u16,sample_rate:u32)->TrackResult<Track<f32>>{
+    if data.is_empty() || channels==0{
+        TrackResult::NoData
+    }
+    else{
+        TrackResult::Ok(Track::raw(data,channels,sample_rate))
+    }
+}
+
+/// Множитель для приведения целых значений к [-1; 1].
+///
+/// Scales integer samples to [-1; 1].
+fn integer_scale(bits:u32)->f32{
+    1f32/(1u64<<(bits.clamp(1,32)-1)) as f32
+}
+
+/// Декодер, выдающий трек частями.
+///
+/// A decoder producing a track by chunks.
+pub (crate) trait ChunkDecoder{
+    fn channels(&self)->u16;
+
+    fn sample_rate(&self)->u32;
+
+    /// Добавляет в буфер следующую часть трека (чередующиеся каналы).
+    /// Ничего не добавляет, если трек закончился.
+    ///
+    /// Appends the next chunk of the track (interleaved channels) to the buffer.
+    /// Appends nothing if the track is over.
+    fn next_chunk(&mut self,buffer:&mut Vec<f32>);
+
+    /// Возвращается к началу трека.
+    /// Возвращает `false`, если это невозможно.
+    ///
+    /// Returns to the beginning of the track.
+    /// Returns `false` if it's impossible.
+    fn rewind(&mut self)->bool;
+}
+
+enum FormatDecoder<R:Read+Seek>{
+    Mp3(Decoder<R>),
+    /// Множитель для целых значений, `None` для вещественных.
+    Wav(hound::WavReader<R>,Option<f32>),
+    /// Множитель и буфер блока.
+    Flac(claxon::FlacReader<R>,f32,Vec<i32>),
+    Vorbis(Box<lewton::inside_ogg::OggStreamReader<R>>),
+}
+
+struct OpenedDecoder<R:Read+Seek>{
+    decoder:FormatDecoder<R>,
+    channels:u16,
+    sample_rate:u32,
+    /// Данные, декодированные при открытии.
+    pending:Vec<f32>,
+}
+
+impl<R:Read+Seek> OpenedDecoder<R>{
+    fn open(reader:R,format:AudioFormat)->TrackResult<OpenedDecoder<R>>{
+        match format{
+            AudioFormat::Mp3=>{
+                let mut decoder=Decoder::new(reader);
+
+                // Параметры трека известны только после первого фрейма
+                match decoder.next_frame(){
+                    Ok(frame)=>TrackResult::Ok(Self{
+                        channels:frame.channels as u16,
+                        sample_rate:frame.sample_rate as u32,
+                        pending:frame.data.into_iter().map(SampleTransform::into_f32).collect(),
+                        decoder:FormatDecoder::Mp3(decoder),
+                    }),
+                    Err(_)=>TrackResult::NoData,
+                }
+            }
+
+            AudioFormat::Wav=>{
+                let reader=match hound::WavReader::new(reader){
+                    Ok(reader)=>reader,
+                    Err(hound::Error::IoError(e))=>return TrackResult::FileError(e),
+                    Err(_)=>return TrackResult::NoData,
+                };
+
+                let spec=reader.spec();
+
+                let scale=match spec.sample_format{
+                    hound::SampleFormat::Float=>None,
+                    hound::SampleFormat::Int=>Some(integer_scale(spec.bits_per_sample as u32)),
+                };
+
+                TrackResult::Ok(Self{
+                    decoder:FormatDecoder::Wav(reader,scale),
+                    channels:spec.channels,
+                    sample_rate:spec.sample_rate,
+                    pending:Vec::new(),
+                })
+            }
+
+            AudioFormat::Flac=>{
+                let reader=match claxon::FlacReader::new(reader){
+                    Ok(reader)=>reader,
+                    Err(claxon::Error::IoError(e))=>return TrackResult::FileError(e),
+                    Err(_)=>return TrackResult::NoData,
+                };
+
+                let info=reader.streaminfo();
+
+                TrackResult::Ok(Self{
+                    decoder:FormatDecoder::Flac(reader,integer_scale(info.bits_per_sample),Vec::new()),
+                    channels:info.channels as u16,
+                    sample_rate:info.sample_rate,
+                    pending:Vec::new(),
+                })
+            }
+
+            AudioFormat::Vorbis=>{
+                let reader=match lewton::inside_ogg::OggStreamReader::new(reader){
+                    Ok(reader)=>reader,
+                    Err(_)=>return TrackResult::NoData,
+                };
+
+                TrackResult::Ok(Self{
+                    channels:reader.ident_hdr.audio_channels as u16,
+                    sample_rate:reader.ident_hdr.audio_sample_rate,
+                    decoder:FormatDecoder::Vorbis(Box::new(reader)),
+                    pending:Vec::new(),
+                })
+            }
+        }
+    }
+}
+
+/// Потоковый декодер любого из поддерживаемых форматов.
+///
+/// A stream decoder of any supported format.
+pub (crate) struct StreamDecoder<R:Read+Seek>{
+    /// `None` после неудачной перемотки.
+    decoder:Option<FormatDecoder<R>>,
+    format:AudioFormat,
+    channels:u16,
+    sample_rate:u32,
+    pending:Vec<f32>,
+    /// Начало трека в потоке.
+    start:u64,
+}
+
+impl<R:Read+Seek> StreamDecoder<R>{
+    pub fn new(mut reader:R,format:AudioFormat)->TrackResult<StreamDecoder<R>>{
+        let start=match reader.stream_position(){
+            Ok(start)=>start,
+            Err(e)=>return TrackResult::FileError(e),
+        };
+
+        match OpenedDecoder::open(reader,format){
+            TrackResult::Ok(opened)=>TrackResult::Ok(Self{
+                decoder:Some(opened.decoder),
+                format,
+                channels:opened.channels,
+                sample_rate:opened.sample_rate,
+                pending:opened.pending,
+                start,
+            }),
+            TrackResult::FileError(e)=>TrackResult::FileError(e),
+            TrackResult::NoData=>TrackResult::NoData,
+            TrackResult::UnsupportedFormat=>TrackResult::UnsupportedFormat,
+        }
+    }
+}
+
+impl<R:Read+Seek> ChunkDecoder for StreamDecoder<R>{
+    fn channels(&self)->u16{
+        self.channels
+    }
+
+    fn sample_rate(&self)->u32{
+        self.sample_rate
+    }
+
+    fn next_chunk(&mut self,buffer:&mut Vec<f32>){
+        if !self.pending.is_empty(){
+            buffer.append(&mut self.pending);
+            return
+        }
+
+        // Ошибки декодирования считаются концом трека -
+        // остаётся то, что успели декодировать
+        // Decoding errors are treated as the end of the track -
+        // keeping what is decoded
+        match &mut self.decoder{
+            Some(FormatDecoder::Mp3(decoder))=>{
+                if let Ok(frame)=decoder.next_frame(){
+                    buffer.extend(frame.data.into_iter().map(SampleTransform::into_f32));
+                }
+            }
+
+            Some(FormatDecoder::Wav(reader,scale))=>{
+                let samples=wav_chunk_frames*self.channels as usize;
+
+                match *scale{
+                    Some(scale)=>for sample in reader.samples::<i32>().take(samples){
+                        match sample{
+                            Ok(sample)=>buffer.push(sample as f32*scale),
+                            Err(_)=>break,
+                        }
+                    }
+                    None=>for sample in reader.samples::<f32>().take(samples){
+                        match sample{
+                            Ok(sample)=>buffer.push(sample),
+                            Err(_)=>break,
+                        }
+                    }
+                }
+            }
+
+            Some(FormatDecoder::Flac(reader,scale,block_buffer))=>{
+                let block_buffer_data=std::mem::take(block_buffer);
+
+                if let Ok(Some(block))=reader.blocks().read_next_or_eof(block_buffer_data){
+                    buffer.reserve(block.len() as usize);
+                    for frame in 0..block.duration(){
+                        for channel in 0..block.channels(){
+                            buffer.push(block.sample(channel,frame) as f32**scale);
+                        }
+                    }
+                    *block_buffer=block.into_buffer();
+                }
+            }
+
+            Some(FormatDecoder::Vorbis(reader))=>{
+                // Первые пакеты могут быть пустыми
+                while let Ok(Some(packet))=reader.read_dec_packet_itl(){
+                    if !packet.is_empty(){
+                        buffer.extend(packet.into_iter().map(SampleTransform::into_f32));
+                        break
+                    }
+                }
+            }
+
+            None=>{}
+        }
+    }
+
+    fn rewind(&mut self)->bool{
+        let mut reader=match self.decoder.take(){
+            Some(FormatDecoder::Mp3(decoder))=>decoder.into_inner(),
+            Some(FormatDecoder::Wav(reader,_))=>reader.into_inner(),
+            Some(FormatDecoder::Flac(reader,..))=>reader.into_inner(),
+            Some(FormatDecoder::Vorbis(reader))=>reader.into_inner().into_inner(),
+            None=>return false,
+        };
+
+        if reader.seek(SeekFrom::Start(self.start)).is_err(){
+            return false
+        }
+
+        match OpenedDecoder::open(reader,self.format){
+            TrackResult::Ok(opened)=>{
+                self.decoder=Some(opened.decoder);
+                self.pending=opened.pending;
+                true
+            }
+            _=>false,
+        }
+    }
+}
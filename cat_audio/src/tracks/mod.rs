@@ -1,6 +1,23 @@
+use crate::{
+    ResamplerQuality,
+    AudioEffect,
+    TrackPlacement,
+};
+
 mod track;
 pub use track::*;
 
+mod formats;
+pub use formats::AudioFormat;
+
+mod streaming;
+pub use streaming::{
+    StreamingTrack,
+    StreamSet,
+    StreamReceiver,
+};
+pub (crate) use streaming::StreamSample;
+
 /// Результат загрузки трека.
 /// 
 /// The result of loading a track.
@@ -9,6 +26,10 @@ pub enum TrackResult<T>{
     Ok(T),
     FileError(std::io::Error),
     NoData,
+    /// Формат файла не распознан.
+    /// 
+    /// The file format isn't recognized.
+    UnsupportedFormat,
 }
 
 impl<T:std::fmt::Debug> TrackResult<T>{
@@ -82,6 +103,58 @@ pub struct TrackSet{
     /// 
     /// A track volume.
     pub volume:f32,
+    /// Качество перевода частоты трека,
+    /// `None` - из настроек аудио системы.
+    /// 
+    /// The quality of converting the track sample rate,
+    /// `None` - from the audio system settings.
+    pub quality:Option<ResamplerQuality>,
+    /// Цепочка эффектов трека (выполняются по порядку).
+    /// 
+    /// The effect chain of the track (run in order).
+    pub effects:Vec<Box<dyn AudioEffect>>,
+    /// Размещение трека между каналами
+    /// (каналы считаются расположенными слева направо).
+    /// 
+    /// The placement of the track between the channels
+    /// (channels are considered to be located from left to right).
+    pub placement:TrackPlacement,
+    /// Время плавного появления в секундах,
+    /// 0 - сразу с полной громкостью.
+    /// 
+    /// The fade-in time in seconds,
+    /// 0 - at full volume immediately.
+    pub fade_in:f32,
+    /// Повторяемая часть трека, `None` - весь трек.
+    /// 
+    /// Часть до неё проигрывается один раз,
+    /// после неё - после последнего повторения.
+    /// 
+    /// The repeated part of the track, `None` - the whole track.
+    /// 
+    /// The part before it is played once,
+    /// the part after it - after the last repeat.
+    pub loop_region:Option<LoopRegion>,
+    /// Шина, в которую сводится трек,
+    /// `None` - сразу в общую шину.
+    /// 
+    /// The bus the track is mixed into,
+    /// `None` - directly into the master bus.
+    pub bus:Option<usize>,
+    /// Скорость проигрывания (изменяет и высоту звука), 1 - исходная.
+    /// 
+    /// The playback rate (changes the pitch too), 1 - the original one.
+    pub rate:f32,
+    /// Наибольшее случайное отклонение высоты звука в полутонах
+    /// при каждом запуске, 0 - без отклонения.
+    /// 
+    /// Убирает однообразие часто повторяющихся звуков (шагов, выстрелов).
+    /// 
+    /// The greatest random pitch deviation in semitones
+    /// on every start, 0 - no deviation.
+    /// 
+    /// Removes the monotony of frequently repeated sounds (footsteps, gunshots).
+    pub pitch_variation:f32,
 }
 
 impl TrackSet{
@@ -91,6 +164,98 @@ impl TrackSet{
             channels,
             repeats:1u32,
             volume:1f32,
+            quality:None,
+            effects:Vec::new(),
+            placement:TrackPlacement::Fixed,
+            fade_in:0f32,
+            loop_region:None,
+            bus:None,
+            rate:1f32,
+            pitch_variation:0f32,
+        }
+    }
+}
+
+/// Переводит изменение высоты звука в полутонах в скорость проигрывания.
+/// 
+/// Converts a pitch change in semitones to a playback rate.
+pub fn semitones_to_rate(semitones:f32)->f32{
+    (semitones/12f32).exp2()
+}
+
+/// Повторяемая часть трека в значениях трека `[start; end)`.
+/// 
+/// The repeated part of a track in track samples `[start; end)`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct LoopRegion{
+    pub start:usize,
+    pub end:usize,
+}
+
+impl LoopRegion{
+    pub fn new(start:usize,end:usize)->LoopRegion{
+        Self{
+            start,
+            end,
         }
     }
-}
\ No newline at end of file
+
+    /// Часть трека между данными моментами в секундах.
+    /// 
+    /// The part of a track between the given moments in seconds.
+    pub fn seconds(start:f32,end:f32,sample_rate:u32)->LoopRegion{
+        let frame=|seconds:f32|(seconds.max(0f32)*sample_rate as f32).round() as usize;
+
+        Self{
+            start:frame(start),
+            end:frame(end),
+        }
+    }
+}
+
+/// Положение в играющем треке.
+/// 
+/// При переводе частоты положение отстаёт
+/// на несколько значений (окно интерполяции).
+/// 
+/// A position in a playing track.
+/// 
+/// When converting the sample rate the position lags
+/// by a few samples (the interpolation window).
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct TrackPosition{
+    /// Номер текущего значения трека.
+    /// 
+    /// Для потоковых треков и источников звука - количество проигранных значений.
+    /// 
+    /// The index of the current sample of the track.
+    /// 
+    /// For streaming tracks and sound sources - the amount of played samples.
+    pub frame:usize,
+
+    /// Длина трека, `None` для потоковых треков и источников звука.
+    /// 
+    /// The track length, `None` for streaming tracks and sound sources.
+    pub length:Option<usize>,
+
+    pub sample_rate:u32,
+}
+
+impl TrackPosition{
+    /// Текущее положение в секундах.
+    /// 
+    /// The current position in seconds.
+    pub fn seconds(&self)->f32{
+        self.frame as f32/self.sample_rate as f32
+    }
+
+    /// Длительность трека в секундах.
+    /// 
+    /// The track duration in seconds.
+    pub fn duration(&self)->Option<f32>{
+        self.length.map(|length|length as f32/self.sample_rate as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,406 @@
+use super::{
+    TrackResult,
+    AudioFormat,
+    formats::{
+        ChunkDecoder,
+        StreamDecoder,
+    },
+};
+
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    fs::File,
+    io::{
+        self,
+        BufReader,
+        Read,
+        Seek,
+    },
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{
+            AtomicBool,
+            AtomicUsize,
+            Ordering,
+        },
+    },
+    thread::{
+        Builder,
+        sleep,
+    },
+    time::Duration,
+};
+
+/// Размер буфера канала в секундах.
+const stream_buffer_seconds:f32=0.5f32;
+
+/// Минимальный размер буфера канала в кадрах.
+const stream_buffer_min_frames:usize=4096;
+
+/// Пауза потока декодирования, когда буферы заполнены.
+const stream_worker_sleep:Duration=Duration::from_millis(10);
+
+/// Кольцевой буфер с одним писателем и одним читателем.
+struct RingBuffer{
+    buffer:Box<[UnsafeCell<f32>]>,
+    /// Позиция чтения (изменяется только читателем).
+    head:AtomicUsize,
+    /// Позиция записи (изменяется только писателем).
+    tail:AtomicUsize,
+}
+
+// Ячейки между `head` и `tail` принадлежат читателю, остальные - писателю
+unsafe impl Sync for RingBuffer{}
+
+impl RingBuffer{
+    fn new(capacity:usize)->RingBuffer{
+        // Одна ячейка всегда пустая, чтобы отличать полный буфер от пустого
+        let buffer=(0..capacity+1).map(|_|UnsafeCell::new(0f32)).collect();
+
+        Self{
+            buffer,
+            head:AtomicUsize::new(0),
+            tail:AtomicUsize::new(0),
+        }
+    }
+
+    /// Свободное место (только для писателя).
+    fn free(&self)->usize{
+        let len=self.buffer.len();
+        let head=self.head.load(Ordering::Acquire);
+        let tail=self.tail.load(Ordering::Relaxed);
+        (head+len-tail-1)%len
+    }
+
+    /// Записывает значение (только для писателя).
+    /// Возвращает `false`, если буфер заполнен.
+    fn push(&self,sample:f32)->bool{
+        let tail=self.tail.load(Ordering::Relaxed);
+        let next=(tail+1)%self.buffer.len();
+
+        if next==self.head.load(Ordering::Acquire){
+            return false
+        }
+
+        unsafe{
+            *self.buffer[tail].get()=sample;
+        }
+
+        self.tail.store(next,Ordering::Release);
+        true
+    }
+
+    /// Считывает значение (только для читателя).
+    fn pop(&self)->Option<f32>{
+        let head=self.head.load(Ordering::Relaxed);
+
+        if head==self.tail.load(Ordering::Acquire){
+            return None
+        }
+
+        let sample=unsafe{
+            *self.buffer[head].get()
+        };
+
+        self.head.store((head+1)%self.buffer.len(),Ordering::Release);
+        Some(sample)
+    }
+}
+
+/// Общие данные потока декодирования и его читателей.
+struct StreamShared{
+    /// Буферы каналов.
+    channels:Vec<RingBuffer>,
+    /// Читатели каналов, которые ещё не удалены.
+    /// Буферы удалённых каналов не заполняются.
+    alive:Vec<AtomicBool>,
+    sample_rate:u32,
+    /// Трек закончился, новых данных не будет.
+    finished:AtomicBool,
+}
+
+/// Значение потокового трека.
+pub (crate) enum StreamSample{
+    Sample(f32),
+    /// Декодер не успевает - буфер пуст.
+    Empty,
+    /// Трек закончился.
+    End,
+}
+
+/// Канал потокового трека, читаемый аудио потоком.
+///
+/// Поток декодирования останавливается,
+/// когда все каналы трека удалены.
+///
+/// A channel of a streaming track read by the audio thread.
+///
+/// The decoding thread stops
+/// when all the channels of the track are dropped.
+pub struct StreamReceiver{
+    shared:Arc<StreamShared>,
+    channel:usize,
+}
+
+impl Drop for StreamReceiver{
+    fn drop(&mut self){
+        self.shared.alive[self.channel].store(false,Ordering::Release);
+    }
+}
+
+impl StreamReceiver{
+    pub fn sample_rate(&self)->u32{
+        self.shared.sample_rate
+    }
+
+    /// Номер канала трека.
+    ///
+    /// The index of the track channel.
+    pub fn channel(&self)->usize{
+        self.channel
+    }
+
+    pub (crate) fn next_sample(&self)->StreamSample{
+        let buffer=&self.shared.channels[self.channel];
+
+        if let Some(sample)=buffer.pop(){
+            StreamSample::Sample(sample)
+        }
+        else if self.shared.finished.load(Ordering::Acquire){
+            // Последние значения могли быть записаны
+            // между проверкой буфера и флага
+            match buffer.pop(){
+                Some(sample)=>StreamSample::Sample(sample),
+                None=>StreamSample::End,
+            }
+        }
+        else{
+            StreamSample::Empty
+        }
+    }
+}
+
+/// Потоковый трек.
+///
+/// В отличие от `ChanneledTrack` не декодируется целиком при загрузке:
+/// при запуске создаётся поток, который декодирует трек по частям
+/// в кольцевые буферы каналов, читаемые аудио потоком.
+/// Подходит для длинных треков (музыки).
+///
+/// A streaming track.
+///
+/// Unlike `ChanneledTrack` it isn't decoded entirely while loading:
+/// starting creates a thread that decodes the track by chunks
+/// into ring buffers of the channels read by the audio thread.
+/// Suits long tracks (music).
+pub struct StreamingTrack{
+    decoder:Box<dyn ChunkDecoder+Send>,
+}
+
+impl StreamingTrack{
+    /// Открывает трек формата `mp3`, `wav`, `flac` или `ogg` (Vorbis).
+    ///
+    /// Opens an `mp3`, `wav`, `flac` or `ogg` (Vorbis) track.
+    pub fn new<P:AsRef<Path>>(path:P)->TrackResult<StreamingTrack>{
+        let file=match File::open(path){
+            Ok(file)=>BufReader::new(file),
+            Err(e)=>return TrackResult::FileError(e),
+        };
+
+        StreamingTrack::from_reader(file)
+    }
+
+    /// Открывает трек из потока, определяя формат по его началу.
+    ///
+    /// Opens a track from a stream detecting the format by its beginning.
+    pub fn from_reader<R:Read+Seek+Send+'static>(mut reader:R)->TrackResult<StreamingTrack>{
+        let format=match AudioFormat::detect_stream(&mut reader){
+            Ok(Some(format))=>format,
+            Ok(None)=>return TrackResult::UnsupportedFormat,
+            Err(e)=>return TrackResult::FileError(e),
+        };
+
+        let decoder=match StreamDecoder::new(reader,format){
+            TrackResult::Ok(decoder)=>decoder,
+            TrackResult::FileError(e)=>return TrackResult::FileError(e),
+            TrackResult::NoData=>return TrackResult::NoData,
+            TrackResult::UnsupportedFormat=>return TrackResult::UnsupportedFormat,
+        };
+
+        if decoder.channels()==0{
+            return TrackResult::NoData
+        }
+
+        TrackResult::Ok(Self{
+            decoder:Box::new(decoder),
+        })
+    }
+
+    pub fn channels(&self)->usize{
+        self.decoder.channels() as usize
+    }
+
+    pub fn sample_rate(&self)->u32{
+        self.decoder.sample_rate()
+    }
+
+    /// Запускает поток декодирования.
+    ///
+    /// Возвращает каналы трека для аудио потока.
+    ///
+    /// Starts the decoding thread.
+    ///
+    /// Returns the channels of the track for the audio thread.
+    ///
+    /// repeats - 0 - forever
+    pub fn start(self,repeats:u32)->io::Result<Vec<StreamReceiver>>{
+        let channels=self.channels();
+        let sample_rate=self.sample_rate();
+
+        let capacity=((sample_rate as f32*stream_buffer_seconds) as usize).max(stream_buffer_min_frames);
+
+        let shared=Arc::new(StreamShared{
+            channels:(0..channels).map(|_|RingBuffer::new(capacity)).collect(),
+            alive:(0..channels).map(|_|AtomicBool::new(true)).collect(),
+            sample_rate,
+            finished:AtomicBool::new(false),
+        });
+
+        let worker_shared=shared.clone();
+        let decoder=self.decoder;
+
+        Builder::new()
+            .name("CatEngine's audio stream thread".to_string())
+            .spawn(move||stream_worker(decoder,worker_shared,repeats))?;
+
+        Ok((0..channels).map(|channel|StreamReceiver{
+            shared:shared.clone(),
+            channel,
+        }).collect())
+    }
+}
+
+impl fmt::Debug for StreamingTrack{
+    fn fmt(&self,f:&mut fmt::Formatter)->fmt::Result{
+        f.debug_struct("StreamingTrack")
+            .field("channels",&self.channels())
+            .field("sample_rate",&self.sample_rate())
+            .finish()
+    }
+}
+
+/// Декодирует трек в буферы каналов,
+/// пока трек не закончится или каналы не будут удалены.
+fn stream_worker(mut decoder:Box<dyn ChunkDecoder+Send>,shared:Arc<StreamShared>,mut repeats:u32){
+    let channels=shared.channels.len();
+
+    // Декодированная часть трека и позиция в ней
+    let mut chunk=Vec::new();
+    let mut position=0usize;
+    // Были ли данные с начала трека
+    let mut decoded=false;
+
+    loop{
+        // Все каналы удалены
+        if !shared.alive.iter().any(|alive|alive.load(Ordering::Acquire)){
+            return
+        }
+
+        if position==chunk.len(){
+            chunk.clear();
+            position=0;
+
+            decoder.next_chunk(&mut chunk);
+
+            if chunk.is_empty(){
+                // Конец трека
+                let again=match repeats{
+                    0=>true,
+                    1=>false,
+                    _=>{
+                        repeats-=1;
+                        true
+                    }
+                };
+
+                // Пустой трек повторять бесполезно
+                if !again || !decoded || !decoder.rewind(){
+                    shared.finished.store(true,Ordering::Release);
+                    return
+                }
+
+                decoded=false;
+                continue
+            }
+
+            decoded=true;
+            // Неполный кадр отбрасывается
+            chunk.truncate(chunk.len()-chunk.len()%channels);
+        }
+
+        // Заполненные буферы удалённых каналов не задерживают остальные
+        let free=shared.channels.iter()
+            .zip(&shared.alive)
+            .filter(|(_,alive)|alive.load(Ordering::Acquire))
+            .map(|(buffer,_)|buffer.free())
+            .min()
+            .unwrap_or(0);
+        let frames=((chunk.len()-position)/channels).min(free);
+
+        if frames==0{
+            sleep(stream_worker_sleep);
+            continue
+        }
+
+        for frame in chunk[position..position+frames*channels].chunks_exact(channels){
+            for ((buffer,alive),&sample) in shared.channels.iter().zip(&shared.alive).zip(frame){
+                if alive.load(Ordering::Relaxed){
+                    buffer.push(sample);
+                }
+            }
+        }
+
+        position+=frames*channels;
+    }
+}
+
+/// Параметры проигрывания потокового трека.
+///
+/// Parameters of playing a streaming track.
+pub struct StreamSet{
+    pub track:StreamingTrack,
+    /// Каналы для распределения каждого канала трека.
+    ///
+    /// Output channels for each channel of the track.
+    pub channels:Vec<Vec<usize>>,
+    /// Количество повторенй.
+    ///
+    /// 0 - постоянно
+    ///
+    /// The amount of repeats.
+    ///
+    /// 0 - forever
+    pub repeats:u32,
+    /// Громкость трека.
+    ///
+    /// A track volume.
+    pub volume:f32,
+}
+
+impl StreamSet{
+    /// Каждый канал трека выводится в канал с тем же номером.
+    ///
+    /// Each channel of the track is output to the channel with the same index.
+    pub fn once(track:StreamingTrack)->StreamSet{
+        let channels=(0..track.channels()).map(|c|vec![c]).collect();
+
+        Self{
+            track,
+            channels,
+            repeats:1u32,
+            volume:1f32,
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use super::{
+    AudioFormat,
+    ChanneledTrack,
+    StreamingTrack,
+    StreamSample,
+    TrackResult,
+};
+
+use std::{
+    io::Cursor,
+    thread::sleep,
+    time::Duration,
+};
+
+fn wav_file(channels:u16,samples:&[i16])->Cursor<Vec<u8>>{
+    let spec=hound::WavSpec{
+        channels,
+        sample_rate:22050,
+        bits_per_sample:16,
+        sample_format:hound::SampleFormat::Int,
+    };
+
+    let mut file=Cursor::new(Vec::new());
+    let mut writer=hound::WavWriter::new(&mut file,spec).unwrap();
+    for &sample in samples{
+        writer.write_sample(sample).unwrap();
+    }
+    writer.finalize().unwrap();
+    file.set_position(0);
+    file
+}
+
+#[test]
+fn format_detection(){
+    assert_eq!(AudioFormat::detect(b"RIFF\x24\0\0\0WAVEfmt "),Some(AudioFormat::Wav));
+    assert_eq!(AudioFormat::detect(b"fLaC\0\0\0\x22"),Some(AudioFormat::Flac));
+    assert_eq!(AudioFormat::detect(b"OggS\0\x02"),Some(AudioFormat::Vorbis));
+    assert_eq!(AudioFormat::detect(b"ID3\x03\0"),Some(AudioFormat::Mp3));
+    assert_eq!(AudioFormat::detect(&[0xFF,0xFB,0x90,0x64]),Some(AudioFormat::Mp3));
+    assert_eq!(AudioFormat::detect(b"RIFF"),None);
+    assert_eq!(AudioFormat::from_extension("sounds/Step.WAV"),Some(AudioFormat::Wav));
+
+    assert!(matches!(
+        ChanneledTrack::from_reader(Cursor::new(b"not a sound".to_vec())),
+        TrackResult::UnsupportedFormat
+    ));
+}
+
+#[test]
+fn wav_decoding(){
+    let spec=hound::WavSpec{
+        channels:2,
+        sample_rate:22050,
+        bits_per_sample:24,
+        sample_format:hound::SampleFormat::Int,
+    };
+
+    let mut file=Cursor::new(Vec::new());
+    let mut writer=hound::WavWriter::new(&mut file,spec).unwrap();
+    for &sample in &[0i32,-8388608,4194304,8388607]{
+        writer.write_sample(sample).unwrap();
+    }
+    writer.finalize().unwrap();
+    file.set_position(0);
+
+    let track=ChanneledTrack::from_reader(file).unwrap();
+    assert_eq!(track.channels(),2);
+    assert_eq!(track.sample_rate(),22050);
+    assert_eq!(track.get_channel(0).unwrap(),&vec![0f32,0.5]);
+    assert_eq!(track.get_channel(1).unwrap()[0],-1f32);
+    assert!((track.get_channel(1).unwrap()[1]-1f32).abs()<1e-6);
+}
+
+#[test]
+fn wav_streaming(){
+    // 3000 стерео кадров - больше одной части декодера
+    let samples:Vec<i16>=(0..6000).map(|s|(s%2000-1000) as i16).collect();
+
+    let track=StreamingTrack::from_reader(wav_file(2,&samples)).unwrap();
+    assert_eq!(track.channels(),2);
+    assert_eq!(track.sample_rate(),22050);
+
+    let receivers=track.start(2).unwrap();
+
+    let mut channels=[Vec::new(),Vec::new()];
+    let mut finished=[false,false];
+    let mut underruns=0;
+    while finished!=[true,true]{
+        for (c,receiver) in receivers.iter().enumerate(){
+            if finished[c]{
+                continue
+            }
+            match receiver.next_sample(){
+                StreamSample::Sample(sample)=>channels[c].push(sample),
+                StreamSample::Empty=>{
+                    underruns+=1;
+                    assert!(underruns<1000,"the stream is stuck");
+                    sleep(Duration::from_millis(1))
+                }
+                StreamSample::End=>finished[c]=true,
+            }
+        }
+    }
+
+    // Трек повторяется дважды
+    let expected:Vec<f32>=samples.iter().step_by(2).map(|&s|s as f32/32768f32).collect();
+    assert_eq!(channels[0].len(),expected.len()*2);
+    assert_eq!(&channels[0][..3000],expected.as_slice());
+    assert_eq!(&channels[0][3000..],expected.as_slice());
+    assert_eq!(channels[1][1],samples[3] as f32/32768f32);
+}
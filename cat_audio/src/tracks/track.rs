@@ -3,57 +3,53 @@ use crate::sample::SampleTransform;
 use super::{
     TrackResult,
     MonoTrack,
+    AudioFormat,
+    formats::decode,
 };
 
-use minimp3::Decoder;
-
 use cpal::Sample;
 
 use std::{
     fs::File,
+    io::{
+        BufReader,
+        Read,
+        Seek,
+    },
     path::Path,
 };
 
 /// Аудио трек.
 /// An audio track.
 #[derive(Clone,Debug)]
-struct Track<T:Clone+SampleTransform>{
+pub (crate) struct Track<T:Clone+SampleTransform>{
     data:Vec<T>,
     channels:u16,
     sample_rate:u32,
 }
 
-impl Track<i16>{
-    pub fn new<P:AsRef<Path>>(path:P)->TrackResult<Track<i16>>{
-        let mut data=Vec::new();
-
+impl Track<f32>{
+    /// Загружает трек, определяя формат по сигнатуре файла.
+    /// 
+    /// Loads a track detecting the format by the file signature.
+    pub fn new<P:AsRef<Path>>(path:P)->TrackResult<Track<f32>>{
         let file=match File::open(path){
-            Ok(file)=>file,
+            Ok(file)=>BufReader::new(file),
             Err(e)=>return TrackResult::FileError(e),
         };
 
-        let mut decoder=Decoder::new(file);
-
-        let (channels,sample_rate)=match decoder.next_frame(){
-            Ok(mut frame)=>{
-                data.append(&mut frame.data);
-                (
-                    frame.channels,
-                    frame.sample_rate as u32,
-                )
-            }
-            Err(_)=>return TrackResult::NoData
-        };
+        Track::from_reader(file)
+    }
 
-        while let Ok(mut frame)=decoder.next_frame(){
-            data.append(&mut frame.data);
+    /// Декодирует трек из потока, определяя формат по его началу.
+    /// 
+    /// Decodes a track from a stream detecting the format by its beginning.
+    pub fn from_reader<R:Read+Seek>(mut reader:R)->TrackResult<Track<f32>>{
+        match AudioFormat::detect_stream(&mut reader){
+            Ok(Some(format))=>decode(reader,format),
+            Ok(None)=>TrackResult::UnsupportedFormat,
+            Err(e)=>TrackResult::FileError(e),
         }
-
-        TrackResult::Ok(Self{
-            data,
-            channels:channels as u16,
-            sample_rate,
-        })
     }
 }
 
@@ -158,13 +154,31 @@ pub struct ChanneledTrack{
 }
 
 impl ChanneledTrack{
+    /// Загружает трек формата `mp3`, `wav`, `flac` или `ogg` (Vorbis).
+    /// 
+    /// Loads an `mp3`, `wav`, `flac` or `ogg` (Vorbis) track.
     pub fn new<P:AsRef<Path>>(path:P)->TrackResult<ChanneledTrack>{
-        let track=match Track::new(path){
-            TrackResult::Ok(track)=>track,
-            TrackResult::NoData=>return TrackResult::NoData,
-            TrackResult::FileError(err)=>return TrackResult::FileError(err),
-        };
-        
+        match Track::new(path){
+            TrackResult::Ok(track)=>TrackResult::Ok(ChanneledTrack::from_track(track)),
+            TrackResult::NoData=>TrackResult::NoData,
+            TrackResult::UnsupportedFormat=>TrackResult::UnsupportedFormat,
+            TrackResult::FileError(err)=>TrackResult::FileError(err),
+        }
+    }
+
+    /// Декодирует трек из потока (например, встроенного в программу файла).
+    /// 
+    /// Decodes a track from a stream (e.g. an embedded file).
+    pub fn from_reader<R:Read+Seek>(reader:R)->TrackResult<ChanneledTrack>{
+        match Track::from_reader(reader){
+            TrackResult::Ok(track)=>TrackResult::Ok(ChanneledTrack::from_track(track)),
+            TrackResult::NoData=>TrackResult::NoData,
+            TrackResult::UnsupportedFormat=>TrackResult::UnsupportedFormat,
+            TrackResult::FileError(err)=>TrackResult::FileError(err),
+        }
+    }
+
+    fn from_track(track:Track<f32>)->ChanneledTrack{
         let monos=track.to_mono_tracks();
 
         let sample_rate=track.sample_rate();
@@ -233,11 +247,11 @@ impl ChanneledTrack{
         // Считывание оставшихся данных
         //load_mp3_separate(decoder,&mut channels);
 
-        TrackResult::Ok(Self{
+        Self{
             channels,
             output_channels,
             sample_rate
-        })
+        }
     }
 
     pub fn channels(&self)->usize{
@@ -3,6 +3,7 @@ use super::{
     ChanneledTrack,
     MonoTrack,
     TrackSet,
+    TrackPlacement,
     TrackResult,
     AudioCommandResult,
 };
@@ -202,6 +203,15 @@ impl AudioWrapper{
     /// Repeats:
     /// 0 - forever, 1 - once, 2 - twice and so on...
     pub fn play_track(&self,name:&str,repeats:u32)->AudioCommandResult{
+        self.play_track_fade_in(name,repeats,0f32)
+    }
+
+    /// Запускает трек, плавно проявляя его
+    /// за данное время в секундах.
+    /// 
+    /// Plays a track fading it in
+    /// during the given time in seconds.
+    pub fn play_track_fade_in(&self,name:&str,repeats:u32,seconds:f32)->AudioCommandResult{
         if let Some(track_sets_index)=self.search_track(name){
             let track_sets=&self.track_sets[track_sets_index];
             let mut full_track_sets=Vec::with_capacity(track_sets.len());
@@ -212,6 +222,14 @@ impl AudioWrapper{
                     channels:set.channels.clone(),
                     repeats,
                     volume:1f32,
+                    quality:None,
+                    effects:Vec::new(),
+                    placement:TrackPlacement::Fixed,
+                    fade_in:seconds,
+                    loop_region:None,
+                    bus:None,
+                    rate:1f32,
+                    pitch_variation:0f32,
                 };
                 full_track_sets.push(track_set)
             }
@@ -240,6 +258,39 @@ impl AudioWrapper{
         }
     }
 
+    /// Плавно убирает трек за данное время в секундах,
+    /// после чего останавливает его.
+    /// 
+    /// Fades out a track during the given time in seconds
+    /// and then stops it.
+    pub fn fade_out_track(&self,name:&str,seconds:f32)->AudioCommandResult{
+        if let Some(track_sets_index)=self.search_track(name){
+            // Получение индексов треков
+            let track_indices:Vec<usize>=self.track_sets[track_sets_index].iter().map(|set|set.index).collect();
+
+            self.audio.fade_out_tracks_storage(track_indices,seconds)
+        }
+        else{
+            AudioCommandResult::NoSuchTrack
+        }
+    }
+
+    /// Плавно убирает трек `from` и запускает трек `to`,
+    /// плавно проявляя его, за данное время в секундах.
+    /// 
+    /// Fades out the track `from` and plays the track `to`
+    /// fading it in during the given time in seconds.
+    pub fn crossfade_tracks(&self,from:&str,to:&str,repeats:u32,seconds:f32)->AudioCommandResult{
+        if self.search_track(to).is_none(){
+            return AudioCommandResult::NoSuchTrack
+        }
+
+        match self.fade_out_track(from,seconds){
+            AudioCommandResult::Sent=>self.play_track_fade_in(to,repeats,seconds),
+            result=>result,
+        }
+    }
+
     /// Запускает поток проигрывания.
     /// 
     /// Starts playing the output stream.
@@ -315,6 +366,23 @@ impl AudioWrapper{
         }
     }
 
+    /// Плавно изменяет уровень громкости трека
+    /// за данное время в секундах.
+    /// 
+    /// Smoothly changes track's volume level
+    /// during the given time in seconds.
+    pub fn fade_track(&self,name:&str,level:f32,seconds:f32)->AudioCommandResult{
+        if let Some(track_sets_index)=self.search_track(name){
+            // Получение индексов треков
+            let tracks:Vec<usize>=self.track_sets[track_sets_index].iter().map(|set|set.index).collect();
+
+            self.audio.fade_tracks_storage(tracks,level,seconds)
+        }
+        else{
+            AudioCommandResult::NoSuchTrack
+        }
+    }
+
     /// Устанавливает общую громкость.
     /// 
     /// Sets the general volume.
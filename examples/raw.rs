@@ -6,30 +6,51 @@ use cat_engine::{
     graphics::{
         Graphics,
         Graphics2DAttributes,
-    },
-    basement::{
-        windows::{
-            EventLoop,
-            LoopControl,
-            EventLoopAttributes,
-            Window,
-            WindowAttributes,
-            WindowClass,
-            WindowClassAttributes,
-            WindowProcedure,
-            OpenGraphicsLibrary,
-            OpenGLRenderContext,
-            OpenGLRenderContextAttributes,
-            Event,
-            WindowEvent,
-            ProcessEvent,
-            WinError,
-            WindowResizeType,
-            quit,
-        },
     }
 };
 
+#[cfg(target_os="windows")]
+use cat_engine::basement::windows::{
+    EventLoop,
+    LoopControl,
+    EventLoopAttributes,
+    Window,
+    WindowAttributes,
+    WindowClass,
+    WindowClassAttributes,
+    WindowProcedure,
+    OpenGraphicsLibrary,
+    OpenGLRenderContext,
+    OpenGLRenderContextAttributes,
+    Event,
+    WindowEvent,
+    ProcessEvent,
+    WinError,
+    WindowResizeType,
+    quit,
+};
+
+#[cfg(target_os="linux")]
+use cat_engine::basement::linux::{
+    EventLoop,
+    LoopControl,
+    EventLoopAttributes,
+    Window,
+    WindowAttributes,
+    WindowClass,
+    WindowClassAttributes,
+    WindowProcedure,
+    OpenGraphicsLibrary,
+    OpenGLRenderContext,
+    OpenGLRenderContextAttributes,
+    Event,
+    WindowEvent,
+    ProcessEvent,
+    X11Error as WinError,
+    WindowResizeType,
+    quit,
+};
+
 struct WindowGraphics{
     context:OpenGLRenderContext,
     graphics:Graphics,
@@ -45,7 +66,10 @@ impl WindowProcedure for Handler{
     fn create(window:&Window,create_parameters:&mut Self::CreateParameters)->Result<Self::Data,WinError>{
         let render_context=OpenGLRenderContext::new(window,create_parameters.0.clone()).unwrap();
 
+        #[cfg(target_os="windows")]
         let library=OpenGraphicsLibrary::new();
+        #[cfg(target_os="linux")]
+        let library=OpenGraphicsLibrary::new().unwrap();
         library.load_functions(); // only after render context creation
 
         let mut graphics=Graphics::new(create_parameters.1.clone());
@@ -26,5 +26,114 @@ pub use mono_windowing::windows::{
     EventInterval,
     WinError,
     WindowResizeType,
+    EventRecorder,
+    EventPlayer,
+    RecordedEvent,
+    RecordingError,
     quit,
-};
\ No newline at end of file
+};
+
+#[cfg(target_os="linux")]
+pub use mono_windowing::linux::{
+    App,
+    AppAttributes,
+    AppWindowProcedure,
+    Window,
+    Fullscreen,
+    Monitor,
+    WindowAttributes,
+    WindowClassAttributes,
+    Event,
+    EventLoop,
+    LoopControl,
+    MouseButton,
+    ProcessEvent,
+    WindowEvent,
+    VirtualKeyCode,
+    EventLoopAttributes,
+    OpenGLRenderContext,
+    OpenGLRenderContextAttributes,
+    EventInterval,
+    X11Error,
+    // The same name as on Windows
+    X11Error as WinError,
+    WindowResizeType,
+    EventRecorder,
+    EventPlayer,
+    RecordedEvent,
+    RecordingError,
+    quit,
+};
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    use crate::graphics::Graphics;
+
+    /// Stores the handled window events.
+    struct RecordingProcedure;
+
+    impl AppWindowProcedure<Vec<String>,()> for RecordingProcedure{
+        fn create(_window:&Window,_data:(&mut OpenGLRenderContext,&mut Graphics,()))->Vec<String>{
+            Vec::new()
+        }
+
+        fn close_request(_window:&Window,_data:(&mut OpenGLRenderContext,&mut Graphics,&mut Vec<String>)){}
+
+        fn destroy(_window:&Window,_data:(&mut OpenGLRenderContext,&mut Graphics,&mut Vec<String>)){}
+
+        fn paint(_window:&Window,_data:(&mut OpenGLRenderContext,&mut Graphics,&mut Vec<String>)){}
+
+        #[cfg(feature="set_cursor_event")]
+        fn set_cursor(_window:&Window,_data:(&mut OpenGLRenderContext,&mut Graphics,&mut Vec<String>)){}
+
+        fn resized(
+            _client_size:[u16;2],
+            _resize_type:WindowResizeType,
+            _window:&Window,
+            _data:(&mut OpenGLRenderContext,&mut Graphics,&mut Vec<String>)
+        ){}
+
+        fn moved(_client_position:[i16;2],_window:&Window,_data:(&mut OpenGLRenderContext,&mut Graphics,&mut Vec<String>)){}
+
+        fn handle(event:WindowEvent,_window:&Window,(_,_,events):(&mut OpenGLRenderContext,&mut Graphics,&mut Vec<String>)){
+            events.push(format!("{:?}",event))
+        }
+
+        #[cfg(feature="wnd_proc_catch_panic")]
+        fn catch_panic(
+            _window:&Window,
+            _data:(*mut OpenGLRenderContext,*mut Graphics,*mut Vec<String>),
+            _error:Box<dyn std::any::Any+Send>
+        ){}
+    }
+
+    #[test]
+    #[cfg_attr(target_os="linux",ignore="needs an X server, run with `xvfb-run cargo test -- --ignored`")]
+    fn recording_playback(){
+        let mut recorder=EventRecorder::new(Vec::new()).unwrap();
+        recorder.write(10,&RecordedEvent::Window(WindowEvent::MouseMove([120,45]))).unwrap();
+        recorder.write(20,&RecordedEvent::Window(WindowEvent::KeyPress(VirtualKeyCode::A))).unwrap();
+        recorder.write(20,&RecordedEvent::Window(WindowEvent::CharacterInput('a'))).unwrap();
+        recorder.write(30,&RecordedEvent::Quit).unwrap();
+        recorder.write(40,&RecordedEvent::Window(WindowEvent::MouseScroll(1))).unwrap();
+
+        let recording=recorder.into_inner();
+        let mut player=EventPlayer::read(recording.as_slice()).unwrap();
+
+        let app=App::new::<RecordingProcedure,()>(AppAttributes::new(),()).unwrap();
+
+        // Only the events due by the given time are passed
+        assert!(!app.play::<RecordingProcedure,()>(&mut player,15));
+        assert_eq!(app.storage().as_slice(),["MouseMove([120, 45])"]);
+
+        // The replay stops at the quit request
+        assert!(app.play::<RecordingProcedure,()>(&mut player,100));
+        assert_eq!(
+            app.storage().as_slice(),
+            ["MouseMove([120, 45])","KeyPress(A)","CharacterInput('a')"]
+        );
+        assert_eq!(player.len(),1);
+    }
+}
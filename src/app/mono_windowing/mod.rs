@@ -1,2 +1,5 @@
 #[cfg(any(target_os="windows"))]
-pub mod windows;
\ No newline at end of file
+pub mod windows;
+
+#[cfg(target_os="linux")]
+pub mod linux;
@@ -34,6 +34,12 @@ pub use cat_engine_basement::{
         WindowResizeType,
         quit,
     },
+    event_loop::{
+        EventRecorder,
+        EventPlayer,
+        RecordedEvent,
+        RecordingError,
+    },
 };
 
 use std::{
@@ -249,6 +255,26 @@ impl<S:Sized+'static> App<S>{
             self.window.set_window_handle::<AppWindowHandler<P,S,C>>()
         }
     }
+
+    /// Passes the recorded window events due by `ticks` to `P::handle`
+    /// as if they came from the window.
+    ///
+    /// `ticks` is the time since the start of the recording,
+    /// e.g. the sum of the `ProcessEvent::Update` ticks.
+    /// Returns `true` when the recording reaches a quit request,
+    /// the events after it are left in the player.
+    pub fn play<P:AppWindowProcedure<S,C>,C>(&self,player:&mut EventPlayer,ticks:u64)->bool{
+        while let Some(event)=player.next_event(ticks){
+            match event{
+                RecordedEvent::Window(event)=>{
+                    let data=unsafe{(&mut*self.data.0,&mut*self.data.1,&mut*self.data.2)};
+                    P::handle(event,&self.window,data)
+                }
+                RecordedEvent::Quit=>return true,
+            }
+        }
+        false
+    }
 }
 
 impl<S:Sized+'static> App<S>{
@@ -40,16 +40,38 @@ impl GlyphImageBuilder{
 
     /// [offset_x,offset_y,width,height]
     pub fn build_image(&mut self,glyph_id:GlyphId,font:&Face)->Option<[f32;4]>{
+        self.build_padded_image(glyph_id,font,0)
+    }
+
+    /// Builds a signed distance field of a glyph.
+    /// 
+    /// `spread` - the max distance in pixels, also the empty space around the glyph.
+    /// A pixel value of 128 is the glyph edge, greater values are inside.
+    /// 
+    /// [offset_x,offset_y,width,height] including the empty space
+    pub fn build_distance_field(&mut self,glyph_id:GlyphId,font:&Face,spread:u32)->Option<[f32;4]>{
+        let bounding_box=self.build_padded_image(glyph_id,font,spread)?;
+
+        let [width,height]=self.dimensions();
+        self.image_buffer=distance_field(&self.image_buffer,[width,height],spread as f32);
+
+        Some(bounding_box)
+    }
+
+    // Изображение с пустым местом `padding` пикселей вокруг глифа
+    fn build_padded_image(&mut self,glyph_id:GlyphId,font:&Face,padding:u32)->Option<[f32;4]>{
+        let padding=padding as f32;
+
         // Получение размера и положения глифа
         if let Some(bounding_box)=font.glyph_bounding_box(glyph_id){
-            let width=(bounding_box.width() as f32*self.scale.horizontal).ceil();
-            let height=(bounding_box.height() as f32*self.scale.vertical).ceil();
+            let width=(bounding_box.width() as f32*self.scale.horizontal).ceil()+2f32*padding;
+            let height=(bounding_box.height() as f32*self.scale.vertical).ceil()+2f32*padding;
 
             // Не масштабируется, потому что вычитается из
             // начальных данных
             self.offset=[
-                bounding_box.x_min as f32,
-                bounding_box.y_min as f32,
+                bounding_box.x_min as f32-padding/self.scale.horizontal,
+                bounding_box.y_min as f32-padding/self.scale.vertical,
             ];
             // Установка размера под новый глиф
             self.rasterizer.reset(width as usize,height as usize);
@@ -92,6 +114,94 @@ impl GlyphImageBuilder{
     }
 }
 
+// Большое конечное число вместо бесконечности, чтобы не получить NaN
+const far:f32=1e20;
+
+/// Знаковое поле расстояний по изображению покрытия:
+/// 128 - граница глифа, больше - внутри.
+fn distance_field(coverage:&[u8],[width,height]:[usize;2],spread:f32)->Vec<u8>{
+    let to_inside=squared_distances(coverage,[width,height],true);
+    let to_outside=squared_distances(coverage,[width,height],false);
+
+    coverage.iter().enumerate().map(|(index,&value)|{
+        // Расстояния между центрами пикселей, граница - посередине
+        let distance=if value>=128{
+            to_outside[index].sqrt()-0.5f32
+        }
+        else{
+            0.5f32-to_inside[index].sqrt()
+        };
+
+        ((0.5f32+distance/(2f32*spread)).clamp(0f32,1f32)*255f32).round() as u8
+    }).collect()
+}
+
+/// Квадраты расстояний до ближайших пикселей внутри (`inside`) или снаружи глифа.
+/// 
+/// Точное евклидово преобразование (Felzenszwalb, Huttenlocher) - по столбцам, затем по строкам.
+fn squared_distances(coverage:&[u8],[width,height]:[usize;2],inside:bool)->Vec<f32>{
+    let mut grid:Vec<f32>=coverage.iter().map(|&value|{
+        if (value>=128)==inside{0f32}else{far}
+    }).collect();
+
+    let length=width.max(height);
+    let mut column=vec![0f32;length];
+    let mut distances=vec![0f32;length];
+    let mut parabolas=vec![0usize;length];
+    let mut bounds=vec![0f32;length+1];
+
+    for x in 0..width{
+        for y in 0..height{
+            column[y]=grid[y*width+x]
+        }
+        distance_transform(&column[..height],&mut distances[..height],&mut parabolas,&mut bounds);
+        for y in 0..height{
+            grid[y*width+x]=distances[y]
+        }
+    }
+
+    for row in grid.chunks_mut(width){
+        distance_transform(row,&mut distances[..width],&mut parabolas,&mut bounds);
+        row.copy_from_slice(&distances[..width]);
+    }
+
+    grid
+}
+
+/// Одномерное преобразование - нижняя огибающая парабол.
+fn distance_transform(f:&[f32],distances:&mut [f32],parabolas:&mut [usize],bounds:&mut [f32]){
+    let intersection=|q:usize,p:usize|{
+        let [q_f,p_f]=[q as f32,p as f32];
+        ((f[q]+q_f*q_f)-(f[p]+p_f*p_f))/(2f32*q_f-2f32*p_f)
+    };
+
+    let mut k=0usize;
+    parabolas[0]=0;
+    bounds[0]=f32::NEG_INFINITY;
+    bounds[1]=f32::INFINITY;
+
+    for q in 1..f.len(){
+        let mut s=intersection(q,parabolas[k]);
+        while s<=bounds[k]{
+            k-=1;
+            s=intersection(q,parabolas[k]);
+        }
+        k+=1;
+        parabolas[k]=q;
+        bounds[k]=s;
+        bounds[k+1]=f32::INFINITY;
+    }
+
+    k=0;
+    for (q,distance) in distances.iter_mut().enumerate(){
+        while bounds[k+1]<q as f32{
+            k+=1
+        }
+        let d=q as f32-parabolas[k] as f32;
+        *distance=d*d+f[parabolas[k]];
+    }
+}
+
 impl OutlineBuilder for GlyphImageBuilder{
     fn move_to(&mut self,x:f32,y:f32){
         self.last=point(
@@ -151,4 +261,33 @@ impl OutlineBuilder for GlyphImageBuilder{
             self.rasterizer.draw_line(self.last,m);
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn signed_distance_field(){
+        // Квадрат 3x3 посреди изображения 9x9
+        let mut coverage=vec![0u8;81];
+        for y in 3..6{
+            for x in 3..6{
+                coverage[y*9+x]=255
+            }
+        }
+
+        let field=distance_field(&coverage,[9,9],4f32);
+
+        // Граница между пикселями 3 и 2 строки 4
+        assert_eq!(field[4*9+3],143);
+        assert_eq!(field[4*9+2],112);
+        assert_eq!(field[4*9+4],175);
+        assert_eq!(field[4*9],48);
+        // По диагонали расстояние больше
+        assert!(field[2*9+2]<field[4*9+2]);
+        // Симметрия
+        assert_eq!(field[4*9+5],field[4*9+3]);
+        assert_eq!(field[2*9+4],field[4*9+2]);
+    }
+}
@@ -6,6 +6,9 @@ use crate::{
 use crate::text::{
     Scale,
     CachedFont,
+    FontCollection,
+    TextLayout,
+    TexturedGlyph,
 };
 
 use super::{
@@ -27,7 +30,11 @@ use super::SimpleGraphics;
 use super::TextureGraphics;
 
 #[cfg(feature="text_graphics")]
-use super::TextGraphics;
+use super::{
+    TextGraphics,
+    TextVertex2D,
+    TextEffects,
+};
 
 use cat_engine_basement::graphics::level1::Texture2D;
 
@@ -111,6 +118,8 @@ pub struct Graphics2D{
     texture:TextureGraphics,
     #[cfg(feature="text_graphics")]
     text:TextGraphics,
+    #[cfg(feature="text_graphics")]
+    text_effects:TextEffects,
     draw_parameters:DrawParameters,
 }
 
@@ -148,6 +157,8 @@ impl Graphics2D{
             texture,
             #[cfg(feature="text_graphics")]
             text,
+            #[cfg(feature="text_graphics")]
+            text_effects:TextEffects::new(),
             draw_parameters,
         }
     }
@@ -164,6 +175,17 @@ impl Graphics2D{
         self.text.build_glyph_image(glyph_id,scale,font)
     }
 
+    pub fn build_glyph_distance_field(&self,glyph_id:GlyphId,scale:Scale,font:&Face,spread:u32)->Option<([f32;4],&[u8])>{
+        self.text.build_glyph_distance_field(glyph_id,scale,font,spread)
+    }
+
+    /// Effects for distance field glyphs.
+    /// 
+    /// Эффекты для глифов-полей расстояний.
+    pub fn text_effects(&mut self)->&mut TextEffects{
+        &mut self.text_effects
+    }
+
     pub fn draw_glyph(&self,glyph_texture:&Texture2D,colour:Colour,position:[f32;2],size:[f32;2]){
         self.text.draw_glyph(glyph_texture,colour,position,size,&self.draw_parameters);
     }
@@ -184,24 +206,27 @@ impl Graphics2D{
             GlyphId(0u16)
         };
 
-        if let Some(glyph)=font.cached_glyph(glyph_id){
+        let cached_glyph=font.cached_glyph(glyph_id).and_then(|glyph|{
+            font.glyph_cache().page_texture(glyph.page()).map(|texture|(glyph,texture))
+        });
+
+        if let Some((glyph,texture))=cached_glyph{
             let glyph_scale=scale/font.glyph_cache().scale();
 
-            let texture=glyph.texture();
             let advance_width=glyph.advance_width(glyph_scale.horizontal);
 
             if let Some(horisontal_advance)=horisontal_advance{
                 *horisontal_advance=advance_width
             }
 
-            let [offset_x,offset_y,width,height]=glyph.bounding_box(glyph_scale);
-
-            let position=[
-                position[0]+offset_x,
-                position[1]-offset_y-height,
-            ];
-
-            self.text.draw_glyph(texture,colour,position,[width,height],&self.draw_parameters);
+            self.text.draw_glyph_batch(
+                texture,
+                &glyph_vertices(glyph,position,glyph_scale),
+                colour,
+                font.glyph_cache().mode(),
+                &self.text_effects,
+                &self.draw_parameters
+            );
         }
         else{
             if let Some([offset_x,offset_y,_,height])=self.text.load_glyph(glyph_id,scale,font.font().face()){
@@ -222,6 +247,125 @@ impl Graphics2D{
             }
         }
     }
+
+    /// Draws laid out text, `position` is the top left corner of the text.
+    /// 
+    /// Missing glyphs are cached first, then the glyphs of every atlas page are drawn with a single draw call.
+    /// Glyphs that can't be cached (larger than an atlas page) are drawn one by one.
+    /// 
+    /// Рисует размеченный текст, `position` - левый верхний угол текста.
+    /// 
+    /// Сначала кэшируются недостающие глифы, затем глифы каждой страницы атласа рисуются за один вызов.
+    /// Глифы, которые не удалось кэшировать (больше страницы атласа), рисуются по одному.
+    pub fn draw_text(
+        &self,
+        layout:&TextLayout,
+        colour:Colour,
+        position:[f32;2],
+        font:&mut CachedFont
+    ){
+        self.draw_layout(layout,colour,position,std::slice::from_mut(font))
+    }
+
+    /// Draws text laid out with `FontCollection::layout`, see `draw_text`.
+    /// 
+    /// Рисует текст, размеченный с помощью `FontCollection::layout`, см. `draw_text`.
+    pub fn draw_text_collection(
+        &self,
+        layout:&TextLayout,
+        colour:Colour,
+        position:[f32;2],
+        fonts:&mut FontCollection
+    ){
+        self.draw_layout(layout,colour,position,fonts.fonts_mut())
+    }
+
+    fn draw_layout(
+        &self,
+        layout:&TextLayout,
+        colour:Colour,
+        position:[f32;2],
+        fonts:&mut [CachedFont]
+    ){
+        for glyph in layout.glyphs(){
+            if let Some(font)=fonts.get_mut(glyph.font){
+                if font.cached_glyph(glyph.id).is_none(){
+                    font.insert_glyph(glyph.id,self)
+                }
+            }
+        }
+
+        // Глифы шрифтов замены приводятся к размеру em основного шрифта
+        let units_per_em=|font:&CachedFont|font.font().face().units_per_em().unwrap_or(1000) as f32;
+        let primary=fonts.first().map(units_per_em).unwrap_or(1000f32);
+        let font_scales:Vec<Scale>=fonts.iter().map(|font|{
+            let units=primary/units_per_em(font);
+            Scale::new(layout.scale().horizontal*units,layout.scale().vertical*units)
+        }).collect();
+
+        // Вершины по шрифтам и страницам атласа
+        let mut batches:Vec<Vec<Vec<TextVertex2D>>>=fonts.iter().map(|font|{
+            let mut pages=Vec::new();
+            pages.resize_with(font.glyph_cache().atlas().pages(),Vec::new);
+            pages
+        }).collect();
+
+        for glyph in layout.glyphs(){
+            let font=match fonts.get(glyph.font){
+                Some(font)=>font,
+                None=>continue,
+            };
+
+            let pen=[
+                position[0]+glyph.position[0],
+                position[1]+glyph.position[1],
+            ];
+
+            let font_scale=font_scales[glyph.font];
+
+            if let Some(cached)=font.cached_glyph(glyph.id){
+                let glyph_scale=font_scale/font.glyph_cache().scale();
+                batches[glyph.font][cached.page()].extend_from_slice(&glyph_vertices(cached,pen,glyph_scale));
+            }
+            else if let Some([offset_x,offset_y,_,height])=self.text.load_glyph(glyph.id,font_scale,font.font().face()){
+                let position=[
+                    pen[0]+offset_x,
+                    pen[1]-offset_y-height,
+                ];
+
+                self.text.draw_loaded_glyph(colour,position,&self.draw_parameters);
+            }
+        }
+
+        for (font,pages) in fonts.iter().zip(batches.iter()){
+            let mode=font.glyph_cache().mode();
+            for (page,vertices) in pages.iter().enumerate(){
+                if let Some(texture)=font.glyph_cache().page_texture(page){
+                    self.text.draw_glyph_batch(texture,vertices,colour,mode,&self.text_effects,&self.draw_parameters)
+                }
+            }
+        }
+    }
+}
+
+/// Two triangles of a cached glyph, `position` is the pen position on the baseline.
+#[cfg(feature="text_graphics")]
+fn glyph_vertices(glyph:&TexturedGlyph,position:[f32;2],glyph_scale:Scale)->[TextVertex2D;6]{
+    let [offset_x,offset_y,width,height]=glyph.bounding_box(glyph_scale);
+
+    let x1=position[0]+offset_x;
+    let y1=position[1]-offset_y-height;
+    let [x2,y2]=[x1+width,y1+height];
+    let [u1,v1,u2,v2]=glyph.texture_coords();
+
+    [
+        TextVertex2D::new([x1,y1],[u1,v2]),
+        TextVertex2D::new([x2,y1],[u2,v2]),
+        TextVertex2D::new([x1,y2],[u1,v1]),
+        TextVertex2D::new([x1,y2],[u1,v1]),
+        TextVertex2D::new([x2,y1],[u2,v2]),
+        TextVertex2D::new([x2,y2],[u2,v1]),
+    ]
 }
 
 /// Simple graphics.
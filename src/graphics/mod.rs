@@ -7,6 +7,8 @@ use crate::{
 use crate::text::{
     Scale,
     CachedFont,
+    FontCollection,
+    TextLayout,
 };
 
 mod object_allocation;
@@ -45,6 +47,11 @@ use texture_graphics::TextureGraphics;
 mod text_graphics;
 #[cfg(feature="text_graphics")]
 use text_graphics::TextGraphics;
+#[cfg(feature="text_graphics")]
+pub use text_graphics::TextEffects;
+
+#[cfg(feature="software_graphics")]
+pub mod software;
 
 mod graphics_2d;
 pub use graphics_2d::{
@@ -87,7 +94,7 @@ pub struct Graphics{
 }
 
 impl Graphics{
-    #[cfg(any(windows))]
+    #[cfg(any(windows,target_os="linux"))]
     pub fn new(attributes:Graphics2DAttributes)->Graphics{
         Self{
             graphics_2d:Graphics2D::new(attributes),
@@ -130,6 +137,36 @@ impl Graphics{
             font,
         )
     }
+
+    pub fn draw_text(
+        &self,
+        layout:&TextLayout,
+        colour:Colour,
+        position:[f32;2],
+        font:&mut CachedFont
+    ){
+        self.graphics_2d.draw_text(
+            layout,
+            colour,
+            position,
+            font,
+        )
+    }
+
+    pub fn draw_text_collection(
+        &self,
+        layout:&TextLayout,
+        colour:Colour,
+        position:[f32;2],
+        fonts:&mut FontCollection
+    ){
+        self.graphics_2d.draw_text_collection(
+            layout,
+            colour,
+            position,
+            fonts,
+        )
+    }
 }
 
 /// Simple graphics.
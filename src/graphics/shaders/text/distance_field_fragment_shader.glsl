@@ -0,0 +1,70 @@
+#version 330 core
+
+in vec2 glyph_texture_coords;
+
+out vec4 colour;
+
+uniform sampler2D glyph_texture_2d;
+uniform vec4 glyph_colour;
+
+uniform float distance_spread; // the max distance in pixels of glyph images
+
+uniform float outline_width;
+uniform vec4 outline_colour;
+
+uniform vec2 shadow_offset; // [dx, dy] in pixels of glyph images
+uniform float shadow_softness;
+uniform vec4 shadow_colour;
+
+uniform float glow_width;
+uniform vec4 glow_colour;
+
+// The distance to the glyph edge in pixels of glyph images, positive inside
+float edge_distance(vec2 coords) {
+    return (texture(glyph_texture_2d, coords).r - 0.5) * 2.0 * distance_spread;
+}
+
+// Puts a layer with premultiplied alpha over another one
+vec4 over(vec4 top, vec4 bottom) {
+    return top + bottom * (1.0 - top.a);
+}
+
+vec4 layer(vec4 layer_colour, float alpha) {
+    return vec4(layer_colour.rgb, 1.0) * layer_colour.a * alpha;
+}
+
+void main() {
+    float distance = edge_distance(glyph_texture_coords);
+    // Half a screen pixel in pixels of glyph images
+    float smoothing = max(fwidth(distance) * 0.5, 0.001);
+
+    vec4 result = vec4(0.0);
+
+    if (shadow_colour.a > 0.0) {
+        // Texture rows go from the bottom
+        vec2 offset = shadow_offset / vec2(textureSize(glyph_texture_2d, 0));
+        float shadow_distance = edge_distance(glyph_texture_coords + vec2(-offset.x, offset.y));
+        float alpha = smoothstep(-shadow_softness - smoothing, smoothing, shadow_distance);
+        result = over(layer(shadow_colour, alpha), result);
+    }
+
+    if (glow_colour.a > 0.0 && glow_width > 0.0) {
+        float alpha = 1.0 - smoothstep(0.0, glow_width, -distance);
+        result = over(layer(glow_colour, alpha), result);
+    }
+
+    if (outline_colour.a > 0.0 && outline_width > 0.0) {
+        float alpha = smoothstep(-outline_width - smoothing, -outline_width + smoothing, distance);
+        result = over(layer(outline_colour, alpha), result);
+    }
+
+    float alpha = smoothstep(-smoothing, smoothing, distance);
+    result = over(layer(glyph_colour, alpha), result);
+
+    if (result.a > 0.0) {
+        colour = vec4(result.rgb / result.a, result.a);
+    }
+    else {
+        colour = vec4(0.0);
+    }
+}
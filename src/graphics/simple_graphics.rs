@@ -236,4 +236,77 @@ impl SimpleGraphics{
             self.vertex_array.unbind();
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    use cat_engine_basement::graphics::mock;
+
+    fn vertices(count:usize)->Vec<SimpleVertex2D>{
+        (0..count).map(|c|SimpleVertex2D::new([c as f32,0f32],[1f32;4])).collect()
+    }
+
+    fn positions(vertices:&[SimpleVertex2D])->Vec<[f32;2]>{
+        vertices.iter().map(|vertex|vertex.position).collect()
+    }
+
+    #[test]
+    fn draw_stack_object(){
+        mock::load();
+
+        let mut graphics=SimpleGraphics::new(16,16,4,4,4,4);
+        let draw_parameters=DrawParameters::new();
+
+        let triangle=vertices(3);
+        let quad=vertices(4);
+
+        graphics.push_object_raw(&triangle,&[],PrimitiveType::Triangles).unwrap();
+        let quad_id=graphics.push_object_raw(&quad,&[0,1,2,2,1,3],PrimitiveType::Triangles).unwrap();
+
+        mock::take_draw_calls();
+        graphics.draw_stack_object(quad_id,&draw_parameters);
+
+        let draw_calls=mock::take_draw_calls();
+        assert_eq!(draw_calls.len(),1);
+        assert_eq!(draw_calls[0].name,"glDrawElements");
+        assert_eq!(draw_calls[0].primitive_type,PrimitiveType::Triangles as u32);
+
+        mock::with_state(|state|{
+            // The stack goes after 4 heap frames and the triangle
+            assert_eq!(state.drawn_elements(&draw_calls[0]),vec![15,16,17,17,16,18]);
+
+            let drawn=state.drawn_vertices::<SimpleVertex2D>(&draw_calls[0]);
+            assert_eq!(positions(&drawn),positions(&[0,1,2,2,1,3].map(|i|quad[i].clone())));
+        });
+    }
+
+    #[test]
+    fn draw_heap_object(){
+        mock::load();
+
+        let mut graphics=SimpleGraphics::new(16,16,4,4,4,4);
+        let draw_parameters=DrawParameters::new();
+
+        let first=graphics.add_object_raw(&vertices(2),&[],PrimitiveType::Lines).unwrap();
+        let polygon=vertices(5);
+        let second=graphics.add_object_raw(&polygon,&[],PrimitiveType::LineStrip).unwrap();
+        graphics.remove_object(first);
+
+        mock::take_draw_calls();
+        graphics.draw_heap_object(second,&draw_parameters);
+
+        let draw_calls=mock::take_draw_calls();
+        assert_eq!(draw_calls.len(),1);
+        assert_eq!(draw_calls[0].name,"glMultiDrawArrays");
+        // Two frames: a full one and one with two vertices
+        assert_eq!(draw_calls[0].ranges.len(),2);
+        assert_eq!(draw_calls[0].ranges[1].1,2);
+
+        mock::with_state(|state|{
+            let drawn=state.drawn_vertices::<SimpleVertex2D>(&draw_calls[0]);
+            assert_eq!(positions(&drawn),positions(&polygon));
+        });
+    }
+}
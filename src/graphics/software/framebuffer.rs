@@ -0,0 +1,124 @@
+use crate::Colour;
+
+use cat_engine_basement::image::{
+    RgbaImage,
+    ImageResult,
+};
+
+use std::path::Path;
+
+/// An in-memory 8-bit RGBA framebuffer.
+///
+/// Rows go from the top of the frame to the bottom,
+/// so the data may be compared or saved directly as an image.
+pub struct Framebuffer{
+    size:[u32;2],
+    pixels:Vec<u8>,
+}
+
+impl Framebuffer{
+    /// Creates a framebuffer filled with transparent black.
+    pub fn new([width,height]:[u32;2])->Framebuffer{
+        Self{
+            size:[width,height],
+            pixels:vec![0u8;width as usize*height as usize*4],
+        }
+    }
+
+    pub fn size(&self)->[u32;2]{
+        self.size
+    }
+
+    /// Returns the raw RGBA data.
+    pub fn pixels(&self)->&[u8]{
+        &self.pixels
+    }
+
+    /// Returns the pixel at the given position or `None` if it's out of the frame.
+    pub fn pixel(&self,[x,y]:[u32;2])->Option<[u8;4]>{
+        if x>=self.size[0] || y>=self.size[1]{
+            return None
+        }
+
+        let index=(y as usize*self.size[0] as usize+x as usize)*4;
+        let mut pixel=[0u8;4];
+        pixel.copy_from_slice(&self.pixels[index..index+4]);
+        Some(pixel)
+    }
+
+    /// Changes the size of the framebuffer and clears it.
+    pub fn resize(&mut self,[width,height]:[u32;2]){
+        self.size=[width,height];
+        self.pixels.clear();
+        self.pixels.resize(width as usize*height as usize*4,0u8);
+    }
+
+    /// Fills the framebuffer with the given colour.
+    pub fn clear(&mut self,colour:Colour){
+        let pixel=quantize(colour);
+        for chunk in self.pixels.chunks_exact_mut(4){
+            chunk.copy_from_slice(&pixel);
+        }
+    }
+
+    /// Writes a fragment.
+    ///
+    /// With blending enabled the fragment is combined with the stored pixel
+    /// as `BlendingFunction::SourceAlpha` and `BlendingFunction::OneMinusSourceAlpha` do.
+    pub (crate) fn write_fragment(&mut self,x:usize,y:usize,colour:Colour,blending:bool){
+        let index=(y*self.size[0] as usize+x)*4;
+        let pixel=&mut self.pixels[index..index+4];
+
+        let colour=if blending{
+            let alpha=colour[3].clamp(0f32,1f32);
+            let mut blended=[0f32;4];
+            for (blended,(&source,&destination)) in blended.iter_mut().zip(colour.iter().zip(pixel.iter())){
+                *blended=source*alpha+destination as f32/255f32*(1f32-alpha);
+            }
+            blended
+        }
+        else{
+            colour
+        };
+
+        pixel.copy_from_slice(&quantize(colour));
+    }
+}
+
+impl Framebuffer{
+    /// Copies the framebuffer into an image.
+    pub fn to_image(&self)->RgbaImage{
+        RgbaImage::from_raw(self.size[0],self.size[1],self.pixels.clone()).unwrap()
+    }
+
+    /// Saves the framebuffer.
+    ///
+    /// The format is deduced from the path.
+    pub fn save<P:AsRef<Path>>(&self,path:P)->ImageResult<()>{
+        self.to_image().save(path)
+    }
+
+    /// Compares the framebuffer with an image.
+    ///
+    /// Returns the number of pixels that have any channel
+    /// differing by more than `tolerance`.
+    /// If the sizes are different, all the pixels of the framebuffer are counted.
+    pub fn compare(&self,image:&RgbaImage,tolerance:u8)->usize{
+        let (width,height)=image.dimensions();
+        if [width,height]!=self.size{
+            return self.size[0] as usize*self.size[1] as usize
+        }
+
+        let image:&[u8]=image.as_ref();
+
+        self.pixels.chunks_exact(4).zip(image.chunks_exact(4)).filter(|(a,b)|{
+            a.iter().zip(b.iter()).any(|(&a,&b)|{
+                (a as i16-b as i16).abs()>tolerance as i16
+            })
+        }).count()
+    }
+}
+
+fn quantize(colour:Colour)->[u8;4]{
+    colour.map(|c|(c.clamp(0f32,1f32)*255f32).round() as u8)
+}
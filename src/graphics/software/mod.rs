@@ -0,0 +1,738 @@
+//! A headless CPU backend for the 2D graphics.
+//!
+//! `SoftwareGraphics2D` follows the draw paths of `Graphics2D`
+//! (the same object allocation limits, the same vertex transformations as the shaders)
+//! but rasterizes into an in-memory `Framebuffer`,
+//! so frames can be rendered without a GPU or a window.
+
+use crate::Colour;
+
+#[cfg(feature="text_graphics")]
+use crate::text::{
+    Scale,
+    FontOwner,
+};
+
+use super::{
+    // types
+    ObjectIDType,
+    ElementIndexType,
+    // structs
+    SimpleVertex2D,
+    TexturedVertex2D,
+    DrawParameters,
+    Graphics2DAttributes,
+    ShapeObject,
+    // enums
+    PrimitiveType,
+};
+
+#[cfg(feature="text_graphics")]
+use super::GlyphImageBuilder;
+
+mod framebuffer;
+pub use framebuffer::Framebuffer;
+
+mod texture;
+pub use texture::SoftwareTexture;
+use texture::bilinear;
+
+mod objects;
+use objects::{
+    SoftwareObject,
+    SoftwareObjects,
+};
+
+mod rasterizer;
+use rasterizer::{
+    RasterVertex,
+    draw_primitives,
+};
+
+#[cfg(feature="text_graphics")]
+use ttf_parser::GlyphId;
+
+pub struct SoftwareGraphics2D{
+    framebuffer:Framebuffer,
+    blending:bool,
+
+    #[cfg(feature="simple_graphics")]
+    simple:SoftwareObjects<SimpleVertex2D>,
+    #[cfg(feature="texture_graphics")]
+    texture:SoftwareObjects<TexturedVertex2D>,
+    #[cfg(feature="text_graphics")]
+    glyph_image_builder:GlyphImageBuilder,
+
+    draw_parameters:DrawParameters,
+}
+
+impl SoftwareGraphics2D{
+    /// Creates graphics with a framebuffer of the given size.
+    ///
+    /// The viewport is set to the whole framebuffer.
+    /// Blending is disabled as for a new OpenGL context.
+    pub fn new(size:[u32;2],attributes:Graphics2DAttributes)->SoftwareGraphics2D{
+        let mut draw_parameters=DrawParameters::new();
+        draw_parameters.set_viewport([0f32,0f32,size[0] as f32,size[1] as f32]);
+
+        Self{
+            framebuffer:Framebuffer::new(size),
+            blending:false,
+
+            #[cfg(feature="simple_graphics")]
+            simple:SoftwareObjects::new(
+                attributes.simple_stack_vertices,
+                attributes.simple_stack_indices,
+                attributes.simple_stack_objects,
+                attributes.simple_heap_vertex_frames,
+                attributes.simple_heap_index_frames,
+                attributes.simple_heap_objects
+            ),
+            #[cfg(feature="texture_graphics")]
+            texture:SoftwareObjects::new(
+                attributes.texture_stack_vertices,
+                attributes.texture_stack_indices,
+                attributes.texture_stack_objects,
+                attributes.texture_heap_vertex_frames,
+                attributes.texture_heap_index_frames,
+                attributes.texture_heap_objects
+            ),
+            #[cfg(feature="text_graphics")]
+            glyph_image_builder:GlyphImageBuilder::new([
+                attributes.glyph_texture_size[0] as usize,
+                attributes.glyph_texture_size[1] as usize
+            ]),
+
+            draw_parameters,
+        }
+    }
+
+    pub fn draw_parameters(&mut self)->&mut DrawParameters{
+        &mut self.draw_parameters
+    }
+
+    pub fn framebuffer(&self)->&Framebuffer{
+        &self.framebuffer
+    }
+
+    pub fn framebuffer_mut(&mut self)->&mut Framebuffer{
+        &mut self.framebuffer
+    }
+
+    /// Enables or disables blending.
+    ///
+    /// Blending uses the `SourceAlpha` and `OneMinusSourceAlpha` functions.
+    pub fn set_blending(&mut self,enabled:bool){
+        self.blending=enabled
+    }
+
+    pub fn blending(&self)->bool{
+        self.blending
+    }
+
+    pub fn clear_colour(&mut self,colour:Colour){
+        self.framebuffer.clear(colour)
+    }
+}
+
+/// Text graphics.
+#[cfg(feature="text_graphics")]
+impl SoftwareGraphics2D{
+    /// Draws a character.
+    ///
+    /// The glyph is built for every call as `Graphics2D::draw_char` does for uncached glyphs.
+    pub fn draw_char(
+        &mut self,
+        character:char,
+        colour:Colour,
+        position:[f32;2],
+        horisontal_advance:Option<&mut f32>,
+        scale:Scale,
+        font:&FontOwner
+    ){
+        let face=font.face();
+
+        let glyph_id=if let Some(id)=face.glyph_index(character){
+            id
+        }
+        else{
+            GlyphId(0u16)
+        };
+
+        self.glyph_image_builder.set_scale(scale);
+
+        if let Some([offset_x,offset_y,_,height])=self.glyph_image_builder.build_image(glyph_id,face){
+            let [width,height_px]=self.glyph_image_builder.dimensions();
+
+            let [x1,y1]=[
+                position[0]+offset_x,
+                position[1]-offset_y-height,
+            ];
+            let [x2,y2]=[x1+width as f32,y1+height_px as f32];
+
+            let object=SoftwareObject{
+                vertices:vec![
+                    ([x1,y1],[0f32,1f32]),
+                    ([x2,y1],[1f32,1f32]),
+                    ([x1,y2],[0f32,0f32]),
+                    ([x2,y2],[1f32,0f32]),
+                ],
+                indices:Vec::new(),
+                primitive_type:PrimitiveType::TriangleStrip,
+            };
+
+            let image=self.glyph_image_builder.image();
+
+            draw_object(
+                &mut self.framebuffer,
+                &self.draw_parameters,
+                self.blending,
+                &object,
+                false,
+                |&(position,tex_coords)|(position,tex_coords),
+                |tex_coords|{
+                    let [coverage]=bilinear([width,height_px],tex_coords,|x,y|{
+                        if x<0 || y<0 || x>=width as isize || y>=height_px as isize{
+                            [0f32]
+                        }
+                        else{
+                            [image[y as usize*width+x as usize] as f32/255f32]
+                        }
+                    });
+                    [colour[0],colour[1],colour[2],colour[3]*coverage]
+                }
+            );
+        }
+
+        if let Some(horisontal_advance)=horisontal_advance{
+            if let Some(glyph_advance)=face.glyph_hor_advance(glyph_id){
+                *horisontal_advance=glyph_advance as f32*scale.horizontal;
+            }
+            else{
+                *horisontal_advance=0f32;
+            }
+        }
+    }
+}
+
+/// Simple graphics.
+#[cfg(feature="simple_graphics")]
+impl SoftwareGraphics2D{
+    pub fn add_simple_object_raw(
+        &mut self,
+        vertices:&[SimpleVertex2D],
+        indices:&[ElementIndexType],
+        primitive_type:PrimitiveType
+    )->Option<ObjectIDType>{
+        self.simple.heap.add_object(vertices,indices,primitive_type)
+    }
+
+    pub fn add_simple_object<O:ShapeObject<SimpleVertex2D,ElementIndexType>>(
+        &mut self,
+        object:&O
+    )->Option<ObjectIDType>{
+        self.add_simple_object_raw(
+            object.vertices().as_ref(),
+            object.indices().as_ref(),
+            object.primitive_type()
+        )
+    }
+
+    /// Removes an object.
+    pub fn remove_simple_object(&mut self,index:ObjectIDType){
+        self.simple.heap.remove_object(index);
+    }
+
+    pub fn write_heap_simple_object_vertices(&mut self,index:ObjectIDType,vertices:&[SimpleVertex2D]){
+        self.simple.heap.write_object_vertices(index,vertices)
+    }
+
+    pub fn write_heap_simple_object_indices(&mut self,index:ObjectIDType,indices:&[ElementIndexType]){
+        self.simple.heap.write_object_indices(index,indices)
+    }
+
+    pub fn draw_heap_simple_object(&mut self,index:ObjectIDType){
+        if let Some(object)=self.simple.heap.get_object(index){
+            draw_simple(&mut self.framebuffer,&self.draw_parameters,self.blending,object,true)
+        }
+    }
+
+    pub fn push_simple_object_raw(
+        &mut self,
+        vertices:&[SimpleVertex2D],
+        indices:&[ElementIndexType],
+        primitive_type:PrimitiveType
+    )->Option<ObjectIDType>{
+        self.simple.stack.push_object(vertices,indices,primitive_type)
+    }
+
+    pub fn push_simple_object<O:ShapeObject<SimpleVertex2D,ElementIndexType>>(
+        &mut self,
+        object:&O
+    )->Option<ObjectIDType>{
+        self.push_simple_object_raw(
+            object.vertices().as_ref(),
+            object.indices().as_ref(),
+            object.primitive_type()
+        )
+    }
+
+    pub fn pop_simple_object(&mut self){
+        self.simple.stack.pop_object();
+    }
+
+    pub fn clear_stack_simple_objects(&mut self){
+        self.simple.stack.clear()
+    }
+
+    pub fn write_stack_simple_object_vertices(&mut self,index:ObjectIDType,vertices:&[SimpleVertex2D]){
+        self.simple.stack.write_object_vertices(index,vertices)
+    }
+
+    pub fn write_stack_simple_object_indices(&mut self,index:ObjectIDType,indices:&[ElementIndexType]){
+        self.simple.stack.write_object_indices(index,indices)
+    }
+
+    pub fn draw_stack_simple_object(&mut self,index:ObjectIDType){
+        if let Some(object)=self.simple.stack.get_object(index){
+            draw_simple(&mut self.framebuffer,&self.draw_parameters,self.blending,object,false)
+        }
+    }
+}
+
+#[cfg(feature="simple_graphics")]
+fn draw_simple(
+    framebuffer:&mut Framebuffer,
+    draw_parameters:&DrawParameters,
+    blending:bool,
+    object:&SoftwareObject<SimpleVertex2D>,
+    heap:bool
+){
+    draw_object(
+        framebuffer,
+        draw_parameters,
+        blending,
+        object,
+        heap,
+        |vertex|(vertex.position,vertex.colour),
+        |colour|colour
+    )
+}
+
+/// Texture graphics.
+#[cfg(feature="texture_graphics")]
+impl SoftwareGraphics2D{
+    pub fn add_textured_object_raw(
+        &mut self,
+        vertices:&[TexturedVertex2D],
+        indices:&[ElementIndexType],
+        primitive_type:PrimitiveType
+    )->Option<ObjectIDType>{
+        self.texture.heap.add_object(vertices,indices,primitive_type)
+    }
+
+    pub fn add_textured_object<O:ShapeObject<TexturedVertex2D,ElementIndexType>>(
+        &mut self,
+        object:&O
+    )->Option<ObjectIDType>{
+        self.add_textured_object_raw(
+            object.vertices().as_ref(),
+            object.indices().as_ref(),
+            object.primitive_type()
+        )
+    }
+
+    /// Removes an object.
+    pub fn remove_textured_object(&mut self,index:ObjectIDType){
+        self.texture.heap.remove_object(index);
+    }
+
+    pub fn write_heap_textured_object_vertices(&mut self,index:ObjectIDType,vertices:&[TexturedVertex2D]){
+        self.texture.heap.write_object_vertices(index,vertices)
+    }
+
+    pub fn write_heap_textured_object_indices(&mut self,index:ObjectIDType,indices:&[ElementIndexType]){
+        self.texture.heap.write_object_indices(index,indices)
+    }
+
+    pub fn draw_heap_textured_object(&mut self,index:ObjectIDType,texture:&SoftwareTexture){
+        if let Some(object)=self.texture.heap.get_object(index){
+            draw_textured(&mut self.framebuffer,&self.draw_parameters,self.blending,object,true,texture)
+        }
+    }
+
+    pub fn push_textured_object_raw(
+        &mut self,
+        vertices:&[TexturedVertex2D],
+        indices:&[ElementIndexType],
+        primitive_type:PrimitiveType
+    )->Option<ObjectIDType>{
+        self.texture.stack.push_object(vertices,indices,primitive_type)
+    }
+
+    pub fn push_textured_object<O:ShapeObject<TexturedVertex2D,ElementIndexType>>(
+        &mut self,
+        object:&O
+    )->Option<ObjectIDType>{
+        self.push_textured_object_raw(
+            object.vertices().as_ref(),
+            object.indices().as_ref(),
+            object.primitive_type()
+        )
+    }
+
+    pub fn pop_textured_object(&mut self){
+        self.texture.stack.pop_object();
+    }
+
+    pub fn clear_stack_textured_objects(&mut self){
+        self.texture.stack.clear()
+    }
+
+    pub fn write_stack_textured_object_vertices(&mut self,index:ObjectIDType,vertices:&[TexturedVertex2D]){
+        self.texture.stack.write_object_vertices(index,vertices)
+    }
+
+    pub fn write_stack_textured_object_indices(&mut self,index:ObjectIDType,indices:&[ElementIndexType]){
+        self.texture.stack.write_object_indices(index,indices)
+    }
+
+    pub fn draw_stack_textured_object(&mut self,index:ObjectIDType,texture:&SoftwareTexture){
+        if let Some(object)=self.texture.stack.get_object(index){
+            draw_textured(&mut self.framebuffer,&self.draw_parameters,self.blending,object,false,texture)
+        }
+    }
+}
+
+#[cfg(feature="texture_graphics")]
+fn draw_textured(
+    framebuffer:&mut Framebuffer,
+    draw_parameters:&DrawParameters,
+    blending:bool,
+    object:&SoftwareObject<TexturedVertex2D>,
+    heap:bool,
+    texture:&SoftwareTexture
+){
+    draw_object(
+        framebuffer,
+        draw_parameters,
+        blending,
+        object,
+        heap,
+        |vertex|{
+            let [u,v]=vertex.tex_coords;
+            let [r,g,b,a]=vertex.colour;
+            (vertex.position,[u,v,r,g,b,a])
+        },
+        |[u,v,r,g,b,a]|{
+            let texel=texture.sample([u,v]);
+            [texel[0]*r,texel[1]*g,texel[2]*b,texel[3]*a]
+        }
+    )
+}
+
+/// Applies the transformations of the vertex shaders:
+/// shift, rotation and the conversion from the viewport to framebuffer pixels.
+fn transform(draw_parameters:&DrawParameters,[width,height]:[u32;2],[mut x,mut y]:[f32;2])->[f32;2]{
+    if let Some([dx,dy])=draw_parameters.shift(){
+        x+=dx;
+        y+=dy;
+    }
+
+    if let Some([cos,sin,center_x,center_y])=draw_parameters.rotation(){
+        let rx=x-center_x;
+        let ry=y-center_y;
+        x=rx*cos-ry*sin+center_x;
+        y=rx*sin+ry*cos+center_y;
+    }
+
+    let [offset_x,offset_y,viewport_width,viewport_height]=draw_parameters.viewport();
+
+    [
+        (x+offset_x)/viewport_width*width as f32,
+        (y+offset_y)/viewport_height*height as f32,
+    ]
+}
+
+fn draw_object<V,const N:usize,A:Fn(&V)->([f32;2],[f32;N]),S:Fn([f32;N])->Colour>(
+    framebuffer:&mut Framebuffer,
+    draw_parameters:&DrawParameters,
+    blending:bool,
+    object:&SoftwareObject<V>,
+    heap:bool,
+    attributes:A,
+    shader:S
+){
+    let size=framebuffer.size();
+
+    let vertices:Vec<RasterVertex<N>>=object.vertices.iter().map(|vertex|{
+        let (position,attributes)=attributes(vertex);
+        RasterVertex{
+            position:transform(draw_parameters,size,position),
+            attributes,
+        }
+    }).collect();
+
+    for elements in object.element_runs(heap){
+        draw_primitives(
+            framebuffer,
+            blending,
+            &vertices,
+            &elements,
+            object.primitive_type,
+            &shader
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[cfg(feature="simple_graphics")]
+    use super::super::DrawMode;
+
+    /// Compares the framebuffer with a picture,
+    /// '#' is the given colour and '.' is transparent black.
+    fn assert_picture(framebuffer:&Framebuffer,picture:&[&str],colour:[u8;4]){
+        for (y,row) in picture.iter().enumerate(){
+            for (x,symbol) in row.chars().enumerate(){
+                let expected=if symbol=='#'{colour}else{[0u8;4]};
+                assert_eq!(framebuffer.pixel([x as u32,y as u32]),Some(expected),"pixel [{}, {}]",x,y);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature="simple_graphics")]
+    fn solid_triangle(){
+        let mut graphics=SoftwareGraphics2D::new([8,8],Graphics2DAttributes::new());
+
+        let colour=[1f32,0f32,0f32,1f32];
+        let id=graphics.add_simple_object_raw(
+            &[
+                SimpleVertex2D::new([0f32,0f32],colour),
+                SimpleVertex2D::new([6.2f32,0f32],colour),
+                SimpleVertex2D::new([0f32,6.2f32],colour),
+            ],
+            &[],
+            PrimitiveType::Triangles
+        ).unwrap();
+        graphics.draw_heap_simple_object(id);
+
+        assert_picture(
+            graphics.framebuffer(),
+            &[
+                "######..",
+                "#####...",
+                "####....",
+                "###.....",
+                "##......",
+                "#.......",
+                "........",
+                "........",
+            ],
+            [255,0,0,255]
+        );
+    }
+
+    #[test]
+    #[cfg(feature="simple_graphics")]
+    fn shared_edges(){
+        let mut graphics=SoftwareGraphics2D::new([8,8],Graphics2DAttributes::new());
+        graphics.set_blending(true);
+
+        // The edges of the fan go through pixel centers,
+        // the center of the fan is the center of the pixel [4, 4]
+        let colour=[1f32,1f32,1f32,0.5f32];
+        let id=graphics.push_simple_object_raw(
+            &[
+                SimpleVertex2D::new([4.5f32,4.5f32],colour),
+                SimpleVertex2D::new([0f32,0f32],colour),
+                SimpleVertex2D::new([8f32,0f32],colour),
+                SimpleVertex2D::new([8f32,8f32],colour),
+                SimpleVertex2D::new([0f32,8f32],colour),
+                SimpleVertex2D::new([0f32,0f32],colour),
+            ],
+            &[],
+            PrimitiveType::TriangleFan
+        ).unwrap();
+        graphics.draw_stack_simple_object(id);
+
+        // A pixel drawn twice would be [191, 191, 191, 96]
+        assert_picture(
+            graphics.framebuffer(),
+            &["########";8],
+            [128,128,128,64]
+        );
+    }
+
+    #[test]
+    #[cfg(feature="simple_graphics")]
+    fn shift_and_rotation(){
+        let mut graphics=SoftwareGraphics2D::new([6,6],Graphics2DAttributes::new());
+
+        let colour=[0f32,1f32,0f32,1f32];
+        let id=graphics.push_simple_object_raw(
+            &[
+                SimpleVertex2D::new([0f32,0f32],colour),
+                SimpleVertex2D::new([0f32,1f32],colour),
+                SimpleVertex2D::new([2f32,0f32],colour),
+                SimpleVertex2D::new([2f32,1f32],colour),
+            ],
+            &[],
+            PrimitiveType::TriangleStrip
+        ).unwrap();
+
+        graphics.draw_parameters().set_shift([3f32,2f32]);
+        graphics.draw_parameters().enable(DrawMode::Shift);
+        graphics.draw_stack_simple_object(id);
+
+        assert_picture(
+            graphics.framebuffer(),
+            &[
+                "......",
+                "......",
+                "...##.",
+                "......",
+                "......",
+                "......",
+            ],
+            [0,255,0,255]
+        );
+
+        // A quarter turn around the shifted origin
+        graphics.clear_colour([0f32;4]);
+        graphics.draw_parameters().set_rotation([0f32,1f32,3f32,2f32]);
+        graphics.draw_parameters().enable(DrawMode::Rotation);
+        graphics.draw_stack_simple_object(id);
+
+        assert_picture(
+            graphics.framebuffer(),
+            &[
+                "......",
+                "......",
+                "..#...",
+                "..#...",
+                "......",
+                "......",
+            ],
+            [0,255,0,255]
+        );
+    }
+
+    #[test]
+    #[cfg(feature="texture_graphics")]
+    fn textured_quad(){
+        let mut graphics=SoftwareGraphics2D::new([4,1],Graphics2DAttributes::new());
+
+        // Black and white texels, the sampling wraps around
+        let texture=SoftwareTexture::new([2,1],&[0,0,0,255,255,255,255,255]);
+
+        let colour=[1f32;4];
+        let id=graphics.push_textured_object_raw(
+            &[
+                TexturedVertex2D::new([0f32,0f32],[0f32,1f32],colour),
+                TexturedVertex2D::new([0f32,1f32],[0f32,0f32],colour),
+                TexturedVertex2D::new([4f32,0f32],[1f32,1f32],colour),
+                TexturedVertex2D::new([4f32,1f32],[1f32,0f32],colour),
+            ],
+            &[],
+            PrimitiveType::TriangleStrip
+        ).unwrap();
+        graphics.draw_stack_textured_object(id,&texture);
+
+        let framebuffer=graphics.framebuffer();
+        assert_eq!(framebuffer.pixel([0,0]),Some([64,64,64,255]));
+        assert_eq!(framebuffer.pixel([1,0]),Some([64,64,64,255]));
+        assert_eq!(framebuffer.pixel([2,0]),Some([191,191,191,255]));
+        assert_eq!(framebuffer.pixel([3,0]),Some([191,191,191,255]));
+    }
+
+    /// A TrueType font with the only glyph for 'A':
+    /// a square of 800 units with the bottom left corner at the origin.
+    #[cfg(feature="text_graphics")]
+    fn square_font()->FontOwner{
+        fn be(values:&[i32])->Vec<u8>{
+            values.iter().flat_map(|&value|(value as u16).to_be_bytes()).collect()
+        }
+
+        let mut head=be(&[1,0, 0,0, 0,0, 0x5F0F,0x3CF5, 0, 1000]);
+        head.resize(36,0);
+        head.extend(be(&[0,0,800,800, 0,0,0, 0,0]));
+
+        let mut hhea=be(&[1,0, 800,-200,0, 1000, 0,0,800, 1,0,0]);
+        hhea.resize(34,0);
+        hhea.extend(be(&[2]));
+
+        let maxp=be(&[0,0x5000,2]);
+        let hmtx=be(&[1000,0,1000,0]);
+        // Format 6: 'A' - glyph 1
+        let cmap=be(&[0,1, 0,3,0,12, 6,12,0,'A' as i32,1,1]);
+        // Contour (0, 0), (0, 800), (800, 800), (800, 0) with on curve points
+        let glyf=be(&[1, 0,0,800,800, 3, 0, 0x0101,0x0101, 0,0,800,0, 0,800,0,-800]);
+        let loca=be(&[0,0,glyf.len() as i32/2]);
+
+        let tables:[(&[u8;4],Vec<u8>);7]=[
+            (b"cmap",cmap),
+            (b"glyf",glyf),
+            (b"head",head),
+            (b"hhea",hhea),
+            (b"hmtx",hmtx),
+            (b"loca",loca),
+            (b"maxp",maxp),
+        ];
+
+        let mut font=be(&[1,0,tables.len() as i32,0,0,0]);
+        let mut offset=font.len()+tables.len()*16;
+        for (tag,table) in tables.iter(){
+            font.extend_from_slice(*tag);
+            font.extend_from_slice(&[0u8;4]);
+            font.extend_from_slice(&(offset as u32).to_be_bytes());
+            font.extend_from_slice(&(table.len() as u32).to_be_bytes());
+            offset+=table.len().div_ceil(4)*4;
+        }
+        for (_,table) in tables.iter(){
+            font.extend_from_slice(table);
+            font.resize(font.len().div_ceil(4)*4,0);
+        }
+
+        FontOwner::parse(font).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature="text_graphics")]
+    fn glyph(){
+        let mut graphics=SoftwareGraphics2D::new([10,10],Graphics2DAttributes::new());
+        let font=square_font();
+
+        let mut advance=0f32;
+        // The glyph is 6 pixels, the baseline is at the bottom of the row 7
+        graphics.draw_char(
+            'A',
+            [0f32,0f32,1f32,1f32],
+            [2f32,8f32],
+            Some(&mut advance),
+            Scale::new(0.0075f32,0.0075f32),
+            &font
+        );
+
+        assert_eq!(advance,7.5f32);
+        assert_picture(
+            graphics.framebuffer(),
+            &[
+                "..........",
+                "..........",
+                "..######..",
+                "..######..",
+                "..######..",
+                "..######..",
+                "..######..",
+                "..######..",
+                "..........",
+                "..........",
+            ],
+            [0,0,255,255]
+        );
+    }
+}
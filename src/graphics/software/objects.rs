@@ -0,0 +1,245 @@
+use crate::graphics::{
+    // types
+    FrameIDType,
+    ObjectIDType,
+    ElementIndexType,
+    // consts
+    frame_size,
+    // enums
+    PrimitiveType,
+};
+
+/// An object stored in memory.
+pub struct SoftwareObject<V>{
+    pub vertices:Vec<V>,
+    pub indices:Vec<ElementIndexType>,
+    pub primitive_type:PrimitiveType,
+}
+
+impl<V> SoftwareObject<V>{
+    /// Returns the runs of elements that are drawn separately.
+    ///
+    /// Heap objects are split into frames the same way `HeapObject::drawable` does,
+    /// so the output matches the OpenGL graphics.
+    pub fn element_runs(&self,heap:bool)->Vec<Vec<usize>>{
+        let elements:Vec<usize>=if self.indices.is_empty(){
+            (0..self.vertices.len()).collect()
+        }
+        else{
+            self.indices.iter().map(|&i|i as usize).collect()
+        };
+
+        if heap{
+            elements.chunks(frame_size).map(|frame|frame.to_vec()).collect()
+        }
+        else{
+            vec![elements]
+        }
+    }
+}
+
+/// Mirrors the limits of `HeapSystem`.
+pub struct SoftwareHeap<V>{
+    objects:Vec<Option<SoftwareObject<V>>>,
+    free_objects:Vec<ObjectIDType>,
+    free_vertex_frames:usize,
+    free_index_frames:usize,
+}
+
+impl<V:Clone> SoftwareHeap<V>{
+    pub fn new(
+        vertex_frames:FrameIDType,
+        index_frames:FrameIDType,
+        objects:ObjectIDType
+    )->SoftwareHeap<V>{
+        Self{
+            objects:(0..objects).map(|_|None).collect(),
+            free_objects:(0..objects).rev().collect(),
+            free_vertex_frames:vertex_frames as usize,
+            free_index_frames:index_frames as usize,
+        }
+    }
+
+    pub fn add_object(
+        &mut self,
+        vertices:&[V],
+        indices:&[ElementIndexType],
+        primitive_type:PrimitiveType
+    )->Option<ObjectIDType>{
+        if vertices.is_empty(){
+            return None
+        }
+
+        if indices.iter().any(|&i|i as usize>=vertices.len()){
+            panic!("No such vertex");
+        }
+
+        let vertex_frames=vertices.len().div_ceil(frame_size);
+        let index_frames=indices.len().div_ceil(frame_size);
+
+        if vertex_frames>self.free_vertex_frames || index_frames>self.free_index_frames{
+            return None
+        }
+
+        let id=self.free_objects.pop()?;
+
+        self.free_vertex_frames-=vertex_frames;
+        self.free_index_frames-=index_frames;
+
+        self.objects[id as usize]=Some(SoftwareObject{
+            vertices:vertices.to_vec(),
+            indices:indices.to_vec(),
+            primitive_type,
+        });
+
+        Some(id)
+    }
+
+    /// Removes an object.
+    pub fn remove_object(&mut self,id:ObjectIDType){
+        if let Some(slot)=self.objects.get_mut(id as usize){
+            if let Some(object)=slot.take(){
+                self.free_vertex_frames+=object.vertices.len().div_ceil(frame_size);
+                self.free_index_frames+=object.indices.len().div_ceil(frame_size);
+                self.free_objects.push(id);
+            }
+        }
+    }
+
+    pub fn get_object(&self,id:ObjectIDType)->Option<&SoftwareObject<V>>{
+        self.objects.get(id as usize).and_then(|object|object.as_ref())
+    }
+
+    /// Rewrites the vertices of an object if the amount is the same.
+    pub fn write_object_vertices(&mut self,id:ObjectIDType,vertices:&[V]){
+        if let Some(Some(object))=self.objects.get_mut(id as usize){
+            if object.vertices.len()==vertices.len(){
+                object.vertices.clone_from_slice(vertices)
+            }
+        }
+    }
+
+    /// Rewrites the indices of an object if the amount is the same.
+    pub fn write_object_indices(&mut self,id:ObjectIDType,indices:&[ElementIndexType]){
+        if let Some(Some(object))=self.objects.get_mut(id as usize){
+            if object.indices.len()==indices.len(){
+                object.indices.copy_from_slice(indices)
+            }
+        }
+    }
+}
+
+/// Mirrors the limits of `StackSystem`.
+pub struct SoftwareStack<V>{
+    vertices:usize,
+    vertices_left:usize,
+    indices:usize,
+    indices_left:usize,
+    objects:Vec<SoftwareObject<V>>,
+    max_objects:usize,
+}
+
+impl<V:Clone> SoftwareStack<V>{
+    pub fn new(vertices:ElementIndexType,indices:i32,objects:ObjectIDType)->SoftwareStack<V>{
+        Self{
+            vertices:vertices as usize,
+            vertices_left:vertices as usize,
+            indices:indices.max(0) as usize,
+            indices_left:indices.max(0) as usize,
+            objects:Vec::with_capacity(objects as usize),
+            max_objects:objects as usize,
+        }
+    }
+
+    pub fn push_object(
+        &mut self,
+        vertices:&[V],
+        indices:&[ElementIndexType],
+        primitive_type:PrimitiveType
+    )->Option<ObjectIDType>{
+        if vertices.is_empty(){
+            return None
+        }
+
+        if vertices.len()>self.vertices_left
+            || indices.len()>self.indices_left
+            || self.objects.len()>=self.max_objects
+        {
+            return None
+        }
+
+        if let Some(&i)=indices.iter().find(|&&i|i as usize>=vertices.len()){
+            panic!("IndexOutOfBounds. The index is {}. The amount of vertices is {}.",i,vertices.len())
+        }
+
+        self.vertices_left-=vertices.len();
+        self.indices_left-=indices.len();
+
+        let id=self.objects.len() as ObjectIDType;
+
+        self.objects.push(SoftwareObject{
+            vertices:vertices.to_vec(),
+            indices:indices.to_vec(),
+            primitive_type,
+        });
+
+        Some(id)
+    }
+
+    pub fn pop_object(&mut self){
+        if let Some(object)=self.objects.pop(){
+            self.vertices_left+=object.vertices.len();
+            self.indices_left+=object.indices.len();
+        }
+    }
+
+    pub fn clear(&mut self){
+        self.objects.clear();
+        self.vertices_left=self.vertices;
+        self.indices_left=self.indices;
+    }
+
+    pub fn get_object(&self,id:ObjectIDType)->Option<&SoftwareObject<V>>{
+        self.objects.get(id as usize)
+    }
+
+    /// Rewrites the vertices of an object if the amount is the same.
+    pub fn write_object_vertices(&mut self,id:ObjectIDType,vertices:&[V]){
+        if let Some(object)=self.objects.get_mut(id as usize){
+            if object.vertices.len()==vertices.len(){
+                object.vertices.clone_from_slice(vertices)
+            }
+        }
+    }
+
+    /// Rewrites the indices of an object if the amount is the same.
+    pub fn write_object_indices(&mut self,id:ObjectIDType,indices:&[ElementIndexType]){
+        if let Some(object)=self.objects.get_mut(id as usize){
+            if object.indices.len()==indices.len(){
+                object.indices.copy_from_slice(indices)
+            }
+        }
+    }
+}
+
+/// Heap and stack storages of one kind of objects.
+pub struct SoftwareObjects<V>{
+    pub heap:SoftwareHeap<V>,
+    pub stack:SoftwareStack<V>,
+}
+
+impl<V:Clone> SoftwareObjects<V>{
+    pub fn new(
+        stack_vertices:ElementIndexType,
+        stack_indices:i32,
+        stack_objects:ObjectIDType,
+        heap_vertex_frames:FrameIDType,
+        heap_index_frames:FrameIDType,
+        heap_objects:ObjectIDType
+    )->SoftwareObjects<V>{
+        Self{
+            heap:SoftwareHeap::new(heap_vertex_frames,heap_index_frames,heap_objects),
+            stack:SoftwareStack::new(stack_vertices,stack_indices,stack_objects),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use crate::Colour;
+
+use super::{
+    Framebuffer,
+    PrimitiveType,
+};
+
+/// A vertex that has passed the vertex stage.
+///
+/// The position is in framebuffer pixels,
+/// the attributes are interpolated over the primitive.
+#[derive(Clone,Copy)]
+pub struct RasterVertex<const N:usize>{
+    pub position:[f32;2],
+    pub attributes:[f32;N],
+}
+
+/// Assembles primitives from `elements` (indices into `vertices`)
+/// and rasterizes them.
+///
+/// `shader` computes the colour of a fragment from the interpolated attributes.
+///
+/// Adjacency primitive types are ignored
+/// since there is no geometry stage to consume them.
+pub fn draw_primitives<const N:usize,S:Fn([f32;N])->Colour>(
+    framebuffer:&mut Framebuffer,
+    blending:bool,
+    vertices:&[RasterVertex<N>],
+    elements:&[usize],
+    primitive_type:PrimitiveType,
+    shader:S
+){
+    let vertex=|index:usize|->Option<&RasterVertex<N>>{
+        elements.get(index).and_then(|&element|vertices.get(element))
+    };
+
+    let count=elements.len();
+
+    match primitive_type{
+        PrimitiveType::Points=>{
+            for c in 0..count{
+                if let Some(v)=vertex(c){
+                    rasterize_point(framebuffer,blending,v,&shader)
+                }
+            }
+        }
+
+        PrimitiveType::Lines=>{
+            for c in 0..count/2{
+                if let (Some(a),Some(b))=(vertex(2*c),vertex(2*c+1)){
+                    rasterize_line(framebuffer,blending,a,b,&shader)
+                }
+            }
+        }
+
+        PrimitiveType::LineStrip|PrimitiveType::LineLoop=>{
+            for c in 1..count{
+                if let (Some(a),Some(b))=(vertex(c-1),vertex(c)){
+                    rasterize_line(framebuffer,blending,a,b,&shader)
+                }
+            }
+
+            if primitive_type==PrimitiveType::LineLoop && count>2{
+                if let (Some(a),Some(b))=(vertex(count-1),vertex(0)){
+                    rasterize_line(framebuffer,blending,a,b,&shader)
+                }
+            }
+        }
+
+        PrimitiveType::Triangles=>{
+            for c in 0..count/3{
+                if let (Some(a),Some(b),Some(c))=(vertex(3*c),vertex(3*c+1),vertex(3*c+2)){
+                    rasterize_triangle(framebuffer,blending,[a,b,c],&shader)
+                }
+            }
+        }
+
+        PrimitiveType::TriangleStrip=>{
+            for c in 2..count{
+                // Keeping the winding of every second triangle
+                let (a,b)=if c%2==0{(c-2,c-1)}else{(c-1,c-2)};
+                if let (Some(a),Some(b),Some(c))=(vertex(a),vertex(b),vertex(c)){
+                    rasterize_triangle(framebuffer,blending,[a,b,c],&shader)
+                }
+            }
+        }
+
+        PrimitiveType::TriangleFan=>{
+            for c in 2..count{
+                if let (Some(a),Some(b),Some(c))=(vertex(0),vertex(c-1),vertex(c)){
+                    rasterize_triangle(framebuffer,blending,[a,b,c],&shader)
+                }
+            }
+        }
+
+        _=>{}
+    }
+}
+
+fn rasterize_point<const N:usize,S:Fn([f32;N])->Colour>(
+    framebuffer:&mut Framebuffer,
+    blending:bool,
+    vertex:&RasterVertex<N>,
+    shader:&S
+){
+    let [width,height]=framebuffer.size();
+    let [x,y]=vertex.position;
+
+    if x>=0f32 && y>=0f32 && x<width as f32 && y<height as f32{
+        framebuffer.write_fragment(x as usize,y as usize,shader(vertex.attributes),blending)
+    }
+}
+
+/// Draws a one pixel wide line.
+///
+/// The last pixel is not drawn, so connected lines don't overlap.
+fn rasterize_line<const N:usize,S:Fn([f32;N])->Colour>(
+    framebuffer:&mut Framebuffer,
+    blending:bool,
+    a:&RasterVertex<N>,
+    b:&RasterVertex<N>,
+    shader:&S
+){
+    let [width,height]=framebuffer.size();
+
+    let dx=b.position[0]-a.position[0];
+    let dy=b.position[1]-a.position[1];
+
+    let steps=dx.abs().max(dy.abs()).round() as usize;
+
+    for step in 0..steps{
+        let t=(step as f32+0.5f32)/steps as f32;
+
+        let x=a.position[0]+dx*t;
+        let y=a.position[1]+dy*t;
+
+        if x<0f32 || y<0f32 || x>=width as f32 || y>=height as f32{
+            continue
+        }
+
+        let attributes=interpolate([&a.attributes,&b.attributes],[1f32-t,t]);
+        framebuffer.write_fragment(x as usize,y as usize,shader(attributes),blending)
+    }
+}
+
+/// Fills the pixels which centers lay inside the triangle.
+///
+/// Pixels on a shared edge are filled only once.
+fn rasterize_triangle<const N:usize,S:Fn([f32;N])->Colour>(
+    framebuffer:&mut Framebuffer,
+    blending:bool,
+    [a,mut b,mut c]:[&RasterVertex<N>;3],
+    shader:&S
+){
+    let mut area=edge(a.position,b.position,c.position);
+    if area==0f32{
+        return
+    }
+    // Bringing all the triangles to the same winding
+    if area<0f32{
+        std::mem::swap(&mut b,&mut c);
+        area=area.abs();
+    }
+
+    let [width,height]=framebuffer.size();
+
+    let min_x=a.position[0].min(b.position[0]).min(c.position[0]).floor().max(0f32) as usize;
+    let min_y=a.position[1].min(b.position[1]).min(c.position[1]).floor().max(0f32) as usize;
+    let max_x=a.position[0].max(b.position[0]).max(c.position[0]).ceil().min(width as f32) as usize;
+    let max_y=a.position[1].max(b.position[1]).max(c.position[1]).ceil().min(height as f32) as usize;
+
+    let edges=[
+        (b.position,c.position),
+        (c.position,a.position),
+        (a.position,b.position),
+    ];
+
+    for y in min_y..max_y{
+        for x in min_x..max_x{
+            let point=[x as f32+0.5f32,y as f32+0.5f32];
+
+            let mut weights=[0f32;3];
+            let mut inside=true;
+
+            for (weight,&(from,to)) in weights.iter_mut().zip(edges.iter()){
+                let e=edge(from,to,point);
+                if e<0f32 || e==0f32 && !is_owner_edge(from,to){
+                    inside=false;
+                    break
+                }
+                *weight=e/area;
+            }
+
+            if inside{
+                let attributes=interpolate([&a.attributes,&b.attributes,&c.attributes],weights);
+                framebuffer.write_fragment(x,y,shader(attributes),blending)
+            }
+        }
+    }
+}
+
+#[inline(always)]
+fn edge(from:[f32;2],to:[f32;2],point:[f32;2])->f32{
+    (to[0]-from[0])*(point[1]-from[1])-(to[1]-from[1])*(point[0]-from[0])
+}
+
+/// An edge is walked in opposite directions by two triangles sharing it,
+/// so only one of them owns the pixels laying exactly on it.
+#[inline(always)]
+fn is_owner_edge(from:[f32;2],to:[f32;2])->bool{
+    let dy=to[1]-from[1];
+    dy<0f32 || dy==0f32 && to[0]>from[0]
+}
+
+#[inline(always)]
+fn interpolate<const N:usize,const V:usize>(attributes:[&[f32;N];V],weights:[f32;V])->[f32;N]{
+    let mut result=[0f32;N];
+    for (attributes,weight) in attributes.iter().zip(weights.iter()){
+        for (result,attribute) in result.iter_mut().zip(attributes.iter()){
+            *result+=attribute*weight;
+        }
+    }
+    result
+}
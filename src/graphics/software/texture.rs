@@ -0,0 +1,175 @@
+use crate::Colour;
+
+use cat_engine_basement::image::{
+    RgbaImage,
+    ImageError,
+    open,
+};
+
+use std::path::Path;
+
+/// A 8-bit RGBA texture for the software graphics.
+///
+/// The data layout is the same as for `Texture`:
+/// the first row of the data is sampled at `v = 0`.
+pub struct SoftwareTexture{
+    size:[u32;2],
+    data:Vec<u8>,
+}
+
+impl SoftwareTexture{
+    /// Creates a texture.
+    ///
+    /// Panics if `data` is shorter than `width * height * 4`.
+    pub fn new([width,height]:[u32;2],data:&[u8])->SoftwareTexture{
+        let len=width as usize*height as usize*4;
+        if data.len()<len{
+            panic!("Not enough texture data. The length is {}, expected {}.",data.len(),len)
+        }
+
+        Self{
+            size:[width,height],
+            data:data[..len].to_vec(),
+        }
+    }
+
+    /// Creates a texture filled with transparent black.
+    pub fn empty([width,height]:[u32;2])->SoftwareTexture{
+        Self{
+            size:[width,height],
+            data:vec![0u8;width as usize*height as usize*4],
+        }
+    }
+
+    /// Loads an image from the path, flips it verticaly,
+    /// converts to 8-bit RGBA and creates a texture.
+    pub fn from_path<P:AsRef<Path>>(path:P)->Result<SoftwareTexture,ImageError>{
+        let image=open(path)?.flipv().to_rgba8();
+        Ok(Self::from_image(&image))
+    }
+
+    /// Creates a texture with the given image.
+    pub fn from_image(image:&RgbaImage)->SoftwareTexture{
+        let (w,h)=image.dimensions();
+        Self::new([w,h],image.as_ref())
+    }
+
+    pub fn size(&self)->[u32;2]{
+        self.size
+    }
+
+    pub fn data(&self)->&[u8]{
+        &self.data
+    }
+
+    /// Writes RGBA data to the given region of the texture.
+    ///
+    /// The part of the region that is out of the texture is ignored.
+    pub fn write(&mut self,[x,y,width,height]:[u32;4],data:&[u8]){
+        if x>=self.size[0]{
+            return
+        }
+
+        for row in 0..height{
+            let texture_y=y+row;
+            if texture_y>=self.size[1]{
+                break
+            }
+
+            let columns=width.min(self.size[0].saturating_sub(x)) as usize;
+            let source=row as usize*width as usize*4;
+            let destination=(texture_y as usize*self.size[0] as usize+x as usize)*4;
+
+            if let Some(source)=data.get(source..source+columns*4){
+                self.data[destination..destination+columns*4].copy_from_slice(source);
+            }
+        }
+    }
+
+    pub fn write_image(&mut self,[x,y]:[u32;2],image:&RgbaImage){
+        let (w,h)=image.dimensions();
+        self.write([x,y,w,h],image.as_ref())
+    }
+
+    /// Samples the texture with linear filtering and repeat wrapping,
+    /// as the default `Texture` does.
+    pub (crate) fn sample(&self,[u,v]:[f32;2])->Colour{
+        let [width,height]=self.size;
+        if width==0 || height==0{
+            return [0f32;4]
+        }
+
+        bilinear([width as usize,height as usize],[u,v],|x,y|{
+            let x=x.rem_euclid(width as isize) as usize;
+            let y=y.rem_euclid(height as isize) as usize;
+            let index=(y*width as usize+x)*4;
+            let mut texel=[0f32;4];
+            for (texel,&channel) in texel.iter_mut().zip(self.data[index..index+4].iter()){
+                *texel=channel as f32/255f32;
+            }
+            texel
+        })
+    }
+}
+
+/// Samples a grid of values with linear filtering.
+///
+/// `[u,v]` are normalized coordinates, texel centers are at `(i + 0.5) / size`.
+pub (crate) fn bilinear<const N:usize,F:Fn(isize,isize)->[f32;N]>(
+    [width,height]:[usize;2],
+    [u,v]:[f32;2],
+    fetch:F
+)->[f32;N]{
+    let x=u*width as f32-0.5f32;
+    let y=v*height as f32-0.5f32;
+
+    let x0=x.floor();
+    let y0=y.floor();
+
+    let fx=x-x0;
+    let fy=y-y0;
+
+    let x0=x0 as isize;
+    let y0=y0 as isize;
+
+    let samples=[
+        (fetch(x0,y0),(1f32-fx)*(1f32-fy)),
+        (fetch(x0+1,y0),fx*(1f32-fy)),
+        (fetch(x0,y0+1),(1f32-fx)*fy),
+        (fetch(x0+1,y0+1),fx*fy),
+    ];
+
+    let mut result=[0f32;N];
+    for (sample,weight) in samples.iter(){
+        for (result,value) in result.iter_mut().zip(sample.iter()){
+            *result+=value*weight;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn write_region(){
+        let mut texture=SoftwareTexture::empty([2,2]);
+
+        // The region is partly out of the texture
+        texture.write([1,1,2,2],&[1u8;16]);
+        assert_eq!(
+            texture.data(),
+            &[
+                0,0,0,0, 0,0,0,0,
+                0,0,0,0, 1,1,1,1,
+            ]
+        );
+
+        // The region is entirely to the right of the texture
+        texture.write([3,0,1,2],&[2u8;8]);
+        texture.write([2,0,1,2],&[2u8;8]);
+        assert_eq!(texture.data()[..8],[0u8;8]);
+        assert_eq!(texture.data()[12..],[1u8;4]);
+    }
+}
@@ -1,6 +1,9 @@
 use crate::{
     Colour,
-    text::Scale,
+    text::{
+        Scale,
+        GlyphMode,
+    },
 };
 
 use super::{
@@ -38,19 +41,83 @@ use ttf_parser::{
     Face,
 };
 
-use std::cell::UnsafeCell;
+use std::cell::{
+    Cell,
+    UnsafeCell,
+};
+
+/// Effects for glyphs built as distance fields (`GlyphMode::DistanceField`).
+/// 
+/// Sizes are in pixels of glyph images, so effects scale with text.
+/// An effect is disabled when its colour is transparent.
+/// 
+/// Эффекты для глифов, построенных как поля расстояний (`GlyphMode::DistanceField`).
+/// 
+/// Размеры задаются в пикселях изображений глифов, так что эффекты масштабируются вместе с текстом.
+/// Эффект отключён, если его цвет прозрачный.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct TextEffects{
+    /// Обводка.
+    pub outline_width:f32,
+    pub outline_colour:Colour,
+
+    /// [dx, dy], positive values shift the shadow right and down.
+    /// 
+    /// [dx, dy], положительные значения сдвигают тень вправо и вниз.
+    pub shadow_offset:[f32;2],
+    /// The width of the blurred edge of the shadow.
+    /// 
+    /// Ширина размытого края тени.
+    pub shadow_softness:f32,
+    pub shadow_colour:Colour,
+
+    /// Свечение.
+    pub glow_width:f32,
+    pub glow_colour:Colour,
+}
+
+impl TextEffects{
+    /// No effects.
+    /// 
+    /// Без эффектов.
+    pub fn new()->TextEffects{
+        Self{
+            outline_width:0f32,
+            outline_colour:[0f32;4],
+
+            shadow_offset:[0f32;2],
+            shadow_softness:0f32,
+            shadow_colour:[0f32;4],
+
+            glow_width:0f32,
+            glow_colour:[0f32;4],
+        }
+    }
+}
+
+impl Default for TextEffects{
+    fn default()->TextEffects{
+        TextEffects::new()
+    }
+}
 
 pub struct TextGraphics{
     vertex_buffer:VertexBuffer<TextVertex2D>,
 
     vertex_array:VertexArray<TextVertex2D>,
 
+    /// For batched glyphs, grows when needed
+    batch_buffer:VertexBuffer<TextVertex2D>,
+    batch_array:VertexArray<TextVertex2D>,
+    batch_capacity:Cell<usize>,
+
     /// For dynamic building glyphs
     glyph_image_builder:UnsafeCell<GlyphImageBuilder>,
     texture:Texture2D,
     texture_size:[f32;2],
 
     draw:Program,
+    draw_distance_field:Program,
 }
 
 impl TextGraphics{
@@ -60,9 +127,15 @@ impl TextGraphics{
 
         let program=Program::new(&vertex_shader,&fragment_shader).unwrap();
 
+        let distance_field_shader=FragmentShader::new(include_str!("shaders/text/distance_field_fragment_shader.glsl")).unwrap();
+        let distance_field_program=Program::new(&vertex_shader,&distance_field_shader).unwrap();
+
         let vertex_buffer=VertexBuffer::<TextVertex2D>::empty(4,BufferUsage::DynamicDraw).unwrap();
         let vertex_array=VertexArray::<TextVertex2D>::new(vertex_buffer.as_raw());
 
+        let batch_buffer=VertexBuffer::<TextVertex2D>::empty(6*64,BufferUsage::DynamicDraw).unwrap();
+        let batch_array=VertexArray::<TextVertex2D>::new(batch_buffer.as_raw());
+
         let texture=Texture2D::empty(
             Texture2DInternalFormat::R8,
             TextureMagFilter::Linear,
@@ -74,6 +147,10 @@ impl TextGraphics{
             vertex_buffer,
             vertex_array,
 
+            batch_buffer,
+            batch_array,
+            batch_capacity:Cell::new(6*64),
+
             glyph_image_builder:UnsafeCell::new(
                 GlyphImageBuilder::new([
                     glyph_texture_size[0] as usize,
@@ -87,6 +164,7 @@ impl TextGraphics{
             ],
 
             draw:program,
+            draw_distance_field:distance_field_program,
         }
     }
 }
@@ -119,6 +197,18 @@ impl TextGraphics{
         }
     }
 
+    /// Builds a signed distance field, see `GlyphMode::DistanceField`.
+    /// 
+    /// [offset_x,offset_y,width,height]
+    pub fn build_glyph_distance_field(&self,glyph_id:GlyphId,scale:Scale,font:&Face,spread:u32)->Option<([f32;4],&[u8])>{
+        let glyph_image_builder=unsafe{&mut *self.glyph_image_builder.get()};
+
+        glyph_image_builder.set_scale(scale);
+
+        let bounding_box=glyph_image_builder.build_distance_field(glyph_id,font,spread)?;
+        Some((bounding_box,glyph_image_builder.image()))
+    }
+
     /// [offset_x,offset_y,width,height]
     pub fn load_glyph(&self,glyph_id:GlyphId,scale:Scale,font:&Face)->Option<[f32;4]>{
         if let Some((bounding_box,image))=self.build_glyph_image(glyph_id,scale,font){
@@ -197,6 +287,28 @@ impl TextGraphics{
         &self,
         glyph_texture:&Texture2D,
         colour:Colour,
+        position:[f32;2],
+        size:[f32;2],
+        draw_parameters:&DrawParameters
+    ){
+        self.draw_glyph_region(
+            glyph_texture,
+            [0f32,0f32,1f32,1f32],
+            colour,
+            position,
+            size,
+            draw_parameters
+        )
+    }
+
+    /// Draws a part of a texture, e.g. a glyph from an atlas page.
+    /// 
+    /// `texture_coords` - [u1, v1, u2, v2], the bottom left and the top right corners.
+    pub fn draw_glyph_region(
+        &self,
+        texture:&Texture2D,
+        [u1,v1,u2,v2]:[f32;4],
+        colour:Colour,
         [x,y]:[f32;2],
         [width,height]:[f32;2],
         draw_parameters:&DrawParameters
@@ -209,17 +321,17 @@ impl TextGraphics{
         ];
 
         let vertices=&[
-            TextVertex2D::new([x1,y1],[0f32,1f32]),
-            TextVertex2D::new([x2,y1],[1f32,1f32]),
-            TextVertex2D::new([x1,y2],[0f32,0f32]),
-            TextVertex2D::new([x2,y2],[1f32,0f32]),
+            TextVertex2D::new([x1,y1],[u1,v2]),
+            TextVertex2D::new([x2,y1],[u2,v2]),
+            TextVertex2D::new([x1,y2],[u1,v1]),
+            TextVertex2D::new([x2,y2],[u2,v1]),
         ];
 
         self.load_vertices(vertices);
         self.draw.bind();
         self.vertex_array.bind();
         self.vertex_buffer.bind();
-        glyph_texture.bind();
+        texture.bind();
 
         let _=self.draw.set_uniform_value("viewport",draw_parameters.viewport());
 
@@ -240,4 +352,76 @@ impl TextGraphics{
         }
         self.vertex_array.unbind();
     }
-}
\ No newline at end of file
+
+    /// Draws triangles of glyphs from one texture with a single draw call.
+    /// 
+    /// `effects` are applied only to distance field glyphs.
+    /// 
+    /// Рисует треугольники глифов из одной текстуры за один вызов.
+    /// 
+    /// `effects` применяются только к глифам-полям расстояний.
+    pub fn draw_glyph_batch(
+        &self,
+        texture:&Texture2D,
+        vertices:&[TextVertex2D],
+        colour:Colour,
+        mode:GlyphMode,
+        effects:&TextEffects,
+        draw_parameters:&DrawParameters
+    ){
+        if vertices.is_empty(){
+            return
+        }
+
+        if vertices.len()>self.batch_capacity.get(){
+            self.batch_buffer.rewrite(vertices,BufferUsage::DynamicDraw);
+            self.batch_capacity.set(vertices.len());
+        }
+        else{
+            self.batch_buffer.write(0,vertices);
+        }
+
+        let program=match mode{
+            GlyphMode::Coverage=>&self.draw,
+            GlyphMode::DistanceField{..}=>&self.draw_distance_field,
+        };
+
+        program.bind();
+        self.batch_array.bind();
+        self.batch_buffer.bind();
+        texture.bind();
+
+        if let GlyphMode::DistanceField{spread}=mode{
+            let _=program.set_uniform_value("distance_spread",spread as f32);
+
+            let _=program.set_uniform_value("outline_width",effects.outline_width);
+            let _=program.set_uniform_value("outline_colour",effects.outline_colour);
+
+            let _=program.set_uniform_value("shadow_offset",effects.shadow_offset);
+            let _=program.set_uniform_value("shadow_softness",effects.shadow_softness);
+            let _=program.set_uniform_value("shadow_colour",effects.shadow_colour);
+
+            let _=program.set_uniform_value("glow_width",effects.glow_width);
+            let _=program.set_uniform_value("glow_colour",effects.glow_colour);
+        }
+
+        let _=program.set_uniform_value("viewport",draw_parameters.viewport());
+
+        let _=program.set_uniform_value("draw_mode",draw_parameters.flag());
+
+        if let Some(shift)=draw_parameters.shift(){
+            let _=program.set_uniform_value("vertex_shift",shift);
+        }
+
+        if let Some(rotation)=draw_parameters.rotation(){
+            let _=program.set_uniform_value("vertex_rotation",rotation);
+        }
+
+        let _=program.set_uniform_value("glyph_colour",colour);
+
+        unsafe{
+            GLCore.drawing.draw_arrays(0,vertices.len() as i32,PrimitiveType::Triangles)
+        }
+        self.batch_array.unbind();
+    }
+}
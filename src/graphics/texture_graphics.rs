@@ -252,4 +252,69 @@ impl TextureGraphics{
             self.vertex_array.unbind();
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    use cat_engine_basement::graphics::{
+        mock,
+        core::texture::{
+            Texture2DInternalFormat,
+            TextureMagFilter,
+            TextureMinFilter,
+            ImageDataFormat,
+        },
+    };
+
+    #[test]
+    fn draw_heap_object(){
+        mock::load();
+
+        let mut graphics=TextureGraphics::new(16,16,4,4,4,4);
+        let mut draw_parameters=DrawParameters::new();
+        draw_parameters.set_viewport([0f32,0f32,800f32,600f32]);
+
+        let texture=Texture2D::new(
+            Texture2DInternalFormat::RGBA8,
+            TextureMagFilter::Linear,
+            TextureMinFilter::Linear,
+            [1,1],
+            ImageDataFormat::RGBA_U8,
+            &[255u8;4]
+        ).unwrap();
+
+        let vertices=[
+            TexturedVertex2D::new([0f32,0f32],[0f32,1f32],[1f32;4]),
+            TexturedVertex2D::new([0f32,1f32],[0f32,0f32],[1f32;4]),
+            TexturedVertex2D::new([1f32,0f32],[1f32,1f32],[1f32;4]),
+            TexturedVertex2D::new([1f32,1f32],[1f32,0f32],[1f32;4]),
+        ];
+        let id=graphics.add_object_raw(&vertices,&[0,1,2,2,1,3],PrimitiveType::Triangles).unwrap();
+
+        mock::take_draw_calls();
+        graphics.draw_heap_object(id,&texture,&draw_parameters);
+
+        let draw_calls=mock::take_draw_calls();
+        assert_eq!(draw_calls.len(),1);
+        assert_eq!(draw_calls[0].name,"glMultiDrawElements");
+        assert_eq!(draw_calls[0].texture_2d,texture.as_raw().id());
+
+        mock::with_state(|state|{
+            let drawn:Vec<[f32;2]>=state.drawn_vertices::<TexturedVertex2D>(&draw_calls[0])
+                .iter()
+                .map(|vertex|vertex.tex_coords)
+                .collect();
+            let expected:Vec<[f32;2]>=[0,1,2,2,1,3].iter().map(|&i|vertices[i].tex_coords).collect();
+            assert_eq!(drawn,expected);
+
+            let program=&state.programs[&draw_calls[0].program];
+            let viewport=program.uniforms["viewport"];
+            assert_eq!(
+                program.uniform_values[&viewport],
+                vec![0f32.into(),0f32.into(),800f32.into(),600f32.into()]
+            );
+        });
+    }
+}
@@ -1,3 +1,30 @@
+// Фигуры \\
+
+// Простые фигуры (`Rectangle`, `Line`, `Circle` и т.д.) строят вершины напрямую.
+// Остальные фигуры описываются контурами (`Path`) из отрезков, кривых Безье и дуг.
+// Контуры разбиваются на треугольники (`TessellatedShape`) заливкой
+// по правилу ненулевого индекса или чётности (`FillRule`)
+// или обводкой с толщиной, соединениями, концами и пунктиром (`StrokeSettings`).
+
+mod path;
+pub use path::{
+    PathCommand,
+    Path,
+    PathBuilder,
+};
+
+mod tessellation;
+pub use tessellation::{
+    FillRule,
+    FillSettings,
+    LineJoin,
+    LineCap,
+    StrokeSettings,
+    TessellatedShape,
+};
+
+use path::arc_segments;
+
 use super::{
     Colour,
     graphics::{
@@ -234,71 +261,61 @@ impl <'o> ShapeObject<SimpleVertex2D,ElementIndexType> for Line{
     }
 }
 
-// const ellipse_points:usize=15; // Количество точек для эллипса
-
-// pub struct Circle{
-//     pub x:f32,
-//     pub y:f32,
-//     pub radius:f32,
-//     pub colour:Colour,
-// }
-
-// impl Circle{
-//     /// circle - [x, y, radius]
-//     pub const fn new(circle:[f32;3],colour:Colour)->Circle{
-//         Self{
-//             x:circle[0],
-//             y:circle[1],
-//             radius:circle[2],
-//             colour
-//         }
-//     }
-// }
-
-// impl <'o> ShapeObject<SimpleVertex2D,ElementIndexType> for Circle{
-//     type Vertices=Vec<SimpleVertex2D>;
-//     type Indices=[ElementIndexType;0];
+/// The tolerance of circles in pixels.
+///
+/// Допустимое отклонение кругов в пикселях.
+const circle_tolerance:f32=0.25f32;
 
-//     fn vertices(&self)->Vec<SimpleVertex2D>{
-//         let r_x=self.radius;
-//         let r_y=self.radius;
-
-//         let c_x=self.x;
-//         let c_y=self.y;
-
-//         let mut shape=vec![SimpleVertex2D::new(position:[c_x,c_y]};4*ellipse_points+2];
-
-//         let dx=r_x/ellipse_points as f32;
-//         let mut x=dx;
-
-//         for c in 1..ellipse_points{
-//             let y=((r_x-x)*(r_x+x)).sqrt();
-            
-//             shape[c].position=[c_x+x,c_y+y];
-
-//             shape[2*ellipse_points-c].position=[c_x+x,c_y-y];
-
-//             shape[2*ellipse_points+c].position=[c_x-x,c_y-y];
+#[derive(Clone)]
+pub struct Circle{
+    pub x:f32,
+    pub y:f32,
+    pub radius:f32,
+    pub colour:Colour,
+}
 
-//             shape[4*ellipse_points-c].position=[c_x-x,c_y+y];
+impl Circle{
+    /// circle - [x, y, radius]
+    pub const fn new(circle:[f32;3],colour:Colour)->Circle{
+        Self{
+            x:circle[0],
+            y:circle[1],
+            radius:circle[2],
+            colour
+        }
+    }
+}
 
-//             x+=dx;
-//         }
+impl ShapeObject<SimpleVertex2D,ElementIndexType> for Circle{
+    type Vertices=Vec<SimpleVertex2D>;
+    type Indices=[ElementIndexType;0];
 
-//         shape[1].position=[c_x,c_y+r_y];
-//         shape[ellipse_points].position=[c_x+r_x,c_y];
-//         shape[2*ellipse_points].position=[c_x,c_y-r_y];
-//         shape[3*ellipse_points].position=[c_x-r_x,c_y];
-//         shape[4*ellipse_points].position=[c_x,c_y+r_y];
+    fn vertices(&self)->Vec<SimpleVertex2D>{
+        let segments=arc_segments(self.radius,std::f32::consts::TAU,circle_tolerance).max(8);
+
+        let mut shape=Vec::with_capacity(segments+2);
+        shape.push(SimpleVertex2D::new([self.x,self.y],self.colour));
+
+        // Последняя точка совпадает с первой
+        for c in 0..=segments{
+            let angle=std::f32::consts::TAU*(c%segments) as f32/segments as f32;
+            shape.push(SimpleVertex2D::new(
+                [
+                    self.x+self.radius*angle.cos(),
+                    self.y+self.radius*angle.sin(),
+                ],
+                self.colour
+            ))
+        }
 
-//         shape
-//     }
+        shape
+    }
 
-//     fn indices(&self)->[ElementIndexType;0]{
-//         []
-//     }
+    fn indices(&self)->[ElementIndexType;0]{
+        []
+    }
 
-//     fn primitive_type(&self)->PrimitiveType{
-//         PrimitiveType::TriangleFan
-//     }
-// }
\ No newline at end of file
+    fn primitive_type(&self)->PrimitiveType{
+        PrimitiveType::TriangleFan
+    }
+}
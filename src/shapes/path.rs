@@ -0,0 +1,503 @@
+// Контуры и их разбиение на ломаные (flattening).
+// Дуги при построении заменяются кубическими кривыми Безье,
+// кривые разбиваются на отрезки с заданным допустимым отклонением.
+
+use std::f32::consts::{
+    FRAC_PI_2,
+    TAU,
+};
+
+/// Points closer than this are merged while flattening.
+///
+/// Точки ближе этого расстояния объединяются при разбиении.
+const merge_distance:f32=1e-4f32;
+
+/// The maximum number of segments of a curve.
+///
+/// Максимальное количество отрезков кривой.
+const max_curve_segments:usize=1024;
+
+/// A path command.
+///
+/// Команда контура.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum PathCommand{
+    /// Starts a new subpath.
+    ///
+    /// Начинает новый подконтур.
+    MoveTo([f32;2]),
+
+    LineTo([f32;2]),
+
+    /// A quadratic Bézier curve.
+    ///
+    /// Квадратичная кривая Безье.
+    QuadraticTo{
+        control:[f32;2],
+        to:[f32;2],
+    },
+
+    /// A cubic Bézier curve.
+    ///
+    /// Кубическая кривая Безье.
+    CubicTo{
+        control1:[f32;2],
+        control2:[f32;2],
+        to:[f32;2],
+    },
+
+    /// Closes the current subpath with a line to its start.
+    ///
+    /// Замыкает текущий подконтур отрезком к его началу.
+    Close,
+}
+
+/// A vector path of lines, curves and arcs.
+///
+/// Paths are filled with `Path::fill` and stroked with `Path::stroke`.
+/// Coordinates are in pixels, the y axis goes down.
+///
+/// Векторный контур из отрезков, кривых и дуг.
+///
+/// Контуры заливаются с помощью `Path::fill` и обводятся с помощью `Path::stroke`.
+/// Координаты в пикселях, ось y направлена вниз.
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct Path{
+    commands:Vec<PathCommand>,
+}
+
+impl Path{
+    pub fn new()->Path{
+        Self{
+            commands:Vec::new(),
+        }
+    }
+
+    pub fn commands(&self)->&[PathCommand]{
+        &self.commands
+    }
+
+    pub fn is_empty(&self)->bool{
+        self.commands.is_empty()
+    }
+
+    /// A line segment.
+    ///
+    /// Отрезок.
+    pub fn line(from:[f32;2],to:[f32;2])->Path{
+        let mut builder=PathBuilder::new();
+        builder.move_to(from).line_to(to);
+        builder.build()
+    }
+
+    /// An open polyline.
+    ///
+    /// Незамкнутая ломаная.
+    pub fn polyline(points:&[[f32;2]])->Path{
+        let mut builder=PathBuilder::new();
+        for &point in points{
+            builder.line_to(point);
+        }
+        builder.build()
+    }
+
+    /// A closed polygon.
+    ///
+    /// Замкнутый многоугольник.
+    pub fn polygon(points:&[[f32;2]])->Path{
+        let mut builder=PathBuilder::new();
+        for &point in points{
+            builder.line_to(point);
+        }
+        builder.close();
+        builder.build()
+    }
+
+    /// rect - [x, y, width, height]
+    pub fn rectangle([x,y,width,height]:[f32;4])->Path{
+        Path::polygon(&[
+            [x,y],
+            [x+width,y],
+            [x+width,y+height],
+            [x,y+height],
+        ])
+    }
+
+    /// A rectangle with rounded corners.
+    /// The radius is limited by half of the smaller side.
+    ///
+    /// Прямоугольник со скруглёнными углами.
+    /// Радиус ограничивается половиной меньшей стороны.
+    ///
+    /// rect - [x, y, width, height]
+    pub fn rounded_rectangle([x,y,width,height]:[f32;4],radius:f32)->Path{
+        let radius=radius.min(width.abs()*0.5f32).min(height.abs()*0.5f32).max(0f32);
+        if radius==0f32{
+            return Path::rectangle([x,y,width,height])
+        }
+
+        let [x1,y1,x2,y2]=[x+radius,y+radius,x+width-radius,y+height-radius];
+
+        let mut builder=PathBuilder::new();
+        builder.arc([x2,y1],radius,-FRAC_PI_2,FRAC_PI_2)
+            .arc([x2,y2],radius,0f32,FRAC_PI_2)
+            .arc([x1,y2],radius,FRAC_PI_2,FRAC_PI_2)
+            .arc([x1,y1],radius,std::f32::consts::PI,FRAC_PI_2)
+            .close();
+        builder.build()
+    }
+
+    pub fn circle(center:[f32;2],radius:f32)->Path{
+        Path::ellipse(center,[radius,radius])
+    }
+
+    /// radii - [horizontal, vertical]
+    pub fn ellipse(center:[f32;2],radii:[f32;2])->Path{
+        let mut builder=PathBuilder::new();
+        builder.elliptical_arc(center,radii,0f32,TAU).close();
+        builder.build()
+    }
+
+    /// Converts the path to polylines.
+    /// The tolerance is the maximum distance between curves and their segments.
+    ///
+    /// Разбивает контур на ломаные.
+    /// Допустимое отклонение - наибольшее расстояние между кривыми и их отрезками.
+    pub (crate) fn flatten(&self,tolerance:f32)->Vec<Polyline>{
+        let tolerance=tolerance.max(1e-3f32);
+
+        let mut polylines=Vec::new();
+        let mut current=Polyline::new();
+
+        for command in &self.commands{
+            match *command{
+                PathCommand::MoveTo(point)=>{
+                    current.finish(&mut polylines,false);
+                    current.push(point);
+                }
+
+                PathCommand::LineTo(point)=>{
+                    current.push(point);
+                    current.drawn=true;
+                }
+
+                PathCommand::QuadraticTo{control,to}=>{
+                    let from=current.last();
+                    // Отклонение n отрезков не больше |p0-2p1+p2|/(4n²)
+                    let deviation=length(add(sub(from,control),sub(to,control)));
+                    let segments=curve_segments(deviation*0.25f32,tolerance);
+                    current.drawn=true;
+
+                    for c in 1..=segments{
+                        let t=c as f32/segments as f32;
+                        let u=1f32-t;
+                        current.push([
+                            u*u*from[0]+2f32*u*t*control[0]+t*t*to[0],
+                            u*u*from[1]+2f32*u*t*control[1]+t*t*to[1],
+                        ])
+                    }
+                }
+
+                PathCommand::CubicTo{control1,control2,to}=>{
+                    let from=current.last();
+                    // Отклонение n отрезков не больше 3max(|p0-2p1+p2|,|p1-2p2+p3|)/(4n²)
+                    let deviation=length(add(sub(from,control1),sub(control2,control1)))
+                        .max(length(add(sub(control1,control2),sub(to,control2))));
+                    let segments=curve_segments(deviation*0.75f32,tolerance);
+                    current.drawn=true;
+
+                    for c in 1..=segments{
+                        let t=c as f32/segments as f32;
+                        let u=1f32-t;
+                        let [a,b,c,d]=[u*u*u,3f32*u*u*t,3f32*u*t*t,t*t*t];
+                        current.push([
+                            a*from[0]+b*control1[0]+c*control2[0]+d*to[0],
+                            a*from[1]+b*control1[1]+c*control2[1]+d*to[1],
+                        ])
+                    }
+                }
+
+                PathCommand::Close=>{
+                    let start=current.points.first().copied();
+                    current.finish(&mut polylines,true);
+                    // Следующий подконтур начинается в начале замкнутого
+                    if let Some(start)=start{
+                        current.push(start)
+                    }
+                }
+            }
+        }
+
+        current.finish(&mut polylines,false);
+
+        polylines
+    }
+}
+
+/// Builds paths.
+///
+/// Drawing commands without a current point start a new subpath,
+/// as in the HTML canvas.
+///
+/// Строит контуры.
+///
+/// Команды рисования без текущей точки начинают новый подконтур,
+/// как в HTML canvas.
+#[derive(Clone,Debug,Default)]
+pub struct PathBuilder{
+    commands:Vec<PathCommand>,
+    current:Option<[f32;2]>,
+    start:[f32;2],
+}
+
+impl PathBuilder{
+    pub fn new()->PathBuilder{
+        Self{
+            commands:Vec::new(),
+            current:None,
+            start:[0f32;2],
+        }
+    }
+
+    /// The end of the last command.
+    ///
+    /// Конец последней команды.
+    pub fn current_point(&self)->Option<[f32;2]>{
+        self.current
+    }
+
+    pub fn move_to(&mut self,point:[f32;2])->&mut PathBuilder{
+        self.commands.push(PathCommand::MoveTo(point));
+        self.current=Some(point);
+        self.start=point;
+        self
+    }
+
+    pub fn line_to(&mut self,point:[f32;2])->&mut PathBuilder{
+        if self.current.is_none(){
+            return self.move_to(point)
+        }
+
+        self.commands.push(PathCommand::LineTo(point));
+        self.current=Some(point);
+        self
+    }
+
+    pub fn quadratic_to(&mut self,control:[f32;2],to:[f32;2])->&mut PathBuilder{
+        if self.current.is_none(){
+            self.move_to(control);
+        }
+
+        self.commands.push(PathCommand::QuadraticTo{control,to});
+        self.current=Some(to);
+        self
+    }
+
+    pub fn cubic_to(&mut self,control1:[f32;2],control2:[f32;2],to:[f32;2])->&mut PathBuilder{
+        if self.current.is_none(){
+            self.move_to(control1);
+        }
+
+        self.commands.push(PathCommand::CubicTo{control1,control2,to});
+        self.current=Some(to);
+        self
+    }
+
+    /// A circular arc.
+    ///
+    /// Angles are in radians from the x axis to the y axis (clockwise on the screen).
+    /// A negative sweep angle draws the arc counterclockwise.
+    /// The arc is connected to the current point with a line.
+    ///
+    /// Дуга окружности.
+    ///
+    /// Углы в радианах от оси x к оси y (по часовой стрелке на экране).
+    /// Отрицательный угол дуги рисует её против часовой стрелки.
+    /// Дуга соединяется с текущей точкой отрезком.
+    pub fn arc(&mut self,center:[f32;2],radius:f32,start_angle:f32,sweep_angle:f32)->&mut PathBuilder{
+        self.elliptical_arc(center,[radius,radius],start_angle,sweep_angle)
+    }
+
+    /// An elliptical arc with the axes along the coordinate axes.
+    ///
+    /// Дуга эллипса с осями вдоль осей координат.
+    ///
+    /// radii - [horizontal, vertical]
+    pub fn elliptical_arc(&mut self,center:[f32;2],radii:[f32;2],start_angle:f32,sweep_angle:f32)->&mut PathBuilder{
+        let point=|angle:f32|[center[0]+radii[0]*angle.cos(),center[1]+radii[1]*angle.sin()];
+        // Производная точки по углу
+        let tangent=|angle:f32|[-radii[0]*angle.sin(),radii[1]*angle.cos()];
+
+        self.line_to(point(start_angle));
+
+        let sweep_angle=sweep_angle.clamp(-TAU,TAU);
+        // Части не больше четверти окружности
+        let parts=(sweep_angle.abs()/FRAC_PI_2).ceil().max(1f32) as usize;
+        let step=sweep_angle/parts as f32;
+        // Длина касательных кубической кривой для дуги
+        let k=4f32/3f32*(step*0.25f32).tan();
+
+        let mut angle=start_angle;
+        for _ in 0..parts{
+            let end=angle+step;
+            let [from,to]=[point(angle),point(end)];
+            let [tangent1,tangent2]=[tangent(angle),tangent(end)];
+
+            self.cubic_to(
+                [from[0]+k*tangent1[0],from[1]+k*tangent1[1]],
+                [to[0]-k*tangent2[0],to[1]-k*tangent2[1]],
+                to
+            );
+            angle=end;
+        }
+
+        self
+    }
+
+    /// Closes the current subpath.
+    /// The next drawing command starts at the start of the closed subpath.
+    ///
+    /// Замыкает текущий подконтур.
+    /// Следующая команда рисования начинается в начале замкнутого подконтура.
+    pub fn close(&mut self)->&mut PathBuilder{
+        if self.current.is_some(){
+            self.commands.push(PathCommand::Close);
+            self.current=Some(self.start);
+        }
+        self
+    }
+
+    /// Returns the built path and clears the builder.
+    ///
+    /// Возвращает построенный контур и очищает строитель.
+    pub fn build(&mut self)->Path{
+        self.current=None;
+        Path{
+            commands:std::mem::take(&mut self.commands),
+        }
+    }
+}
+
+/// A flattened subpath.
+pub (crate) struct Polyline{
+    pub points:Vec<[f32;2]>,
+    pub closed:bool,
+    // Были ли команды рисования (отрезок нулевой длины даёт одну точку)
+    drawn:bool,
+}
+
+impl Polyline{
+    fn new()->Polyline{
+        Self{
+            points:Vec::new(),
+            closed:false,
+            drawn:false,
+        }
+    }
+
+    pub fn open(points:Vec<[f32;2]>)->Polyline{
+        Self{
+            points,
+            closed:false,
+            drawn:true,
+        }
+    }
+
+    fn last(&self)->[f32;2]{
+        self.points.last().copied().unwrap_or([0f32;2])
+    }
+
+    fn push(&mut self,point:[f32;2]){
+        match self.points.last(){
+            Some(&last) if length(sub(point,last))<merge_distance=>{}
+            _=>self.points.push(point),
+        }
+    }
+
+    /// Moves the polyline to the list and starts a new one.
+    fn finish(&mut self,polylines:&mut Vec<Polyline>,closed:bool){
+        let mut polyline=std::mem::replace(self,Polyline::new());
+
+        if closed && polyline.points.len()>1{
+            // Замыкающая точка совпадает с первой
+            let first=polyline.points[0];
+            if length(sub(polyline.last(),first))<merge_distance{
+                polyline.points.pop();
+            }
+        }
+
+        // Точка после `move_to` без рисования пропускается
+        if !polyline.points.is_empty() && (polyline.drawn || closed){
+            polyline.closed=closed && polyline.points.len()>1;
+            polylines.push(polyline)
+        }
+    }
+}
+
+/// The number of segments of an arc with the given deviation.
+///
+/// Количество отрезков дуги с данным отклонением.
+pub (crate) fn arc_segments(radius:f32,angle:f32,tolerance:f32)->usize{
+    let radius=radius.abs();
+    if radius<=tolerance{
+        return 1
+    }
+    // Отклонение хорды с углом a - r(1-cos(a/2))
+    let step=2f32*(1f32-tolerance/radius).acos();
+    ((angle.abs()/step).ceil() as usize).clamp(1,max_curve_segments)
+}
+
+/// The number of segments of a curve with the deviation `d/n²`.
+fn curve_segments(deviation:f32,tolerance:f32)->usize{
+    ((deviation/tolerance).sqrt().ceil() as usize).clamp(1,max_curve_segments)
+}
+
+pub (crate) fn add(a:[f32;2],b:[f32;2])->[f32;2]{
+    [a[0]+b[0],a[1]+b[1]]
+}
+
+pub (crate) fn sub(a:[f32;2],b:[f32;2])->[f32;2]{
+    [a[0]-b[0],a[1]-b[1]]
+}
+
+pub (crate) fn length(a:[f32;2])->f32{
+    a[0].hypot(a[1])
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn flattening(){
+        let mut builder=PathBuilder::new();
+        builder.move_to([0f32,0f32])
+            .line_to([10f32,0f32])
+            .quadratic_to([20f32,0f32],[20f32,10f32])
+            .close()
+            .line_to([0f32,20f32]);
+        let path=builder.build();
+
+        let polylines=path.flatten(0.1f32);
+        assert_eq!(polylines.len(),2);
+
+        assert!(polylines[0].closed);
+        assert_eq!(polylines[0].points[0],[0f32,0f32]);
+        assert_eq!(*polylines[0].points.last().unwrap(),[20f32,10f32]);
+        assert!(polylines[0].points.len()>4);
+
+        // Продолжение после замыкания начинается в начале подконтура
+        assert!(!polylines[1].closed);
+        assert_eq!(polylines[1].points,vec![[0f32,0f32],[0f32,20f32]]);
+
+        // Точки круга лежат на окружности с допустимым отклонением
+        let circle=Path::circle([5f32,5f32],100f32).flatten(0.25f32);
+        assert_eq!(circle.len(),1);
+        assert!(circle[0].closed);
+        for window in circle[0].points.windows(2){
+            let middle=[(window[0][0]+window[1][0])*0.5f32,(window[0][1]+window[1][1])*0.5f32];
+            let distance=100f32-length(sub(middle,[5f32,5f32]));
+            assert!((-0.05f32..=0.3f32).contains(&distance),"{}",distance);
+        }
+    }
+}
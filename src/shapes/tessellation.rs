@@ -0,0 +1,866 @@
+// Разбиение контуров на треугольники.
+
+// Заливка \\
+
+// Ломаные контура разбиваются на горизонтальные полосы по всем вершинам
+// и точкам пересечения рёбер. Внутри полосы рёбра не пересекаются,
+// поэтому они упорядочиваются по x, а промежутки между ними,
+// которые внутри контура по правилу заливки, становятся трапециями.
+
+// Обводка \\
+
+// Каждый отрезок ломаной становится прямоугольником шириной в толщину линии.
+// Стыки отрезков закрываются соединениями (`LineJoin`), концы - `LineCap`.
+// Пунктир разрезает ломаные на отдельные штрихи до обводки.
+// Части обводки могут перекрываться, поэтому полупрозрачный цвет
+// на стыках получается плотнее.
+
+use super::{
+    Path,
+    path::{
+        Polyline,
+        arc_segments,
+        add,
+        sub,
+        length,
+    },
+};
+
+use crate::{
+    Colour,
+    graphics::{
+        ShapeObject,
+        SimpleVertex2D,
+        PrimitiveType,
+        ElementIndexType,
+    },
+};
+
+use std::f32::consts::PI;
+
+/// Slabs thinner than this are skipped.
+///
+/// Полосы тоньше этого пропускаются.
+const min_slab_height:f32=1e-5f32;
+
+/// The number of vertices that `ElementIndexType` can index.
+///
+/// Количество вершин, которое можно обозначить `ElementIndexType`.
+const max_vertices:usize=ElementIndexType::MAX as usize+1;
+
+/// The rule that defines the inside of a path.
+///
+/// Правило, определяющее внутреннюю часть контура.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum FillRule{
+    /// A point is inside if the path winds around it a non-zero number of times.
+    ///
+    /// Точка внутри, если контур обходит её ненулевое число раз.
+    NonZero,
+
+    /// A point is inside if a ray from it crosses the path an odd number of times.
+    /// Nested subpaths make holes regardless of their direction.
+    ///
+    /// Точка внутри, если луч из неё пересекает контур нечётное число раз.
+    /// Вложенные подконтуры образуют дыры независимо от направления.
+    EvenOdd,
+}
+
+/// Path fill settings.
+///
+/// Настройки заливки контура.
+#[derive(Clone,Copy,Debug)]
+pub struct FillSettings{
+    /// The default is `FillRule::NonZero`.
+    ///
+    /// По умолчанию `FillRule::NonZero`.
+    pub rule:FillRule,
+
+    /// The maximum distance between curves and their segments in pixels.
+    ///
+    /// The default is 0.25.
+    ///
+    /// Наибольшее расстояние между кривыми и их отрезками в пикселях.
+    ///
+    /// По умолчанию 0.25.
+    pub tolerance:f32,
+}
+
+impl FillSettings{
+    pub fn new()->FillSettings{
+        Self{
+            rule:FillRule::NonZero,
+            tolerance:0.25f32,
+        }
+    }
+}
+
+impl Default for FillSettings{
+    fn default()->FillSettings{
+        FillSettings::new()
+    }
+}
+
+/// The shape of corners between stroked segments.
+///
+/// Форма углов между обводимыми отрезками.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum LineJoin{
+    /// A sharp corner, replaced with `Bevel` if it's longer than the miter limit.
+    ///
+    /// Острый угол, заменяется на `Bevel`, если он длиннее ограничения.
+    Miter,
+
+    /// A cut corner.
+    ///
+    /// Срезанный угол.
+    Bevel,
+
+    Round,
+}
+
+/// The shape of the ends of open subpaths and dashes.
+///
+/// Форма концов незамкнутых подконтуров и штрихов.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum LineCap{
+    /// The line ends exactly at the end point.
+    ///
+    /// Линия заканчивается точно в конечной точке.
+    Butt,
+
+    /// The line is extended by half of the width.
+    ///
+    /// Линия продлевается на половину толщины.
+    Square,
+
+    Round,
+}
+
+/// Path stroke settings.
+///
+/// Настройки обводки контура.
+#[derive(Clone,Debug)]
+pub struct StrokeSettings{
+    /// The line width in pixels.
+    ///
+    /// The default is 1.
+    ///
+    /// Толщина линии в пикселях.
+    ///
+    /// По умолчанию 1.
+    pub width:f32,
+
+    /// The default is `LineJoin::Miter`.
+    ///
+    /// По умолчанию `LineJoin::Miter`.
+    pub join:LineJoin,
+
+    /// The default is `LineCap::Butt`.
+    ///
+    /// По умолчанию `LineCap::Butt`.
+    pub cap:LineCap,
+
+    /// The maximum ratio of the miter length to the line width.
+    ///
+    /// The default is 4.
+    ///
+    /// Наибольшее отношение длины острого угла к толщине линии.
+    ///
+    /// По умолчанию 4.
+    pub miter_limit:f32,
+
+    /// Alternating lengths of dashes and gaps in pixels.
+    /// A list of an odd length is repeated twice.
+    /// Empty or shorter than `tolerance` in total - a solid line.
+    ///
+    /// The default is empty.
+    ///
+    /// Чередующиеся длины штрихов и промежутков в пикселях.
+    /// Список нечётной длины повторяется дважды.
+    /// Пустой или короче `tolerance` в сумме - сплошная линия.
+    ///
+    /// По умолчанию пустой.
+    pub dashes:Vec<f32>,
+
+    /// The distance into the dash pattern at the start of subpaths.
+    ///
+    /// The default is 0.
+    ///
+    /// Сдвиг пунктира в начале подконтуров.
+    ///
+    /// По умолчанию 0.
+    pub dash_offset:f32,
+
+    /// The maximum distance between curves and their segments in pixels.
+    ///
+    /// The default is 0.25.
+    ///
+    /// Наибольшее расстояние между кривыми и их отрезками в пикселях.
+    ///
+    /// По умолчанию 0.25.
+    pub tolerance:f32,
+}
+
+impl StrokeSettings{
+    pub fn new()->StrokeSettings{
+        Self{
+            width:1f32,
+            join:LineJoin::Miter,
+            cap:LineCap::Butt,
+            miter_limit:4f32,
+            dashes:Vec::new(),
+            dash_offset:0f32,
+            tolerance:0.25f32,
+        }
+    }
+}
+
+impl Default for StrokeSettings{
+    fn default()->StrokeSettings{
+        StrokeSettings::new()
+    }
+}
+
+/// Triangles of a filled or stroked path.
+///
+/// Indices are `ElementIndexType`, so a shape may have up to 65536 vertices.
+/// Triangles that don't fit are left out:
+/// a fill or a stroke at the limit returns only the part that fits.
+///
+/// Треугольники залитого или обведённого контура.
+///
+/// Индексы типа `ElementIndexType`, поэтому у фигуры может быть до 65536 вершин.
+/// Не поместившиеся треугольники пропускаются:
+/// заливка или обводка на пределе возвращает только поместившуюся часть.
+#[derive(Clone,Debug,Default)]
+pub struct TessellatedShape{
+    pub vertices:Vec<SimpleVertex2D>,
+    pub indices:Vec<ElementIndexType>,
+}
+
+impl TessellatedShape{
+    pub fn new()->TessellatedShape{
+        Self{
+            vertices:Vec::new(),
+            indices:Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self)->bool{
+        self.indices.is_empty()
+    }
+
+    /// Adds triangles of another shape.
+    /// Nothing is added if the vertices don't fit.
+    ///
+    /// Добавляет треугольники другой фигуры.
+    /// Если вершины не помещаются, ничего не добавляется.
+    pub fn append(&mut self,shape:&TessellatedShape){
+        if !self.fits(shape.vertices.len()){
+            return
+        }
+
+        let base=self.vertices.len() as ElementIndexType;
+        self.vertices.extend_from_slice(&shape.vertices);
+        self.indices.extend(shape.indices.iter().map(|index|base+index));
+    }
+
+    /// Sets the colour of all vertices.
+    ///
+    /// Устанавливает цвет всех вершин.
+    pub fn set_colour(&mut self,colour:Colour){
+        for vertex in &mut self.vertices{
+            vertex.colour=colour
+        }
+    }
+
+    /// Checks whether the number of vertices can be added.
+    fn fits(&self,vertices:usize)->bool{
+        self.vertices.len()+vertices<=max_vertices
+    }
+
+    /// The caller checks that the vertex fits.
+    fn push_vertex(&mut self,position:[f32;2],colour:Colour)->ElementIndexType{
+        let index=self.vertices.len() as ElementIndexType;
+        self.vertices.push(SimpleVertex2D::new(position,colour));
+        index
+    }
+
+    fn push_triangle(&mut self,points:[[f32;2];3],colour:Colour){
+        if !self.fits(3){
+            return
+        }
+
+        for point in points{
+            let index=self.push_vertex(point,colour);
+            self.indices.push(index)
+        }
+    }
+
+    /// Points go around the quadrilateral.
+    fn push_quadrilateral(&mut self,points:[[f32;2];4],colour:Colour){
+        if !self.fits(4){
+            return
+        }
+
+        let base=self.vertices.len() as ElementIndexType;
+        for point in points{
+            self.push_vertex(point,colour);
+        }
+        self.indices.extend_from_slice(&[base,base+1,base+2,base,base+2,base+3]);
+    }
+
+    /// A fan of triangles from the center rotating the `start` vector by the angle.
+    fn push_fan(&mut self,center:[f32;2],start:[f32;2],angle:f32,tolerance:f32,colour:Colour){
+        let segments=arc_segments(length(start),angle,tolerance);
+        if !self.fits(segments+2){
+            return
+        }
+
+        let center_index=self.push_vertex(center,colour);
+        let mut previous=self.push_vertex(add(center,start),colour);
+        for c in 1..=segments{
+            let (sin,cos)=(angle*c as f32/segments as f32).sin_cos();
+            let vector=[start[0]*cos-start[1]*sin,start[0]*sin+start[1]*cos];
+            let index=self.push_vertex(add(center,vector),colour);
+            self.indices.extend_from_slice(&[center_index,previous,index]);
+            previous=index;
+        }
+    }
+}
+
+impl ShapeObject<SimpleVertex2D,ElementIndexType> for TessellatedShape{
+    type Vertices=Vec<SimpleVertex2D>;
+    type Indices=Vec<ElementIndexType>;
+
+    fn vertices(&self)->Vec<SimpleVertex2D>{
+        self.vertices.clone()
+    }
+
+    fn indices(&self)->Vec<ElementIndexType>{
+        self.indices.clone()
+    }
+
+    fn primitive_type(&self)->PrimitiveType{
+        PrimitiveType::Triangles
+    }
+}
+
+/// A non-horizontal edge going down.
+struct Edge{
+    top:[f32;2],
+    bottom:[f32;2],
+    // 1, если ребро в контуре идёт вниз, -1 - вверх
+    winding:i32,
+}
+
+impl Edge{
+    fn x(&self,y:f32)->f32{
+        let t=(y-self.top[1])/(self.bottom[1]-self.top[1]);
+        self.top[0]+(self.bottom[0]-self.top[0])*t.clamp(0f32,1f32)
+    }
+
+    /// The y coordinate of the intersection inside both edges.
+    fn intersection(&self,edge:&Edge)->Option<f32>{
+        let top=self.top[1].max(edge.top[1]);
+        let bottom=self.bottom[1].min(edge.bottom[1]);
+        if top>=bottom{
+            return None
+        }
+
+        // Разность x рёбер линейна по y
+        let difference_top=self.x(top)-edge.x(top);
+        let difference_bottom=self.x(bottom)-edge.x(bottom);
+        if difference_top*difference_bottom>=0f32{
+            return None
+        }
+
+        let t=difference_top/(difference_top-difference_bottom);
+        Some(top+(bottom-top)*t)
+    }
+}
+
+impl Path{
+    /// Fills the path, subpaths are closed automatically.
+    ///
+    /// Заливает контур, подконтуры замыкаются автоматически.
+    pub fn fill(&self,settings:&FillSettings,colour:Colour)->TessellatedShape{
+        let mut edges=Vec::new();
+        for polyline in self.flatten(settings.tolerance){
+            let points=&polyline.points;
+            for (c,&a) in points.iter().enumerate(){
+                let b=points[(c+1)%points.len()];
+                if a[1]<b[1]{
+                    edges.push(Edge{top:a,bottom:b,winding:1})
+                }
+                else if a[1]>b[1]{
+                    edges.push(Edge{top:b,bottom:a,winding:-1})
+                }
+            }
+        }
+
+        // Границы полос - вершины и пересечения рёбер
+        let mut ys=Vec::with_capacity(edges.len()*2);
+        for (c,edge) in edges.iter().enumerate(){
+            ys.push(edge.top[1]);
+            ys.push(edge.bottom[1]);
+            for other in &edges[c+1..]{
+                if let Some(y)=edge.intersection(other){
+                    ys.push(y)
+                }
+            }
+        }
+        ys.sort_unstable_by(f32::total_cmp);
+        ys.dedup();
+
+        let mut shape=TessellatedShape::new();
+        // Рёбра полосы: x сверху, x снизу, x в середине, направление
+        let mut active:Vec<(f32,f32,f32,i32)>=Vec::new();
+
+        for slab in ys.windows(2){
+            // Все полосы состоят из четырёхугольников
+            if !shape.fits(4){
+                break
+            }
+
+            let [top,bottom]=[slab[0],slab[1]];
+            if bottom-top<min_slab_height{
+                continue
+            }
+            let middle=(top+bottom)*0.5f32;
+
+            active.clear();
+            for edge in &edges{
+                if edge.top[1]<middle && edge.bottom[1]>middle{
+                    active.push((edge.x(top),edge.x(bottom),edge.x(middle),edge.winding))
+                }
+            }
+            active.sort_unstable_by(|a,b|a.2.total_cmp(&b.2));
+
+            let mut winding=0;
+            let mut left=None;
+            for &(x_top,x_bottom,_,edge_winding) in &active{
+                let was_inside=is_inside(winding,settings.rule);
+                winding+=edge_winding;
+                let inside=is_inside(winding,settings.rule);
+
+                if !was_inside && inside{
+                    left=Some((x_top,x_bottom))
+                }
+                else if was_inside && !inside{
+                    match left.take(){
+                        // Промежутки нулевой ширины пропускаются
+                        Some((left_top,left_bottom)) if x_top>left_top || x_bottom>left_bottom=>{
+                            shape.push_quadrilateral(
+                                [
+                                    [left_top,top],
+                                    [x_top,top],
+                                    [x_bottom,bottom],
+                                    [left_bottom,bottom],
+                                ],
+                                colour
+                            )
+                        }
+                        _=>{}
+                    }
+                }
+            }
+        }
+
+        shape
+    }
+
+    /// Strokes the path.
+    ///
+    /// Обводит контур.
+    pub fn stroke(&self,settings:&StrokeSettings,colour:Colour)->TessellatedShape{
+        let mut shape=TessellatedShape::new();
+        if settings.width<=0f32{
+            return shape
+        }
+
+        let dashes=dash_pattern(&settings.dashes,settings.tolerance);
+
+        for polyline in self.flatten(settings.tolerance){
+            match &dashes{
+                Some(dashes)=>{
+                    for dash in split_dashes(&polyline,dashes,settings.dash_offset){
+                        stroke_polyline(&mut shape,&dash,settings,colour)
+                    }
+                }
+                None=>stroke_polyline(&mut shape,&polyline,settings,colour),
+            }
+        }
+
+        shape
+    }
+}
+
+fn is_inside(winding:i32,rule:FillRule)->bool{
+    match rule{
+        FillRule::NonZero=>winding!=0,
+        FillRule::EvenOdd=>winding%2!=0,
+    }
+}
+
+/// Checks the pattern and repeats it if its length is odd.
+///
+/// Patterns shorter than the tolerance are rejected,
+/// otherwise a line would be cut into countless dashes.
+fn dash_pattern(dashes:&[f32],tolerance:f32)->Option<Vec<f32>>{
+    let total:f32=dashes.iter().sum();
+    if dashes.is_empty()
+        || dashes.iter().any(|&length|length<0f32 || !length.is_finite())
+        || total<=0f32
+        || total<tolerance
+    {
+        return None
+    }
+
+    let mut pattern=dashes.to_vec();
+    if pattern.len()%2==1{
+        pattern.extend_from_slice(dashes)
+    }
+    Some(pattern)
+}
+
+/// Cuts a polyline into dashes.
+fn split_dashes(polyline:&Polyline,pattern:&[f32],offset:f32)->Vec<Polyline>{
+    let mut points=polyline.points.clone();
+    if polyline.closed{
+        points.push(points[0])
+    }
+
+    // Начальное положение в образце
+    let total:f32=pattern.iter().sum();
+    let mut index=0;
+    let mut remaining=pattern[0];
+    let mut skip=offset.rem_euclid(total);
+    // Штрих нулевой длины в начале образца остаётся
+    while skip>0f32 && skip>=remaining{
+        skip-=remaining;
+        index=(index+1)%pattern.len();
+        remaining=pattern[index];
+    }
+    remaining-=skip;
+
+    let mut dashes=Vec::new();
+    let mut dash=Vec::new();
+    if index%2==0{
+        dash.push(points[0])
+    }
+
+    for segment in points.windows(2){
+        let [a,b]=[segment[0],segment[1]];
+        let segment_length=length(sub(b,a));
+        let mut position=0f32;
+
+        while segment_length-position>remaining{
+            position+=remaining;
+            let t=position/segment_length;
+            let point=[a[0]+(b[0]-a[0])*t,a[1]+(b[1]-a[1])*t];
+
+            if index%2==0{
+                dash.push(point);
+                dashes.push(dash_polyline(std::mem::take(&mut dash)))
+            }
+            else{
+                dash.push(point)
+            }
+
+            index=(index+1)%pattern.len();
+            remaining=pattern[index];
+        }
+
+        remaining-=segment_length-position;
+        if index%2==0{
+            dash.push(b)
+        }
+    }
+
+    if !dash.is_empty(){
+        dashes.push(dash_polyline(dash))
+    }
+
+    dashes
+}
+
+/// An open polyline without repeated points.
+fn dash_polyline(mut points:Vec<[f32;2]>)->Polyline{
+    points.dedup();
+    Polyline::open(points)
+}
+
+fn stroke_polyline(shape:&mut TessellatedShape,polyline:&Polyline,settings:&StrokeSettings,colour:Colour){
+    let half_width=settings.width*0.5f32;
+    let points=&polyline.points;
+
+    // Отрезок нулевой длины рисуется только концами
+    if points.len()==1{
+        let point=points[0];
+        match settings.cap{
+            LineCap::Butt=>{}
+            LineCap::Square=>shape.push_quadrilateral(
+                [
+                    [point[0]-half_width,point[1]-half_width],
+                    [point[0]+half_width,point[1]-half_width],
+                    [point[0]+half_width,point[1]+half_width],
+                    [point[0]-half_width,point[1]+half_width],
+                ],
+                colour
+            ),
+            LineCap::Round=>shape.push_fan(point,[half_width,0f32],2f32*PI,settings.tolerance,colour),
+        }
+        return
+    }
+
+    let segment_count=if polyline.closed{points.len()}else{points.len()-1};
+    // Направление и нормаль длиной в половину толщины
+    let segment=|c:usize|{
+        let [a,b]=[points[c],points[(c+1)%points.len()]];
+        let direction=sub(b,a);
+        let segment_length=length(direction);
+        let direction=[direction[0]/segment_length,direction[1]/segment_length];
+        let normal=[-direction[1]*half_width,direction[0]*half_width];
+        (a,b,direction,normal)
+    };
+
+    for c in 0..segment_count{
+        let (a,b,_,normal)=segment(c);
+        shape.push_quadrilateral([add(a,normal),add(b,normal),sub(b,normal),sub(a,normal)],colour);
+
+        if c+1<segment_count || polyline.closed{
+            let (_,_,direction,_)=segment(c);
+            let (_,_,next_direction,next_normal)=segment((c+1)%segment_count);
+            push_join(shape,b,[direction,normal],[next_direction,next_normal],settings,colour);
+        }
+    }
+
+    if !polyline.closed{
+        let (a,_,direction,normal)=segment(0);
+        push_cap(shape,a,[-direction[0],-direction[1]],normal,settings,colour);
+
+        let (_,b,direction,normal)=segment(segment_count-1);
+        push_cap(shape,b,direction,[-normal[0],-normal[1]],settings,colour);
+    }
+}
+
+/// Closes the corner between two segments.
+fn push_join(
+    shape:&mut TessellatedShape,
+    point:[f32;2],
+    [direction,normal]:[[f32;2];2],
+    [next_direction,next_normal]:[[f32;2];2],
+    settings:&StrokeSettings,
+    colour:Colour
+){
+    let cross=direction[0]*next_direction[1]-direction[1]*next_direction[0];
+    let dot=direction[0]*next_direction[0]+direction[1]*next_direction[1];
+    // Отрезки продолжают друг друга
+    if cross.abs()<1e-6f32 && dot>0f32{
+        return
+    }
+
+    // Внешняя сторона угла противоположна повороту
+    let side=if cross>0f32{-1f32}else{1f32};
+    let outer=[normal[0]*side,normal[1]*side];
+    let next_outer=[next_normal[0]*side,next_normal[1]*side];
+
+    match settings.join{
+        LineJoin::Round=>{
+            let outer_cross=outer[0]*next_outer[1]-outer[1]*next_outer[0];
+            let outer_dot=outer[0]*next_outer[0]+outer[1]*next_outer[1];
+            shape.push_fan(point,outer,outer_cross.atan2(outer_dot),settings.tolerance,colour)
+        }
+
+        LineJoin::Miter=>{
+            let bisector=add(outer,next_outer);
+            let bisector_length=length(bisector);
+            let half_width=length(outer);
+            if bisector_length>1e-6f32{
+                // Косинус половины угла между нормалями
+                let cos=(bisector[0]*outer[0]+bisector[1]*outer[1])/(bisector_length*half_width);
+                if cos*settings.miter_limit>=1f32{
+                    let distance=half_width/(cos*bisector_length);
+                    let miter=[point[0]+bisector[0]*distance,point[1]+bisector[1]*distance];
+                    shape.push_quadrilateral([point,add(point,outer),miter,add(point,next_outer)],colour);
+                    return
+                }
+            }
+            shape.push_triangle([point,add(point,outer),add(point,next_outer)],colour)
+        }
+
+        LineJoin::Bevel=>shape.push_triangle([point,add(point,outer),add(point,next_outer)],colour),
+    }
+}
+
+/// Adds a cap at the end going in the direction,
+/// the normal is rotated by a quarter turn to the direction.
+fn push_cap(
+    shape:&mut TessellatedShape,
+    point:[f32;2],
+    direction:[f32;2],
+    normal:[f32;2],
+    settings:&StrokeSettings,
+    colour:Colour
+){
+    let half_width=settings.width*0.5f32;
+    match settings.cap{
+        LineCap::Butt=>{}
+        LineCap::Square=>{
+            let extension=[direction[0]*half_width,direction[1]*half_width];
+            shape.push_quadrilateral(
+                [
+                    add(point,normal),
+                    add(add(point,normal),extension),
+                    add(sub(point,normal),extension),
+                    sub(point,normal),
+                ],
+                colour
+            )
+        }
+        LineCap::Round=>shape.push_fan(point,normal,PI,settings.tolerance,colour),
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    use crate::shapes::PathBuilder;
+
+    fn area(shape:&TessellatedShape)->f32{
+        shape.indices.chunks(3)
+            .map(|triangle|{
+                let [a,b,c]=[0,1,2].map(|c|shape.vertices[triangle[c] as usize].position);
+                ((b[0]-a[0])*(c[1]-a[1])-(c[0]-a[0])*(b[1]-a[1])).abs()*0.5f32
+            })
+            .sum()
+    }
+
+    fn assert_area(shape:&TessellatedShape,expected:f32){
+        let area=area(shape);
+        assert!((area-expected).abs()<=expected*0.01f32,"area {} expected {}",area,expected);
+    }
+
+    #[test]
+    fn fill_rules(){
+        let colour=[1f32;4];
+        let non_zero=FillSettings::new();
+        let even_odd=FillSettings{
+            rule:FillRule::EvenOdd,
+            ..FillSettings::new()
+        };
+
+        assert_area(&Path::rectangle([10f32,20f32,30f32,40f32]).fill(&non_zero,colour),1200f32);
+        assert_area(&Path::circle([0f32,0f32],50f32).fill(&non_zero,colour),PI*2500f32);
+        assert_area(&Path::rounded_rectangle([0f32,0f32,100f32,50f32],10f32).fill(&non_zero,colour),5000f32-(4f32-PI)*100f32);
+
+        // Квадрат с квадратом внутри в том же направлении
+        let mut builder=PathBuilder::new();
+        builder.move_to([0f32,0f32]).line_to([30f32,0f32]).line_to([30f32,30f32]).line_to([0f32,30f32]).close()
+            .move_to([10f32,10f32]).line_to([20f32,10f32]).line_to([20f32,20f32]).line_to([10f32,20f32]).close();
+        let nested=builder.build();
+        assert_area(&nested.fill(&non_zero,colour),900f32);
+        assert_area(&nested.fill(&even_odd,colour),800f32);
+
+        // Пентаграмма: центральный пятиугольник обходится дважды
+        let star:Vec<[f32;2]>=(0..5).map(|c|{
+            let angle=c as f32*4f32*PI/5f32-PI*0.5f32;
+            [100f32*angle.cos(),100f32*angle.sin()]
+        }).collect();
+        let star=Path::polygon(&star);
+        let star_non_zero=area(&star.fill(&non_zero,colour));
+        let star_even_odd=area(&star.fill(&even_odd,colour));
+        // Площадь внутреннего пятиугольника
+        let inner_radius=100f32*(0.4f32*PI).cos()/(0.2f32*PI).cos();
+        let pentagon=2.5f32*inner_radius*inner_radius*(0.4f32*PI).sin();
+        assert!((star_non_zero-star_even_odd-pentagon).abs()<pentagon*0.01f32,"{} {} {}",star_non_zero,star_even_odd,pentagon);
+
+        // Незамкнутые и вырожденные контуры
+        assert!(Path::line([0f32,0f32],[10f32,10f32]).fill(&non_zero,colour).is_empty());
+        assert!(Path::new().fill(&non_zero,colour).is_empty());
+    }
+
+    #[test]
+    fn strokes(){
+        let colour=[1f32;4];
+        let line=Path::line([0f32,0f32],[100f32,0f32]);
+
+        let mut settings=StrokeSettings{
+            width:10f32,
+            ..StrokeSettings::new()
+        };
+        assert_area(&line.stroke(&settings,colour),1000f32);
+
+        settings.cap=LineCap::Square;
+        assert_area(&line.stroke(&settings,colour),1100f32);
+
+        settings.cap=LineCap::Round;
+        assert_area(&line.stroke(&settings,colour),1000f32+PI*25f32);
+
+        // Прямой угол: острый угол добавляет квадрат, срезанный - половину
+        let corner=Path::polyline(&[[0f32,0f32],[100f32,0f32],[100f32,100f32]]);
+        settings.cap=LineCap::Butt;
+        settings.join=LineJoin::Miter;
+        assert_area(&corner.stroke(&settings,colour),2025f32);
+        settings.join=LineJoin::Bevel;
+        assert_area(&corner.stroke(&settings,colour),2012.5f32);
+        settings.join=LineJoin::Round;
+        assert_area(&corner.stroke(&settings,colour),2000f32+PI*25f32*0.25f32);
+
+        // Острый угол длиннее ограничения становится срезанным
+        let sharp=Path::polyline(&[[0f32,0f32],[100f32,0f32],[0f32,10f32]]);
+        settings.join=LineJoin::Miter;
+        let miter=sharp.stroke(&settings,colour);
+        settings.join=LineJoin::Bevel;
+        assert_eq!(miter.indices.len(),sharp.stroke(&settings,colour).indices.len());
+
+        // Пунктир: 100 пикселей по 10 с промежутками 10
+        settings.dashes=vec![10f32];
+        let dashed=line.stroke(&settings,colour);
+        assert_eq!(dashed.indices.len(),5*6);
+        assert_area(&dashed,500f32);
+
+        settings.dash_offset=5f32;
+        assert_area(&line.stroke(&settings,colour),500f32);
+
+        // Точки: штрихи нулевой длины с круглыми концами
+        settings.dashes=vec![0f32,20f32];
+        settings.dash_offset=0f32;
+        settings.cap=LineCap::Round;
+        settings.tolerance=0.01f32;
+        assert_area(&line.stroke(&settings,colour),5f32*PI*25f32);
+
+        // Образец короче допуска - сплошная линия
+        settings.dashes=vec![1e-30f32];
+        settings.cap=LineCap::Butt;
+        assert_eq!(line.stroke(&settings,colour).indices.len(),6);
+        settings.dashes=vec![0.005f32,0.004f32];
+        assert_area(&line.stroke(&settings,colour),1000f32);
+
+        // Штрихи сверх предела индексов пропускаются
+        let long_line=Path::line([0f32,0f32],[100000f32,0f32]);
+        let mut dashed_settings=StrokeSettings{
+            width:2f32,
+            dashes:vec![1f32],
+            ..StrokeSettings::new()
+        };
+        let dashed=long_line.stroke(&dashed_settings,colour);
+        assert_eq!(dashed.vertices.len(),65536);
+        assert_eq!(dashed.indices.len(),16384*6);
+        assert!(dashed.indices.iter().all(|&index|(index as usize)<dashed.vertices.len()));
+        dashed_settings.cap=LineCap::Round;
+        let dashed=long_line.stroke(&dashed_settings,colour);
+        assert!(dashed.indices.iter().all(|&index|(index as usize)<dashed.vertices.len()));
+
+        // Замкнутый квадрат соединяется и в начальной точке
+        let square=Path::rectangle([0f32,0f32,100f32,100f32]);
+        let square_settings=StrokeSettings{
+            width:10f32,
+            ..StrokeSettings::new()
+        };
+        assert_area(&square.stroke(&square_settings,colour),4f32*1000f32+4f32*25f32);
+    }
+}
@@ -0,0 +1,406 @@
+use cat_engine_basement::graphics::{
+    GLCore,
+    core::parameters::UNPACK_ALIGNMENT,
+    core::texture::{
+        Texture2DInternalFormat,
+        ImageDataFormat,
+        TextureMagFilter,
+        TextureMinFilter,
+    },
+    level1::Texture2D,
+};
+
+use std::cell::Cell;
+
+/// Glyph atlas settings.
+///
+/// Настройки атласа глифов.
+#[derive(Clone,Copy,Debug)]
+pub struct GlyphAtlasSettings{
+    /// The size of a page texture.
+    ///
+    /// The default is [1024, 1024].
+    ///
+    /// Размер текстуры страницы.
+    ///
+    /// По умолчанию [1024, 1024].
+    pub page_size:[u32;2],
+
+    /// The limit of pages.
+    /// When all the pages are full, the least recently used one is cleared.
+    ///
+    /// The default is 4.
+    ///
+    /// Максимальное количество страниц.
+    /// Когда все страницы заполнены, очищается давно не использованная.
+    ///
+    /// По умолчанию 4.
+    pub max_pages:usize,
+
+    /// The empty space around glyphs in pixels,
+    /// so neighbouring glyphs don't bleed into each other when filtered.
+    ///
+    /// The default is 1.
+    ///
+    /// Пустое место вокруг глифов в пикселях,
+    /// чтобы соседние глифы не смешивались при фильтрации.
+    ///
+    /// По умолчанию 1.
+    pub padding:u32,
+}
+
+impl GlyphAtlasSettings{
+    pub fn new()->GlyphAtlasSettings{
+        Self{
+            page_size:[1024u32;2],
+            max_pages:4,
+            padding:1,
+        }
+    }
+}
+
+impl Default for GlyphAtlasSettings{
+    fn default()->GlyphAtlasSettings{
+        GlyphAtlasSettings::new()
+    }
+}
+
+/// A place of a glyph in an atlas.
+///
+/// Место глифа в атласе.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct AtlasRegion{
+    /// The page index.
+    ///
+    /// Номер страницы.
+    pub page:usize,
+
+    /// [u1, v1, u2, v2] - the bottom left and the top right corners.
+    ///
+    /// [u1, v1, u2, v2] - левый нижний и правый верхний углы.
+    pub texture_coords:[f32;4],
+}
+
+// Полка - ряд прямоугольников одной высоты.
+struct Shelf{
+    y:u32,
+    height:u32,
+    // Занятая ширина
+    width:u32,
+}
+
+/// Упаковщик прямоугольников по полкам.
+///
+/// Прямоугольник кладётся на полку с наименьшей подходящей высотой,
+/// если такой нет, то открывается новая полка.
+pub (crate) struct ShelfPacker{
+    size:[u32;2],
+    shelves:Vec<Shelf>,
+}
+
+impl ShelfPacker{
+    pub fn new(size:[u32;2])->ShelfPacker{
+        Self{
+            size,
+            shelves:Vec::new(),
+        }
+    }
+
+    /// Возвращает положение прямоугольника [x, y]
+    /// или `None`, если места нет.
+    pub fn pack(&mut self,[width,height]:[u32;2])->Option<[u32;2]>{
+        if width>self.size[0] || height>self.size[1]{
+            return None
+        }
+
+        // Самая низкая подходящая полка
+        let mut best:Option<usize>=None;
+        for (index,shelf) in self.shelves.iter().enumerate(){
+            if shelf.height>=height && self.size[0]-shelf.width>=width{
+                match best{
+                    Some(b) if self.shelves[b].height<=shelf.height=>{}
+                    _=>best=Some(index),
+                }
+            }
+        }
+
+        // Новая полка
+        let index=match best{
+            Some(index)=>index,
+            None=>{
+                let y=match self.shelves.last(){
+                    Some(shelf)=>shelf.y+shelf.height,
+                    None=>0,
+                };
+
+                if self.size[1]-y<height{
+                    return None
+                }
+
+                self.shelves.push(Shelf{
+                    y,
+                    height,
+                    width:0,
+                });
+
+                self.shelves.len()-1
+            }
+        };
+
+        let shelf=&mut self.shelves[index];
+        let position=[shelf.width,shelf.y];
+        shelf.width+=width;
+
+        Some(position)
+    }
+
+    pub fn clear(&mut self){
+        self.shelves.clear()
+    }
+}
+
+struct AtlasPage<K>{
+    texture:Texture2D,
+    packer:ShelfPacker,
+    // Ключи глифов на странице
+    keys:Vec<K>,
+    // Время последнего использования
+    last_used:Cell<u64>,
+}
+
+/// Glyph images packed into shared textures (pages).
+///
+/// Pages are added when the previous ones are full.
+/// When the limit of pages is reached,
+/// the least recently used page is cleared and its glyphs are removed.
+///
+/// Изображения глифов, упакованные в общие текстуры (страницы).
+///
+/// Страницы добавляются, когда предыдущие заполнены.
+/// Когда количество страниц достигает предела,
+/// давно не использованная страница очищается, а её глифы удаляются.
+pub struct GlyphAtlas<K:Copy>{
+    settings:GlyphAtlasSettings,
+    pages:Vec<AtlasPage<K>>,
+    // Счётчик использований
+    clock:Cell<u64>,
+}
+
+impl<K:Copy> GlyphAtlas<K>{
+    pub fn new(settings:GlyphAtlasSettings)->GlyphAtlas<K>{
+        Self{
+            settings,
+            pages:Vec::with_capacity(settings.max_pages),
+            clock:Cell::new(0),
+        }
+    }
+
+    pub fn settings(&self)->GlyphAtlasSettings{
+        self.settings
+    }
+
+    pub fn pages(&self)->usize{
+        self.pages.len()
+    }
+
+    pub fn page_texture(&self,page:usize)->Option<&Texture2D>{
+        self.pages.get(page).map(|page|&page.texture)
+    }
+
+    /// Marks a page as used.
+    ///
+    /// Отмечает страницу как использованную.
+    pub fn touch(&self,page:usize){
+        if let Some(page)=self.pages.get(page){
+            let time=self.clock.get()+1;
+            self.clock.set(time);
+            page.last_used.set(time);
+        }
+    }
+
+    /// Packs a glyph image (one byte per pixel, rows go from the bottom).
+    ///
+    /// Returns the region of the glyph and the keys of the glyphs removed from a cleared page.
+    /// Returns `None` if the image is larger than a page.
+    ///
+    /// Упаковывает изображение глифа (байт на пиксель, строки идут снизу).
+    ///
+    /// Возвращает место глифа и ключи глифов, удалённых с очищенной страницы.
+    /// Возвращает `None`, если изображение больше страницы.
+    pub fn insert(&mut self,key:K,[width,height]:[u32;2],image:&[u8])->Option<(AtlasRegion,Vec<K>)>{
+        let padding=self.settings.padding;
+        let padded=[width+2*padding,height+2*padding];
+
+        // Страницы не очищаются ради слишком больших глифов
+        if padded[0]>self.settings.page_size[0] || padded[1]>self.settings.page_size[1]{
+            return None
+        }
+
+        let mut removed=Vec::new();
+
+        // Поиск места на имеющихся страницах
+        let mut place=None;
+        for (index,page) in self.pages.iter_mut().enumerate(){
+            if let Some(position)=page.packer.pack(padded){
+                place=Some((index,position));
+                break
+            }
+        }
+
+        if place.is_none(){
+            let index=if self.pages.len()<self.settings.max_pages{
+                // Новая страница
+                self.pages.push(AtlasPage{
+                    texture:empty_page(self.settings.page_size),
+                    packer:ShelfPacker::new(self.settings.page_size),
+                    keys:Vec::new(),
+                    last_used:Cell::new(0),
+                });
+                self.pages.len()-1
+            }
+            else{
+                // Очистка давно не использованной страницы
+                let index=self.pages.iter()
+                    .enumerate()
+                    .min_by_key(|(_,page)|page.last_used.get())
+                    .map(|(index,_)|index)?;
+
+                let page=&mut self.pages[index];
+                removed.append(&mut page.keys);
+                page.packer.clear();
+                clear_page(&page.texture,self.settings.page_size);
+                index
+            };
+
+            place=self.pages[index].packer.pack(padded).map(|position|(index,position));
+        }
+
+        let (index,[x,y])=place?;
+        let page=&mut self.pages[index];
+        let [x,y]=[x+padding,y+padding];
+
+        if width!=0 && height!=0{
+            unsafe{GLCore.parameters.set_pixel_storage_modei(UNPACK_ALIGNMENT,1)}
+            page.texture.write_image([x,y,width,height],ImageDataFormat::R_U8,image);
+            unsafe{GLCore.parameters.set_pixel_storage_modei(UNPACK_ALIGNMENT,4)}
+        }
+
+        page.keys.push(key);
+
+        let [page_width,page_height]=self.settings.page_size;
+        let region=AtlasRegion{
+            page:index,
+            texture_coords:[
+                x as f32/page_width as f32,
+                y as f32/page_height as f32,
+                (x+width) as f32/page_width as f32,
+                (y+height) as f32/page_height as f32,
+            ],
+        };
+
+        self.touch(index);
+
+        Some((region,removed))
+    }
+
+    /// Clears all the pages.
+    ///
+    /// Очищает все страницы.
+    pub fn clear(&mut self){
+        self.pages.clear()
+    }
+}
+
+fn empty_page(size:[u32;2])->Texture2D{
+    let image=vec![0u8;size[0] as usize*size[1] as usize];
+
+    unsafe{GLCore.parameters.set_pixel_storage_modei(UNPACK_ALIGNMENT,1)}
+
+    let texture=Texture2D::new(
+        Texture2DInternalFormat::R8,
+        TextureMagFilter::Linear,
+        TextureMinFilter::Linear,
+        size,
+        ImageDataFormat::R_U8,
+        &image
+    ).unwrap();
+
+    unsafe{GLCore.parameters.set_pixel_storage_modei(UNPACK_ALIGNMENT,4)}
+
+    texture
+}
+
+// Старые глифы не должны проступать через отступы новых
+fn clear_page(texture:&Texture2D,size:[u32;2]){
+    let image=vec![0u8;size[0] as usize*size[1] as usize];
+
+    unsafe{GLCore.parameters.set_pixel_storage_modei(UNPACK_ALIGNMENT,1)}
+    texture.write_image([0,0,size[0],size[1]],ImageDataFormat::R_U8,&image);
+    unsafe{GLCore.parameters.set_pixel_storage_modei(UNPACK_ALIGNMENT,4)}
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    use cat_engine_basement::graphics::mock;
+
+    #[test]
+    fn shelf_packing(){
+        let mut packer=ShelfPacker::new([16,16]);
+
+        assert_eq!(packer.pack([8,4]),Some([0,0]));
+        assert_eq!(packer.pack([8,6]),Some([0,4]));
+        // Низкий прямоугольник на первую полку
+        assert_eq!(packer.pack([8,3]),Some([8,0]));
+        assert_eq!(packer.pack([4,6]),Some([8,4]));
+        assert_eq!(packer.pack([16,6]),Some([0,10]));
+        assert_eq!(packer.pack([1,1]),Some([12,4]));
+        // Места нет
+        assert_eq!(packer.pack([8,8]),None);
+        assert_eq!(packer.pack([17,1]),None);
+
+        packer.clear();
+        assert_eq!(packer.pack([16,16]),Some([0,0]));
+    }
+
+    #[test]
+    fn atlas_pages(){
+        mock::load();
+
+        let mut atlas=GlyphAtlas::<u16>::new(GlyphAtlasSettings{
+            page_size:[8,8],
+            max_pages:2,
+            padding:1,
+        });
+
+        // Три глифа 4x4 (6x6 с отступами) - по одному на страницу
+        let (region,removed)=atlas.insert(1,[4,4],&[255u8;16]).unwrap();
+        assert_eq!(region,AtlasRegion{page:0,texture_coords:[0.125,0.125,0.625,0.625]});
+        assert!(removed.is_empty());
+
+        let (region,removed)=atlas.insert(2,[4,4],&[255u8;16]).unwrap();
+        assert_eq!(region.page,1);
+        assert!(removed.is_empty());
+        assert_eq!(atlas.pages(),2);
+
+        // Первая страница использована недавно - очищается вторая
+        atlas.touch(0);
+        let (region,removed)=atlas.insert(3,[4,4],&[255u8;16]).unwrap();
+        assert_eq!(region.page,1);
+        assert_eq!(removed,vec![2]);
+
+        // Изображение записано с отступом
+        let texture=atlas.page_texture(1).unwrap().as_raw().id();
+        mock::with_state(|state|{
+            let image=&state.textures[&texture].images[&0];
+            let data=image.data.as_ref().unwrap();
+            assert_eq!(data[0],0);
+            assert_eq!(data[8+1],255);
+            assert_eq!(data[4*8+4],255);
+            assert_eq!(data[5*8+5],0);
+        });
+
+        assert!(atlas.insert(4,[9,1],&[255u8;9]).is_none());
+    }
+}
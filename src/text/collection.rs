@@ -0,0 +1,70 @@
+use super::{
+    Scale,
+    CachedFont,
+    TextLayout,
+    TextLayoutSettings,
+    layout::layout_fonts,
+};
+
+use ttf_parser::GlyphId;
+
+/// Fonts with an ordered fallback list.
+///
+/// Characters missing in the primary font are taken from the next fonts in order,
+/// e.g. a Latin font, then a CJK font, then an emoji font.
+/// Line metrics are taken from the primary font,
+/// glyphs of other fonts are scaled to its units per em.
+///
+/// Шрифты с упорядоченным списком замены.
+///
+/// Символы, которых нет в основном шрифте, берутся из следующих шрифтов по порядку,
+/// например, латинский шрифт, затем шрифт CJK, затем шрифт эмодзи.
+/// Метрики строк берутся из основного шрифта,
+/// глифы остальных шрифтов приводятся к его размеру em.
+pub struct FontCollection{
+    fonts:Vec<CachedFont>,
+}
+
+impl FontCollection{
+    pub fn new(primary:CachedFont)->FontCollection{
+        Self{
+            fonts:vec![primary],
+        }
+    }
+
+    /// Adds a font to the end of the fallback list.
+    ///
+    /// Добавляет шрифт в конец списка замены.
+    pub fn push(&mut self,font:CachedFont){
+        self.fonts.push(font)
+    }
+
+    pub fn fonts(&self)->&[CachedFont]{
+        &self.fonts
+    }
+
+    pub fn fonts_mut(&mut self)->&mut [CachedFont]{
+        &mut self.fonts
+    }
+
+    /// Returns the index of the first font containing the character and the glyph id.
+    ///
+    /// Возвращает номер первого шрифта, в котором есть символ, и номер глифа.
+    pub fn glyph_id(&self,character:char)->Option<(usize,GlyphId)>{
+        self.fonts.iter()
+            .enumerate()
+            .find_map(|(index,font)|font.glyph_id(character).map(|id|(index,id)))
+    }
+
+    /// Lays out a text with the fallback fonts.
+    ///
+    /// The text is shaped if the `text_shaping` feature is enabled.
+    ///
+    /// Размечает текст шрифтами по порядку замены.
+    ///
+    /// Текст формируется, если включена возможность `text_shaping`.
+    pub fn layout(&self,text:&str,scale:Scale,settings:&TextLayoutSettings)->TextLayout{
+        let fonts:Vec<_>=self.fonts.iter().map(|font|font.font()).collect();
+        layout_fonts(&fonts,text,scale,settings)
+    }
+}
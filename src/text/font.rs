@@ -4,6 +4,9 @@ use super::{
     TexturedGlyph,
     Scale,
     GlyphCache,
+    TextLayout,
+    TextLayoutSettings,
+    layout::layout_fonts,
 };
 
 use ttf_parser::{
@@ -106,6 +109,10 @@ impl FontOwner{
         &self.face
     }
 
+    pub fn data(&self)->&[u8]{
+        &self.data
+    }
+
     pub fn face_wrapper<'a>(&'a self)->FaceWrapper<'a>{
         FaceWrapper(self.face.clone())
     }
@@ -239,7 +246,32 @@ impl CachedFont{
         &self.cache
     }
 
+    /// Caches glyphs for the given characters.
+    /// 
+    /// Кэширует глифы для данных символов.
+    pub fn insert_str(&mut self,text:&str,graphics:&Graphics2D){
+        self.cache.insert_str(self.font.face(),text,graphics)
+    }
+
+    /// Caches a glyph.
+    /// 
+    /// Кэширует глиф.
+    pub fn insert_glyph(&mut self,id:GlyphId,graphics:&Graphics2D){
+        self.cache.insert_glyph(id,self.font.face(),graphics)
+    }
+
     pub fn cached_glyph(&self,id:GlyphId)->Option<&TexturedGlyph>{
         self.cache.glyph(id)
     }
+
+    /// Lays out a text with the font metrics.
+    /// 
+    /// The text is shaped if the `text_shaping` feature is enabled.
+    /// 
+    /// Размечает текст по метрикам шрифта.
+    /// 
+    /// Текст формируется, если включена возможность `text_shaping`.
+    pub fn layout(&self,text:&str,scale:Scale,settings:&TextLayoutSettings)->TextLayout{
+        layout_fonts(&[&self.font],text,scale,settings)
+    }
 }
\ No newline at end of file
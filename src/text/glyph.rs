@@ -1,6 +1,7 @@
-use super::Scale;
-
-use cat_engine_basement::graphics::level1::Texture2D;
+use super::{
+    Scale,
+    AtlasRegion,
+};
 
 
 struct Glyph<T>{
@@ -71,21 +72,21 @@ impl<T> Glyph<T>{
     }
 }
 
-/// A glyph represented as a texture.
+/// A glyph represented as a region of a glyph atlas page.
 pub struct TexturedGlyph{
-    glyph:Glyph<Texture2D>,
+    glyph:Glyph<AtlasRegion>,
 }
 
 impl TexturedGlyph{
     pub (crate) fn raw(
-        texture:Texture2D,
+        region:AtlasRegion,
         size:[f32;2],
         offset:[f32;2],
         advance_width:f32,
     )->TexturedGlyph{
         Self{
             glyph:Glyph::raw(
-                texture,
+                region,
                 size,
                 offset,
                 advance_width
@@ -123,7 +124,17 @@ impl TexturedGlyph{
         self.glyph.bounding_box(scale)
     }
 
-    pub fn texture(&self)->&Texture2D{
-        &self.glyph.data
+    /// Returns the index of the atlas page containing the glyph.
+    pub fn page(&self)->usize{
+        self.glyph.data.page
+    }
+
+    /// Returns glyph's texture coordinates on the atlas page - [u1, v1, u2, v2].
+    pub fn texture_coords(&self)->[f32;4]{
+        self.glyph.data.texture_coords
+    }
+
+    pub fn region(&self)->AtlasRegion{
+        self.glyph.data
     }
 }
\ No newline at end of file
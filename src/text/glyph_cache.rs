@@ -3,19 +3,11 @@ use crate::graphics::Graphics2D;
 use super::{
     TexturedGlyph,
     Scale,
+    GlyphAtlas,
+    GlyphAtlasSettings,
 };
 
-use cat_engine_basement::graphics::{
-    GLCore,
-    core::parameters::UNPACK_ALIGNMENT,
-    core::texture::{
-        Texture2DInternalFormat,
-        ImageDataFormat,
-        TextureMagFilter,
-        TextureMinFilter,
-    },
-    level1::Texture2D,
-};
+use cat_engine_basement::graphics::level1::Texture2D;
 
 use ttf_parser::{
     Face,
@@ -27,13 +19,44 @@ use std::{
     ops::Range,
 };
 
+/// The way glyph images are built.
+/// 
+/// Способ построения изображений глифов.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum GlyphMode{
+    /// Coverage images, sharp only near the cache scale.
+    /// 
+    /// Изображения покрытия, чёткие только около масштаба хранилища.
+    Coverage,
+
+    /// Signed distance fields, stay sharp when scaled
+    /// and allow outlines, shadows and glow (`TextEffects`).
+    /// 
+    /// `spread` - the max distance in pixels, also the empty space around glyph images.
+    /// Effects can't reach further than `spread` from glyph edges.
+    /// 
+    /// Знаковые поля расстояний, остаются чёткими при масштабировании
+    /// и позволяют рисовать обводку, тень и свечение (`TextEffects`).
+    /// 
+    /// `spread` - максимальное расстояние в пикселях, а также пустое место вокруг изображений глифов.
+    /// Эффекты не могут отходить от границ глифов дальше `spread`.
+    DistanceField{spread:u32},
+}
+
 // ᶠᵉᵉᵈ ᵐᵉ /ᐠ-ⱉ-ᐟ\ﾉ
 /// A glyph cache.
+/// 
+/// Glyphs are packed into the pages of a glyph atlas.
+/// 
 /// Хранилище глифов.
+/// 
+/// Глифы упаковываются в страницы атласа глифов.
 pub struct GlyphCache{
     // Глифы
     glyphs:HashMap<u16,TexturedGlyph>,
     scale:Scale,
+    atlas:GlyphAtlas<u16>,
+    mode:GlyphMode,
 }
 
 impl GlyphCache{
@@ -58,7 +81,9 @@ impl GlyphCache{
 
         let mut cache=Self{
             glyphs:HashMap::with_capacity(range.len()),
-            scale
+            scale,
+            atlas:GlyphAtlas::new(GlyphAtlasSettings::new()),
+            mode:GlyphMode::Coverage,
         };
 
         for g in range{
@@ -81,6 +106,8 @@ impl GlyphCache{
         let mut cache=Self{
             glyphs:HashMap::with_capacity(alphabet.len()),
             scale,
+            atlas:GlyphAtlas::new(GlyphAtlasSettings::new()),
+            mode:GlyphMode::Coverage,
         };
 
         for character in alphabet.chars(){
@@ -92,18 +119,61 @@ impl GlyphCache{
         cache
     }
 
+    /// Creates an empty glyph cache with the given atlas settings.
+    /// 
+    /// Создаёт пустое хранилище глифов с данными настройками атласа.
+    pub fn empty(scale:Scale,atlas_settings:GlyphAtlasSettings)->GlyphCache{
+        GlyphCache::empty_with_mode(scale,atlas_settings,GlyphMode::Coverage)
+    }
+
+    /// Creates an empty glyph cache with the given atlas settings and glyph mode.
+    /// 
+    /// Создаёт пустое хранилище глифов с данными настройками атласа и способом построения глифов.
+    pub fn empty_with_mode(scale:Scale,atlas_settings:GlyphAtlasSettings,mode:GlyphMode)->GlyphCache{
+        Self{
+            glyphs:HashMap::new(),
+            scale,
+            atlas:GlyphAtlas::new(atlas_settings),
+            mode,
+        }
+    }
+
     /// Создаёт и добавляет новый глиф для данного символа.
     /// 
-    /// Игнорирует неопределённые символы.
+    /// Игнорирует неопределённые символы и глифы больше страницы атласа.
     /// Заменяет старый глиф для этого символа, если такой есть.
+    /// Если атлас заполнен, то удаляются глифы давно не использованной страницы.
     /// 
     /// Creates and inserts a new glyph for the given character.
     /// 
-    /// Ignors undefined characters.
+    /// Ignors undefined characters and glyphs larger than an atlas page.
     /// Replaces the old glyph for this character if there is one.
+    /// If the atlas is full, the glyphs of the least recently used page are removed.
     pub fn insert_glyph(&mut self,id:GlyphId,font:&Face,graphics:&Graphics2D){
-        if let Some(glyph)=build_glyph(id,self.scale,font,graphics){
-            self.glyphs.insert(id.0,glyph);
+        let glyph_image=match self.mode{
+            GlyphMode::Coverage=>graphics.build_glyph_image(id,self.scale,font),
+            GlyphMode::DistanceField{spread}=>graphics.build_glyph_distance_field(id,self.scale,font,spread),
+        };
+
+        if let Some(([offset_x,offset_y,width,height],image))=glyph_image{
+            self.glyphs.remove(&id.0);
+
+            if let Some((region,removed))=self.atlas.insert(id.0,[width as u32,height as u32],image){
+                for key in removed{
+                    self.glyphs.remove(&key);
+                }
+
+                let advance_width=font.glyph_hor_advance(id).unwrap_or(0) as f32*self.scale.horizontal;
+
+                let glyph=TexturedGlyph::raw(
+                    region,
+                    [width,height],
+                    [offset_x,offset_y],
+                    advance_width,
+                );
+
+                self.glyphs.insert(id.0,glyph);
+            }
         }
     }
 
@@ -132,49 +202,36 @@ impl GlyphCache{
         self.scale
     }
 
-    pub fn glyph(&self,id:GlyphId)->Option<&TexturedGlyph>{
-        self.glyphs.get(&id.0)
+    pub fn mode(&self)->GlyphMode{
+        self.mode
     }
-}
 
+    /// Returns a cached glyph and marks its atlas page as used.
+    /// 
+    /// Возвращает глиф и отмечает его страницу атласа как использованную.
+    pub fn glyph(&self,id:GlyphId)->Option<&TexturedGlyph>{
+        let glyph=self.glyphs.get(&id.0)?;
+        self.atlas.touch(glyph.page());
+        Some(glyph)
+    }
 
-fn build_glyph(id:GlyphId,scale:Scale,face:&Face,graphics:&Graphics2D)->Option<TexturedGlyph>{
-    if let Some((
-        [
-            offset_x,
-            offset_y,
-            width,
-            height,
-        ],
-        image,
-    ))=graphics.build_glyph_image(id,scale,face){
-        let size=[width as u32,height as u32];
-
-        unsafe{GLCore.parameters.set_pixel_storage_modei(UNPACK_ALIGNMENT,1)}
-
-        let texture_2d=Texture2D::new(
-            Texture2DInternalFormat::R8,
-            TextureMagFilter::Linear,
-            TextureMinFilter::Linear,
-            size,
-            ImageDataFormat::R_U8,
-            &image
-        ).unwrap();
-
-        unsafe{GLCore.parameters.set_pixel_storage_modei(UNPACK_ALIGNMENT,4)}
-
-        let advance_width=face.glyph_hor_advance(id).unwrap() as f32*scale.horizontal;
-
-        let glyph=TexturedGlyph::raw(
-            texture_2d,
-            [width,height],
-            [offset_x,offset_y],
-            advance_width,
-        );
+    pub fn atlas(&self)->&GlyphAtlas<u16>{
+        &self.atlas
+    }
 
-        Some(glyph)
+    /// Returns the texture of an atlas page.
+    /// 
+    /// Возвращает текстуру страницы атласа.
+    pub fn page_texture(&self,page:usize)->Option<&Texture2D>{
+        self.atlas.page_texture(page)
     }
-    else{
-        None
+
+    /// Removes all the glyphs and the atlas pages.
+    /// 
+    /// Удаляет все глифы и страницы атласа.
+    pub fn clear(&mut self){
+        self.glyphs.clear();
+        self.atlas.clear()
     }
-}
\ No newline at end of file
+}
+
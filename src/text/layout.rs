@@ -0,0 +1,831 @@
+use super::{
+    Scale,
+    FontOwner,
+    opentype::Kerning,
+};
+
+use ttf_parser::{
+    Face,
+    GlyphId,
+};
+
+use std::ops::Range;
+
+/// Horizontal alignment of text lines.
+///
+/// Горизонтальное выравнивание строк текста.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum TextAlignment{
+    Left,
+    Center,
+    Right,
+    /// Stretches wrapped lines to the full width by widening spaces.
+    /// The last line of a paragraph is aligned to the left.
+    ///
+    /// Растягивает перенесённые строки на всю ширину, расширяя пробелы.
+    /// Последняя строка абзаца выравнивается по левому краю.
+    Justify,
+}
+
+/// Text layout settings.
+///
+/// Настройки расположения текста.
+#[derive(Clone,Copy,Debug)]
+pub struct TextLayoutSettings{
+    /// The maximum width of lines, longer lines are wrapped.
+    /// `None` - lines are broken only at '\n'.
+    ///
+    /// The default is `None`.
+    ///
+    /// Максимальная ширина строк, более длинные строки переносятся.
+    /// `None` - строки разрываются только символом '\n'.
+    ///
+    /// По умолчанию `None`.
+    pub max_width:Option<f32>,
+
+    /// The default is `TextAlignment::Left`.
+    ///
+    /// По умолчанию `TextAlignment::Left`.
+    pub alignment:TextAlignment,
+
+    /// The multiplier of the font line height.
+    ///
+    /// The default is 1.
+    ///
+    /// Множитель высоты строки шрифта.
+    ///
+    /// По умолчанию 1.
+    pub line_spacing:f32,
+
+    /// Applies kerning from the GPOS or `kern` tables.
+    ///
+    /// The default is `true`.
+    ///
+    /// Применяет кернинг из таблиц GPOS или `kern`.
+    ///
+    /// По умолчанию `true`.
+    pub kerning:bool,
+
+    /// Shapes the text: ligatures, joining forms, mark positioning
+    /// and bidirectional reordering (right-to-left runs).
+    ///
+    /// The default is `true`.
+    ///
+    /// Формирует текст: лигатуры, формы соединения, расположение диакритических знаков
+    /// и двунаправленное упорядочивание (отрезки справа налево).
+    ///
+    /// По умолчанию `true`.
+    #[cfg(feature="text_shaping")]
+    pub shaping:bool,
+}
+
+impl TextLayoutSettings{
+    pub fn new()->TextLayoutSettings{
+        Self{
+            max_width:None,
+            alignment:TextAlignment::Left,
+            line_spacing:1f32,
+            kerning:true,
+            #[cfg(feature="text_shaping")]
+            shaping:true,
+        }
+    }
+}
+
+impl Default for TextLayoutSettings{
+    fn default()->TextLayoutSettings{
+        TextLayoutSettings::new()
+    }
+}
+
+/// A glyph placed by the layout.
+///
+/// Глиф, расположенный при разметке.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct PositionedGlyph{
+    /// The index of the font in the fallback list, 0 for a single font.
+    ///
+    /// Номер шрифта в списке замены, 0 для одного шрифта.
+    pub font:usize,
+
+    pub id:GlyphId,
+
+    /// The byte index of the character in the text.
+    ///
+    /// Номер байта символа в тексте.
+    pub cluster:usize,
+
+    /// The pen position on the baseline relative to the top left corner of the text,
+    /// the same as the `position` of `Graphics2D::draw_char`.
+    ///
+    /// Положение пера на базовой линии относительно левого верхнего угла текста,
+    /// то же, что и `position` у `Graphics2D::draw_char`.
+    pub position:[f32;2],
+}
+
+/// A line of laid out text.
+///
+/// Строка размеченного текста.
+#[derive(Clone,Debug,PartialEq)]
+pub struct TextLine{
+    /// The glyphs of the line in `TextLayout::glyphs`.
+    ///
+    /// Глифы строки в `TextLayout::glyphs`.
+    pub glyphs:Range<usize>,
+
+    /// The width without trailing whitespaces.
+    ///
+    /// Ширина без пробелов в конце.
+    pub width:f32,
+
+    /// The vertical position of the baseline.
+    ///
+    /// Вертикальное положение базовой линии.
+    pub baseline:f32,
+}
+
+/// Positioned glyphs of a text.
+///
+/// Расположенные глифы текста.
+#[derive(Clone,Debug)]
+pub struct TextLayout{
+    glyphs:Vec<PositionedGlyph>,
+    lines:Vec<TextLine>,
+    size:[f32;2],
+    scale:Scale,
+}
+
+impl TextLayout{
+    /// Lays out a text with the metrics of the font.
+    ///
+    /// The text is not shaped, use `CachedFont::layout` or `FontCollection::layout` for shaping.
+    ///
+    /// Размечает текст по метрикам шрифта.
+    ///
+    /// Текст не формируется (shaping), для этого используйте `CachedFont::layout` или `FontCollection::layout`.
+    pub fn new(face:&Face,text:&str,scale:Scale,settings:&TextLayoutSettings)->TextLayout{
+        let metrics=FaceMetrics::new(&[face],settings);
+
+        layout(&metrics,text,scale,settings)
+    }
+
+    pub fn glyphs(&self)->&[PositionedGlyph]{
+        &self.glyphs
+    }
+
+    pub fn lines(&self)->&[TextLine]{
+        &self.lines
+    }
+
+    /// [the width of the longest line, the height of all the lines]
+    ///
+    /// [ширина самой длинной строки, высота всех строк]
+    pub fn size(&self)->[f32;2]{
+        self.size
+    }
+
+    pub fn scale(&self)->Scale{
+        self.scale
+    }
+}
+
+/// Размечает текст шрифтами по порядку замены,
+/// с формированием, если оно включено.
+pub (crate) fn layout_fonts(fonts:&[&FontOwner],text:&str,scale:Scale,settings:&TextLayoutSettings)->TextLayout{
+    let faces:Vec<&Face>=fonts.iter().map(|font|font.face()).collect();
+    let metrics=FaceMetrics::new(&faces,settings);
+
+    #[cfg(feature="text_shaping")]
+    if settings.shaping{
+        return super::shaping::layout(&metrics,fonts,text,scale,settings)
+    }
+
+    layout(&metrics,text,scale,settings)
+}
+
+/// Метрики шрифтов в единицах первого шрифта.
+pub (crate) trait LayoutMetrics{
+    fn fonts(&self)->usize;
+
+    /// `None` - символа нет в шрифте.
+    fn glyph_id(&self,font:usize,character:char)->Option<GlyphId>;
+
+    fn advance(&self,font:usize,id:GlyphId)->f32;
+
+    fn kerning(&self,font:usize,left:GlyphId,right:GlyphId)->f32;
+
+    /// [ascender, descender, line_gap] первого шрифта
+    fn line_metrics(&self)->[f32;3];
+}
+
+/// Шрифт для символа - первый шрифт, в котором есть символ.
+/// Продолжения кластеров остаются в шрифте предыдущего символа.
+/// Если символа нет ни в одном шрифте, то берётся пустой глиф первого шрифта.
+pub (crate) fn select_font<M:LayoutMetrics>(metrics:&M,character:char,previous:Option<usize>)->(usize,GlyphId){
+    if let Some(previous)=previous{
+        if is_cluster_extension(character){
+            if let Some(id)=metrics.glyph_id(previous,character){
+                return (previous,id)
+            }
+        }
+    }
+
+    for font in 0..metrics.fonts(){
+        if let Some(id)=metrics.glyph_id(font,character){
+            return (font,id)
+        }
+    }
+
+    (0,GlyphId(0u16))
+}
+
+/// Диакритические знаки, соединители и селекторы вариантов.
+fn is_cluster_extension(character:char)->bool{
+    matches!(character as u32,
+        0x0300..=0x036F | 0x0483..=0x0489 | 0x0591..=0x05BD | 0x05BF | 0x05C1..=0x05C2 | 0x05C4..=0x05C5 | 0x05C7 |
+        0x0610..=0x061A | 0x064B..=0x065F | 0x0670 | 0x06D6..=0x06DC | 0x06DF..=0x06E4 | 0x06E7..=0x06E8 | 0x06EA..=0x06ED |
+        0x0900..=0x0903 | 0x093A..=0x094F | 0x3099..=0x309A |
+        0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x200C..=0x200D | 0x20D0..=0x20FF | 0xFE00..=0xFE0F | 0xFE20..=0xFE2F |
+        0x1F3FB..=0x1F3FF | 0xE0100..=0xE01EF
+    )
+}
+
+pub (crate) struct FaceMetrics<'a>{
+    fonts:Vec<FontMetrics<'a>>,
+}
+
+struct FontMetrics<'a>{
+    face:&'a Face<'a>,
+    kerning:Option<Kerning<'a>>,
+    // Единиц первого шрифта в единице шрифта
+    units:f32,
+}
+
+impl<'a> FaceMetrics<'a>{
+    pub fn new(faces:&[&'a Face<'a>],settings:&TextLayoutSettings)->FaceMetrics<'a>{
+        let units_per_em=|face:&Face|face.units_per_em().unwrap_or(1000) as f32;
+        let primary=faces.first().map(|face|units_per_em(face)).unwrap_or(1000f32);
+
+        Self{
+            fonts:faces.iter().map(|&face|{
+                FontMetrics{
+                    face,
+                    kerning:if settings.kerning{
+                        Some(Kerning::new(face))
+                    }
+                    else{
+                        None
+                    },
+                    units:primary/units_per_em(face),
+                }
+            }).collect()
+        }
+    }
+}
+
+impl<'a> LayoutMetrics for FaceMetrics<'a>{
+    fn fonts(&self)->usize{
+        self.fonts.len()
+    }
+
+    fn glyph_id(&self,font:usize,character:char)->Option<GlyphId>{
+        self.fonts[font].face.glyph_index(character)
+    }
+
+    fn advance(&self,font:usize,id:GlyphId)->f32{
+        let font=&self.fonts[font];
+        font.face.glyph_hor_advance(id).unwrap_or(0) as f32*font.units
+    }
+
+    fn kerning(&self,font:usize,left:GlyphId,right:GlyphId)->f32{
+        let font=&self.fonts[font];
+        match &font.kerning{
+            Some(kerning)=>kerning.kerning(left,right) as f32*font.units,
+            None=>0f32,
+        }
+    }
+
+    fn line_metrics(&self)->[f32;3]{
+        match self.fonts.first(){
+            Some(font)=>[
+                font.face.ascender() as f32,
+                font.face.descender() as f32,
+                font.face.line_gap() as f32,
+            ],
+            None=>[0f32;3],
+        }
+    }
+}
+
+/// Глиф абзаца до разбиения на строки.
+pub (crate) struct Item{
+    pub font:usize,
+    pub id:GlyphId,
+    pub cluster:usize,
+    pub advance:f32,
+    /// Кернинг с предыдущим глифом
+    pub kerning:f32,
+    /// Смещение от положения пера (вниз - положительное)
+    pub offset:[f32;2],
+    pub whitespace:bool,
+    /// Уровень направления (нечётный - справа налево)
+    pub level:u8,
+}
+
+// Строка до выравнивания
+struct Line{
+    // Символы в визуальном порядке, пробелы в конце строки - последние
+    order:Vec<usize>,
+    // Положения символов относительно начала строки
+    positions:Vec<[f32;2]>,
+    width:f32,
+    // Перенесённая строка (не последняя в абзаце)
+    wrapped:bool,
+}
+
+pub (crate) fn layout<M:LayoutMetrics>(metrics:&M,text:&str,scale:Scale,settings:&TextLayoutSettings)->TextLayout{
+    build_layout(metrics.line_metrics(),text,scale,settings,|paragraph,offset,items|{
+        map_characters(metrics,paragraph,offset,scale,settings,items)
+    })
+}
+
+/// Глифы символов без формирования.
+fn map_characters<M:LayoutMetrics>(
+    metrics:&M,
+    paragraph:&str,
+    offset:usize,
+    scale:Scale,
+    settings:&TextLayoutSettings,
+    items:&mut Vec<Item>
+){
+    let mut previous:Option<(usize,GlyphId)>=None;
+    for (index,character) in paragraph.char_indices(){
+        let (font,id)=select_font(metrics,character,previous.map(|(font,_)|font));
+
+        let kerning=match previous{
+            Some((previous_font,previous)) if settings.kerning && previous_font==font=>{
+                metrics.kerning(font,previous,id)*scale.horizontal
+            }
+            _=>0f32,
+        };
+        previous=Some((font,id));
+
+        items.push(Item{
+            font,
+            id,
+            cluster:offset+index,
+            advance:metrics.advance(font,id)*scale.horizontal,
+            kerning,
+            offset:[0f32;2],
+            whitespace:character.is_whitespace(),
+            level:0,
+        });
+    }
+}
+
+/// Разбивает абзацы на строки и выравнивает их.
+///
+/// `paragraph_items` - глифы абзаца (текст, смещение абзаца в тексте, глифы).
+pub (crate) fn build_layout<F:FnMut(&str,usize,&mut Vec<Item>)>(
+    [ascender,descender,line_gap]:[f32;3],
+    text:&str,
+    scale:Scale,
+    settings:&TextLayoutSettings,
+    mut paragraph_items:F
+)->TextLayout{
+    let line_height=(ascender-descender+line_gap)*scale.vertical*settings.line_spacing;
+    let ascent=ascender*scale.vertical;
+
+    let mut glyphs=Vec::with_capacity(text.len());
+    let mut lines=Vec::new();
+
+    let mut items=Vec::with_capacity(text.len());
+
+    let mut paragraph_start=0usize;
+    for paragraph in text.split('\n'){
+        let paragraph_offset=paragraph_start;
+        paragraph_start+=paragraph.len()+1;
+
+        let paragraph=paragraph.strip_suffix('\r').unwrap_or(paragraph);
+
+        // Глифы абзаца
+        items.clear();
+        paragraph_items(paragraph,paragraph_offset,&mut items);
+
+        // Разбиение на строки
+        let mut paragraph_lines=Vec::new();
+        let mut line_start=0usize;
+        // Первый символ после последнего пробела строки
+        let mut last_break:Option<usize>=None;
+        let mut pen=0f32;
+
+        for index in 0..items.len(){
+            let item=&items[index];
+            let kerning=if index==line_start{0f32}else{item.kerning};
+
+            if let Some(max_width)=settings.max_width{
+                if !item.whitespace && index>line_start && pen+kerning+item.advance>max_width{
+                    // Перенос по пробелу, а для длинных слов - по символу
+                    let end=match last_break{
+                        Some(end) if end>line_start=>end,
+                        _=>{
+                            // Кластеры (лигатуры, знаки) не разрываются
+                            let mut end=index;
+                            while end>line_start+1 && items[end].cluster==items[end-1].cluster{
+                                end-=1
+                            }
+                            end
+                        }
+                    };
+
+                    paragraph_lines.push(break_line(&items,line_start..end,true));
+
+                    line_start=end;
+                    last_break=None;
+                    pen=0f32;
+                    for (i,item) in items[line_start..index].iter().enumerate(){
+                        pen+=if i==0{0f32}else{item.kerning};
+                        pen+=item.advance;
+                    }
+                }
+            }
+
+            pen+=if index==line_start{0f32}else{item.kerning};
+            pen+=item.advance;
+
+            if item.whitespace{
+                last_break=Some(index+1)
+            }
+        }
+
+        paragraph_lines.push(break_line(&items,line_start..items.len(),false));
+
+        // Выравнивание откладывается до известной ширины текста
+        for line in paragraph_lines{
+            let first=glyphs.len();
+            for (&i,&position) in line.order.iter().zip(line.positions.iter()){
+                glyphs.push(PositionedGlyph{
+                    font:items[i].font,
+                    id:items[i].id,
+                    cluster:items[i].cluster,
+                    position,
+                });
+            }
+
+            lines.push((
+                TextLine{
+                    glyphs:first..glyphs.len(),
+                    width:line.width,
+                    baseline:ascent+lines.len() as f32*line_height,
+                },
+                line.wrapped,
+                line.order.iter().map(|&i|items[i].whitespace).collect::<Vec<bool>>(),
+            ));
+        }
+    }
+
+    let text_width=lines.iter().fold(0f32,|width,(line,_,_)|width.max(line.width));
+    let box_width=settings.max_width.unwrap_or(text_width);
+
+    let mut size=[text_width,lines.len() as f32*line_height];
+
+    let lines=lines.into_iter().map(|(mut line,wrapped,whitespaces)|{
+        let free=(box_width-line.width).max(0f32);
+
+        let shift=match settings.alignment{
+            TextAlignment::Left | TextAlignment::Justify=>0f32,
+            TextAlignment::Center=>free/2f32,
+            TextAlignment::Right=>free,
+        };
+
+        // Пробелы между словами (без пробелов в конце строки)
+        let visible=whitespaces.iter().rposition(|whitespace|!whitespace).map(|i|i+1).unwrap_or(0);
+        let spaces=whitespaces[..visible].iter().filter(|whitespace|**whitespace).count();
+
+        let extra=if settings.alignment==TextAlignment::Justify && wrapped && spaces!=0{
+            free/spaces as f32
+        }
+        else{
+            0f32
+        };
+
+        let mut stretch=0f32;
+        for (glyph,&whitespace) in glyphs[line.glyphs.clone()].iter_mut().zip(whitespaces.iter()){
+            glyph.position[0]+=shift+stretch;
+            glyph.position[1]+=line.baseline;
+            if whitespace{
+                stretch+=extra
+            }
+        }
+
+        if extra!=0f32{
+            line.width=box_width;
+            size[0]=size[0].max(box_width);
+        }
+
+        line
+    }).collect();
+
+    TextLayout{
+        glyphs,
+        lines,
+        size,
+        scale,
+    }
+}
+
+fn break_line(items:&[Item],range:Range<usize>,wrapped:bool)->Line{
+    // Пробелы в конце строки не входят в ширину и не переупорядочиваются
+    let visible_end=items[range.clone()].iter()
+        .rposition(|item|!item.whitespace)
+        .map(|i|range.start+i+1)
+        .unwrap_or(range.start);
+
+    let mut order:Vec<usize>=(range.start..visible_end).collect();
+    reorder(&mut order,items);
+    order.extend(visible_end..range.end);
+
+    let mut positions=Vec::with_capacity(order.len());
+    let mut pen=0f32;
+    let mut width=0f32;
+
+    for (n,&i) in order.iter().enumerate(){
+        // Кернинг с предыдущим символом, если он стоит рядом
+        if n!=0 && order[n-1]+1==i{
+            pen+=items[i].kerning
+        }
+        positions.push([pen+items[i].offset[0],items[i].offset[1]]);
+        pen+=items[i].advance;
+
+        if i<visible_end{
+            width=pen
+        }
+    }
+
+    Line{
+        order,
+        positions,
+        width,
+        wrapped,
+    }
+}
+
+/// Визуальный порядок по уровням направления (правило L2 Unicode Bidi):
+/// от наибольшего уровня до наименьшего нечётного
+/// переворачиваются отрезки с уровнем не меньше текущего.
+fn reorder(order:&mut [usize],items:&[Item]){
+    let levels=order.iter().map(|&i|items[i].level);
+    let highest=levels.clone().max().unwrap_or(0);
+    let lowest_odd=match levels.filter(|level|level%2==1).min(){
+        Some(level)=>level,
+        None=>return,
+    };
+
+    for level in (lowest_odd..=highest).rev(){
+        let mut start=0usize;
+        while start<order.len(){
+            if items[order[start]].level>=level{
+                let mut end=start;
+                while end<order.len() && items[order[end]].level>=level{
+                    end+=1
+                }
+                order[start..end].reverse();
+                start=end;
+            }
+            else{
+                start+=1
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    // Моноширинный шрифт: символ - 10 единиц,
+    // пара "AV" сближается на 2 единицы
+    struct Monospace;
+
+    impl LayoutMetrics for Monospace{
+        fn fonts(&self)->usize{
+            1
+        }
+
+        fn glyph_id(&self,_:usize,character:char)->Option<GlyphId>{
+            Some(GlyphId(character as u16))
+        }
+
+        fn advance(&self,_:usize,_:GlyphId)->f32{
+            10f32
+        }
+
+        fn kerning(&self,_:usize,left:GlyphId,right:GlyphId)->f32{
+            if left.0=='A' as u16 && right.0=='V' as u16{
+                -2f32
+            }
+            else{
+                0f32
+            }
+        }
+
+        fn line_metrics(&self)->[f32;3]{
+            [8f32,-2f32,0f32]
+        }
+    }
+
+    // Латинский шрифт (10 единиц) и кириллический шрифт (20 единиц),
+    // знак ударения есть в обоих
+    struct Fallback;
+
+    impl LayoutMetrics for Fallback{
+        fn fonts(&self)->usize{
+            2
+        }
+
+        fn glyph_id(&self,font:usize,character:char)->Option<GlyphId>{
+            let latin=character.is_ascii() || character=='\u{301}';
+            let cyrillic=('а'..='я').contains(&character) || character=='\u{301}';
+            if (font==0 && latin) || (font==1 && cyrillic){
+                Some(GlyphId(character as u16))
+            }
+            else{
+                None
+            }
+        }
+
+        fn advance(&self,font:usize,_:GlyphId)->f32{
+            if font==0{10f32}else{20f32}
+        }
+
+        fn kerning(&self,_:usize,_:GlyphId,_:GlyphId)->f32{
+            -1f32
+        }
+
+        fn line_metrics(&self)->[f32;3]{
+            [8f32,-2f32,0f32]
+        }
+    }
+
+    fn line_text(text:&str,layout:&TextLayout,line:usize)->String{
+        layout.glyphs()[layout.lines()[line].glyphs.clone()].iter()
+            .map(|glyph|&text[glyph.cluster..glyph.cluster+1])
+            .collect()
+    }
+
+    #[test]
+    fn wrapping(){
+        let scale=Scale::new(1f32,1f32);
+        let settings=TextLayoutSettings{
+            max_width:Some(60f32),
+            ..TextLayoutSettings::new()
+        };
+
+        let text="aaa bbb cc\r\nlonglongword";
+        let layout=layout(&Monospace,text,scale,&settings);
+
+        let lines:Vec<String>=(0..layout.lines().len()).map(|line|line_text(text,&layout,line)).collect();
+        assert_eq!(lines,vec!["aaa ","bbb cc","longlo","ngword"]);
+
+        // Пробел в конце не входит в ширину
+        assert_eq!(layout.lines()[0].width,30f32);
+        assert_eq!(layout.lines()[1].width,60f32);
+        assert_eq!(layout.size(),[60f32,40f32]);
+
+        assert_eq!(layout.lines()[0].baseline,8f32);
+        assert_eq!(layout.lines()[2].baseline,28f32);
+        assert_eq!(layout.glyphs()[4].position,[0f32,18f32]);
+        assert_eq!(layout.glyphs()[6].cluster,6);
+
+        // Пустые строки сохраняются
+        let layout=super::layout(&Monospace,"a\n\nb",scale,&TextLayoutSettings::new());
+        assert_eq!(layout.lines().len(),3);
+        assert_eq!(layout.lines()[1].glyphs,1..1);
+    }
+
+    #[test]
+    fn alignment(){
+        let scale=Scale::new(1f32,1f32);
+        let text="aa b\nc";
+
+        let mut settings=TextLayoutSettings{
+            max_width:Some(30f32),
+            alignment:TextAlignment::Right,
+            ..TextLayoutSettings::new()
+        };
+        let layout=layout(&Monospace,text,scale,&settings);
+        // "aa " / "b" / "c"
+        assert_eq!(layout.glyphs()[0].position[0],10f32);
+        assert_eq!(layout.glyphs()[3].position[0],20f32);
+
+        settings.alignment=TextAlignment::Center;
+        let layout=super::layout(&Monospace,text,scale,&settings);
+        assert_eq!(layout.glyphs()[0].position[0],5f32);
+        assert_eq!(layout.glyphs()[3].position[0],10f32);
+
+        // Без максимальной ширины - по самой длинной строке
+        settings.max_width=None;
+        let layout=super::layout(&Monospace,text,scale,&settings);
+        assert_eq!(layout.size()[0],40f32);
+        assert_eq!(layout.glyphs()[4].position[0],15f32);
+
+        settings.max_width=Some(55f32);
+        settings.alignment=TextAlignment::Justify;
+        let text="a b c d\nc";
+        let layout=super::layout(&Monospace,text,scale,&settings);
+        // "a b c " растягивается до 55, последние строки абзацев - нет
+        let positions:Vec<f32>=layout.glyphs()[layout.lines()[0].glyphs.clone()].iter().map(|glyph|glyph.position[0]).collect();
+        assert_eq!(positions,vec![0f32,10f32,22.5f32,32.5f32,45f32,55f32]);
+        assert_eq!(layout.lines()[0].width,55f32);
+        assert_eq!(layout.glyphs()[6].position[0],0f32);
+        assert_eq!(layout.lines()[1].width,10f32);
+    }
+
+    #[test]
+    fn kerning_and_spacing(){
+        let settings=TextLayoutSettings{
+            line_spacing:2f32,
+            ..TextLayoutSettings::new()
+        };
+
+        let layout=layout(&Monospace,"AVA\nA",Scale::new(2f32,1f32),&settings);
+        let positions:Vec<f32>=layout.glyphs().iter().map(|glyph|glyph.position[0]).collect();
+        assert_eq!(positions,vec![0f32,16f32,36f32,0f32]);
+        assert_eq!(layout.lines()[0].width,56f32);
+        assert_eq!(layout.lines()[1].baseline,28f32);
+
+        let settings=TextLayoutSettings{
+            kerning:false,
+            ..TextLayoutSettings::new()
+        };
+        let layout=super::layout(&Monospace,"AV",Scale::new(1f32,1f32),&settings);
+        assert_eq!(layout.lines()[0].width,20f32);
+    }
+
+    #[test]
+    fn font_fallback(){
+        let text="aя\u{301}b";
+        let layout=layout(&Fallback,text,Scale::new(1f32,1f32),&TextLayoutSettings::new());
+
+        let fonts:Vec<usize>=layout.glyphs().iter().map(|glyph|glyph.font).collect();
+        // Знак остаётся в шрифте буквы
+        assert_eq!(fonts,vec![0,1,1,0]);
+
+        // Кернинг только внутри одного шрифта
+        let positions:Vec<f32>=layout.glyphs().iter().map(|glyph|glyph.position[0]).collect();
+        assert_eq!(positions,vec![0f32,10f32,29f32,49f32]);
+    }
+
+    #[test]
+    fn bidirectional_reordering(){
+        // "ab CD ef", где CD - отрезок справа налево
+        let text="ab CD ef";
+        let settings=TextLayoutSettings::new();
+
+        let layout=build_layout([8f32,-2f32,0f32],text,Scale::new(1f32,1f32),&settings,|paragraph,offset,items|{
+            for (index,character) in paragraph.char_indices(){
+                items.push(Item{
+                    font:0,
+                    id:GlyphId(character as u16),
+                    cluster:offset+index,
+                    advance:10f32,
+                    kerning:0f32,
+                    offset:[0f32,-1f32],
+                    whitespace:character==' ',
+                    level:if character.is_ascii_uppercase(){1}else{0},
+                });
+            }
+        });
+
+        let visual:String=layout.glyphs().iter().map(|glyph|&text[glyph.cluster..glyph.cluster+1]).collect();
+        assert_eq!(visual,"ab DC ef");
+
+        let positions:Vec<[f32;2]>=layout.glyphs()[3..5].iter().map(|glyph|glyph.position).collect();
+        assert_eq!(positions,vec![[30f32,7f32],[40f32,7f32]]);
+
+        // Перенос по логическому порядку: "ab C" / "D ef" с отрезками в визуальном порядке
+        let settings=TextLayoutSettings{
+            max_width:Some(45f32),
+            ..TextLayoutSettings::new()
+        };
+        let text="ab CDE f";
+        let layout=build_layout([8f32,-2f32,0f32],text,Scale::new(1f32,1f32),&settings,|paragraph,offset,items|{
+            for (index,character) in paragraph.char_indices(){
+                items.push(Item{
+                    font:0,
+                    id:GlyphId(character as u16),
+                    cluster:offset+index,
+                    advance:10f32,
+                    kerning:0f32,
+                    offset:[0f32;2],
+                    whitespace:character==' ',
+                    level:if character.is_ascii_uppercase(){1}else{0},
+                });
+            }
+        });
+
+        let lines:Vec<String>=(0..layout.lines().len()).map(|line|line_text(text,&layout,line)).collect();
+        assert_eq!(lines,vec!["ab ","EDC ","f"]);
+        assert_eq!(layout.lines()[1].width,30f32);
+    }
+}
@@ -17,14 +17,47 @@
 // Хранилище \\
 
 // Все символы хранятся вместе с глифами в хранилище (`GlyphCache`).
-// Для каждого символа создаётся текстура и в неё загружается глиф.
+// Глифы упаковываются в общие текстуры - страницы атласа (`GlyphAtlas`),
+// так что символы рисуются без смены текстуры.
+// Глифы строятся как изображения покрытия или как поля расстояний (`GlyphMode`),
+// которые остаются чёткими при масштабировании.
 // Поиск глифов по символам выполняется с помощью функций `HashMap`.
 
+// Разметка \\
+
+// Разметка (`TextLayout`) располагает глифы текста по строкам
+// с переносами, выравниванием и кернингом.
+// Размеченный текст рисуется одним пакетом на страницу атласа (`Graphics2D::draw_text`).
+// Символы, которых нет в шрифте, берутся из шрифтов замены (`FontCollection`).
+// С возможностью `text_shaping` текст формируется (лигатуры, соединение арабских букв,
+// диакритические знаки) и упорядочивается для письма справа налево.
+
+mod atlas;
+pub use atlas::{
+    GlyphAtlas,
+    GlyphAtlasSettings,
+    AtlasRegion,
+};
+
 mod glyph;
 pub use glyph::TexturedGlyph;
 
 mod glyph_cache;
-pub use glyph_cache::GlyphCache;
+pub use glyph_cache::{
+    GlyphCache,
+    GlyphMode,
+};
+
+mod opentype;
+
+mod layout;
+pub use layout::{
+    TextAlignment,
+    TextLayoutSettings,
+    PositionedGlyph,
+    TextLine,
+    TextLayout,
+};
 
 mod font;
 pub use font::{
@@ -33,6 +66,12 @@ pub use font::{
     CachedFont,
 };
 
+mod collection;
+pub use collection::FontCollection;
+
+#[cfg(feature="text_shaping")]
+mod shaping;
+
 // re-export
 pub use ttf_parser;
 
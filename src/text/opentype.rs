@@ -0,0 +1,351 @@
+// Чтение таблиц OpenType, которые не разбирает `ttf_parser` (GPOS).
+// Все значения в таблицах записаны в big-endian.
+
+use ttf_parser::{
+    Face,
+    GlyphId,
+    Tag,
+};
+
+pub (crate) fn read_u16(data:&[u8],offset:usize)->Option<u16>{
+    let bytes=data.get(offset..offset+2)?;
+    Some(u16::from_be_bytes([bytes[0],bytes[1]]))
+}
+
+pub (crate) fn read_i16(data:&[u8],offset:usize)->Option<i16>{
+    read_u16(data,offset).map(|value|value as i16)
+}
+
+pub (crate) fn read_u32(data:&[u8],offset:usize)->Option<u32>{
+    let bytes=data.get(offset..offset+4)?;
+    Some(u32::from_be_bytes([bytes[0],bytes[1],bytes[2],bytes[3]]))
+}
+
+/// Подтаблица по смещению от начала `data`.
+pub (crate) fn subtable(data:&[u8],offset:usize)->Option<&[u8]>{
+    data.get(offset..)
+}
+
+/// Номер глифа в таблице покрытия (Coverage).
+pub (crate) fn coverage_index(coverage:&[u8],glyph:GlyphId)->Option<u16>{
+    match read_u16(coverage,0)?{
+        1=>{
+            let count=read_u16(coverage,2)? as usize;
+            // Глифы отсортированы
+            let (mut low,mut high)=(0usize,count);
+            while low<high{
+                let middle=(low+high)/2;
+                let id=read_u16(coverage,4+middle*2)?;
+                if id==glyph.0{
+                    return Some(middle as u16)
+                }
+                else if id<glyph.0{
+                    low=middle+1
+                }
+                else{
+                    high=middle
+                }
+            }
+            None
+        }
+
+        2=>{
+            let count=read_u16(coverage,2)? as usize;
+            for range in 0..count{
+                let record=4+range*6;
+                let start=read_u16(coverage,record)?;
+                let end=read_u16(coverage,record+2)?;
+                if (start..=end).contains(&glyph.0){
+                    let start_index=read_u16(coverage,record+4)?;
+                    return Some(start_index+glyph.0-start)
+                }
+            }
+            None
+        }
+
+        _=>None,
+    }
+}
+
+/// Класс глифа в таблице классов (ClassDef), 0 - глифы без класса.
+pub (crate) fn glyph_class(class_definition:&[u8],glyph:GlyphId)->u16{
+    glyph_class_impl(class_definition,glyph).unwrap_or(0)
+}
+
+fn glyph_class_impl(class_definition:&[u8],glyph:GlyphId)->Option<u16>{
+    match read_u16(class_definition,0)?{
+        1=>{
+            let start=read_u16(class_definition,2)?;
+            let count=read_u16(class_definition,4)?;
+            if glyph.0<start || glyph.0-start>=count{
+                return None
+            }
+            read_u16(class_definition,6+(glyph.0-start) as usize*2)
+        }
+
+        2=>{
+            let count=read_u16(class_definition,2)? as usize;
+            for range in 0..count{
+                let record=4+range*6;
+                let start=read_u16(class_definition,record)?;
+                let end=read_u16(class_definition,record+2)?;
+                if (start..=end).contains(&glyph.0){
+                    return read_u16(class_definition,record+4)
+                }
+            }
+            None
+        }
+
+        _=>None,
+    }
+}
+
+/// Подстановка или позиционирование (GSUB или GPOS).
+pub (crate) struct LayoutTable<'a>{
+    data:&'a [u8],
+    // Тип подтаблицы-расширения (7 для GSUB, 9 для GPOS)
+    extension_type:u16,
+}
+
+/// Правило (Lookup) с подтаблицами.
+pub (crate) struct Lookup<'a>{
+    pub lookup_type:u16,
+    pub flag:u16,
+    pub subtables:Vec<&'a [u8]>,
+}
+
+impl<'a> LayoutTable<'a>{
+    pub fn gsub(face:&Face<'a>)->Option<LayoutTable<'a>>{
+        Some(Self{
+            data:face.table_data(Tag::from_bytes(b"GSUB"))?,
+            extension_type:7,
+        })
+    }
+
+    pub fn gpos(face:&Face<'a>)->Option<LayoutTable<'a>>{
+        Some(Self{
+            data:face.table_data(Tag::from_bytes(b"GPOS"))?,
+            extension_type:9,
+        })
+    }
+
+    /// Номера правил возможности с данным тегом во всех системах письма
+    /// (по возрастанию, без повторений).
+    pub fn feature_lookups(&self,tag:&[u8;4])->Vec<u16>{
+        let mut lookups=Vec::new();
+
+        let feature_list=match read_u16(self.data,6).and_then(|offset|subtable(self.data,offset as usize)){
+            Some(feature_list)=>feature_list,
+            None=>return lookups,
+        };
+
+        let count=read_u16(feature_list,0).unwrap_or(0) as usize;
+        for feature in 0..count{
+            let record=2+feature*6;
+            if feature_list.get(record..record+4)!=Some(&tag[..]){
+                continue
+            }
+
+            let feature_table=match read_u16(feature_list,record+4).and_then(|offset|subtable(feature_list,offset as usize)){
+                Some(feature_table)=>feature_table,
+                None=>continue,
+            };
+
+            let lookup_count=read_u16(feature_table,2).unwrap_or(0) as usize;
+            for lookup in 0..lookup_count{
+                if let Some(index)=read_u16(feature_table,4+lookup*2){
+                    lookups.push(index)
+                }
+            }
+        }
+
+        lookups.sort_unstable();
+        lookups.dedup();
+        lookups
+    }
+
+    /// Правило с данным номером, расширения заменяются подтаблицами.
+    pub fn lookup(&self,index:u16)->Option<Lookup<'a>>{
+        let lookup_list=subtable(self.data,read_u16(self.data,8)? as usize)?;
+        let lookup=subtable(lookup_list,read_u16(lookup_list,2+index as usize*2)? as usize)?;
+
+        let mut lookup_type=read_u16(lookup,0)?;
+        let flag=read_u16(lookup,2)?;
+        let count=read_u16(lookup,4)? as usize;
+
+        let mut subtables=Vec::with_capacity(count);
+        for s in 0..count{
+            let mut table=subtable(lookup,read_u16(lookup,6+s*2)? as usize)?;
+
+            // Расширение - ссылка на подтаблицу с 32-битным смещением
+            if lookup_type==self.extension_type{
+                let extension_type=read_u16(table,2)?;
+                table=subtable(table,read_u32(table,4)? as usize)?;
+                if s==0{
+                    lookup_type=extension_type
+                }
+            }
+
+            subtables.push(table)
+        }
+
+        Some(Lookup{
+            lookup_type,
+            flag,
+            subtables,
+        })
+    }
+}
+
+/// Размер записи значений (ValueRecord) данного формата в байтах.
+fn value_record_size(format:u16)->usize{
+    (format & 0xFF).count_ones() as usize*2
+}
+
+/// Горизонтальное смещение (XAdvance) из записи значений.
+fn value_record_x_advance(data:&[u8],offset:usize,format:u16)->Option<i16>{
+    if format & 0x0004==0{
+        return None
+    }
+    // Перед XAdvance могут быть XPlacement и YPlacement
+    let before=(format & 0x0003).count_ones() as usize*2;
+    read_i16(data,offset+before)
+}
+
+/// Кернинг пары глифов из подтаблицы позиционирования пар (GPOS, тип 2).
+pub (crate) fn pair_adjustment(pair_pos:&[u8],left:GlyphId,right:GlyphId)->Option<i16>{
+    let format=read_u16(pair_pos,0)?;
+    let coverage=subtable(pair_pos,read_u16(pair_pos,2)? as usize)?;
+    let coverage_index=coverage_index(coverage,left)? as usize;
+
+    let value_format1=read_u16(pair_pos,4)?;
+    let value_format2=read_u16(pair_pos,6)?;
+    let size1=value_record_size(value_format1);
+    let size2=value_record_size(value_format2);
+
+    match format{
+        1=>{
+            let pair_set_count=read_u16(pair_pos,8)? as usize;
+            if coverage_index>=pair_set_count{
+                return None
+            }
+            let pair_set=subtable(pair_pos,read_u16(pair_pos,10+coverage_index*2)? as usize)?;
+
+            let record_size=2+size1+size2;
+            let count=read_u16(pair_set,0)? as usize;
+            for pair in 0..count{
+                let record=2+pair*record_size;
+                if read_u16(pair_set,record)?==right.0{
+                    return value_record_x_advance(pair_set,record+2,value_format1)
+                }
+            }
+            None
+        }
+
+        2=>{
+            let class_definition1=subtable(pair_pos,read_u16(pair_pos,8)? as usize)?;
+            let class_definition2=subtable(pair_pos,read_u16(pair_pos,10)? as usize)?;
+            let class1_count=read_u16(pair_pos,12)? as usize;
+            let class2_count=read_u16(pair_pos,14)? as usize;
+
+            let class1=glyph_class(class_definition1,left) as usize;
+            let class2=glyph_class(class_definition2,right) as usize;
+            if class1>=class1_count || class2>=class2_count{
+                return None
+            }
+
+            let record=16+(class1*class2_count+class2)*(size1+size2);
+            value_record_x_advance(pair_pos,record,value_format1)
+        }
+
+        _=>None,
+    }
+}
+
+/// Кернинг шрифта - GPOS (возможность `kern`), а если его нет, то таблица `kern`.
+pub (crate) struct Kerning<'a>{
+    face:Face<'a>,
+    // Подтаблицы позиционирования пар
+    pair_pos:Vec<&'a [u8]>,
+}
+
+impl<'a> Kerning<'a>{
+    pub fn new(face:&Face<'a>)->Kerning<'a>{
+        let mut pair_pos=Vec::new();
+
+        if let Some(gpos)=LayoutTable::gpos(face){
+            for index in gpos.feature_lookups(b"kern"){
+                if let Some(lookup)=gpos.lookup(index){
+                    if lookup.lookup_type==2{
+                        pair_pos.extend(lookup.subtables)
+                    }
+                }
+            }
+        }
+
+        Self{
+            face:face.clone(),
+            pair_pos,
+        }
+    }
+
+    /// Кернинг пары глифов в единицах шрифта.
+    pub fn kerning(&self,left:GlyphId,right:GlyphId)->i16{
+        if !self.pair_pos.is_empty(){
+            // Применяется первая подходящая подтаблица
+            return self.pair_pos.iter()
+                .find_map(|pair_pos|pair_adjustment(pair_pos,left,right))
+                .unwrap_or(0)
+        }
+
+        self.face.kerning_subtables()
+            .filter(|subtable|subtable.is_horizontal() && !subtable.is_variable() && !subtable.has_cross_stream())
+            .find_map(|subtable|subtable.glyphs_kerning(left,right))
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn push_u16(data:&mut Vec<u8>,values:&[u16]){
+        for value in values{
+            data.extend_from_slice(&value.to_be_bytes())
+        }
+    }
+
+    #[test]
+    fn pair_positioning(){
+        // Формат 1: глиф 5 с глифами 7 (-50) и 9 (20)
+        let mut format1=Vec::new();
+        // format, coverage, value formats (XAdvance), pair set count, pair set offset
+        push_u16(&mut format1,&[1,12,0x0004,0,1,18]);
+        // coverage: format 1, один глиф
+        push_u16(&mut format1,&[1,1,5]);
+        // pair set
+        push_u16(&mut format1,&[2,7,(-50i16) as u16,9,20]);
+
+        assert_eq!(pair_adjustment(&format1,GlyphId(5),GlyphId(7)),Some(-50));
+        assert_eq!(pair_adjustment(&format1,GlyphId(5),GlyphId(9)),Some(20));
+        assert_eq!(pair_adjustment(&format1,GlyphId(5),GlyphId(8)),None);
+        assert_eq!(pair_adjustment(&format1,GlyphId(6),GlyphId(7)),None);
+
+        // Формат 2: классы глифов 3..=4 (1) и 10 (1), XPlacement и XAdvance
+        let mut format2=Vec::new();
+        // format, coverage, value formats, class definitions, class counts
+        push_u16(&mut format2,&[2,32,0x0005,0,42,52,2,2]);
+        // записи классов 2x2: (XPlacement, XAdvance)
+        push_u16(&mut format2,&[0,0, 0,0, 0,0, 1,(-30i16) as u16]);
+        // coverage: format 2, один диапазон
+        push_u16(&mut format2,&[2,1,3,4,0]);
+        // class definition 1: format 1
+        push_u16(&mut format2,&[1,3,2,1,1]);
+        // class definition 2: format 2
+        push_u16(&mut format2,&[2,1,10,10,1]);
+
+        assert_eq!(pair_adjustment(&format2,GlyphId(4),GlyphId(10)),Some(-30));
+        assert_eq!(pair_adjustment(&format2,GlyphId(3),GlyphId(11)),Some(0));
+        assert_eq!(pair_adjustment(&format2,GlyphId(5),GlyphId(10)),None);
+    }
+}
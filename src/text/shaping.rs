@@ -0,0 +1,121 @@
+// Формирование текста (shaping) с помощью `rustybuzz`
+// и двунаправленное упорядочивание с помощью `unicode_bidi`.
+
+use super::{
+    Scale,
+    FontOwner,
+    TextLayout,
+    TextLayoutSettings,
+    layout::{
+        LayoutMetrics,
+        Item,
+        build_layout,
+        select_font,
+    },
+};
+
+use rustybuzz::{
+    UnicodeBuffer,
+    Direction,
+    Feature,
+    ttf_parser::Tag,
+};
+
+use unicode_bidi::ParagraphBidiInfo;
+
+use ttf_parser::GlyphId;
+
+use std::ops::Range;
+
+pub (crate) fn layout<M:LayoutMetrics>(
+    metrics:&M,
+    fonts:&[&FontOwner],
+    text:&str,
+    scale:Scale,
+    settings:&TextLayoutSettings
+)->TextLayout{
+    let faces:Vec<Option<rustybuzz::Face>>=fonts.iter().map(|font|rustybuzz::Face::from_slice(font.data(),0)).collect();
+
+    // Единиц первого шрифта в единице шрифта
+    let units_per_em=|face:&Option<rustybuzz::Face>|face.as_ref().map(|face|face.units_per_em() as f32).unwrap_or(1000f32);
+    let primary=faces.first().map(units_per_em).unwrap_or(1000f32);
+    let units:Vec<f32>=faces.iter().map(|face|primary/units_per_em(face)).collect();
+
+    let features=if settings.kerning{
+        Vec::new()
+    }
+    else{
+        vec![Feature::new(Tag::from_bytes(b"kern"),0,..)]
+    };
+
+    build_layout(metrics.line_metrics(),text,scale,settings,|paragraph,offset,items|{
+        if paragraph.is_empty(){
+            return
+        }
+
+        let bidi=ParagraphBidiInfo::new(paragraph,None);
+
+        // Отрезки с одним шрифтом и направлением
+        let mut runs:Vec<(Range<usize>,usize,u8)>=Vec::new();
+        let mut previous=None;
+        for (index,character) in paragraph.char_indices(){
+            let (font,_)=select_font(metrics,character,previous);
+            previous=Some(font);
+
+            let level=bidi.levels[index].number();
+            let end=index+character.len_utf8();
+
+            match runs.last_mut(){
+                Some((range,run_font,run_level)) if *run_font==font && *run_level==level=>range.end=end,
+                _=>runs.push((index..end,font,level)),
+            }
+        }
+
+        for (range,font,level) in runs{
+            let face=match &faces[font]{
+                Some(face)=>face,
+                None=>continue,
+            };
+
+            let mut buffer=UnicodeBuffer::new();
+            buffer.push_str(&paragraph[range.clone()]);
+            buffer.set_direction(if level%2==1{
+                Direction::RightToLeft
+            }
+            else{
+                Direction::LeftToRight
+            });
+
+            let glyphs=rustybuzz::shape(face,&features,buffer);
+
+            let horizontal=units[font]*scale.horizontal;
+            let vertical=units[font]*scale.vertical;
+
+            let first=items.len();
+            for (info,position) in glyphs.glyph_infos().iter().zip(glyphs.glyph_positions().iter()){
+                let cluster=range.start+info.cluster as usize;
+
+                items.push(Item{
+                    font,
+                    id:GlyphId(info.glyph_id as u16),
+                    cluster:offset+cluster,
+                    advance:position.x_advance as f32*horizontal,
+                    // Кернинг уже входит в ширины
+                    kerning:0f32,
+                    offset:[
+                        position.x_offset as f32*horizontal,
+                        -position.y_offset as f32*vertical,
+                    ],
+                    whitespace:paragraph[cluster..].chars().next().map(char::is_whitespace).unwrap_or(false),
+                    level,
+                });
+            }
+
+            // Глифы отрезков справа налево идут в визуальном порядке,
+            // а строки разбиваются в логическом
+            if level%2==1{
+                items[first..].reverse()
+            }
+        }
+    })
+}